@@ -1,7 +1,7 @@
 //! A set of high-level utility fixture methods to use in tests.
 use std::{mem, str::FromStr, sync::Arc};
 
-use cfg::CfgOptions;
+use cfg::{CfgOptions, CheckCfg};
 use rustc_hash::FxHashMap;
 use test_utils::{
     extract_range_or_offset, Fixture, RangeOrOffset, CURSOR_MARKER, ESCAPED_CURSOR_MARKER,
@@ -158,6 +158,7 @@ impl ChangeFixture {
                     version,
                     meta.cfg.clone(),
                     meta.cfg,
+                    meta.build_script_cfg,
                     meta.env,
                     Ok(Vec::new()),
                     false,
@@ -193,6 +194,7 @@ impl ChangeFixture {
                 None,
                 default_cfg.clone(),
                 default_cfg,
+                CfgOptions::default(),
                 Env::default(),
                 Ok(Vec::new()),
                 false,
@@ -230,6 +232,7 @@ impl ChangeFixture {
                 None,
                 CfgOptions::default(),
                 CfgOptions::default(),
+                CfgOptions::default(),
                 Env::default(),
                 Ok(Vec::new()),
                 false,
@@ -267,6 +270,7 @@ impl ChangeFixture {
                 None,
                 CfgOptions::default(),
                 CfgOptions::default(),
+                CfgOptions::default(),
                 Env::default(),
                 Ok(proc_macro),
                 true,
@@ -388,6 +392,7 @@ struct FileMeta {
     deps: Vec<String>,
     extern_prelude: Vec<String>,
     cfg: CfgOptions,
+    build_script_cfg: CfgOptions,
     edition: Edition,
     env: Env,
     introduce_new_source_root: Option<SourceRootKind>,
@@ -420,6 +425,23 @@ impl From<Fixture> for FileMeta {
         let mut cfg = CfgOptions::default();
         f.cfg_atoms.iter().for_each(|it| cfg.insert_atom(it.into()));
         f.cfg_key_values.iter().for_each(|(k, v)| cfg.insert_key_value(k.into(), v.into()));
+        let mut build_script_cfg = CfgOptions::default();
+        for (k, v) in f.build_script_cfg {
+            for cfg in [&mut cfg, &mut build_script_cfg] {
+                match &v {
+                    Some(v) => cfg.insert_key_value(k.as_str().into(), v.as_str().into()),
+                    None => cfg.insert_atom(k.as_str().into()),
+                }
+            }
+        }
+        if let Some(check_cfg) = f.check_cfg {
+            let mut expected = CheckCfg::well_known();
+            cfg.iter().for_each(|atom| expected.expect_atom(atom));
+            for (name, value) in check_cfg {
+                expected.expect(name.into(), value.map(Into::into));
+            }
+            cfg.set_expected(Arc::new(expected));
+        }
         let deps = f.deps;
         FileMeta {
             path: f.path,
@@ -427,6 +449,7 @@ impl From<Fixture> for FileMeta {
             extern_prelude: f.extern_prelude.unwrap_or_else(|| deps.clone()),
             deps,
             cfg,
+            build_script_cfg,
             edition: f.edition.as_ref().map_or(Edition::CURRENT, |v| Edition::from_str(v).unwrap()),
             env: f.env.into_iter().collect(),
             introduce_new_source_root: f.introduce_new_source_root.map(|kind| match &*kind {
@@ -10,7 +10,10 @@ use std::{fmt, ops, panic::RefUnwindSafe, str::FromStr, sync::Arc};
 
 use cfg::CfgOptions;
 use rustc_hash::FxHashMap;
-use stdx::hash::{NoHashHashMap, NoHashHashSet};
+use stdx::{
+    always,
+    hash::{NoHashHashMap, NoHashHashSet},
+};
 use syntax::SmolStr;
 use tt::Subtree;
 use vfs::{file_set::FileSet, AnchoredPath, FileId, VfsPath};
@@ -139,6 +142,8 @@ pub enum CrateOrigin {
     CratesIo { repo: Option<String> },
     /// Crates that are provided by the language, like std, core, proc-macro, ...
     Lang(LangCrateOrigin),
+    /// Crates that only exist locally, like the ones created for files outside of any workspace.
+    Local,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -270,6 +275,9 @@ pub struct CrateData {
     pub display_name: Option<CrateDisplayName>,
     pub cfg_options: CfgOptions,
     pub potential_cfg_options: CfgOptions,
+    /// The cfg options set by the build script of the crate's package. These are already
+    /// included in `cfg_options`, this is kept around to show them to the user.
+    pub build_script_cfg_options: CfgOptions,
     pub env: Env,
     pub dependencies: Vec<Dependency>,
     pub proc_macro: ProcMacroLoadResult,
@@ -324,6 +332,7 @@ impl CrateGraph {
         version: Option<String>,
         cfg_options: CfgOptions,
         potential_cfg_options: CfgOptions,
+        build_script_cfg_options: CfgOptions,
         env: Env,
         proc_macro: ProcMacroLoadResult,
         is_proc_macro: bool,
@@ -336,13 +345,14 @@ impl CrateGraph {
             display_name,
             cfg_options,
             potential_cfg_options,
+            build_script_cfg_options,
             env,
             proc_macro,
             dependencies: Vec::new(),
             origin,
             is_proc_macro,
         };
-        let crate_id = CrateId(self.arena.len() as u32);
+        let crate_id = CrateId(self.next_crate_id());
         let prev = self.arena.insert(crate_id, data);
         assert!(prev.is_none());
         crate_id
@@ -369,6 +379,17 @@ impl CrateGraph {
         Ok(())
     }
 
+    /// Removes a crate no other crate depends on.
+    pub fn remove_crate(&mut self, crate_id: CrateId) {
+        always!(
+            self.arena
+                .values()
+                .all(|data| data.dependencies.iter().all(|dep| dep.crate_id != crate_id)),
+            "removing a crate that is depended on"
+        );
+        self.arena.remove(&crate_id);
+    }
+
     pub fn is_empty(&self) -> bool {
         self.arena.is_empty()
     }
@@ -462,7 +483,7 @@ impl CrateGraph {
     /// The ids of the crates in the `other` graph are shifted by the return
     /// amount.
     pub fn extend(&mut self, other: CrateGraph) -> u32 {
-        let start = self.arena.len() as u32;
+        let start = self.next_crate_id();
         self.arena.extend(other.arena.into_iter().map(|(id, mut data)| {
             let new_id = id.shift(start);
             for dep in &mut data.dependencies {
@@ -473,6 +494,11 @@ impl CrateGraph {
         start
     }
 
+    /// Crates may have been removed, so the number of crates isn't necessarily a free id.
+    fn next_crate_id(&self) -> u32 {
+        self.arena.keys().map(|it| it.0 + 1).max().unwrap_or(0)
+    }
+
     fn find_path(
         &self,
         visited: &mut NoHashHashSet<CrateId>,
@@ -645,6 +671,7 @@ mod tests {
             None,
             CfgOptions::default(),
             CfgOptions::default(),
+            CfgOptions::default(),
             Env::default(),
             Ok(Vec::new()),
             false,
@@ -657,6 +684,7 @@ mod tests {
             None,
             CfgOptions::default(),
             CfgOptions::default(),
+            CfgOptions::default(),
             Env::default(),
             Ok(Vec::new()),
             false,
@@ -669,6 +697,7 @@ mod tests {
             None,
             CfgOptions::default(),
             CfgOptions::default(),
+            CfgOptions::default(),
             Env::default(),
             Ok(Vec::new()),
             false,
@@ -695,6 +724,7 @@ mod tests {
             None,
             CfgOptions::default(),
             CfgOptions::default(),
+            CfgOptions::default(),
             Env::default(),
             Ok(Vec::new()),
             false,
@@ -707,6 +737,7 @@ mod tests {
             None,
             CfgOptions::default(),
             CfgOptions::default(),
+            CfgOptions::default(),
             Env::default(),
             Ok(Vec::new()),
             false,
@@ -730,6 +761,7 @@ mod tests {
             None,
             CfgOptions::default(),
             CfgOptions::default(),
+            CfgOptions::default(),
             Env::default(),
             Ok(Vec::new()),
             false,
@@ -742,6 +774,7 @@ mod tests {
             None,
             CfgOptions::default(),
             CfgOptions::default(),
+            CfgOptions::default(),
             Env::default(),
             Ok(Vec::new()),
             false,
@@ -754,6 +787,7 @@ mod tests {
             None,
             CfgOptions::default(),
             CfgOptions::default(),
+            CfgOptions::default(),
             Env::default(),
             Ok(Vec::new()),
             false,
@@ -777,6 +811,7 @@ mod tests {
             None,
             CfgOptions::default(),
             CfgOptions::default(),
+            CfgOptions::default(),
             Env::default(),
             Ok(Vec::new()),
             false,
@@ -789,6 +824,7 @@ mod tests {
             None,
             CfgOptions::default(),
             CfgOptions::default(),
+            CfgOptions::default(),
             Env::default(),
             Ok(Vec::new()),
             false,
@@ -805,4 +841,32 @@ mod tests {
             vec![Dependency::new(CrateName::new("crate_name_with_dashes").unwrap(), crate2)]
         );
     }
+
+    #[test]
+    fn removed_crate_ids_are_not_reused_for_others() {
+        let mut graph = CrateGraph::default();
+        let add = |graph: &mut CrateGraph, file_id| {
+            graph.add_crate_root(
+                FileId(file_id),
+                Edition2018,
+                None,
+                None,
+                CfgOptions::default(),
+                CfgOptions::default(),
+                CfgOptions::default(),
+                Env::default(),
+                Ok(Vec::new()),
+                false,
+                CrateOrigin::Local,
+            )
+        };
+        let crate1 = add(&mut graph, 1u32);
+        let crate2 = add(&mut graph, 2u32);
+        graph.remove_crate(crate1);
+        let crate3 = add(&mut graph, 3u32);
+        assert_ne!(crate2, crate3);
+        assert_eq!(graph[crate2].root_file_id, FileId(2u32));
+        assert_eq!(graph[crate3].root_file_id, FileId(3u32));
+        assert_eq!(graph.crate_id_for_crate_root(FileId(1u32)), None);
+    }
 }
@@ -0,0 +1,320 @@
+//! The set of cfg names and values a crate expects, mirroring rustc's `--check-cfg`.
+//!
+//! See: <https://doc.rust-lang.org/rustc/check-cfg.html>
+
+use std::{fmt, iter, sync::Arc};
+
+use rustc_hash::{FxHashMap, FxHashSet};
+use tt::SmolStr;
+
+use crate::CfgAtom;
+
+/// The cfg names and values that are expected to appear in `cfg` conditions of a crate.
+///
+/// A condition using a name or value outside of this set is most likely a typo, and makes the
+/// annotated code silently inactive.
+///
+/// The expectations may extend a `base` set, which lets the crates of a workspace share what rustc
+/// expects, including its long lists of target values, and only store their own additions.
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct CheckCfg {
+    base: Option<Arc<CheckCfg>>,
+    names: FxHashMap<SmolStr, ExpectedValues>,
+    /// Set by `cfg(any())`, any name is expected.
+    any_name: bool,
+}
+
+/// The values expected for a single cfg name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExpectedValues {
+    Any,
+    /// `None` stands for the name being used on its own, like `#[cfg(test)]`.
+    Some(FxHashSet<Option<SmolStr>>),
+}
+
+impl fmt::Debug for CheckCfg {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut names = self.names().collect::<Vec<_>>();
+        names.sort();
+        let any_name = self.layers().any(|it| it.any_name);
+        f.debug_struct("CheckCfg").field("names", &names).field("any_name", &any_name).finish()
+    }
+}
+
+impl CheckCfg {
+    /// The names and values rustc knows about for any target, without any further configuration.
+    ///
+    /// This doesn't include the names whose values depend on the targets rustc supports, like
+    /// `target_os`. Those are either added from rustc's own `--check-cfg` specifications, or with
+    /// [`CheckCfg::expect_any_target_value`].
+    pub fn well_known() -> CheckCfg {
+        let mut res = CheckCfg::default();
+        for name in WELL_KNOWN_FLAGS {
+            res.expect(SmolStr::new(name), None);
+        }
+        for (name, values) in WELL_KNOWN_VALUES {
+            for value in values.iter() {
+                res.expect(SmolStr::new(name), Some(SmolStr::new(value)));
+            }
+        }
+        res
+    }
+
+    /// Expects everything `base` expects, plus whatever gets added to the returned set.
+    pub fn extending(base: Arc<CheckCfg>) -> CheckCfg {
+        CheckCfg { base: Some(base), ..CheckCfg::default() }
+    }
+
+    /// Expects `name` to be used with `value`, or on its own if `value` is `None`.
+    pub fn expect(&mut self, name: SmolStr, value: Option<SmolStr>) {
+        self.expect_values(name, Some(value));
+    }
+
+    /// Expects `name` to be used with any of `values`. The name counts as known even if there are
+    /// no values, like for `cfg(feature, values())`.
+    pub fn expect_values(
+        &mut self,
+        name: SmolStr,
+        values: impl IntoIterator<Item = Option<SmolStr>>,
+    ) {
+        if let ExpectedValues::Some(expected) =
+            self.names.entry(name).or_insert_with(|| ExpectedValues::Some(FxHashSet::default()))
+        {
+            expected.extend(values);
+        }
+    }
+
+    /// Expects `name` to be used with any value, or on its own.
+    pub fn expect_any_value(&mut self, name: SmolStr) {
+        self.names.insert(name, ExpectedValues::Any);
+    }
+
+    /// Expects the names whose values depend on the target, like `target_os`, to be used with any
+    /// value. Used when rustc can't tell which values it expects.
+    pub fn expect_any_target_value(&mut self) {
+        for name in TARGET_NAMES {
+            self.expect_any_value(SmolStr::new(name));
+        }
+    }
+
+    pub fn expect_atom(&mut self, atom: &CfgAtom) {
+        match atom {
+            CfgAtom::Flag(name) => self.expect(name.clone(), None),
+            CfgAtom::KeyValue { key, value } => self.expect(key.clone(), Some(value.clone())),
+        }
+    }
+
+    /// Adds the expectations of a `--check-cfg` specification, like
+    /// `cfg(name1, name2, values("a", "b", none()))` or `cfg(any())`.
+    pub fn add_spec(&mut self, spec: &str) -> Result<(), String> {
+        let tokens = tokenize(spec)?;
+        let err = || format!("invalid `--check-cfg` specification: `{}`", spec);
+        let args = match &*tokens {
+            [Token::Ident(cfg), Token::Open, args @ .., Token::Close] if cfg == "cfg" => args,
+            _ => return Err(err()),
+        };
+
+        let mut names = Vec::new();
+        let mut values = None;
+        let mut any_name = false;
+        for arg in split_args(args) {
+            match arg {
+                [Token::Ident(name)] if values.is_none() => names.push(name.clone()),
+                [Token::Ident(any), Token::Open, Token::Close] if any == "any" => any_name = true,
+                [Token::Ident(kw), Token::Open, list @ .., Token::Close] if kw == "values" => {
+                    let mut expected = Vec::new();
+                    let mut any_value = false;
+                    for value in split_args(list) {
+                        match value {
+                            [Token::Str(value)] => expected.push(Some(value.clone())),
+                            [Token::Ident(kw), Token::Open, Token::Close] if kw == "none" => {
+                                expected.push(None)
+                            }
+                            [Token::Ident(kw), Token::Open, Token::Close] if kw == "any" => {
+                                any_value = true
+                            }
+                            _ => return Err(err()),
+                        }
+                    }
+                    values = Some((expected, any_value));
+                }
+                _ => return Err(err()),
+            }
+        }
+
+        if any_name {
+            self.any_name = true;
+        }
+        let (values, any_value) = values.unwrap_or_else(|| (vec![None], false));
+        for name in names {
+            if any_value {
+                self.expect_any_value(name);
+            } else {
+                self.expect_values(name, values.iter().cloned());
+            }
+        }
+        Ok(())
+    }
+
+    pub fn is_expected_name(&self, name: &str) -> bool {
+        self.layers().any(|it| it.any_name || it.names.contains_key(name))
+    }
+
+    /// Returns whether `name` is expected to be used with `value`, or on its own if `value` is
+    /// `None`. Unknown names are never expected, unless any name is.
+    pub fn is_expected(&self, name: &str, value: Option<&str>) -> bool {
+        match self.values(name) {
+            Some(ExpectedValues::Any) => true,
+            Some(ExpectedValues::Some(values)) => values.contains(&value.map(SmolStr::new)),
+            None => self.layers().any(|it| it.any_name),
+        }
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &SmolStr> {
+        let mut seen = FxHashSet::default();
+        self.layers().flat_map(|it| it.names.keys()).filter(move |name| seen.insert(*name))
+    }
+
+    /// Returns the values expected for `name`, merged across the sets this one extends.
+    pub fn values(&self, name: &str) -> Option<ExpectedValues> {
+        let mut res: Option<FxHashSet<Option<SmolStr>>> = None;
+        for layer in self.layers() {
+            match layer.names.get(name) {
+                Some(ExpectedValues::Any) => return Some(ExpectedValues::Any),
+                Some(ExpectedValues::Some(values)) => {
+                    res.get_or_insert_with(FxHashSet::default).extend(values.iter().cloned())
+                }
+                None => {}
+            }
+        }
+        res.map(ExpectedValues::Some)
+    }
+
+    fn layers(&self) -> impl Iterator<Item = &CheckCfg> {
+        iter::successors(Some(self), |it| it.base.as_deref())
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Token {
+    Ident(SmolStr),
+    Str(SmolStr),
+    Open,
+    Close,
+    Comma,
+}
+
+/// Splits the comma separated `tokens` at the outermost level.
+fn split_args(tokens: &[Token]) -> Vec<&[Token]> {
+    let mut res = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (idx, token) in tokens.iter().enumerate() {
+        match token {
+            Token::Open => depth += 1,
+            Token::Close => depth = depth.saturating_sub(1),
+            Token::Comma if depth == 0 => {
+                res.push(&tokens[start..idx]);
+                start = idx + 1;
+            }
+            _ => {}
+        }
+    }
+    res.push(&tokens[start..]);
+    res.retain(|it| !it.is_empty());
+    res
+}
+
+fn tokenize(spec: &str) -> Result<Vec<Token>, String> {
+    let mut res = Vec::new();
+    let mut chars = spec.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        match c {
+            '(' => res.push(Token::Open),
+            ')' => res.push(Token::Close),
+            ',' => res.push(Token::Comma),
+            '"' => {
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        Some((_, '"')) => break,
+                        Some((_, '\\')) => match chars.next() {
+                            Some((_, c)) => value.push(c),
+                            None => return Err(format!("unterminated string in `{}`", spec)),
+                        },
+                        Some((_, c)) => value.push(c),
+                        None => return Err(format!("unterminated string in `{}`", spec)),
+                    }
+                }
+                res.push(Token::Str(value.into()));
+            }
+            c if c.is_whitespace() => {}
+            c if c.is_alphabetic() || c == '_' => {
+                let mut end = start + c.len_utf8();
+                while let Some(&(idx, c)) = chars.peek() {
+                    if !(c.is_alphanumeric() || c == '_') {
+                        break;
+                    }
+                    end = idx + c.len_utf8();
+                    chars.next();
+                }
+                res.push(Token::Ident(spec[start..end].into()));
+            }
+            _ => return Err(format!("unexpected character `{}` in `{}`", c, spec)),
+        }
+    }
+    Ok(res)
+}
+
+/// Names rustc expects to be used on their own, like `#[cfg(test)]`.
+const WELL_KNOWN_FLAGS: [&str; 15] = [
+    "clippy",
+    "debug_assertions",
+    "doc",
+    "doctest",
+    "miri",
+    "overflow_checks",
+    "proc_macro",
+    "rustfmt",
+    "sanitizer_cfi_generalize_pointers",
+    "sanitizer_cfi_normalize_integers",
+    "target_thread_local",
+    "test",
+    "ub_checks",
+    "unix",
+    "windows",
+];
+
+const WELL_KNOWN_VALUES: [(&str, &[&str]); 9] = [
+    ("panic", &["abort", "unwind"]),
+    ("relocation_model", &["pic", "pie", "dynamic-no-pic", "ropi", "rwpi", "ropi-rwpi", "static"]),
+    (
+        "sanitize",
+        &[
+            "address",
+            "cfi",
+            "hwaddress",
+            "kcfi",
+            "kernel-address",
+            "leak",
+            "memory",
+            "memtag",
+            "safestack",
+            "shadow-call-stack",
+            "thread",
+        ],
+    ),
+    ("target_endian", &["big", "little"]),
+    ("target_family", &["unix", "wasm", "windows"]),
+    ("target_has_atomic", &ATOMIC_WIDTHS),
+    ("target_has_atomic_equal_alignment", &ATOMIC_WIDTHS),
+    ("target_has_atomic_load_store", &ATOMIC_WIDTHS),
+    ("target_pointer_width", &["16", "32", "64"]),
+];
+
+const ATOMIC_WIDTHS: [&str; 6] = ["8", "16", "32", "64", "128", "ptr"];
+
+/// Names whose values depend on the targets supported by the compiler, those are only known by
+/// asking rustc.
+const TARGET_NAMES: [&str; 6] =
+    ["target_abi", "target_arch", "target_env", "target_feature", "target_os", "target_vendor"];
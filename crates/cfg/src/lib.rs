@@ -3,16 +3,18 @@
 #![warn(rust_2018_idioms, unused_lifetimes, semicolon_in_expressions_from_macros)]
 
 mod cfg_expr;
+mod check_cfg;
 mod dnf;
 #[cfg(test)]
 mod tests;
 
-use std::fmt;
+use std::{fmt, sync::Arc};
 
 use rustc_hash::FxHashSet;
 use tt::SmolStr;
 
 pub use cfg_expr::{CfgAtom, CfgExpr};
+pub use check_cfg::{CheckCfg, ExpectedValues};
 pub use dnf::DnfExpr;
 
 /// Configuration options used for conditional compilation on items with `cfg` attributes.
@@ -24,10 +26,15 @@ pub use dnf::DnfExpr;
 /// `foo` and `bar` are both enabled. And here, we store key-value options as a set of tuple
 /// of key and value in `key_values`.
 ///
+/// Besides the enabled options, the options may also know which names and values are expected to
+/// be used at all, see [`CheckCfg`]. Those are shared, as most of them are the same for all the
+/// crates of a workspace.
+///
 /// See: <https://doc.rust-lang.org/reference/conditional-compilation.html#set-configuration-options>
 #[derive(Clone, PartialEq, Eq, Default)]
 pub struct CfgOptions {
     enabled: FxHashSet<CfgAtom>,
+    expected: Option<Arc<CheckCfg>>,
 }
 
 impl fmt::Debug for CfgOptions {
@@ -68,6 +75,10 @@ impl CfgOptions {
         }
     }
 
+    pub fn iter(&self) -> impl Iterator<Item = &CfgAtom> {
+        self.enabled.iter()
+    }
+
     pub fn get_cfg_keys(&self) -> impl Iterator<Item = &SmolStr> {
         self.enabled.iter().map(|x| match x {
             CfgAtom::Flag(key) => key,
@@ -84,6 +95,15 @@ impl CfgOptions {
             _ => None,
         })
     }
+
+    /// The names and values expected in `cfg` conditions, if they are known.
+    pub fn expected(&self) -> Option<&Arc<CheckCfg>> {
+        self.expected.as_ref()
+    }
+
+    pub fn set_expected(&mut self, expected: Arc<CheckCfg>) {
+        self.expected = Some(expected);
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
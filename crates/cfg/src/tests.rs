@@ -1,9 +1,11 @@
+use std::sync::Arc;
+
 use arbitrary::{Arbitrary, Unstructured};
 use expect_test::{expect, Expect};
 use mbe::syntax_node_to_token_tree;
 use syntax::{ast, AstNode};
 
-use crate::{CfgAtom, CfgExpr, CfgOptions, DnfExpr};
+use crate::{CfgAtom, CfgExpr, CfgOptions, CheckCfg, DnfExpr};
 
 fn assert_parse_result(input: &str, expected: CfgExpr) {
     let (tt, _) = {
@@ -205,6 +207,71 @@ fn why_inactive() {
     );
 }
 
+#[test]
+fn check_cfg_well_known() {
+    let mut check = CheckCfg::well_known();
+    assert!(check.is_expected("test", None));
+    assert!(!check.is_expected("test", Some("foo")));
+    assert!(check.is_expected("target_endian", Some("little")));
+    assert!(!check.is_expected("target_endian", Some("litle")));
+    assert!(!check.is_expected_name("target_os"));
+    assert!(!check.is_expected_name("feature"));
+
+    check.expect_any_target_value();
+    assert!(check.is_expected("target_os", Some("linux")));
+    assert!(check.is_expected("target_feature", Some("sse2")));
+}
+
+#[test]
+fn check_cfg_specs() {
+    let mut check = CheckCfg::default();
+    check.add_spec(r#"cfg(has_foo, has_bar)"#).unwrap();
+    check.add_spec(r#"cfg(feature, values("serde", "std", none()))"#).unwrap();
+    check.add_spec(r#"cfg(version, values(any()))"#).unwrap();
+    check.add_spec(r#"cfg(empty, values())"#).unwrap();
+
+    assert!(check.is_expected("has_foo", None));
+    assert!(check.is_expected("has_bar", None));
+    assert!(!check.is_expected("has_bar", Some("x")));
+    assert!(check.is_expected("feature", Some("serde")));
+    assert!(check.is_expected("feature", None));
+    assert!(!check.is_expected("feature", Some("sered")));
+    assert!(check.is_expected("version", Some("1.0")));
+    assert!(check.is_expected_name("empty"));
+    assert!(!check.is_expected("empty", None));
+    assert!(!check.is_expected("unknown", None));
+
+    check.add_spec("cfg(any())").unwrap();
+    assert!(check.is_expected("unknown", None));
+    assert!(!check.is_expected("feature", Some("sered")));
+
+    assert!(check.add_spec("names(foo)").is_err());
+    assert!(check.add_spec(r#"cfg(foo, values(1))"#).is_err());
+}
+
+#[test]
+fn check_cfg_extending() {
+    let mut base = CheckCfg::well_known();
+    base.add_spec(r#"cfg(feature, values("std"))"#).unwrap();
+    let base = Arc::new(base);
+
+    let mut check = CheckCfg::extending(Arc::clone(&base));
+    check.add_spec(r#"cfg(feature, values("serde"))"#).unwrap();
+    check.add_spec("cfg(has_foo)").unwrap();
+
+    assert!(check.is_expected("test", None));
+    assert!(check.is_expected("feature", Some("std")));
+    assert!(check.is_expected("feature", Some("serde")));
+    assert!(!check.is_expected("feature", Some("sered")));
+    assert!(check.is_expected("has_foo", None));
+    assert!(!base.is_expected_name("has_foo"));
+    assert_eq!(check.names().filter(|it| *it == "feature").count(), 1);
+
+    check.add_spec("cfg(any())").unwrap();
+    assert!(check.is_expected("unknown", None));
+    assert!(!check.is_expected("feature", Some("sered")));
+}
+
 #[test]
 fn proptest() {
     const REPEATS: usize = 512;
@@ -136,6 +136,40 @@ impl Path {
     pub fn is_self_type(&self) -> bool {
         self.type_anchor.is_none() && *self.generic_args == [None] && self.mod_path.is_Self()
     }
+
+    /// Returns this path and the paths in its type anchor and generic arguments, in the order
+    /// they appear in the source. See `Path::source_paths` for finding them there.
+    pub fn paths(&self) -> Vec<&Path> {
+        let mut acc = Vec::new();
+        self.collect_paths(&mut acc);
+        acc
+    }
+
+    pub(crate) fn collect_paths<'a>(&'a self, acc: &mut Vec<&'a Path>) {
+        acc.push(self);
+        if let Some(type_ref) = self.type_anchor() {
+            type_ref.collect_paths(acc);
+        }
+        for generic_args in self.generic_args.iter().flatten() {
+            generic_args.collect_paths(acc);
+        }
+    }
+
+    /// Returns the paths `Path::from_src` lowers in `path`, in the same order as `Path::paths`
+    /// returns them.
+    pub fn source_paths(ctx: &LowerCtx<'_>, path: ast::Path) -> Vec<ast::Path> {
+        let mut acc = Vec::new();
+        Path::collect_source_paths(ctx, path, &mut acc);
+        acc
+    }
+
+    pub(crate) fn collect_source_paths(
+        ctx: &LowerCtx<'_>,
+        path: ast::Path,
+        acc: &mut Vec<ast::Path>,
+    ) {
+        lower::collect_source_paths(ctx, path, acc)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -195,6 +229,36 @@ impl GenericArgs {
         lower::lower_generic_args(lower_ctx, node)
     }
 
+    /// Returns the paths in these generic arguments, in the order they appear in the source. See
+    /// `GenericArgs::source_paths` for finding them there.
+    pub fn paths(&self) -> Vec<&Path> {
+        let mut acc = Vec::new();
+        self.collect_paths(&mut acc);
+        acc
+    }
+
+    pub(crate) fn collect_paths<'a>(&'a self, acc: &mut Vec<&'a Path>) {
+        for arg in &self.args {
+            if let GenericArg::Type(type_ref) = arg {
+                type_ref.collect_paths(acc);
+            }
+        }
+        for binding in &self.bindings {
+            if let Some(type_ref) = &binding.type_ref {
+                type_ref.collect_paths(acc);
+            }
+            binding.bounds.iter().for_each(|it| it.collect_paths(acc));
+        }
+    }
+
+    /// Returns the paths `GenericArgs::from_ast` lowers in `node`, in the same order as
+    /// `GenericArgs::paths` returns them.
+    pub fn source_paths(ctx: &LowerCtx<'_>, node: ast::GenericArgList) -> Vec<ast::Path> {
+        let mut acc = Vec::new();
+        lower::collect_generic_args_source_paths(ctx, node, &mut acc);
+        acc
+    }
+
     pub(crate) fn empty() -> GenericArgs {
         GenericArgs {
             args: Vec::new(),
@@ -1,5 +1,7 @@
 //! Transforms syntax into `Path` objects, ideally with accounting for hygiene
 
+use std::iter;
+
 use crate::{intern::Interned, type_ref::ConstScalarOrPath};
 
 use either::Either;
@@ -193,6 +195,89 @@ pub(super) fn lower_generic_args(
     Some(GenericArgs { args, has_self_type: false, bindings, desugared_from_fn: false })
 }
 
+/// Collects `path` and the paths in it `lower_path` lowers, in the order `Path::collect_paths`
+/// visits them.
+pub(super) fn collect_source_paths(ctx: &LowerCtx<'_>, path: ast::Path, acc: &mut Vec<ast::Path>) {
+    if lower_path(path.clone(), ctx).is_none() {
+        return;
+    }
+    acc.push(path.clone());
+    collect_segments_source_paths(ctx, path, None, acc);
+}
+
+/// Collects the paths in the segments of `path`. `self_type` is the `T` of `<T as Trait>`, which
+/// ends up in the generic arguments of the last segment of `Trait`.
+fn collect_segments_source_paths(
+    ctx: &LowerCtx<'_>,
+    path: ast::Path,
+    mut self_type: Option<ast::Type>,
+    acc: &mut Vec<ast::Path>,
+) {
+    let mut segments = iter::successors(Some(path), |it| it.qualifier())
+        .filter_map(|it| it.segment())
+        .collect::<Vec<_>>();
+    segments.reverse();
+    let last = segments.len().saturating_sub(1);
+    for (idx, segment) in segments.into_iter().enumerate() {
+        if idx == last {
+            if let Some(self_type) = self_type.take() {
+                TypeRef::collect_source_paths(ctx, self_type, acc);
+            }
+        }
+        match segment.kind() {
+            Some(ast::PathSegmentKind::Type { type_ref: Some(type_ref), trait_ref: None }) => {
+                TypeRef::collect_source_paths(ctx, type_ref, acc);
+            }
+            Some(ast::PathSegmentKind::Type { type_ref, trait_ref: Some(trait_ref) }) => {
+                if let Some(trait_path) = trait_ref.path() {
+                    collect_segments_source_paths(ctx, trait_path, type_ref, acc);
+                }
+            }
+            Some(ast::PathSegmentKind::Name(_)) => match segment.generic_arg_list() {
+                Some(generic_args) => collect_generic_args_source_paths(ctx, generic_args, acc),
+                None => {
+                    // `Fn(X, Y) -> Z`, the arguments go before the `Output` binding.
+                    let params = segment.param_list().into_iter().flat_map(|it| it.params());
+                    let ret_type = segment.ret_type().and_then(|it| it.ty());
+                    params
+                        .filter_map(|it| it.ty())
+                        .chain(ret_type)
+                        .for_each(|it| TypeRef::collect_source_paths(ctx, it, acc));
+                }
+            },
+            _ => (),
+        }
+    }
+}
+
+/// Collects the paths `lower_generic_args` lowers in `node`, type arguments before associated
+/// type bindings.
+pub(super) fn collect_generic_args_source_paths(
+    ctx: &LowerCtx<'_>,
+    node: ast::GenericArgList,
+    acc: &mut Vec<ast::Path>,
+) {
+    for generic_arg in node.generic_args() {
+        if let ast::GenericArg::TypeArg(type_arg) = generic_arg {
+            if let Some(type_ref) = type_arg.ty() {
+                TypeRef::collect_source_paths(ctx, type_ref, acc);
+            }
+        }
+    }
+    for generic_arg in node.generic_args() {
+        if let ast::GenericArg::AssocTypeArg(assoc_type_arg) = generic_arg {
+            if assoc_type_arg.name_ref().is_none() {
+                continue;
+            }
+            if let Some(type_ref) = assoc_type_arg.ty() {
+                TypeRef::collect_source_paths(ctx, type_ref, acc);
+            }
+            let bounds = assoc_type_arg.type_bound_list().into_iter().flat_map(|it| it.bounds());
+            bounds.for_each(|it| TypeBound::collect_source_paths(ctx, it, acc));
+        }
+    }
+}
+
 /// Collect `GenericArgs` from the parts of a fn-like path, i.e. `Fn(X, Y)
 /// -> Z` (which desugars to `Fn<(X, Y), Output=Z>`).
 fn lower_generic_args_from_fn_path(
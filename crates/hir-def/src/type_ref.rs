@@ -318,6 +318,87 @@ impl TypeRef {
             }
         }
     }
+
+    /// Returns the paths in this type reference in the order they appear in the source. Type
+    /// references don't point back to their source, see `TypeRef::source_paths` for finding the
+    /// paths there.
+    pub fn paths(&self) -> Vec<&Path> {
+        let mut acc = Vec::new();
+        self.collect_paths(&mut acc);
+        acc
+    }
+
+    pub(crate) fn collect_paths<'a>(&'a self, acc: &mut Vec<&'a Path>) {
+        match self {
+            TypeRef::Fn(params, _) => {
+                params.iter().for_each(|(_, param_type)| param_type.collect_paths(acc))
+            }
+            TypeRef::Tuple(types) => types.iter().for_each(|it| it.collect_paths(acc)),
+            TypeRef::RawPtr(type_ref, _)
+            | TypeRef::Reference(type_ref, ..)
+            | TypeRef::Array(type_ref, _)
+            | TypeRef::Slice(type_ref) => type_ref.collect_paths(acc),
+            TypeRef::ImplTrait(bounds) | TypeRef::DynTrait(bounds) => {
+                bounds.iter().for_each(|it| it.collect_paths(acc))
+            }
+            TypeRef::Path(path) => path.collect_paths(acc),
+            TypeRef::Never | TypeRef::Placeholder | TypeRef::Macro(_) | TypeRef::Error => {}
+        }
+    }
+
+    /// Returns the paths `TypeRef::from_ast` lowers in `node`, in the same order as
+    /// `TypeRef::paths` returns them.
+    pub fn source_paths(ctx: &LowerCtx<'_>, node: ast::Type) -> Vec<ast::Path> {
+        let mut acc = Vec::new();
+        TypeRef::collect_source_paths(ctx, node, &mut acc);
+        acc
+    }
+
+    pub(crate) fn collect_source_paths(
+        ctx: &LowerCtx<'_>,
+        node: ast::Type,
+        acc: &mut Vec<ast::Path>,
+    ) {
+        let inner = match node {
+            ast::Type::ParenType(inner) => inner.ty(),
+            ast::Type::ForType(inner) => inner.ty(),
+            ast::Type::PtrType(inner) => inner.ty(),
+            ast::Type::ArrayType(inner) => inner.ty(),
+            ast::Type::SliceType(inner) => inner.ty(),
+            ast::Type::RefType(inner) => inner.ty(),
+            ast::Type::TupleType(inner) => {
+                inner.fields().for_each(|it| TypeRef::collect_source_paths(ctx, it, acc));
+                None
+            }
+            ast::Type::PathType(inner) => {
+                if let Some(path) = inner.path() {
+                    Path::collect_source_paths(ctx, path, acc);
+                }
+                None
+            }
+            ast::Type::FnPtrType(inner) => {
+                let params = inner.param_list().into_iter().flat_map(|it| it.params());
+                params
+                    .filter_map(|it| it.ty())
+                    .for_each(|it| TypeRef::collect_source_paths(ctx, it, acc));
+                inner.ret_type().and_then(|it| it.ty())
+            }
+            ast::Type::ImplTraitType(inner) => {
+                let bounds = inner.type_bound_list().into_iter().flat_map(|it| it.bounds());
+                bounds.for_each(|it| TypeBound::collect_source_paths(ctx, it, acc));
+                None
+            }
+            ast::Type::DynTraitType(inner) => {
+                let bounds = inner.type_bound_list().into_iter().flat_map(|it| it.bounds());
+                bounds.for_each(|it| TypeBound::collect_source_paths(ctx, it, acc));
+                None
+            }
+            ast::Type::NeverType(_) | ast::Type::InferType(_) | ast::Type::MacroType(_) => None,
+        };
+        if let Some(inner) = inner {
+            TypeRef::collect_source_paths(ctx, inner, acc);
+        }
+    }
 }
 
 pub(crate) fn type_bounds_from_ast(
@@ -368,6 +449,30 @@ impl TypeBound {
         }
     }
 
+    pub(crate) fn collect_paths<'a>(&'a self, acc: &mut Vec<&'a Path>) {
+        if let Some((path, _)) = self.as_path() {
+            path.collect_paths(acc);
+        }
+    }
+
+    pub(crate) fn collect_source_paths(
+        ctx: &LowerCtx<'_>,
+        node: ast::TypeBound,
+        acc: &mut Vec<ast::Path>,
+    ) {
+        let path = match node.kind() {
+            ast::TypeBoundKind::PathType(path_type) => path_type.path(),
+            ast::TypeBoundKind::ForType(for_type) => match for_type.ty() {
+                Some(ast::Type::PathType(path_type)) => path_type.path(),
+                _ => None,
+            },
+            ast::TypeBoundKind::Lifetime(_) => None,
+        };
+        if let Some(path) = path {
+            Path::collect_source_paths(ctx, path, acc);
+        }
+    }
+
     pub fn as_path(&self) -> Option<(&Path, &TraitBoundModifier)> {
         match self {
             TypeBound::Path(p, m) => Some((p, m)),
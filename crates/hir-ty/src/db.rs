@@ -7,7 +7,8 @@ use arrayvec::ArrayVec;
 use base_db::{impl_intern_key, salsa, CrateId, Upcast};
 use hir_def::{
     db::DefDatabase, expr::ExprId, BlockId, ConstId, ConstParamId, DefWithBodyId, FunctionId,
-    GenericDefId, ImplId, LifetimeParamId, LocalFieldId, TypeOrConstParamId, VariantId,
+    GenericDefId, ImplId, LifetimeParamId, LocalFieldId, TypeAliasId, TypeOrConstParamId,
+    VariantId,
 };
 use la_arena::ArenaMap;
 
@@ -16,7 +17,8 @@ use crate::{
     consteval::{ComputedExpr, ConstEvalError},
     method_resolution::{InherentImpls, TraitImpls, TyFingerprint},
     Binders, CallableDefId, FnDefId, GenericArg, ImplTraitId, InferenceResult, Interner, PolyFnSig,
-    QuantifiedWhereClause, ReturnTypeImplTraits, TraitRef, Ty, TyDefId, ValueTyDefId,
+    QuantifiedWhereClause, ReturnTypeImplTraits, TraitRef, Ty, TyDefId, TyLoweringDiagnostics,
+    ValueTyDefId,
 };
 use hir_expand::name::Name;
 
@@ -36,10 +38,21 @@ pub trait HirDatabase: DefDatabase + Upcast<dyn DefDatabase> {
     #[salsa::invoke(crate::lower::value_ty_query)]
     fn value_ty(&self, def: ValueTyDefId) -> Binders<Ty>;
 
+    #[salsa::invoke(crate::lower::type_alias_ty_with_diagnostics_query)]
+    #[salsa::cycle(crate::lower::type_alias_ty_with_diagnostics_recover)]
+    fn type_alias_ty_with_diagnostics(
+        &self,
+        def: TypeAliasId,
+    ) -> (Binders<Ty>, TyLoweringDiagnostics);
+
     #[salsa::invoke(crate::lower::impl_self_ty_query)]
     #[salsa::cycle(crate::lower::impl_self_ty_recover)]
     fn impl_self_ty(&self, def: ImplId) -> Binders<Ty>;
 
+    #[salsa::invoke(crate::lower::impl_self_ty_with_diagnostics_query)]
+    #[salsa::cycle(crate::lower::impl_self_ty_with_diagnostics_recover)]
+    fn impl_self_ty_with_diagnostics(&self, def: ImplId) -> (Binders<Ty>, TyLoweringDiagnostics);
+
     #[salsa::invoke(crate::lower::const_param_ty_query)]
     fn const_param_ty(&self, def: ConstParamId) -> Ty;
 
@@ -50,12 +63,27 @@ pub trait HirDatabase: DefDatabase + Upcast<dyn DefDatabase> {
     #[salsa::invoke(crate::lower::impl_trait_query)]
     fn impl_trait(&self, def: ImplId) -> Option<Binders<TraitRef>>;
 
+    #[salsa::invoke(crate::lower::impl_trait_with_diagnostics_query)]
+    fn impl_trait_with_diagnostics(
+        &self,
+        def: ImplId,
+    ) -> (Option<Binders<TraitRef>>, TyLoweringDiagnostics);
+
     #[salsa::invoke(crate::lower::field_types_query)]
     fn field_types(&self, var: VariantId) -> Arc<ArenaMap<LocalFieldId, Binders<Ty>>>;
 
+    #[salsa::invoke(crate::lower::field_types_with_diagnostics_query)]
+    fn field_types_with_diagnostics(
+        &self,
+        var: VariantId,
+    ) -> (Arc<ArenaMap<LocalFieldId, Binders<Ty>>>, TyLoweringDiagnostics);
+
     #[salsa::invoke(crate::lower::callable_item_sig)]
     fn callable_item_signature(&self, def: CallableDefId) -> PolyFnSig;
 
+    #[salsa::invoke(crate::lower::fn_sig_with_diagnostics_query)]
+    fn fn_sig_with_diagnostics(&self, def: FunctionId) -> (PolyFnSig, TyLoweringDiagnostics);
+
     #[salsa::invoke(crate::lower::return_type_impl_traits)]
     fn return_type_impl_traits(
         &self,
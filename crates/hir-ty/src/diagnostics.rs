@@ -7,7 +7,8 @@ mod decl_check;
 pub use crate::diagnostics::{
     decl_check::{incorrect_case, IncorrectCase},
     expr::{
-        record_literal_missing_fields, record_pattern_missing_fields, BodyValidationDiagnostic,
+        missing_match_patterns, record_literal_missing_fields, record_pattern_missing_fields,
+        BodyValidationDiagnostic, MissingPat,
     },
     unsafe_check::{missing_unsafe, unsafe_expressions, UnsafeExpr},
 };
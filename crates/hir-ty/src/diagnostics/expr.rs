@@ -20,7 +20,7 @@ use crate::{
         usefulness::{compute_match_usefulness, MatchCheckCtx},
     },
     display::HirDisplay,
-    InferenceResult, Ty, TyExt,
+    InferenceResult, Interner, Ty, TyExt, TyKind,
 };
 
 pub(crate) use hir_def::{
@@ -166,48 +166,17 @@ impl ExprValidator {
             pattern_arena: &pattern_arena,
         };
 
-        let mut m_arms = Vec::with_capacity(arms.len());
-        let mut has_lowering_errors = false;
-        for arm in arms {
-            if let Some(pat_ty) = infer.type_of_pat.get(arm.pat) {
-                // We only include patterns whose type matches the type
-                // of the match expression. If we had an InvalidMatchArmPattern
-                // diagnostic or similar we could raise that in an else
-                // block here.
-                //
-                // When comparing the types, we also have to consider that rustc
-                // will automatically de-reference the match expression type if
-                // necessary.
-                //
-                // FIXME we should use the type checker for this.
-                if (pat_ty == match_expr_ty
-                    || match_expr_ty
-                        .as_reference()
-                        .map(|(match_expr_ty, ..)| match_expr_ty == pat_ty)
-                        .unwrap_or(false))
-                    && types_of_subpatterns_do_match(arm.pat, &body, &infer)
-                {
-                    // If we had a NotUsefulMatchArm diagnostic, we could
-                    // check the usefulness of each pattern as we added it
-                    // to the matrix here.
-                    let m_arm = match_check::MatchArm {
-                        pat: self.lower_pattern(&cx, arm.pat, db, &body, &mut has_lowering_errors),
-                        has_guard: arm.guard.is_some(),
-                    };
-                    m_arms.push(m_arm);
-                    if !has_lowering_errors {
-                        continue;
-                    }
-                }
+        let m_arms = match lower_match_arms(&cx, db, &body, &infer, match_expr_ty, arms) {
+            Some(it) => it,
+            None => {
+                // If we can't resolve the type of a pattern, or the pattern type doesn't
+                // fit the match expression, we skip this diagnostic. Skipping the entire
+                // diagnostic rather than just not including this match arm is preferred
+                // to avoid the chance of false positives.
+                cov_mark::hit!(validate_match_bailed_out);
+                return;
             }
-
-            // If we can't resolve the type of a pattern, or the pattern type doesn't
-            // fit the match expression, we skip this diagnostic. Skipping the entire
-            // diagnostic rather than just not including this match arm is preferred
-            // to avoid the chance of false positives.
-            cov_mark::hit!(validate_match_bailed_out);
-            return;
-        }
+        };
 
         let report = compute_match_usefulness(&cx, &m_arms, match_expr_ty);
 
@@ -222,23 +191,6 @@ impl ExprValidator {
             });
         }
     }
-
-    fn lower_pattern<'p>(
-        &self,
-        cx: &MatchCheckCtx<'_, 'p>,
-        pat: PatId,
-        db: &dyn HirDatabase,
-        body: &Body,
-        have_errors: &mut bool,
-    ) -> &'p DeconstructedPat<'p> {
-        let mut patcx = match_check::PatCtxt::new(db, &self.infer, body);
-        let pattern = patcx.lower_pattern(pat);
-        let pattern = cx.pattern_arena.alloc(DeconstructedPat::from_pat(cx, &pattern));
-        if !patcx.errors.is_empty() {
-            *have_errors = true;
-        }
-        pattern
-    }
 }
 
 struct FilterMapNextChecker {
@@ -361,6 +313,158 @@ pub fn record_pattern_missing_fields(
     Some((variant_def, missed_fields, exhaustive))
 }
 
+const MAX_MISSING_PATTERN_ROUNDS: usize = 8;
+
+/// A pattern that is not covered by the arms of a `match`, see [`missing_match_patterns`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MissingPat {
+    Wild,
+    Bool(bool),
+    /// A struct, union or enum variant, with the patterns of all its fields.
+    Variant(VariantId, Vec<MissingPat>),
+    Tuple(Vec<MissingPat>),
+    Ref(Box<MissingPat>),
+}
+
+/// Computes the patterns that are not covered by the arms of the `match`
+/// expression `match_expr`, skipping the arm with the pattern `skipped_arm`.
+///
+/// Returns `None` if the arms can't be checked, e.g. because of type mismatches.
+pub fn missing_match_patterns(
+    db: &dyn HirDatabase,
+    owner: DefWithBodyId,
+    infer: &InferenceResult,
+    body: &Body,
+    match_expr: ExprId,
+    skipped_arm: Option<PatId>,
+) -> Option<Vec<MissingPat>> {
+    let (scrutinee, arms) = match &body[match_expr] {
+        Expr::Match { expr, arms } => (*expr, arms),
+        _ => return None,
+    };
+    let scrutinee_ty = &infer[scrutinee];
+    if scrutinee_ty.is_unknown() {
+        return None;
+    }
+
+    let pattern_arena = Arena::new();
+    let cx = MatchCheckCtx {
+        module: owner.module(db.upcast()),
+        body: owner,
+        db,
+        pattern_arena: &pattern_arena,
+    };
+    let arms: Vec<_> = arms.iter().filter(|arm| Some(arm.pat) != skipped_arm).cloned().collect();
+    let mut m_arms = lower_match_arms(&cx, db, body, infer, scrutinee_ty, &arms)?;
+
+    // The usefulness check only reports the constructors missing at the first
+    // level that has any, so add the witnesses as arms and look for more.
+    let mut res = Vec::new();
+    for _ in 0..MAX_MISSING_PATTERN_ROUNDS {
+        let report = compute_match_usefulness(&cx, &m_arms, scrutinee_ty);
+        if report.non_exhaustiveness_witnesses.is_empty() {
+            break;
+        }
+        for witness in report.non_exhaustiveness_witnesses {
+            res.push(missing_pat(db, &witness.to_pat(&cx)));
+            let pat = cx.pattern_arena.alloc(witness);
+            m_arms.push(match_check::MatchArm { pat, has_guard: false });
+        }
+    }
+    Some(res)
+}
+
+fn missing_pat(db: &dyn HirDatabase, pat: &match_check::Pat) -> MissingPat {
+    use match_check::PatKind;
+
+    let fields = |variant: VariantId, subpatterns: &[match_check::FieldPat]| {
+        variant
+            .variant_data(db.upcast())
+            .fields()
+            .iter()
+            .map(|(field, _)| match subpatterns.iter().find(|it| it.field == field) {
+                Some(it) => missing_pat(db, &it.pattern),
+                None => MissingPat::Wild,
+            })
+            .collect()
+    };
+    match &*pat.kind {
+        PatKind::Wild | PatKind::Binding { .. } | PatKind::Or { .. } => MissingPat::Wild,
+        PatKind::LiteralBool { value } => MissingPat::Bool(*value),
+        PatKind::Variant { enum_variant, subpatterns, .. } => {
+            let variant = VariantId::from(*enum_variant);
+            MissingPat::Variant(variant, fields(variant, subpatterns))
+        }
+        PatKind::Leaf { subpatterns } => match pat.ty.as_adt() {
+            Some((AdtId::StructId(it), _)) => {
+                MissingPat::Variant(it.into(), fields(it.into(), subpatterns))
+            }
+            Some((AdtId::UnionId(it), _)) => {
+                MissingPat::Variant(it.into(), fields(it.into(), subpatterns))
+            }
+            Some((AdtId::EnumId(_), _)) => MissingPat::Wild,
+            None => {
+                let mut subpatterns =
+                    subpatterns.iter().map(|it| missing_pat(db, &it.pattern)).collect::<Vec<_>>();
+                if let TyKind::Tuple(len, _) = pat.ty.kind(Interner) {
+                    subpatterns.resize(*len, MissingPat::Wild);
+                }
+                MissingPat::Tuple(subpatterns)
+            }
+        },
+        PatKind::Deref { subpattern } => match pat.ty.kind(Interner) {
+            TyKind::Ref(..) => MissingPat::Ref(Box::new(missing_pat(db, subpattern))),
+            _ => MissingPat::Wild,
+        },
+    }
+}
+
+/// Lowers the arms of a `match` for the usefulness check, returning `None` if
+/// any of them doesn't fit the type of the scrutinee.
+fn lower_match_arms<'p>(
+    cx: &MatchCheckCtx<'_, 'p>,
+    db: &dyn HirDatabase,
+    body: &Body,
+    infer: &InferenceResult,
+    match_expr_ty: &Ty,
+    arms: &[MatchArm],
+) -> Option<Vec<match_check::MatchArm<'p>>> {
+    let mut m_arms = Vec::with_capacity(arms.len());
+    for arm in arms {
+        let pat_ty = infer.type_of_pat.get(arm.pat)?;
+        // We only include patterns whose type matches the type
+        // of the match expression. If we had an InvalidMatchArmPattern
+        // diagnostic or similar we could raise that in an else
+        // block here.
+        //
+        // When comparing the types, we also have to consider that rustc
+        // will automatically de-reference the match expression type if
+        // necessary.
+        //
+        // FIXME we should use the type checker for this.
+        if !(pat_ty == match_expr_ty
+            || match_expr_ty
+                .as_reference()
+                .map(|(match_expr_ty, ..)| match_expr_ty == pat_ty)
+                .unwrap_or(false))
+            || !types_of_subpatterns_do_match(arm.pat, body, infer)
+        {
+            return None;
+        }
+        // If we had a NotUsefulMatchArm diagnostic, we could
+        // check the usefulness of each pattern as we added it
+        // to the matrix here.
+        let mut patcx = match_check::PatCtxt::new(db, infer, body);
+        let pattern = patcx.lower_pattern(arm.pat);
+        if !patcx.errors.is_empty() {
+            return None;
+        }
+        let pat = cx.pattern_arena.alloc(DeconstructedPat::from_pat(cx, &pattern));
+        m_arms.push(match_check::MatchArm { pat, has_guard: arm.guard.is_some() });
+    }
+    Some(m_arms)
+}
+
 fn types_of_subpatterns_do_match(pat: PatId, body: &Body, infer: &InferenceResult) -> bool {
     fn walk(pat: PatId, body: &Body, infer: &InferenceResult, has_type_mismatches: &mut bool) {
         match infer.type_mismatch_for_pat(pat) {
@@ -35,10 +35,13 @@ use rustc_hash::FxHashMap;
 use stdx::{always, impl_from};
 
 use crate::{
-    db::HirDatabase, fold_tys, fold_tys_and_consts, infer::coerce::CoerceMany,
-    lower::ImplTraitLoweringMode, to_assoc_type_id, AliasEq, AliasTy, Const, DomainGoal,
-    GenericArg, Goal, ImplTraitId, InEnvironment, Interner, ProjectionTy, Substitution,
-    TraitEnvironment, TraitRef, Ty, TyBuilder, TyExt, TyKind,
+    db::HirDatabase,
+    fold_tys, fold_tys_and_consts,
+    infer::{cast::CastCheck, coerce::CoerceMany},
+    lower::ImplTraitLoweringMode,
+    to_assoc_type_id, AliasEq, AliasTy, Const, DomainGoal, GenericArg, Goal, ImplTraitId,
+    InEnvironment, Interner, ProjectionTy, Substitution, TraitEnvironment, TraitRef, Ty, TyBuilder,
+    TyExt, TyKind, TyLoweringDiagnostic, TypeSource, ValueTyDefId,
 };
 
 // This lint has a false positive here. See the link below for details.
@@ -49,12 +52,15 @@ pub use coerce::could_coerce;
 #[allow(unreachable_pub)]
 pub use unify::could_unify;
 
+pub use cast::CastError;
+
 pub(crate) mod unify;
 mod path;
 mod expr;
 mod pat;
 mod coerce;
 mod closure;
+mod cast;
 
 /// The entry point of type inference.
 pub(crate) fn infer_query(db: &dyn HirDatabase, def: DefWithBodyId) -> Arc<InferenceResult> {
@@ -70,6 +76,7 @@ pub(crate) fn infer_query(db: &dyn HirDatabase, def: DefWithBodyId) -> Arc<Infer
     }
 
     ctx.infer_body();
+    ctx.check_casts();
 
     Arc::new(ctx.resolve_all())
 }
@@ -101,6 +108,16 @@ enum ExprOrPatId {
 }
 impl_from!(ExprId, PatId for ExprOrPatId);
 
+impl ExprOrPatId {
+    /// Where the type references in the path of this expression or pattern come from.
+    fn path_source(self) -> TypeSource {
+        match self {
+            ExprOrPatId::ExprId(it) => TypeSource::ExprPath(it),
+            ExprOrPatId::PatId(it) => TypeSource::PatPath(it),
+        }
+    }
+}
+
 /// Binding modes inferred for patterns.
 /// <https://doc.rust-lang.org/reference/patterns.html#binding-modes>
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
@@ -181,9 +198,35 @@ pub(crate) type InferResult<T> = Result<InferOk<T>, TypeError>;
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum InferenceDiagnostic {
-    NoSuchField { expr: ExprId },
-    BreakOutsideOfLoop { expr: ExprId },
-    MismatchedArgCount { call_expr: ExprId, expected: usize, found: usize },
+    NoSuchField {
+        expr: ExprId,
+    },
+    BreakOutsideOfLoop {
+        expr: ExprId,
+    },
+    MismatchedArgCount {
+        call_expr: ExprId,
+        expected: usize,
+        found: usize,
+    },
+    /// A problem with a path in a type reference or a value path of the body.
+    TyLowering(TyLoweringDiagnostic),
+    InvalidCast {
+        expr: ExprId,
+        error: CastError,
+        expr_ty: Ty,
+        cast_ty: Ty,
+    },
+    CastToUnsized {
+        expr: ExprId,
+        expr_ty: Ty,
+        cast_ty: Ty,
+    },
+    /// A cast to the type the expression already has.
+    TrivialCast {
+        expr: ExprId,
+        ty: Ty,
+    },
 }
 
 /// A mismatch between an expected and an inferred type.
@@ -414,6 +457,8 @@ pub(crate) struct InferenceContext<'a> {
     return_ty: Ty,
     diverges: Diverges,
     breakables: Vec<BreakableContext>,
+    /// Casts are checked once the body is inferred, as their types often aren't known before.
+    deferred_cast_checks: Vec<CastCheck>,
 }
 
 #[derive(Clone, Debug)]
@@ -455,6 +500,7 @@ impl<'a> InferenceContext<'a> {
             resolver,
             diverges: Diverges::Maybe,
             breakables: Vec::new(),
+            deferred_cast_checks: Vec::new(),
         }
     }
 
@@ -489,11 +535,11 @@ impl<'a> InferenceContext<'a> {
     }
 
     fn collect_const(&mut self, data: &ConstData) {
-        self.return_ty = self.make_ty(&data.type_ref);
+        self.return_ty = self.make_ty(&data.type_ref, TypeSource::Item);
     }
 
     fn collect_static(&mut self, data: &StaticData) {
-        self.return_ty = self.make_ty(&data.type_ref);
+        self.return_ty = self.make_ty(&data.type_ref, TypeSource::Item);
     }
 
     fn collect_fn(&mut self, func: FunctionId) {
@@ -514,8 +560,13 @@ impl<'a> InferenceContext<'a> {
         } else {
             &*data.ret_type
         };
-        let return_ty = self.make_ty_with_mode(return_ty, ImplTraitLoweringMode::Opaque);
-        self.return_ty = return_ty;
+        // Problems with the signature are reported by `fn_sig_with_diagnostics`, so this doesn't
+        // go through `make_ty_with_mode`.
+        let ctx = crate::lower::TyLoweringContext::new(self.db, &self.resolver)
+            .with_impl_trait_mode(ImplTraitLoweringMode::Opaque);
+        let return_ty = ctx.lower_ty(return_ty);
+        let return_ty = self.insert_type_vars(return_ty);
+        self.return_ty = self.normalize_associated_types_in(return_ty);
 
         if let Some(rpits) = self.db.return_type_impl_traits(func) {
             // RPIT opaque types use substitution of their parent function.
@@ -556,6 +607,12 @@ impl<'a> InferenceContext<'a> {
         self.infer_expr_coerce(self.body.body_expr, &Expectation::has_type(self.return_ty.clone()));
     }
 
+    fn check_casts(&mut self) {
+        for check in std::mem::take(&mut self.deferred_cast_checks) {
+            check.check(self);
+        }
+    }
+
     fn write_expr_ty(&mut self, expr: ExprId, ty: Ty) {
         self.result.type_of_expr.insert(expr, ty);
     }
@@ -584,21 +641,36 @@ impl<'a> InferenceContext<'a> {
         self.result.diagnostics.push(diagnostic);
     }
 
+    fn push_ty_lowering_diagnostics(&mut self, diagnostics: Vec<TyLoweringDiagnostic>) {
+        self.result
+            .diagnostics
+            .extend(diagnostics.into_iter().map(InferenceDiagnostic::TyLowering));
+    }
+
+    /// Lowers `type_ref`, which is part of what `source` points to, whose paths are `paths`.
     fn make_ty_with_mode(
         &mut self,
         type_ref: &TypeRef,
         impl_trait_mode: ImplTraitLoweringMode,
+        source: TypeSource,
+        paths: Vec<&Path>,
     ) -> Ty {
         // FIXME use right resolver for block
         let ctx = crate::lower::TyLoweringContext::new(self.db, &self.resolver)
             .with_impl_trait_mode(impl_trait_mode);
-        let ty = ctx.lower_ty(type_ref);
+        let ty = ctx.with_source(source, paths, || ctx.lower_ty(type_ref));
+        self.push_ty_lowering_diagnostics(ctx.into_diagnostics());
         let ty = self.insert_type_vars(ty);
         self.normalize_associated_types_in(ty)
     }
 
-    fn make_ty(&mut self, type_ref: &TypeRef) -> Ty {
-        self.make_ty_with_mode(type_ref, ImplTraitLoweringMode::Disallowed)
+    fn make_ty(&mut self, type_ref: &TypeRef, source: TypeSource) -> Ty {
+        self.make_ty_with_mode(
+            type_ref,
+            ImplTraitLoweringMode::Disallowed,
+            source,
+            type_ref.paths(),
+        )
     }
 
     fn err_ty(&self) -> Ty {
@@ -710,26 +782,30 @@ impl<'a> InferenceContext<'a> {
         }
     }
 
-    fn resolve_variant(&mut self, path: Option<&Path>, value_ns: bool) -> (Ty, Option<VariantId>) {
+    fn resolve_variant(
+        &mut self,
+        id: ExprOrPatId,
+        path: Option<&Path>,
+        value_ns: bool,
+    ) -> (Ty, Option<VariantId>) {
         let path = match path {
             Some(path) => path,
             None => return (self.err_ty(), None),
         };
         let resolver = &self.resolver;
-        let ctx = crate::lower::TyLoweringContext::new(self.db, &self.resolver);
         // FIXME: this should resolve assoc items as well, see this example:
         // https://play.rust-lang.org/?gist=087992e9e22495446c01c0d4e2d69521
         let (resolution, unresolved) = if value_ns {
             match resolver.resolve_path_in_value_ns(self.db.upcast(), path.mod_path()) {
                 Some(ResolveValueResult::ValueNs(value)) => match value {
                     ValueNs::EnumVariantId(var) => {
-                        let substs = ctx.substs_from_path(path, var.into(), true);
+                        let substs = self.substs_from_variant_path(id, path, var.into());
                         let ty = self.db.ty(var.parent.into());
                         let ty = self.insert_type_vars(ty.substitute(Interner, &substs));
                         return (ty, Some(var.into()));
                     }
                     ValueNs::StructId(strukt) => {
-                        let substs = ctx.substs_from_path(path, strukt.into(), true);
+                        let substs = self.substs_from_variant_path(id, path, strukt.into());
                         let ty = self.db.ty(strukt.into());
                         let ty = self.insert_type_vars(ty.substitute(Interner, &substs));
                         return (ty, Some(strukt.into()));
@@ -748,19 +824,19 @@ impl<'a> InferenceContext<'a> {
         };
         return match resolution {
             TypeNs::AdtId(AdtId::StructId(strukt)) => {
-                let substs = ctx.substs_from_path(path, strukt.into(), true);
+                let substs = self.substs_from_variant_path(id, path, strukt.into());
                 let ty = self.db.ty(strukt.into());
                 let ty = self.insert_type_vars(ty.substitute(Interner, &substs));
                 forbid_unresolved_segments((ty, Some(strukt.into())), unresolved)
             }
             TypeNs::AdtId(AdtId::UnionId(u)) => {
-                let substs = ctx.substs_from_path(path, u.into(), true);
+                let substs = self.substs_from_variant_path(id, path, u.into());
                 let ty = self.db.ty(u.into());
                 let ty = self.insert_type_vars(ty.substitute(Interner, &substs));
                 forbid_unresolved_segments((ty, Some(u.into())), unresolved)
             }
             TypeNs::EnumVariantId(var) => {
-                let substs = ctx.substs_from_path(path, var.into(), true);
+                let substs = self.substs_from_variant_path(id, path, var.into());
                 let ty = self.db.ty(var.parent.into());
                 let ty = self.insert_type_vars(ty.substitute(Interner, &substs));
                 forbid_unresolved_segments((ty, Some(var.into())), unresolved)
@@ -804,6 +880,20 @@ impl<'a> InferenceContext<'a> {
         }
     }
 
+    fn substs_from_variant_path(
+        &mut self,
+        id: ExprOrPatId,
+        path: &Path,
+        resolved: ValueTyDefId,
+    ) -> Substitution {
+        let ctx = crate::lower::TyLoweringContext::new(self.db, &self.resolver);
+        let substs = ctx.with_source(id.path_source(), path.paths(), || {
+            ctx.substs_from_path(path, resolved, true)
+        });
+        self.push_ty_lowering_diagnostics(ctx.into_diagnostics());
+        substs
+    }
+
     fn resolve_variant_on_alias(
         &mut self,
         ty: Ty,
@@ -0,0 +1,348 @@
+//! Type checking of `as` casts.
+//!
+//! See <https://doc.rust-lang.org/reference/expressions/operator-expr.html#type-cast-expressions>
+//! and `rustc_hir_typeck/src/cast.rs`, which this follows closely.
+
+use chalk_ir::{cast::Cast, Mutability, Scalar, TyVariableKind, TypeFlags, UintTy};
+use hir_def::{expr::ExprId, AdtId, TraitId};
+use syntax::SmolStr;
+
+use crate::{
+    infer::{InferenceContext, InferenceDiagnostic},
+    Interner, Ty, TyBuilder, TyExt, TyKind,
+};
+
+/// The reason a cast is not allowed, mirroring the errors rustc reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CastError {
+    /// Casting anything but a `bool` to `bool`.
+    CastToBool,
+    /// Casting anything but a `u8` to `char`.
+    CastToChar,
+    /// Casting between fat pointers with different metadata, like `*const [T]` as `*const dyn Tr`.
+    DifferingKinds,
+    /// Casting a thin pointer to a fat one.
+    SizedUnsizedCast,
+    IllegalCast,
+    /// Casting an integer to a fat pointer.
+    IntToFatCast,
+    /// Casting a reference to a number to a number, which only works after a dereference.
+    NeedDeref,
+    /// Casting a reference to an integer, which only works through a raw pointer.
+    NeedViaPtr,
+    /// Casting a fat pointer to an integer, which only works through a thin pointer.
+    NeedViaThinPtr,
+    /// Casting between types that aren't primitives, like structs or references.
+    NonScalar,
+}
+
+/// A cast whose check is deferred to the end of inference, when the types involved are known.
+#[derive(Clone, Debug)]
+pub(super) struct CastCheck {
+    /// The `expr as Ty` expression.
+    expr: ExprId,
+    expr_ty: Ty,
+    cast_ty: Ty,
+}
+
+/// The kinds of types that can take part in a cast that isn't a coercion.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum CastTy {
+    Int(IntTy),
+    Float,
+    FnPtr,
+    Ptr(Ty, Mutability),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum IntTy {
+    U(UintTy),
+    /// Any signed integer, or an integer whose type isn't inferred yet.
+    I,
+    /// A C-like enum, i.e. one without any fields.
+    CEnum,
+    Bool,
+    Char,
+}
+
+/// The metadata of a pointer to a type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PointerKind {
+    Thin,
+    /// A trait object, the trait is the principal one.
+    VTable(Option<TraitId>),
+    /// A slice or `str`.
+    Length,
+    /// A type parameter that might not be sized.
+    OfParam(Ty),
+}
+
+impl CastCheck {
+    pub(super) fn new(expr: ExprId, expr_ty: Ty, cast_ty: Ty) -> Self {
+        CastCheck { expr, expr_ty, cast_ty }
+    }
+
+    pub(super) fn check(self, ctx: &mut InferenceContext<'_>) {
+        let expr_ty = ctx.table.resolve_ty_shallow(&self.expr_ty);
+        let cast_ty = ctx.table.resolve_ty_shallow(&self.cast_ty);
+        let is_unclear = |ty: &Ty| {
+            ty.data(Interner).flags.contains(TypeFlags::HAS_ERROR)
+                || matches!(
+                    ty.kind(Interner),
+                    TyKind::InferenceVar(_, TyVariableKind::General)
+                        | TyKind::Alias(_)
+                        | TyKind::AssociatedType(..)
+                        | TyKind::OpaqueType(..)
+                )
+        };
+        // Projections we fail to normalize would otherwise be reported as non-primitive.
+        if is_unclear(&expr_ty) || is_unclear(&cast_ty) {
+            return;
+        }
+
+        if matches!(cast_ty.kind(Interner), TyKind::Str | TyKind::Slice(_) | TyKind::Dyn(_)) {
+            let expr_ty = ctx.table.resolve_completely(expr_ty);
+            let cast_ty = ctx.table.resolve_completely(cast_ty);
+            ctx.push_diagnostic(InferenceDiagnostic::CastToUnsized {
+                expr: self.expr,
+                expr_ty,
+                cast_ty,
+            });
+            return;
+        }
+
+        // Literals whose type isn't inferred yet aren't trivially cast, rustc would infer them from
+        // the cast instead.
+        if expr_ty == cast_ty && !matches!(expr_ty.kind(Interner), TyKind::InferenceVar(..)) {
+            let ty = ctx.table.resolve_completely(expr_ty);
+            ctx.push_diagnostic(InferenceDiagnostic::TrivialCast { expr: self.expr, ty });
+            return;
+        }
+
+        // Like rustc, casts don't guide inference, so that e.g. an integer literal cast to `i64`
+        // still falls back to `i32`.
+        if ctx.table.run_in_snapshot(|table| table.coerce(&expr_ty, &cast_ty).is_ok()) {
+            return;
+        }
+
+        if let Err(error) = self.do_check(ctx, &expr_ty, &cast_ty) {
+            let expr_ty = ctx.table.resolve_completely(expr_ty);
+            let cast_ty = ctx.table.resolve_completely(cast_ty);
+            ctx.push_diagnostic(InferenceDiagnostic::InvalidCast {
+                expr: self.expr,
+                error,
+                expr_ty,
+                cast_ty,
+            });
+        }
+    }
+
+    fn do_check(
+        &self,
+        ctx: &mut InferenceContext<'_>,
+        expr_ty: &Ty,
+        cast_ty: &Ty,
+    ) -> Result<(), CastError> {
+        let t_from = match (CastTy::from_ty(ctx, expr_ty), CastTy::from_ty(ctx, cast_ty)) {
+            (Some(t_from), Some(_)) => t_from,
+            (None, Some(t_cast)) => match expr_ty.kind(Interner) {
+                // Function items are cast through a function pointer, this coercion always works.
+                TyKind::FnDef(..) => CastTy::FnPtr,
+                TyKind::Ref(mutbl, _, inner) => {
+                    let inner = ctx.table.resolve_ty_shallow(inner);
+                    return match t_cast {
+                        CastTy::Int(_) | CastTy::Float => match inner.kind(Interner) {
+                            TyKind::Scalar(Scalar::Int(_) | Scalar::Uint(_) | Scalar::Float(_))
+                            | TyKind::InferenceVar(
+                                _,
+                                TyVariableKind::Integer | TyVariableKind::Float,
+                            ) => Err(CastError::NeedDeref),
+                            _ => Err(CastError::NeedViaPtr),
+                        },
+                        CastTy::Ptr(cast_inner, m_cast) => {
+                            self.check_ref_cast(ctx, &inner, *mutbl, &cast_inner, m_cast)
+                        }
+                        _ => Err(CastError::NonScalar),
+                    };
+                }
+                _ => return Err(CastError::NonScalar),
+            },
+            _ => return Err(CastError::NonScalar),
+        };
+        let t_cast = CastTy::from_ty(ctx, cast_ty).expect("checked above");
+
+        match (t_from, t_cast) {
+            (_, CastTy::Int(IntTy::CEnum) | CastTy::FnPtr) => Err(CastError::NonScalar),
+            (_, CastTy::Int(IntTy::Bool)) => Err(CastError::CastToBool),
+            (CastTy::Int(IntTy::U(UintTy::U8)), CastTy::Int(IntTy::Char)) => Ok(()),
+            // rustc infers an integer literal cast to `char` to be a `u8`.
+            (CastTy::Int(IntTy::I), CastTy::Int(IntTy::Char))
+                if matches!(
+                    expr_ty.kind(Interner),
+                    TyKind::InferenceVar(_, TyVariableKind::Integer)
+                ) =>
+            {
+                Ok(())
+            }
+            (_, CastTy::Int(IntTy::Char)) => Err(CastError::CastToChar),
+            (CastTy::Int(IntTy::Bool | IntTy::CEnum | IntTy::Char), CastTy::Float) => {
+                Err(CastError::IllegalCast)
+            }
+            (
+                CastTy::Int(IntTy::Bool | IntTy::CEnum | IntTy::Char) | CastTy::Float,
+                CastTy::Ptr(..),
+            )
+            | (CastTy::Ptr(..) | CastTy::FnPtr, CastTy::Float) => Err(CastError::IllegalCast),
+            (CastTy::Ptr(src, _), CastTy::Ptr(dst, _)) => self.check_ptr_ptr_cast(ctx, &src, &dst),
+            (CastTy::Ptr(src, _), CastTy::Int(_)) => self.check_ptr_addr_cast(ctx, &src),
+            (CastTy::Int(_), CastTy::Ptr(dst, _)) => self.check_addr_ptr_cast(ctx, &dst),
+            (CastTy::FnPtr, CastTy::Ptr(dst, _)) => self.check_fptr_ptr_cast(ctx, &dst),
+            (CastTy::FnPtr, CastTy::Int(_))
+            | (CastTy::Int(_), CastTy::Int(_))
+            | (CastTy::Int(_) | CastTy::Float, CastTy::Float)
+            | (CastTy::Float, CastTy::Int(_)) => Ok(()),
+        }
+    }
+
+    /// Checks a cast like `&[T; N] as *const T`, which isn't a coercion.
+    fn check_ref_cast(
+        &self,
+        ctx: &mut InferenceContext<'_>,
+        expr_inner: &Ty,
+        m_expr: Mutability,
+        cast_inner: &Ty,
+        m_cast: Mutability,
+    ) -> Result<(), CastError> {
+        let mutbl_allowed = matches!((m_expr, m_cast), (Mutability::Mut, _) | (_, Mutability::Not));
+        if let TyKind::Array(elem, _) = expr_inner.kind(Interner) {
+            if mutbl_allowed && ctx.table.run_in_snapshot(|table| table.unify(elem, cast_inner)) {
+                return Ok(());
+            }
+        }
+        Err(CastError::IllegalCast)
+    }
+
+    fn check_ptr_ptr_cast(
+        &self,
+        ctx: &mut InferenceContext<'_>,
+        src: &Ty,
+        dst: &Ty,
+    ) -> Result<(), CastError> {
+        let (src_kind, dst_kind) = match (pointer_kind(ctx, src), pointer_kind(ctx, dst)) {
+            (Some(src_kind), Some(dst_kind)) => (src_kind, dst_kind),
+            // FIXME: rustc reports the pointer kind as unknown here.
+            _ => return Ok(()),
+        };
+        if dst_kind == PointerKind::Thin {
+            // Casting to a thin pointer drops the metadata, if any.
+            return Ok(());
+        }
+        if src_kind == PointerKind::Thin {
+            return Err(CastError::SizedUnsizedCast);
+        }
+        if src_kind == dst_kind {
+            Ok(())
+        } else {
+            Err(CastError::DifferingKinds)
+        }
+    }
+
+    fn check_ptr_addr_cast(
+        &self,
+        ctx: &mut InferenceContext<'_>,
+        src: &Ty,
+    ) -> Result<(), CastError> {
+        match pointer_kind(ctx, src) {
+            Some(PointerKind::Thin) | None => Ok(()),
+            Some(_) => Err(CastError::NeedViaThinPtr),
+        }
+    }
+
+    fn check_addr_ptr_cast(
+        &self,
+        ctx: &mut InferenceContext<'_>,
+        dst: &Ty,
+    ) -> Result<(), CastError> {
+        match pointer_kind(ctx, dst) {
+            Some(PointerKind::Thin) | None => Ok(()),
+            Some(_) => Err(CastError::IntToFatCast),
+        }
+    }
+
+    fn check_fptr_ptr_cast(
+        &self,
+        ctx: &mut InferenceContext<'_>,
+        dst: &Ty,
+    ) -> Result<(), CastError> {
+        match pointer_kind(ctx, dst) {
+            Some(PointerKind::Thin) | None => Ok(()),
+            Some(_) => Err(CastError::IllegalCast),
+        }
+    }
+}
+
+impl CastTy {
+    fn from_ty(ctx: &mut InferenceContext<'_>, ty: &Ty) -> Option<CastTy> {
+        let ty = ctx.table.resolve_ty_shallow(ty);
+        match ty.kind(Interner) {
+            TyKind::Scalar(Scalar::Bool) => Some(CastTy::Int(IntTy::Bool)),
+            TyKind::Scalar(Scalar::Char) => Some(CastTy::Int(IntTy::Char)),
+            TyKind::Scalar(Scalar::Int(_)) => Some(CastTy::Int(IntTy::I)),
+            TyKind::Scalar(Scalar::Uint(it)) => Some(CastTy::Int(IntTy::U(*it))),
+            TyKind::Scalar(Scalar::Float(_)) => Some(CastTy::Float),
+            TyKind::InferenceVar(_, TyVariableKind::Integer) => Some(CastTy::Int(IntTy::I)),
+            TyKind::InferenceVar(_, TyVariableKind::Float) => Some(CastTy::Float),
+            TyKind::Adt(chalk_ir::AdtId(AdtId::EnumId(id)), _) => {
+                let data = ctx.db.enum_data(*id);
+                let is_c_like =
+                    data.variants.iter().all(|(_, it)| it.variant_data.fields().is_empty());
+                is_c_like.then(|| CastTy::Int(IntTy::CEnum))
+            }
+            TyKind::Raw(mutbl, inner) => Some(CastTy::Ptr(inner.clone(), *mutbl)),
+            TyKind::Function(_) => Some(CastTy::FnPtr),
+            _ => None,
+        }
+    }
+}
+
+/// Returns the kind of metadata a pointer to `ty` has, `None` if that isn't known.
+fn pointer_kind(ctx: &mut InferenceContext<'_>, ty: &Ty) -> Option<PointerKind> {
+    let ty = ctx.table.resolve_ty_shallow(ty);
+    match ty.kind(Interner) {
+        TyKind::Str | TyKind::Slice(_) => Some(PointerKind::Length),
+        TyKind::Dyn(_) => Some(PointerKind::VTable(ty.dyn_trait())),
+        TyKind::Adt(chalk_ir::AdtId(AdtId::StructId(id)), subst) => {
+            let data = ctx.db.struct_data(*id);
+            let last_field = match data.variant_data.fields().iter().last() {
+                Some((it, _)) => it,
+                None => return Some(PointerKind::Thin),
+            };
+            let field_ty =
+                ctx.db.field_types((*id).into())[last_field].clone().substitute(Interner, subst);
+            pointer_kind(ctx, &field_ty)
+        }
+        TyKind::Tuple(_, subst) => match subst.iter(Interner).last() {
+            Some(it) => pointer_kind(ctx, it.assert_ty_ref(Interner)),
+            None => Some(PointerKind::Thin),
+        },
+        TyKind::Placeholder(_) | TyKind::Alias(_) | TyKind::AssociatedType(..) => {
+            match is_sized(ctx, &ty) {
+                Some(true) => Some(PointerKind::Thin),
+                Some(false) if matches!(ty.kind(Interner), TyKind::Placeholder(_)) => {
+                    Some(PointerKind::OfParam(ty))
+                }
+                _ => None,
+            }
+        }
+        TyKind::Error | TyKind::InferenceVar(..) => None,
+        _ => Some(PointerKind::Thin),
+    }
+}
+
+/// Returns whether `ty` is known to be `Sized`, `None` if there's no `Sized` trait.
+fn is_sized(ctx: &mut InferenceContext<'_>, ty: &Ty) -> Option<bool> {
+    let krate = ctx.resolver.krate();
+    let sized_trait = ctx.db.lang_item(krate, SmolStr::new_inline("sized"))?.as_trait()?;
+    let trait_ref = TyBuilder::trait_ref(ctx.db, sized_trait).push(ty.clone()).build();
+    Some(ctx.table.try_obligation(trait_ref.cast(Interner)).is_some())
+}
@@ -23,9 +23,10 @@ use syntax::ast::RangeOp;
 use crate::{
     autoderef::{self, Autoderef},
     consteval,
-    infer::coerce::CoerceMany,
+    infer::{cast::CastCheck, coerce::CoerceMany},
     lower::{
-        const_or_path_to_chalk, generic_arg_to_chalk, lower_to_chalk_mutability, ParamLoweringMode,
+        const_or_path_to_chalk, generic_arg_to_chalk, lower_to_chalk_mutability,
+        ImplTraitLoweringMode, ParamLoweringMode,
     },
     mapping::{from_chalk, ToChalk},
     method_resolution::{self, lang_names_for_bin_op, VisibleFromModule},
@@ -33,7 +34,7 @@ use crate::{
     static_lifetime, to_chalk_trait_id,
     utils::{generics, Generics},
     AdtId, Binders, CallableDefId, FnPointer, FnSig, FnSubst, Interner, Rawness, Scalar,
-    Substitution, TraitRef, Ty, TyBuilder, TyExt, TyKind,
+    Substitution, TraitRef, Ty, TyBuilder, TyExt, TyKind, TypeSource,
 };
 
 use super::{
@@ -224,9 +225,11 @@ impl<'a> InferenceContext<'a> {
                 let mut sig_tys = Vec::new();
 
                 // collect explicitly written argument types
-                for arg_type in arg_types.iter() {
+                for (idx, arg_type) in arg_types.iter().enumerate() {
                     let arg_ty = match arg_type {
-                        Some(type_ref) => self.make_ty(type_ref),
+                        Some(type_ref) => {
+                            self.make_ty(type_ref, TypeSource::ClosureParam(tgt_expr, idx))
+                        }
                         None => self.table.new_type_var(),
                     };
                     sig_tys.push(arg_ty);
@@ -234,7 +237,7 @@ impl<'a> InferenceContext<'a> {
 
                 // add return type
                 let ret_ty = match ret_type {
-                    Some(type_ref) => self.make_ty(type_ref),
+                    Some(type_ref) => self.make_ty(type_ref, TypeSource::ClosureRet(tgt_expr)),
                     None => self.table.new_type_var(),
                 };
                 sig_tys.push(ret_ty.clone());
@@ -422,7 +425,7 @@ impl<'a> InferenceContext<'a> {
                 TyKind::Never.intern(Interner)
             }
             Expr::RecordLit { path, fields, spread, .. } => {
-                let (ty, def_id) = self.resolve_variant(path.as_deref(), false);
+                let (ty, def_id) = self.resolve_variant(tgt_expr.into(), path.as_deref(), false);
                 if let Some(variant) = def_id {
                     self.write_variant_resolution(tgt_expr.into(), variant);
                 }
@@ -527,9 +530,9 @@ impl<'a> InferenceContext<'a> {
             }
             Expr::Cast { expr, type_ref } => {
                 // FIXME: propagate the "castable to" expectation (and find a test case that shows this is necessary)
-                let _inner_ty = self.infer_expr_inner(*expr, &Expectation::none());
-                let cast_ty = self.make_ty(type_ref);
-                // FIXME check the cast...
+                let inner_ty = self.infer_expr_inner(*expr, &Expectation::none());
+                let cast_ty = self.make_ty(type_ref, TypeSource::Cast(tgt_expr));
+                self.deferred_cast_checks.push(CastCheck::new(tgt_expr, inner_ty, cast_ty.clone()));
                 cast_ty
             }
             Expr::Ref { expr, rawness, mutability } => {
@@ -1004,7 +1007,7 @@ impl<'a> InferenceContext<'a> {
                 Statement::Let { pat, type_ref, initializer, else_branch } => {
                     let decl_ty = type_ref
                         .as_ref()
-                        .map(|tr| self.make_ty(tr))
+                        .map(|tr| self.make_ty(tr, TypeSource::Let(*pat)))
                         .unwrap_or_else(|| self.err_ty());
 
                     // Always use the declared type when specified
@@ -1088,7 +1091,7 @@ impl<'a> InferenceContext<'a> {
             Some((adjust, func)) => {
                 let (ty, adjustments) = adjust.apply(&mut self.table, receiver_ty);
                 let generics = generics(self.db.upcast(), func.into());
-                let substs = self.substs_for_method_call(generics, generic_args);
+                let substs = self.substs_for_method_call(tgt_expr, generics, generic_args);
                 self.write_expr_adj(receiver, adjustments);
                 self.write_method_resolution(tgt_expr, func, substs.clone());
                 (ty, self.db.value_ty(func.into()), substs)
@@ -1232,6 +1235,7 @@ impl<'a> InferenceContext<'a> {
 
     fn substs_for_method_call(
         &mut self,
+        expr: ExprId,
         def_generics: Generics,
         generic_args: Option<&GenericArgs>,
     ) -> Substitution {
@@ -1268,7 +1272,14 @@ impl<'a> InferenceContext<'a> {
                     kind_id,
                     arg,
                     self,
-                    |this, type_ref| this.make_ty(type_ref),
+                    |this, type_ref| {
+                        this.make_ty_with_mode(
+                            type_ref,
+                            ImplTraitLoweringMode::Disallowed,
+                            TypeSource::MethodGenericArgs(expr),
+                            generic_args.paths(),
+                        )
+                    },
                     |this, c, ty| {
                         const_or_path_to_chalk(
                             this.db,
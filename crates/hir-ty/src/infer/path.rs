@@ -11,6 +11,7 @@ use hir_expand::name::Name;
 use crate::{
     builder::ParamKind,
     consteval,
+    lower::ImplTraitLoweringMode,
     method_resolution::{self, VisibleFromModule},
     GenericArgData, Interner, Substitution, TraitRefExt, Ty, TyBuilder, TyExt, TyKind,
     ValueTyDefId,
@@ -42,10 +43,20 @@ impl<'a> InferenceContext<'a> {
                 // This can't actually happen syntax-wise
                 return None;
             }
-            let ty = self.make_ty(type_ref);
+            let ty = self.make_ty_with_mode(
+                type_ref,
+                ImplTraitLoweringMode::Disallowed,
+                id.path_source(),
+                path.paths(),
+            );
             let remaining_segments_for_ty = path.segments().take(path.segments().len() - 1);
             let ctx = crate::lower::TyLoweringContext::new(self.db, resolver);
-            let (ty, _) = ctx.lower_ty_relative_path(ty, None, remaining_segments_for_ty);
+            let (ty, _) = ctx.with_source(id.path_source(), path.paths(), || {
+                ctx.in_path(path, || {
+                    ctx.lower_ty_relative_path(ty, None, remaining_segments_for_ty)
+                })
+            });
+            self.push_ty_lowering_diagnostics(ctx.into_diagnostics());
             self.resolve_ty_assoc_item(
                 ty,
                 path.segments().last().expect("path had at least one segment").name,
@@ -98,7 +109,10 @@ impl<'a> InferenceContext<'a> {
 
         let parent_substs = self_subst.unwrap_or_else(|| Substitution::empty(Interner));
         let ctx = crate::lower::TyLoweringContext::new(self.db, &self.resolver);
-        let substs = ctx.substs_from_path(path, typable, true);
+        let substs = ctx.with_source(id.path_source(), path.paths(), || {
+            ctx.substs_from_path(path, typable, true)
+        });
+        self.push_ty_lowering_diagnostics(ctx.into_diagnostics());
         let mut it = substs.as_slice(Interner)[parent_substs.len(Interner)..].iter().cloned();
         let ty = TyBuilder::value_ty(self.db, typable)
             .use_parent_substs(&parent_substs)
@@ -135,8 +149,12 @@ impl<'a> InferenceContext<'a> {
                 let segment =
                     remaining_segments.last().expect("there should be at least one segment here");
                 let ctx = crate::lower::TyLoweringContext::new(self.db, &self.resolver);
-                let trait_ref =
-                    ctx.lower_trait_ref_from_resolved_path(trait_, resolved_segment, None);
+                let trait_ref = ctx.with_source(id.path_source(), path.paths(), || {
+                    ctx.in_path(path, || {
+                        ctx.lower_trait_ref_from_resolved_path(trait_, resolved_segment, None)
+                    })
+                });
+                self.push_ty_lowering_diagnostics(ctx.into_diagnostics());
                 self.resolve_trait_assoc_item(trait_ref, segment, id)
             }
             (def, _) => {
@@ -147,12 +165,17 @@ impl<'a> InferenceContext<'a> {
                 let remaining_segments_for_ty =
                     remaining_segments.take(remaining_segments.len() - 1);
                 let ctx = crate::lower::TyLoweringContext::new(self.db, &self.resolver);
-                let (ty, _) = ctx.lower_partly_resolved_path(
-                    def,
-                    resolved_segment,
-                    remaining_segments_for_ty,
-                    true,
-                );
+                let (ty, _) = ctx.with_source(id.path_source(), path.paths(), || {
+                    ctx.in_path(path, || {
+                        ctx.lower_partly_resolved_path(
+                            def,
+                            resolved_segment,
+                            remaining_segments_for_ty,
+                            true,
+                        )
+                    })
+                });
+                self.push_ty_lowering_diagnostics(ctx.into_diagnostics());
                 if let TyKind::Error = ty.kind(Interner) {
                     return None;
                 }
@@ -20,6 +20,7 @@ use hir_def::{
     adt::StructKind,
     body::{Expander, LowerCtx},
     builtin_type::BuiltinType,
+    expr::{ExprId, PatId},
     generics::{
         TypeOrConstParamData, TypeParamProvenance, WherePredicate, WherePredicateTypeTarget,
     },
@@ -34,8 +35,8 @@ use hir_def::{
     HasModule, ImplId, ItemContainerId, LocalFieldId, Lookup, StaticId, StructId, TraitId,
     TypeAliasId, TypeOrConstParamId, TypeParamId, UnionId, VariantId,
 };
-use hir_expand::{name::Name, ExpandResult};
-use itertools::Either;
+use hir_expand::{name::Name, ExpandResult, HirFileId};
+use itertools::{Either, Itertools};
 use la_arena::ArenaMap;
 use rustc_hash::FxHashSet;
 use smallvec::SmallVec;
@@ -54,7 +55,8 @@ use crate::{
     AliasEq, AliasTy, Binders, BoundVar, CallableSig, Const, DebruijnIndex, DynTy, FnPointer,
     FnSig, FnSubst, GenericArgData, ImplTraitId, Interner, ParamKind, PolyFnSig, ProjectionTy,
     QuantifiedWhereClause, QuantifiedWhereClauses, ReturnTypeImplTrait, ReturnTypeImplTraits,
-    Substitution, TraitEnvironment, TraitRef, TraitRefExt, Ty, TyBuilder, TyKind, WhereClause,
+    Substitution, TraitEnvironment, TraitRef, TraitRefExt, Ty, TyBuilder, TyExt, TyKind,
+    WhereClause,
 };
 
 #[derive(Debug)]
@@ -81,6 +83,23 @@ pub struct TyLoweringContext<'a> {
     expander: RefCell<Option<Expander>>,
     /// Tracks types with explicit `?Sized` bounds.
     pub(crate) unsized_types: RefCell<FxHashSet<Ty>>,
+    diagnostics: RefCell<Vec<TyLoweringDiagnostic>>,
+    /// Problems found in the path currently being lowered, see `in_path`.
+    path_diagnostics: RefCell<Vec<TyLoweringDiagnosticKind>>,
+    /// The type reference problems are currently attributed to, see `with_source`.
+    source: RefCell<Option<LoweredSource>>,
+}
+
+/// A type reference problems are attributed to, with the paths in it in source order.
+#[derive(Debug)]
+struct LoweredSource {
+    source: TypeSource,
+    /// The paths along with whether they got problems attributed already.
+    ///
+    /// Paths are matched by their interned parts rather than by address, so that a path lowered
+    /// from a clone, like the bound of a trait object type without `dyn`, is still found. Equal
+    /// paths are indistinguishable this way, they get their problems in lowering order.
+    paths: Vec<(Path, bool)>,
 }
 
 impl<'a> TyLoweringContext<'a> {
@@ -100,6 +119,9 @@ impl<'a> TyLoweringContext<'a> {
             opaque_type_data,
             expander: RefCell::new(None),
             unsized_types: RefCell::default(),
+            diagnostics: RefCell::default(),
+            path_diagnostics: RefCell::default(),
+            source: RefCell::default(),
         }
     }
 
@@ -111,12 +133,18 @@ impl<'a> TyLoweringContext<'a> {
         let opaque_ty_data_vec = self.opaque_type_data.take();
         let expander = self.expander.take();
         let unsized_types = self.unsized_types.take();
+        let diagnostics = self.diagnostics.take();
+        let path_diagnostics = self.path_diagnostics.take();
+        let source = self.source.take();
         let new_ctx = Self {
             in_binders: debruijn,
             impl_trait_counter: Cell::new(self.impl_trait_counter.get()),
             opaque_type_data: RefCell::new(opaque_ty_data_vec),
             expander: RefCell::new(expander),
             unsized_types: RefCell::new(unsized_types),
+            diagnostics: RefCell::new(diagnostics),
+            path_diagnostics: RefCell::new(path_diagnostics),
+            source: RefCell::new(source),
             ..*self
         };
         let result = f(&new_ctx);
@@ -124,6 +152,9 @@ impl<'a> TyLoweringContext<'a> {
         self.opaque_type_data.replace(new_ctx.opaque_type_data.into_inner());
         self.expander.replace(new_ctx.expander.into_inner());
         self.unsized_types.replace(new_ctx.unsized_types.into_inner());
+        self.diagnostics.replace(new_ctx.diagnostics.into_inner());
+        self.path_diagnostics.replace(new_ctx.path_diagnostics.into_inner());
+        self.source.replace(new_ctx.source.into_inner());
         result
     }
 
@@ -142,6 +173,73 @@ impl<'a> TyLoweringContext<'a> {
     pub fn with_type_param_mode(self, type_param_mode: ParamLoweringMode) -> Self {
         Self { type_param_mode, ..self }
     }
+
+    /// Returns the problems found in the type references lowered so far.
+    pub fn into_diagnostics(self) -> Vec<TyLoweringDiagnostic> {
+        self.diagnostics.into_inner()
+    }
+
+    /// Lowers `type_ref`, attributing the problems found in it to `source`.
+    pub(crate) fn lower_ty_from(&self, source: TypeSource, type_ref: &TypeRef) -> Ty {
+        self.with_source(source, type_ref.paths(), || self.lower_ty(type_ref))
+    }
+
+    /// Runs `f`, attributing the problems it reports to `source`. `paths` are the paths of the
+    /// type reference, path or generic arguments `source` points to, in source order.
+    ///
+    /// Problems with paths that are not among `paths` are dropped, there is no place in the
+    /// source to show them at.
+    pub(crate) fn with_source<T>(
+        &self,
+        source: TypeSource,
+        paths: Vec<&Path>,
+        f: impl FnOnce() -> T,
+    ) -> T {
+        let paths = paths.into_iter().map(|it| (it.clone(), false)).collect();
+        let outer = self.source.replace(Some(LoweredSource { source, paths }));
+        let result = f();
+        self.source.replace(outer);
+        result
+    }
+
+    /// Runs `f`, attributing the problems it reports to `path`.
+    ///
+    /// Lowering a path lowers the paths in its generic arguments as well, so this keeps the
+    /// problems of the outer path aside in the meantime.
+    pub(crate) fn in_path<T>(&self, path: &Path, f: impl FnOnce() -> T) -> T {
+        let outer = self.path_diagnostics.take();
+        let result = f();
+        let kinds = self.path_diagnostics.replace(outer);
+        if kinds.is_empty() {
+            return result;
+        }
+        let mut lowered = self.source.borrow_mut();
+        let LoweredSource { source, paths } = match &mut *lowered {
+            Some(it) => it,
+            None => return result,
+        };
+        let occurrences = || paths.iter().positions(|(it, _)| it == path);
+        let idx = occurrences().find(|&idx| !paths[idx].1).or_else(|| occurrences().next());
+        let idx = match idx {
+            Some(it) => it,
+            None => {
+                never!("lowered a path missing from its source: {:?}", path);
+                return result;
+            }
+        };
+        paths[idx].1 = true;
+        let source = *source;
+        self.diagnostics.borrow_mut().extend(kinds.into_iter().map(|kind| TyLoweringDiagnostic {
+            source,
+            path: idx,
+            kind,
+        }));
+        result
+    }
+
+    fn report(&self, kind: TyLoweringDiagnosticKind) {
+        self.path_diagnostics.borrow_mut().push(kind);
+    }
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -170,6 +268,64 @@ pub enum ParamLoweringMode {
     Variable,
 }
 
+/// The problems found while lowering the type references of an item.
+pub type TyLoweringDiagnostics = Arc<Vec<TyLoweringDiagnostic>>;
+
+/// A problem with a path found while lowering a type reference.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TyLoweringDiagnostic {
+    /// Where the type reference the problem was found in comes from.
+    pub source: TypeSource,
+    /// The index of the path the problem was found in among the paths of the type reference, in
+    /// source order. See `TypeRef::paths` and `TypeRef::source_paths`.
+    pub path: usize,
+    pub kind: TyLoweringDiagnosticKind,
+}
+
+/// Where a type reference lowered with diagnostics comes from. Type references don't point back
+/// to their source, this is what allows finding the paths in them in the source again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TypeSource {
+    /// The type of a field.
+    Field(LocalFieldId),
+    /// The type of the `n`th parameter of a function, counting `self`.
+    Param(usize),
+    /// The return type of a function.
+    RetType,
+    /// The type of a const, static or type alias, or the self type of an impl.
+    Item,
+    /// The trait an impl implements.
+    ImplTrait,
+    /// The type annotation of the `let` statement binding the pattern.
+    Let(PatId),
+    /// The type an expression gets cast to.
+    Cast(ExprId),
+    /// The type annotation of the `n`th parameter of a closure.
+    ClosureParam(ExprId, usize),
+    /// The return type annotation of a closure.
+    ClosureRet(ExprId),
+    /// The generic arguments of a method call.
+    MethodGenericArgs(ExprId),
+    /// The path of a path, record or call expression.
+    ExprPath(ExprId),
+    /// The path of a path, record or tuple struct pattern.
+    PatPath(PatId),
+    /// The type a type macro expanded to.
+    MacroExpansion(HirFileId),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TyLoweringDiagnosticKind {
+    /// The `segment` of the path got `provided` generic arguments, but the item it refers to takes
+    /// at least `min` and at most `max` of them.
+    WrongGenericArgCount { segment: Name, provided: usize, min: usize, max: usize },
+    /// None of the traits the associated type `name` is looked up in define it.
+    UnresolvedAssocType { name: Name },
+    /// The associated type `name` may come from any of the `candidates`, the path has to be
+    /// qualified with one of them.
+    AmbiguousAssocType { name: Name, candidates: Vec<TraitId> },
+}
+
 impl<'a> TyLoweringContext<'a> {
     pub fn lower_ty(&self, type_ref: &TypeRef) -> Ty {
         self.lower_ty_ext(type_ref).0
@@ -349,11 +505,13 @@ impl<'a> TyLoweringContext<'a> {
                     let macro_call = macro_call.to_node(self.db.upcast());
                     match expander.enter_expand::<ast::Type>(self.db.upcast(), macro_call) {
                         Ok(ExpandResult { value: Some((mark, expanded)), .. }) => {
-                            let ctx = LowerCtx::new(self.db.upcast(), expander.current_file_id());
+                            let file_id = expander.current_file_id();
+                            let ctx = LowerCtx::new(self.db.upcast(), file_id);
                             let type_ref = TypeRef::from_ast(&ctx, expanded);
 
                             drop(expander);
-                            let ty = self.lower_ty(&type_ref);
+                            let source = TypeSource::MacroExpansion(file_id);
+                            let ty = self.lower_ty_from(source, &type_ref);
 
                             self.expander
                                 .borrow_mut()
@@ -412,10 +570,15 @@ impl<'a> TyLoweringContext<'a> {
             1 => {
                 // resolve unselected assoc types
                 let segment = remaining_segments.first().unwrap();
-                (self.select_associated_type(res, segment), None)
+                (self.select_associated_type(&ty, res, segment), None)
             }
             _ => {
-                // FIXME report error (ambiguous associated type)
+                // `T::Assoc::Other` needs to be written as `<T::Assoc as Trait>::Other`.
+                if !ty.is_unknown() {
+                    let name = remaining_segments.get(1).unwrap().name.clone();
+                    let candidates = self.traits_in_scope_with_assoc_type(&name);
+                    self.report(TyLoweringDiagnosticKind::AmbiguousAssocType { name, candidates });
+                }
                 (TyKind::Error.intern(Interner), None)
             }
         }
@@ -449,7 +612,9 @@ impl<'a> TyLoweringContext<'a> {
                                 .intern(Interner)
                             }
                             None => {
-                                // FIXME: report error (associated type not found)
+                                self.report(TyLoweringDiagnosticKind::UnresolvedAssocType {
+                                    name: segment.name.clone(),
+                                });
                                 TyKind::Error.intern(Interner)
                             }
                         }
@@ -461,7 +626,12 @@ impl<'a> TyLoweringContext<'a> {
                         TyKind::Error.intern(Interner)
                     }
                     _ => {
-                        // FIXME report error (ambiguous associated type)
+                        let name = remaining_segments.get(1).unwrap().name.clone();
+                        let candidates = self.traits_in_scope_with_assoc_type(&name);
+                        self.report(TyLoweringDiagnosticKind::AmbiguousAssocType {
+                            name,
+                            candidates,
+                        });
                         TyKind::Error.intern(Interner)
                     }
                 };
@@ -525,80 +695,126 @@ impl<'a> TyLoweringContext<'a> {
     }
 
     pub(crate) fn lower_path(&self, path: &Path) -> (Ty, Option<TypeNs>) {
-        // Resolve the path (in type namespace)
-        if let Some(type_ref) = path.type_anchor() {
-            let (ty, res) = self.lower_ty_ext(type_ref);
-            return self.lower_ty_relative_path(ty, res, path.segments());
-        }
+        self.in_path(path, || {
+            // Resolve the path (in type namespace)
+            if let Some(type_ref) = path.type_anchor() {
+                let (ty, res) = self.lower_ty_ext(type_ref);
+                return self.lower_ty_relative_path(ty, res, path.segments());
+            }
 
-        let (resolution, remaining_index) =
-            match self.resolver.resolve_path_in_type_ns(self.db.upcast(), path.mod_path()) {
-                Some(it) => it,
-                None => return (TyKind::Error.intern(Interner), None),
-            };
+            let (resolution, remaining_index) =
+                match self.resolver.resolve_path_in_type_ns(self.db.upcast(), path.mod_path()) {
+                    Some(it) => it,
+                    None => return (TyKind::Error.intern(Interner), None),
+                };
 
-        if matches!(resolution, TypeNs::TraitId(_)) && remaining_index.is_none() {
-            // trait object type without dyn
-            let bound = TypeBound::Path(path.clone(), TraitBoundModifier::None);
-            let ty = self.lower_dyn_trait(&[Interned::new(bound)]);
-            return (ty, None);
-        }
+            if matches!(resolution, TypeNs::TraitId(_)) && remaining_index.is_none() {
+                // trait object type without dyn
+                let bound = TypeBound::Path(path.clone(), TraitBoundModifier::None);
+                let ty = self.lower_dyn_trait(&[Interned::new(bound)]);
+                return (ty, None);
+            }
 
-        let (resolved_segment, remaining_segments) = match remaining_index {
-            None => (
-                path.segments().last().expect("resolved path has at least one element"),
-                PathSegments::EMPTY,
-            ),
-            Some(i) => (path.segments().get(i - 1).unwrap(), path.segments().skip(i)),
-        };
-        self.lower_partly_resolved_path(resolution, resolved_segment, remaining_segments, false)
+            let (resolved_segment, remaining_segments) = match remaining_index {
+                None => (
+                    path.segments().last().expect("resolved path has at least one element"),
+                    PathSegments::EMPTY,
+                ),
+                Some(i) => (path.segments().get(i - 1).unwrap(), path.segments().skip(i)),
+            };
+            self.lower_partly_resolved_path(resolution, resolved_segment, remaining_segments, false)
+        })
     }
 
-    fn select_associated_type(&self, res: Option<TypeNs>, segment: PathSegment<'_>) -> Ty {
+    fn select_associated_type(&self, ty: &Ty, res: Option<TypeNs>, segment: PathSegment<'_>) -> Ty {
+        let shorthand = match res {
+            Some(TypeNs::GenericParam(_)) => true,
+            Some(TypeNs::SelfType(impl_id)) => self.db.impl_trait(impl_id).is_some(),
+            _ => false,
+        };
         let (def, res) = match (self.resolver.generic_def(), res) {
-            (Some(def), Some(res)) => (def, res),
-            _ => return TyKind::Error.intern(Interner),
+            (Some(def), Some(res)) if shorthand => (def, res),
+            _ => {
+                // `Type::Assoc` is only allowed for type parameters and `Self`, other types need
+                // to name the trait.
+                if !ty.is_unknown() {
+                    let name = segment.name.clone();
+                    let candidates = self.traits_in_scope_with_assoc_type(&name);
+                    self.report(TyLoweringDiagnosticKind::AmbiguousAssocType { name, candidates });
+                }
+                return TyKind::Error.intern(Interner);
+            }
         };
-        let ty = named_associated_type_shorthand_candidates(
+        let mut candidates: Vec<(TraitRef, TypeAliasId)> = Vec::new();
+        named_associated_type_shorthand_candidates(
             self.db,
             def,
             res,
             Some(segment.name.clone()),
-            move |name, t, associated_ty| {
-                if name == segment.name {
-                    let substs = match self.type_param_mode {
-                        ParamLoweringMode::Placeholder => {
-                            // if we're lowering to placeholders, we have to put
-                            // them in now
-                            let generics = generics(
-                                self.db.upcast(),
-                                self.resolver
-                                    .generic_def()
-                                    .expect("there should be generics if there's a generic param"),
-                            );
-                            let s = generics.placeholder_subst(self.db);
-                            s.apply(t.substitution.clone(), Interner)
-                        }
-                        ParamLoweringMode::Variable => t.substitution.clone(),
-                    };
-                    // We need to shift in the bound vars, since
-                    // associated_type_shorthand_candidates does not do that
-                    let substs = substs.shifted_in_from(Interner, self.in_binders);
-                    // FIXME handle type parameters on the segment
-                    Some(
-                        TyKind::Alias(AliasTy::Projection(ProjectionTy {
-                            associated_ty_id: to_assoc_type_id(associated_ty),
-                            substitution: substs,
-                        }))
-                        .intern(Interner),
-                    )
-                } else {
-                    None
+            |name, t, associated_ty| {
+                if name == segment.name && candidates.iter().all(|(_, it)| *it != associated_ty) {
+                    candidates.push((t.clone(), associated_ty));
                 }
+                None::<()>
             },
         );
 
-        ty.unwrap_or_else(|| TyKind::Error.intern(Interner))
+        let (t, associated_ty) = match &*candidates {
+            [] => {
+                self.report(TyLoweringDiagnosticKind::UnresolvedAssocType {
+                    name: segment.name.clone(),
+                });
+                return TyKind::Error.intern(Interner);
+            }
+            [first, rest @ ..] => {
+                if !rest.is_empty() {
+                    let mut traits = candidates.iter().map(|(t, _)| t.hir_trait_id()).collect_vec();
+                    traits.dedup();
+                    self.report(TyLoweringDiagnosticKind::AmbiguousAssocType {
+                        name: segment.name.clone(),
+                        candidates: traits,
+                    });
+                }
+                first
+            }
+        };
+        let substs = match self.type_param_mode {
+            ParamLoweringMode::Placeholder => {
+                // if we're lowering to placeholders, we have to put
+                // them in now
+                let generics = generics(
+                    self.db.upcast(),
+                    self.resolver
+                        .generic_def()
+                        .expect("there should be generics if there's a generic param"),
+                );
+                let s = generics.placeholder_subst(self.db);
+                s.apply(t.substitution.clone(), Interner)
+            }
+            ParamLoweringMode::Variable => t.substitution.clone(),
+        };
+        // We need to shift in the bound vars, since
+        // associated_type_shorthand_candidates does not do that
+        let substs = substs.shifted_in_from(Interner, self.in_binders);
+        // FIXME handle type parameters on the segment
+        TyKind::Alias(AliasTy::Projection(ProjectionTy {
+            associated_ty_id: to_assoc_type_id(*associated_ty),
+            substitution: substs,
+        }))
+        .intern(Interner)
+    }
+
+    /// Returns the traits in scope that have an associated type called `name`, as the candidates
+    /// for qualifying an ambiguous `Type::Assoc` path.
+    fn traits_in_scope_with_assoc_type(&self, name: &Name) -> Vec<TraitId> {
+        let mut traits = self
+            .resolver
+            .traits_in_scope(self.db.upcast())
+            .into_iter()
+            .filter(|&it| self.db.trait_data(it).associated_type_by_name(name).is_some())
+            .collect_vec();
+        traits.sort_by_cached_key(|&it| self.db.trait_data(it).name.to_smol_str());
+        traits
     }
 
     fn lower_path_inner(
@@ -627,29 +843,31 @@ impl<'a> TyLoweringContext<'a> {
         resolved: ValueTyDefId,
         infer_args: bool,
     ) -> Substitution {
-        let last = path.segments().last().expect("path should have at least one segment");
-        let (segment, generic_def) = match resolved {
-            ValueTyDefId::FunctionId(it) => (last, Some(it.into())),
-            ValueTyDefId::StructId(it) => (last, Some(it.into())),
-            ValueTyDefId::UnionId(it) => (last, Some(it.into())),
-            ValueTyDefId::ConstId(it) => (last, Some(it.into())),
-            ValueTyDefId::StaticId(_) => (last, None),
-            ValueTyDefId::EnumVariantId(var) => {
-                // the generic args for an enum variant may be either specified
-                // on the segment referring to the enum, or on the segment
-                // referring to the variant. So `Option::<T>::None` and
-                // `Option::None::<T>` are both allowed (though the former is
-                // preferred). See also `def_ids_for_path_segments` in rustc.
-                let len = path.segments().len();
-                let penultimate = len.checked_sub(2).and_then(|idx| path.segments().get(idx));
-                let segment = match penultimate {
-                    Some(segment) if segment.args_and_bindings.is_some() => segment,
-                    _ => last,
-                };
-                (segment, Some(var.parent.into()))
-            }
-        };
-        self.substs_from_path_segment(segment, generic_def, infer_args, None)
+        self.in_path(path, || {
+            let last = path.segments().last().expect("path should have at least one segment");
+            let (segment, generic_def) = match resolved {
+                ValueTyDefId::FunctionId(it) => (last, Some(it.into())),
+                ValueTyDefId::StructId(it) => (last, Some(it.into())),
+                ValueTyDefId::UnionId(it) => (last, Some(it.into())),
+                ValueTyDefId::ConstId(it) => (last, Some(it.into())),
+                ValueTyDefId::StaticId(_) => (last, None),
+                ValueTyDefId::EnumVariantId(var) => {
+                    // the generic args for an enum variant may be either specified
+                    // on the segment referring to the enum, or on the segment
+                    // referring to the variant. So `Option::<T>::None` and
+                    // `Option::None::<T>` are both allowed (though the former is
+                    // preferred). See also `def_ids_for_path_segments` in rustc.
+                    let len = path.segments().len();
+                    let penultimate = len.checked_sub(2).and_then(|idx| path.segments().get(idx));
+                    let segment = match penultimate {
+                        Some(segment) if segment.args_and_bindings.is_some() => segment,
+                        _ => last,
+                    };
+                    (segment, Some(var.parent.into()))
+                }
+            };
+            self.substs_from_path_segment(segment, generic_def, infer_args, None)
+        })
     }
 
     fn substs_from_path_segment(
@@ -700,6 +918,36 @@ impl<'a> TyLoweringContext<'a> {
         };
         let mut had_explicit_args = false;
 
+        let provided = segment.args_and_bindings.as_ref().map_or(0, |generic_args| {
+            let args = generic_args
+                .args
+                .iter()
+                .filter(|arg| !matches!(arg, GenericArg::Lifetime(_)))
+                .count();
+            args.saturating_sub(generic_args.has_self_type as usize)
+        });
+        let max = type_params + const_params;
+        let min = max
+            - def_generics
+                .params
+                .iter()
+                .filter(|(_, data)| match data {
+                    TypeOrConstParamData::TypeParamData(it) => {
+                        it.provenance == TypeParamProvenance::TypeParamList && it.default.is_some()
+                    }
+                    TypeOrConstParamData::ConstParamData(it) => it.has_default,
+                })
+                .count();
+        // Expressions and patterns may leave out all of the arguments to have them inferred.
+        if provided > max || (provided < min && !(infer_args && provided == 0)) {
+            self.report(TyLoweringDiagnosticKind::WrongGenericArgCount {
+                segment: segment.name.clone(),
+                provided,
+                min,
+                max,
+            });
+        }
+
         if let Some(generic_args) = &segment.args_and_bindings {
             if !generic_args.has_self_type {
                 fill_self_params();
@@ -788,13 +1036,17 @@ impl<'a> TyLoweringContext<'a> {
         path: &Path,
         explicit_self_ty: Option<Ty>,
     ) -> Option<TraitRef> {
-        let resolved =
-            match self.resolver.resolve_path_in_type_ns_fully(self.db.upcast(), path.mod_path())? {
+        self.in_path(path, || {
+            let resolved = match self
+                .resolver
+                .resolve_path_in_type_ns_fully(self.db.upcast(), path.mod_path())?
+            {
                 TypeNs::TraitId(tr) => tr,
                 _ => return None,
             };
-        let segment = path.segments().last().expect("path should have at least one segment");
-        Some(self.lower_trait_ref_from_resolved_path(resolved, segment, explicit_self_ty))
+            let segment = path.segments().last().expect("path should have at least one segment");
+            Some(self.lower_trait_ref_from_resolved_path(resolved, segment, explicit_self_ty))
+        })
     }
 
     pub(crate) fn lower_trait_ref_from_resolved_path(
@@ -1031,7 +1283,7 @@ fn count_impl_traits(type_ref: &TypeRef) -> usize {
 /// Build the signature of a callable item (function, struct or enum variant).
 pub(crate) fn callable_item_sig(db: &dyn HirDatabase, def: CallableDefId) -> PolyFnSig {
     match def {
-        CallableDefId::FunctionId(f) => fn_sig_for_fn(db, f),
+        CallableDefId::FunctionId(f) => db.fn_sig_with_diagnostics(f).0,
         CallableDefId::StructId(s) => fn_sig_for_struct_constructor(db, s),
         CallableDefId::EnumVariantId(e) => fn_sig_for_enum_variant_constructor(db, e),
     }
@@ -1111,6 +1363,13 @@ pub(crate) fn field_types_query(
     db: &dyn HirDatabase,
     variant_id: VariantId,
 ) -> Arc<ArenaMap<LocalFieldId, Binders<Ty>>> {
+    db.field_types_with_diagnostics(variant_id).0
+}
+
+pub(crate) fn field_types_with_diagnostics_query(
+    db: &dyn HirDatabase,
+    variant_id: VariantId,
+) -> (Arc<ArenaMap<LocalFieldId, Binders<Ty>>>, TyLoweringDiagnostics) {
     let var_data = variant_id.variant_data(db.upcast());
     let (resolver, def): (_, GenericDefId) = match variant_id {
         VariantId::StructId(it) => (it.resolver(db.upcast()), it.into()),
@@ -1122,9 +1381,10 @@ pub(crate) fn field_types_query(
     let ctx =
         TyLoweringContext::new(db, &resolver).with_type_param_mode(ParamLoweringMode::Variable);
     for (field_id, field_data) in var_data.fields().iter() {
-        res.insert(field_id, make_binders(db, &generics, ctx.lower_ty(&field_data.type_ref)));
+        let ty = ctx.lower_ty_from(TypeSource::Field(field_id), &field_data.type_ref);
+        res.insert(field_id, make_binders(db, &generics, ty));
     }
-    Arc::new(res)
+    (Arc::new(res), Arc::new(ctx.into_diagnostics()))
 }
 
 /// This query exists only to be used when resolving short-hand associated types
@@ -1393,20 +1653,37 @@ pub(crate) fn generic_defaults_recover(
     defaults
 }
 
-fn fn_sig_for_fn(db: &dyn HirDatabase, def: FunctionId) -> PolyFnSig {
+pub(crate) fn fn_sig_with_diagnostics_query(
+    db: &dyn HirDatabase,
+    def: FunctionId,
+) -> (PolyFnSig, TyLoweringDiagnostics) {
     let data = db.function_data(def);
     let resolver = def.resolver(db.upcast());
     let ctx_params = TyLoweringContext::new(db, &resolver)
         .with_impl_trait_mode(ImplTraitLoweringMode::Variable)
         .with_type_param_mode(ParamLoweringMode::Variable);
-    let params = data.params.iter().map(|(_, tr)| ctx_params.lower_ty(tr)).collect::<Vec<_>>();
+    let params = data
+        .params
+        .iter()
+        .enumerate()
+        .map(|(idx, (_, tr))| ctx_params.lower_ty_from(TypeSource::Param(idx), tr))
+        .collect::<Vec<_>>();
     let ctx_ret = TyLoweringContext::new(db, &resolver)
         .with_impl_trait_mode(ImplTraitLoweringMode::Opaque)
         .with_type_param_mode(ParamLoweringMode::Variable);
-    let ret = ctx_ret.lower_ty(&data.ret_type);
+    let mut ret_paths = data.ret_type.paths();
+    if data.has_async_kw() {
+        // The return type of an async fn is wrapped in a desugared `impl Future<Output = T>`, whose
+        // path does not occur in the source.
+        ret_paths.remove(0);
+    }
+    let ret =
+        ctx_ret.with_source(TypeSource::RetType, ret_paths, || ctx_ret.lower_ty(&data.ret_type));
     let generics = generics(db.upcast(), def.into());
     let sig = CallableSig::from_params_and_return(params, ret, data.is_varargs());
-    make_binders(db, &generics, sig)
+    let mut diagnostics = ctx_params.into_diagnostics();
+    diagnostics.extend(ctx_ret.into_diagnostics());
+    (make_binders(db, &generics, sig), Arc::new(diagnostics))
 }
 
 /// Build the declared type of a function. This should not need to look at the
@@ -1502,18 +1779,32 @@ fn type_for_adt(db: &dyn HirDatabase, adt: AdtId) -> Binders<Ty> {
     make_binders(db, &generics, ty)
 }
 
-fn type_for_type_alias(db: &dyn HirDatabase, t: TypeAliasId) -> Binders<Ty> {
+pub(crate) fn type_alias_ty_with_diagnostics_query(
+    db: &dyn HirDatabase,
+    t: TypeAliasId,
+) -> (Binders<Ty>, TyLoweringDiagnostics) {
     let generics = generics(db.upcast(), t.into());
     let resolver = t.resolver(db.upcast());
     let ctx =
         TyLoweringContext::new(db, &resolver).with_type_param_mode(ParamLoweringMode::Variable);
-    if db.type_alias_data(t).is_extern {
+    let ty = if db.type_alias_data(t).is_extern {
         Binders::empty(Interner, TyKind::Foreign(crate::to_foreign_def_id(t)).intern(Interner))
     } else {
         let type_ref = &db.type_alias_data(t).type_ref;
-        let inner = ctx.lower_ty(type_ref.as_deref().unwrap_or(&TypeRef::Error));
+        let inner =
+            ctx.lower_ty_from(TypeSource::Item, type_ref.as_deref().unwrap_or(&TypeRef::Error));
         make_binders(db, &generics, inner)
-    }
+    };
+    (ty, Arc::new(ctx.into_diagnostics()))
+}
+
+pub(crate) fn type_alias_ty_with_diagnostics_recover(
+    db: &dyn HirDatabase,
+    _cycle: &[String],
+    t: &TypeAliasId,
+) -> (Binders<Ty>, TyLoweringDiagnostics) {
+    let generics = generics(db.upcast(), (*t).into());
+    (make_binders(db, &generics, TyKind::Error.intern(Interner)), Arc::default())
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
@@ -1573,7 +1864,7 @@ pub(crate) fn ty_query(db: &dyn HirDatabase, def: TyDefId) -> Binders<Ty> {
     match def {
         TyDefId::BuiltinType(it) => Binders::empty(Interner, TyBuilder::builtin(it)),
         TyDefId::AdtId(it) => type_for_adt(db, it),
-        TyDefId::TypeAliasId(it) => type_for_type_alias(db, it),
+        TyDefId::TypeAliasId(it) => db.type_alias_ty_with_diagnostics(it).0,
     }
 }
 
@@ -1598,6 +1889,13 @@ pub(crate) fn value_ty_query(db: &dyn HirDatabase, def: ValueTyDefId) -> Binders
 }
 
 pub(crate) fn impl_self_ty_query(db: &dyn HirDatabase, impl_id: ImplId) -> Binders<Ty> {
+    db.impl_self_ty_with_diagnostics(impl_id).0
+}
+
+pub(crate) fn impl_self_ty_with_diagnostics_query(
+    db: &dyn HirDatabase,
+    impl_id: ImplId,
+) -> (Binders<Ty>, TyLoweringDiagnostics) {
     let impl_loc = impl_id.lookup(db.upcast());
     let impl_data = db.impl_data(impl_id);
     let resolver = impl_id.resolver(db.upcast());
@@ -1608,7 +1906,9 @@ pub(crate) fn impl_self_ty_query(db: &dyn HirDatabase, impl_id: ImplId) -> Binde
     let generics = generics(db.upcast(), impl_id.into());
     let ctx =
         TyLoweringContext::new(db, &resolver).with_type_param_mode(ParamLoweringMode::Variable);
-    make_binders(db, &generics, ctx.lower_ty(&impl_data.self_ty))
+    let self_ty =
+        make_binders(db, &generics, ctx.lower_ty_from(TypeSource::Item, &impl_data.self_ty));
+    (self_ty, Arc::new(ctx.into_diagnostics()))
 }
 
 // returns None if def is a type arg
@@ -1635,7 +1935,23 @@ pub(crate) fn impl_self_ty_recover(
     make_binders(db, &generics, TyKind::Error.intern(Interner))
 }
 
+pub(crate) fn impl_self_ty_with_diagnostics_recover(
+    db: &dyn HirDatabase,
+    _cycle: &[String],
+    impl_id: &ImplId,
+) -> (Binders<Ty>, TyLoweringDiagnostics) {
+    let generics = generics(db.upcast(), (*impl_id).into());
+    (make_binders(db, &generics, TyKind::Error.intern(Interner)), Arc::default())
+}
+
 pub(crate) fn impl_trait_query(db: &dyn HirDatabase, impl_id: ImplId) -> Option<Binders<TraitRef>> {
+    db.impl_trait_with_diagnostics(impl_id).0
+}
+
+pub(crate) fn impl_trait_with_diagnostics_query(
+    db: &dyn HirDatabase,
+    impl_id: ImplId,
+) -> (Option<Binders<TraitRef>>, TyLoweringDiagnostics) {
     let impl_loc = impl_id.lookup(db.upcast());
     let impl_data = db.impl_data(impl_id);
     let resolver = impl_id.resolver(db.upcast());
@@ -1646,15 +1962,23 @@ pub(crate) fn impl_trait_query(db: &dyn HirDatabase, impl_id: ImplId) -> Option<
     let ctx =
         TyLoweringContext::new(db, &resolver).with_type_param_mode(ParamLoweringMode::Variable);
     let (self_ty, binders) = db.impl_self_ty(impl_id).into_value_and_skipped_binders();
-    let target_trait = impl_data.target_trait.as_ref()?;
-    Some(Binders::new(binders, ctx.lower_trait_ref(target_trait, Some(self_ty))?))
+    let trait_ref = impl_data
+        .target_trait
+        .as_ref()
+        .and_then(|target_trait| {
+            ctx.with_source(TypeSource::ImplTrait, target_trait.path.paths(), || {
+                ctx.lower_trait_ref(target_trait, Some(self_ty))
+            })
+        })
+        .map(|trait_ref| Binders::new(binders, trait_ref));
+    (trait_ref, Arc::new(ctx.into_diagnostics()))
 }
 
 pub(crate) fn return_type_impl_traits(
     db: &dyn HirDatabase,
     def: hir_def::FunctionId,
 ) -> Option<Arc<Binders<ReturnTypeImplTraits>>> {
-    // FIXME unify with fn_sig_for_fn instead of doing lowering twice, maybe
+    // FIXME unify with fn_sig_with_diagnostics_query instead of doing lowering twice, maybe
     let data = db.function_data(def);
     let resolver = def.resolver(db.upcast());
     let ctx_ret = TyLoweringContext::new(db, &resolver)
@@ -3070,3 +3070,19 @@ fn main() {
         "#,
     );
 }
+
+#[test]
+fn cast_does_not_guide_inference() {
+    check_types(
+        r#"
+fn main() {
+    let n = 1;
+      //^ i32
+    let _ = n as i64;
+    let f = 1.0;
+      //^ f64
+    let _ = f as f32;
+}
+"#,
+    );
+}
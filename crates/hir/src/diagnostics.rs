@@ -8,9 +8,10 @@ use cfg::{CfgExpr, CfgOptions};
 use either::Either;
 use hir_def::path::ModPath;
 use hir_expand::{name::Name, HirFileId, InFile};
+use hir_ty::CastError;
 use syntax::{ast, AstPtr, SyntaxNodePtr, TextRange};
 
-use crate::{MacroKind, Type};
+use crate::{MacroKind, Trait, Type};
 
 macro_rules! diagnostics {
     ($($diag:ident,)*) => {
@@ -30,9 +31,12 @@ macro_rules! diagnostics {
 }
 
 diagnostics![
+    AmbiguousAssocType,
     BreakOutsideOfLoop,
+    CastToUnsized,
     InactiveCode,
     IncorrectCase,
+    InvalidCast,
     InvalidDeriveTarget,
     MacroError,
     MalformedDerive,
@@ -42,13 +46,16 @@ diagnostics![
     MissingUnsafe,
     NoSuchField,
     ReplaceFilterMapNextWithFindMap,
+    TrivialCast,
     TypeMismatch,
     UnimplementedBuiltinMacro,
+    UnresolvedAssocType,
     UnresolvedExternCrate,
     UnresolvedImport,
     UnresolvedMacroCall,
     UnresolvedModule,
     UnresolvedProcMacro,
+    WrongGenericArgCount,
 ];
 
 #[derive(Debug)]
@@ -168,4 +175,49 @@ pub struct TypeMismatch {
     pub actual: Type,
 }
 
+#[derive(Debug)]
+pub struct InvalidCast {
+    pub expr: InFile<AstPtr<ast::Expr>>,
+    pub error: CastError,
+    pub expr_ty: Type,
+    pub cast_ty: Type,
+}
+
+#[derive(Debug)]
+pub struct CastToUnsized {
+    pub expr: InFile<AstPtr<ast::Expr>>,
+    pub expr_ty: Type,
+    pub cast_ty: Type,
+}
+
+#[derive(Debug)]
+pub struct TrivialCast {
+    pub expr: InFile<AstPtr<ast::Expr>>,
+    pub ty: Type,
+}
+
+#[derive(Debug)]
+pub struct WrongGenericArgCount {
+    pub path: InFile<AstPtr<ast::Path>>,
+    /// The segment of `path` the arguments were given to.
+    pub segment: Name,
+    pub provided: usize,
+    pub min: usize,
+    pub max: usize,
+}
+
+#[derive(Debug)]
+pub struct UnresolvedAssocType {
+    pub path: InFile<AstPtr<ast::Path>>,
+    pub name: Name,
+}
+
+#[derive(Debug)]
+pub struct AmbiguousAssocType {
+    pub path: InFile<AstPtr<ast::Path>>,
+    pub name: Name,
+    /// The traits in scope that define an associated type called `name`.
+    pub candidates: Vec<Trait>,
+}
+
 pub use hir_ty::diagnostics::IncorrectCase;
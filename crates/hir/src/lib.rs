@@ -30,6 +30,7 @@ mod has_source;
 pub mod diagnostics;
 pub mod db;
 pub mod symbols;
+pub mod term_search;
 
 mod display;
 
@@ -40,12 +41,13 @@ use base_db::{CrateDisplayName, CrateId, CrateOrigin, Edition, FileId, ProcMacro
 use either::Either;
 use hir_def::{
     adt::{ReprKind, VariantData},
-    body::{BodyDiagnostic, SyntheticSyntax},
+    body::{BodyDiagnostic, BodySourceMap, LowerCtx, SyntheticSyntax},
     expr::{BindingAnnotation, LabelId, Pat, PatId},
     generics::{TypeOrConstParamData, TypeParamProvenance},
     item_tree::ItemTreeNode,
     lang_item::LangItemTarget,
     nameres::{self, diagnostics::DefDiagnostic},
+    path::GenericArgs,
     per_ns::PerNs,
     resolver::{HasResolver, Resolver},
     src::HasSource as _,
@@ -66,7 +68,8 @@ use hir_ty::{
     AliasEq, AliasTy, BoundVar, CallableDefId, CallableSig, Canonical, CanonicalVarKinds, Cast,
     ClosureId, DebruijnIndex, GenericArgData, InEnvironment, Interner, ParamKind,
     QuantifiedWhereClause, Scalar, Solution, Substitution, TraitEnvironment, TraitRefExt, Ty,
-    TyBuilder, TyDefId, TyExt, TyKind, TyVariableKind, WhereClause,
+    TyBuilder, TyDefId, TyExt, TyKind, TyLoweringDiagnostic, TyLoweringDiagnosticKind,
+    TyVariableKind, TypeSource, WhereClause,
 };
 use itertools::Itertools;
 use nameres::diagnostics::DefDiagnosticKind;
@@ -75,7 +78,7 @@ use rustc_hash::FxHashSet;
 use stdx::{impl_from, never};
 use syntax::{
     ast::{self, HasAttrs as _, HasDocComments, HasName},
-    AstNode, AstPtr, SmolStr, SyntaxNodePtr, TextRange, T,
+    AstNode, AstPtr, SmolStr, SyntaxNode, SyntaxNodePtr, TextRange, T,
 };
 
 use crate::db::{DefDatabase, HirDatabase};
@@ -83,11 +86,12 @@ use crate::db::{DefDatabase, HirDatabase};
 pub use crate::{
     attrs::{HasAttrs, Namespace},
     diagnostics::{
-        AnyDiagnostic, BreakOutsideOfLoop, InactiveCode, IncorrectCase, InvalidDeriveTarget,
-        MacroError, MalformedDerive, MismatchedArgCount, MissingFields, MissingMatchArms,
-        MissingUnsafe, NoSuchField, ReplaceFilterMapNextWithFindMap, TypeMismatch,
-        UnimplementedBuiltinMacro, UnresolvedExternCrate, UnresolvedImport, UnresolvedMacroCall,
-        UnresolvedModule, UnresolvedProcMacro,
+        AmbiguousAssocType, AnyDiagnostic, BreakOutsideOfLoop, CastToUnsized, InactiveCode,
+        IncorrectCase, InvalidCast, InvalidDeriveTarget, MacroError, MalformedDerive,
+        MismatchedArgCount, MissingFields, MissingMatchArms, MissingUnsafe, NoSuchField,
+        ReplaceFilterMapNextWithFindMap, TrivialCast, TypeMismatch, UnimplementedBuiltinMacro,
+        UnresolvedAssocType, UnresolvedExternCrate, UnresolvedImport, UnresolvedMacroCall,
+        UnresolvedModule, UnresolvedProcMacro, WrongGenericArgCount,
     },
     has_source::HasSource,
     semantics::{PathResolution, Semantics, SemanticsScope, TypeInfo, VisibleTraits},
@@ -103,7 +107,7 @@ pub use crate::{
 // Generally, a refactoring which *removes* a name from this list is a good
 // idea!
 pub use {
-    cfg::{CfgAtom, CfgExpr, CfgOptions},
+    cfg::{CfgAtom, CfgExpr, CfgOptions, CheckCfg, ExpectedValues},
     hir_def::{
         adt::StructKind,
         attr::{Attr, Attrs, AttrsWithOwner, Documentation},
@@ -119,7 +123,7 @@ pub use {
         name::{known, Name},
         ExpandResult, HirFileId, InFile, MacroFile, Origin,
     },
-    hir_ty::display::HirDisplay,
+    hir_ty::{display::HirDisplay, CastError},
 };
 
 // These are negative re-exports: pub using these names is forbidden, they
@@ -242,6 +246,10 @@ impl Crate {
     pub fn potential_cfg(&self, db: &dyn HirDatabase) -> CfgOptions {
         db.crate_graph()[self.id].potential_cfg_options.clone()
     }
+
+    pub fn build_script_cfg(&self, db: &dyn HirDatabase) -> CfgOptions {
+        db.crate_graph()[self.id].build_script_cfg_options.clone()
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -538,6 +546,14 @@ impl Module {
                     }
                     acc.extend(decl.diagnostics(db))
                 }
+                ModuleDef::Adt(adt) => {
+                    adt.ty_lowering_diagnostics(db, acc);
+                    acc.extend(decl.diagnostics(db))
+                }
+                ModuleDef::TypeAlias(type_alias) => {
+                    type_alias.ty_lowering_diagnostics(db, acc);
+                    acc.extend(decl.diagnostics(db))
+                }
                 _ => acc.extend(decl.diagnostics(db)),
             }
         }
@@ -546,12 +562,16 @@ impl Module {
             for diag in db.impl_data_with_diagnostics(impl_def.id).1.iter() {
                 emit_def_diagnostic(db, acc, diag);
             }
+            impl_def.ty_lowering_diagnostics(db, acc);
 
             for item in impl_def.items(db) {
                 let def: DefWithBody = match item {
                     AssocItem::Function(it) => it.into(),
                     AssocItem::Const(it) => it.into(),
-                    AssocItem::TypeAlias(_) => continue,
+                    AssocItem::TypeAlias(it) => {
+                        it.ty_lowering_diagnostics(db, acc);
+                        continue;
+                    }
                 };
 
                 def.diagnostics(db, acc);
@@ -710,6 +730,71 @@ fn emit_def_diagnostic(db: &dyn HirDatabase, acc: &mut Vec<AnyDiagnostic>, diag:
     }
 }
 
+/// The syntax of a type reference lowered with diagnostics, see `hir_ty::TypeSource`.
+enum TypeRefSyntax {
+    Type(ast::Type),
+    Path(ast::Path),
+    GenericArgs(ast::GenericArgList),
+}
+
+/// Maps the problems found while lowering type references back to the paths they were found in.
+/// `syntax` finds the type reference a problem was found in, the problem points to one of its
+/// paths.
+fn emit_ty_lowering_diagnostics(
+    db: &dyn HirDatabase,
+    acc: &mut Vec<AnyDiagnostic>,
+    diagnostics: &[TyLoweringDiagnostic],
+    syntax: impl Fn(TypeSource) -> Option<InFile<TypeRefSyntax>>,
+) {
+    for diag in diagnostics {
+        let syntax = match diag.source {
+            TypeSource::MacroExpansion(file_id) => db
+                .parse_or_expand(file_id)
+                .and_then(|root| root.descendants().find_map(<ast::Type as AstNode>::cast))
+                .map(|it| InFile::new(file_id, TypeRefSyntax::Type(it))),
+            source => syntax(source),
+        };
+        let InFile { file_id, value: syntax } = match syntax {
+            Some(it) => it,
+            None => continue,
+        };
+        let ctx = LowerCtx::new(db.upcast(), file_id);
+        let paths = match syntax {
+            TypeRefSyntax::Type(it) => TypeRef::source_paths(&ctx, it),
+            TypeRefSyntax::Path(it) => Path::source_paths(&ctx, it),
+            TypeRefSyntax::GenericArgs(it) => GenericArgs::source_paths(&ctx, it),
+        };
+        let path = match paths.get(diag.path) {
+            Some(path) => InFile::new(file_id, AstPtr::new(path)),
+            None => continue,
+        };
+        let diag: AnyDiagnostic = match &diag.kind {
+            TyLoweringDiagnosticKind::WrongGenericArgCount { segment, provided, min, max } => {
+                WrongGenericArgCount {
+                    path,
+                    segment: segment.clone(),
+                    provided: *provided,
+                    min: *min,
+                    max: *max,
+                }
+                .into()
+            }
+            TyLoweringDiagnosticKind::UnresolvedAssocType { name } => {
+                UnresolvedAssocType { path, name: name.clone() }.into()
+            }
+            TyLoweringDiagnosticKind::AmbiguousAssocType { name, candidates } => {
+                AmbiguousAssocType {
+                    path,
+                    name: name.clone(),
+                    candidates: candidates.iter().map(|&id| Trait { id }).collect(),
+                }
+                .into()
+            }
+        };
+        acc.push(diag);
+    }
+}
+
 fn precise_macro_call_location(
     ast: &MacroCallKind,
     db: &dyn HirDatabase,
@@ -1002,6 +1087,29 @@ pub enum Adt {
 impl_from!(Struct, Union, Enum for Adt);
 
 impl Adt {
+    fn ty_lowering_diagnostics(self, db: &dyn HirDatabase, acc: &mut Vec<AnyDiagnostic>) {
+        let variants = match self {
+            Adt::Struct(it) => vec![VariantDef::from(it)],
+            Adt::Union(it) => vec![VariantDef::from(it)],
+            Adt::Enum(it) => it.variants(db).into_iter().map(VariantDef::from).collect(),
+        };
+        for variant in variants {
+            let diagnostics = db.field_types_with_diagnostics(variant.into()).1;
+            emit_ty_lowering_diagnostics(db, acc, &diagnostics, |source| {
+                let id = match source {
+                    TypeSource::Field(id) => id,
+                    _ => return None,
+                };
+                let src = Field { parent: variant, id }.source(db)?;
+                let ty = match src.value {
+                    FieldSource::Named(it) => it.ty(),
+                    FieldSource::Pos(it) => it.ty(),
+                };
+                Some(InFile::new(src.file_id, TypeRefSyntax::Type(ty?)))
+            });
+        }
+    }
+
     pub fn has_non_default_type_params(self, db: &dyn HirDatabase) -> bool {
         let subst = db.generic_defaults(self.into());
         subst.iter().any(|ty| match ty.skip_binders().data(Interner) {
@@ -1112,6 +1220,32 @@ impl VariantDef {
     }
 }
 
+/// A pattern that is not covered by the arms of a `match`, see
+/// [`Semantics::missing_match_patterns`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MissingPat {
+    Wild,
+    Bool(bool),
+    /// A struct, union or enum variant, with the patterns of all its fields.
+    Variant(VariantDef, Vec<MissingPat>),
+    Tuple(Vec<MissingPat>),
+    Ref(Box<MissingPat>),
+}
+
+impl From<hir_ty::diagnostics::MissingPat> for MissingPat {
+    fn from(pat: hir_ty::diagnostics::MissingPat) -> MissingPat {
+        use hir_ty::diagnostics::MissingPat as Pat;
+        let pats = |pats: Vec<Pat>| pats.into_iter().map(MissingPat::from).collect();
+        match pat {
+            Pat::Wild => MissingPat::Wild,
+            Pat::Bool(it) => MissingPat::Bool(it),
+            Pat::Variant(variant, fields) => MissingPat::Variant(variant.into(), pats(fields)),
+            Pat::Tuple(fields) => MissingPat::Tuple(pats(fields)),
+            Pat::Ref(it) => MissingPat::Ref(Box::new(MissingPat::from(*it))),
+        }
+    }
+}
+
 /// The defs which have a body.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum DefWithBody {
@@ -1155,6 +1289,135 @@ impl DefWithBody {
         }
     }
 
+    fn ty_lowering_diagnostics(
+        self,
+        db: &dyn HirDatabase,
+        acc: &mut Vec<AnyDiagnostic>,
+        source_map: &BodySourceMap,
+        infer_diagnostics: &[hir_ty::InferenceDiagnostic],
+    ) {
+        let diagnostics = infer_diagnostics
+            .iter()
+            .filter_map(|it| match it {
+                hir_ty::InferenceDiagnostic::TyLowering(it) => Some(it.clone()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        if let DefWithBody::Function(it) = self {
+            let sig_diagnostics = db.fn_sig_with_diagnostics(it.id).1;
+            emit_ty_lowering_diagnostics(db, acc, &sig_diagnostics, |source| {
+                let src = it.source(db)?;
+                let ty = match source {
+                    TypeSource::Param(idx) => {
+                        let params = src.value.param_list()?;
+                        match params.self_param() {
+                            Some(self_param) => match idx.checked_sub(1) {
+                                Some(idx) => params.params().nth(idx)?.ty(),
+                                None => self_param.ty(),
+                            },
+                            None => params.params().nth(idx)?.ty(),
+                        }
+                    }
+                    TypeSource::RetType => src.value.ret_type()?.ty(),
+                    _ => None,
+                };
+                Some(InFile::new(src.file_id, TypeRefSyntax::Type(ty?)))
+            });
+        }
+        emit_ty_lowering_diagnostics(db, acc, &diagnostics, |source| {
+            self.body_type_ref_syntax(db, source_map, source)
+        });
+    }
+
+    /// Finds the syntax of a type reference lowered while inferring the body.
+    fn body_type_ref_syntax(
+        self,
+        db: &dyn HirDatabase,
+        source_map: &BodySourceMap,
+        source: TypeSource,
+    ) -> Option<InFile<TypeRefSyntax>> {
+        let expr_syntax = |expr| {
+            let ptr = source_map.expr_syntax(expr).ok()?;
+            let root = db.parse_or_expand(ptr.file_id)?;
+            Some(ptr.map(|it| it.to_node(&root)))
+        };
+        let pat_syntax = |pat| {
+            let ptr = source_map.pat_syntax(pat).ok()?;
+            let root = db.parse_or_expand(ptr.file_id)?;
+            Some(InFile::new(ptr.file_id, ptr.value.left()?.to_node(&root)))
+        };
+        let (file_id, syntax) = match source {
+            TypeSource::Item => {
+                let src = match self {
+                    DefWithBody::Static(it) => it.source(db)?.map(|it| it.ty()),
+                    DefWithBody::Const(it) => it.source(db)?.map(|it| it.ty()),
+                    DefWithBody::Function(_) => return None,
+                };
+                (src.file_id, TypeRefSyntax::Type(src.value?))
+            }
+            TypeSource::Let(pat) => {
+                let pat = pat_syntax(pat)?;
+                let let_stmt =
+                    pat.value.syntax().parent().and_then(<ast::LetStmt as AstNode>::cast)?;
+                (pat.file_id, TypeRefSyntax::Type(let_stmt.ty()?))
+            }
+            TypeSource::Cast(expr) => match expr_syntax(expr)? {
+                InFile { file_id, value: ast::Expr::CastExpr(it) } => {
+                    (file_id, TypeRefSyntax::Type(it.ty()?))
+                }
+                _ => return None,
+            },
+            TypeSource::ClosureParam(expr, idx) => match expr_syntax(expr)? {
+                InFile { file_id, value: ast::Expr::ClosureExpr(it) } => {
+                    (file_id, TypeRefSyntax::Type(it.param_list()?.params().nth(idx)?.ty()?))
+                }
+                _ => return None,
+            },
+            TypeSource::ClosureRet(expr) => match expr_syntax(expr)? {
+                InFile { file_id, value: ast::Expr::ClosureExpr(it) } => {
+                    (file_id, TypeRefSyntax::Type(it.ret_type()?.ty()?))
+                }
+                _ => return None,
+            },
+            TypeSource::MethodGenericArgs(expr) => match expr_syntax(expr)? {
+                InFile { file_id, value: ast::Expr::MethodCallExpr(it) } => {
+                    (file_id, TypeRefSyntax::GenericArgs(it.generic_arg_list()?))
+                }
+                _ => return None,
+            },
+            TypeSource::ExprPath(expr) => {
+                let expr = expr_syntax(expr)?;
+                let path = match expr.value {
+                    ast::Expr::PathExpr(it) => it.path(),
+                    ast::Expr::RecordExpr(it) => it.path(),
+                    // Tuple struct patterns in destructuring assignments.
+                    ast::Expr::CallExpr(it) => match it.expr() {
+                        Some(ast::Expr::PathExpr(it)) => it.path(),
+                        _ => None,
+                    },
+                    _ => None,
+                };
+                (expr.file_id, TypeRefSyntax::Path(path?))
+            }
+            TypeSource::PatPath(pat) => {
+                let pat = pat_syntax(pat)?;
+                let path = match pat.value {
+                    ast::Pat::PathPat(it) => it.path(),
+                    ast::Pat::RecordPat(it) => it.path(),
+                    ast::Pat::TupleStructPat(it) => it.path(),
+                    _ => None,
+                };
+                (pat.file_id, TypeRefSyntax::Path(path?))
+            }
+            TypeSource::Field(_)
+            | TypeSource::Param(_)
+            | TypeSource::RetType
+            | TypeSource::ImplTrait
+            | TypeSource::MacroExpansion(_) => return None,
+        };
+        Some(InFile::new(file_id, syntax))
+    }
+
     /// A textual representation of the HIR of this def's body for debugging purposes.
     pub fn debug_hir(self, db: &dyn HirDatabase) -> String {
         let body = db.body(self.id());
@@ -1235,8 +1498,47 @@ impl DefWithBody {
                         Err(SyntheticSyntax) => (),
                     }
                 }
+                // Mapped back to the source below, together with those of the signature.
+                hir_ty::InferenceDiagnostic::TyLowering(_) => (),
+                hir_ty::InferenceDiagnostic::InvalidCast { expr, error, expr_ty, cast_ty } => {
+                    if let Ok(expr) = source_map.expr_syntax(*expr) {
+                        acc.push(
+                            InvalidCast {
+                                expr,
+                                error: *error,
+                                expr_ty: Type::new(db, DefWithBodyId::from(self), expr_ty.clone()),
+                                cast_ty: Type::new(db, DefWithBodyId::from(self), cast_ty.clone()),
+                            }
+                            .into(),
+                        )
+                    }
+                }
+                hir_ty::InferenceDiagnostic::CastToUnsized { expr, expr_ty, cast_ty } => {
+                    if let Ok(expr) = source_map.expr_syntax(*expr) {
+                        acc.push(
+                            CastToUnsized {
+                                expr,
+                                expr_ty: Type::new(db, DefWithBodyId::from(self), expr_ty.clone()),
+                                cast_ty: Type::new(db, DefWithBodyId::from(self), cast_ty.clone()),
+                            }
+                            .into(),
+                        )
+                    }
+                }
+                hir_ty::InferenceDiagnostic::TrivialCast { expr, ty } => {
+                    if let Ok(expr) = source_map.expr_syntax(*expr) {
+                        acc.push(
+                            TrivialCast {
+                                expr,
+                                ty: Type::new(db, DefWithBodyId::from(self), ty.clone()),
+                            }
+                            .into(),
+                        )
+                    }
+                }
             }
         }
+        self.ty_lowering_diagnostics(db, acc, &source_map, &infer.diagnostics);
         for (expr, mismatch) in infer.expr_type_mismatches() {
             let expr = match source_map.expr_syntax(expr) {
                 Ok(expr) => expr,
@@ -1755,6 +2057,18 @@ pub struct TypeAlias {
 }
 
 impl TypeAlias {
+    fn ty_lowering_diagnostics(self, db: &dyn HirDatabase, acc: &mut Vec<AnyDiagnostic>) {
+        if let Some(src) = self.source(db) {
+            let diagnostics = db.type_alias_ty_with_diagnostics(self.id).1;
+            emit_ty_lowering_diagnostics(db, acc, &diagnostics, |source| match source {
+                TypeSource::Item => {
+                    Some(InFile::new(src.file_id, TypeRefSyntax::Type(src.value.ty()?)))
+                }
+                _ => None,
+            });
+        }
+    }
+
     pub fn has_non_default_type_params(self, db: &dyn HirDatabase) -> bool {
         let subst = db.generic_defaults(self.id.into());
         subst.iter().any(|ty| match ty.skip_binders().data(Interner) {
@@ -2584,6 +2898,23 @@ pub struct Impl {
 }
 
 impl Impl {
+    fn ty_lowering_diagnostics(self, db: &dyn HirDatabase, acc: &mut Vec<AnyDiagnostic>) {
+        if let Some(src) = self.source(db) {
+            let syntax = |source| {
+                let ty = match source {
+                    TypeSource::Item => src.value.self_ty(),
+                    TypeSource::ImplTrait => src.value.trait_(),
+                    _ => None,
+                };
+                Some(InFile::new(src.file_id, TypeRefSyntax::Type(ty?)))
+            };
+            let diagnostics = db.impl_self_ty_with_diagnostics(self.id).1;
+            emit_ty_lowering_diagnostics(db, acc, &diagnostics, syntax);
+            let diagnostics = db.impl_trait_with_diagnostics(self.id).1;
+            emit_ty_lowering_diagnostics(db, acc, &diagnostics, syntax);
+        }
+    }
+
     pub fn all_in_crate(db: &dyn HirDatabase, krate: Crate) -> Vec<Impl> {
         let inherent = db.inherent_impls_in_crate(krate.id);
         let trait_ = db.trait_impls_in_crate(krate.id);
@@ -2997,7 +3328,26 @@ impl Type {
             TyKind::Adt(hir_ty::AdtId(AdtId::UnionId(u)), substs) => ((*u).into(), substs),
             _ => return Vec::new(),
         };
+        self.fields_of(db, variant_id, substs)
+    }
 
+    /// Returns the fields of `variant` if this is the type of its enum, with the generic
+    /// arguments of the type substituted in.
+    pub fn variant_fields(&self, db: &dyn HirDatabase, variant: Variant) -> Vec<(Field, Type)> {
+        match self.ty.kind(Interner) {
+            TyKind::Adt(hir_ty::AdtId(AdtId::EnumId(e)), substs) if *e == variant.parent.id => {
+                self.fields_of(db, VariantDef::from(variant).into(), substs)
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn fields_of(
+        &self,
+        db: &dyn HirDatabase,
+        variant_id: hir_def::VariantId,
+        substs: &Substitution,
+    ) -> Vec<(Field, Type)> {
         db.field_types(variant_id)
             .iter()
             .map(|(local_id, ty)| {
@@ -30,8 +30,8 @@ use crate::{
     semantics::source_to_def::{ChildContainer, SourceToDefCache, SourceToDefCtx},
     source_analyzer::{resolve_hir_path, SourceAnalyzer},
     Access, BindingMode, BuiltinAttr, Callable, ConstParam, Crate, DeriveHelper, Field, Function,
-    HasSource, HirFileId, Impl, InFile, Label, LifetimeParam, Local, Macro, Module, ModuleDef,
-    Name, Path, ScopeDef, ToolModule, Trait, Type, TypeAlias, TypeParam, VariantDef,
+    HasSource, HirFileId, Impl, InFile, Label, LifetimeParam, Local, Macro, MissingPat, Module,
+    ModuleDef, Name, Path, ScopeDef, ToolModule, Trait, Type, TypeAlias, TypeParam, VariantDef,
 };
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -432,6 +432,15 @@ impl<'db, DB: HirDatabase> Semantics<'db, DB> {
         self.imp.record_pattern_missing_fields(pattern)
     }
 
+    /// Returns the patterns not covered by the arms of `match_expr`, ignoring `skipped_arm`.
+    pub fn missing_match_patterns(
+        &self,
+        match_expr: &ast::MatchExpr,
+        skipped_arm: Option<&ast::MatchArm>,
+    ) -> Option<Vec<MissingPat>> {
+        self.imp.missing_match_patterns(match_expr, skipped_arm)
+    }
+
     pub fn to_def<T: ToDef>(&self, src: &T) -> Option<T::Def> {
         let src = self.imp.find_file(src.syntax()).with_value(src).cloned();
         T::to_def(&self.imp, src)
@@ -1185,6 +1194,14 @@ impl<'db> SemanticsImpl<'db> {
             .unwrap_or_default()
     }
 
+    fn missing_match_patterns(
+        &self,
+        match_expr: &ast::MatchExpr,
+        skipped_arm: Option<&ast::MatchArm>,
+    ) -> Option<Vec<MissingPat>> {
+        self.analyze(match_expr.syntax())?.missing_match_patterns(self.db, match_expr, skipped_arm)
+    }
+
     fn with_ctx<F: FnOnce(&mut SourceToDefCtx<'_, '_>) -> T, T>(&self, f: F) -> T {
         let mut cache = self.s2d_cache.borrow_mut();
         let mut ctx = SourceToDefCtx { db: self.db, cache: &mut *cache };
@@ -34,8 +34,8 @@ use hir_expand::{
 };
 use hir_ty::{
     diagnostics::{
-        record_literal_missing_fields, record_pattern_missing_fields, unsafe_expressions,
-        UnsafeExpr,
+        missing_match_patterns, record_literal_missing_fields, record_pattern_missing_fields,
+        unsafe_expressions, UnsafeExpr,
     },
     method_resolution::{self, lang_names_for_bin_op},
     Adjust, Adjustment, AutoBorrow, InferenceResult, Interner, Substitution, Ty, TyExt, TyKind,
@@ -50,8 +50,8 @@ use syntax::{
 
 use crate::{
     db::HirDatabase, semantics::PathResolution, Adt, AssocItem, BindingMode, BuiltinAttr,
-    BuiltinType, Callable, Const, DeriveHelper, Field, Function, Local, Macro, ModuleDef, Static,
-    Struct, ToolModule, Trait, Type, TypeAlias, Variant,
+    BuiltinType, Callable, Const, DeriveHelper, Field, Function, Local, Macro, MissingPat,
+    ModuleDef, Static, Struct, ToolModule, Trait, Type, TypeAlias, Variant,
 };
 
 /// `SourceAnalyzer` is a convenience wrapper which exposes HIR API in terms of
@@ -664,6 +664,24 @@ impl SourceAnalyzer {
         Some(res)
     }
 
+    pub(crate) fn missing_match_patterns(
+        &self,
+        db: &dyn HirDatabase,
+        match_expr: &ast::MatchExpr,
+        skipped_arm: Option<&ast::MatchArm>,
+    ) -> Option<Vec<MissingPat>> {
+        let (owner, body, _) = self.def.as_ref()?;
+        let infer = self.infer.as_ref()?;
+
+        let expr_id = self.expr_id(db, &match_expr.clone().into())?;
+        let skipped_arm = match skipped_arm {
+            Some(arm) => Some(self.pat_id(&arm.pat()?)?),
+            None => None,
+        };
+        let res = missing_match_patterns(db, *owner, infer, body, expr_id, skipped_arm)?;
+        Some(res.into_iter().map(MissingPat::from).collect())
+    }
+
     fn missing_fields(
         &self,
         db: &dyn HirDatabase,
@@ -0,0 +1,355 @@
+//! Type-directed search for expressions of a given type.
+//!
+//! Starting from the locals, constants and statics in scope, the search repeatedly builds bigger
+//! terms out of the ones it already knows: field projections, ADT constructors, free and
+//! associated functions and methods. It only explores non-generic items and gives up after a
+//! fixed number of rounds, so it is cheap enough to run on every completion request.
+
+use hir_def::DefWithBodyId;
+
+use crate::{
+    db::HirDatabase, Adt, AsAssocItem, AssocItem, AssocItemContainer, Const, Field, Function,
+    GenericDef, HasVisibility, Impl, Local, ModuleDef, ScopeDef, SemanticsScope, Static,
+    StructKind, Type, Variant,
+};
+
+/// An expression found by the term search.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    Local(Local),
+    Const(Const),
+    Static(Static),
+    /// A call of a free or associated function without `self`.
+    Function {
+        func: Function,
+        params: Vec<Expr>,
+    },
+    /// A method call on `target`.
+    Method {
+        func: Function,
+        target: Box<Expr>,
+        params: Vec<Expr>,
+    },
+    /// An enum variant constructor.
+    Variant {
+        variant: Variant,
+        params: Vec<Expr>,
+    },
+    /// A struct constructor.
+    Struct {
+        strukt: crate::Struct,
+        params: Vec<Expr>,
+    },
+    /// A field projection.
+    Field {
+        expr: Box<Expr>,
+        field: Field,
+    },
+}
+
+impl Expr {
+    /// Renders the expression as source code valid in `sema_scope`.
+    pub fn gen_source_code(&self, sema_scope: &SemanticsScope<'_>) -> Option<String> {
+        let db = sema_scope.db;
+        let module = sema_scope.module();
+        let path = |def: ModuleDef| -> Option<String> {
+            module.find_use_path(db.upcast(), def).map(|it| it.to_string())
+        };
+        let args = |params: &[Expr]| -> Option<String> {
+            let params = params
+                .iter()
+                .map(|it| it.gen_source_code(sema_scope))
+                .collect::<Option<Vec<_>>>()?;
+            Some(params.join(", "))
+        };
+        let res = match self {
+            Expr::Local(local) => local.name(db).to_string(),
+            Expr::Const(it) => path(ModuleDef::Const(*it))?,
+            Expr::Static(it) => path(ModuleDef::Static(*it))?,
+            Expr::Function { func, params } => {
+                let name = match func.as_assoc_item(db).map(|it| it.container(db)) {
+                    Some(AssocItemContainer::Impl(impl_)) => {
+                        let adt = impl_.self_ty(db).as_adt()?;
+                        format!("{}::{}", path(ModuleDef::Adt(adt))?, func.name(db))
+                    }
+                    Some(AssocItemContainer::Trait(_)) => return None,
+                    None => path(ModuleDef::Function(*func))?,
+                };
+                format!("{}({})", name, args(params)?)
+            }
+            Expr::Method { func, target, params } => {
+                let target = target.gen_source_code(sema_scope)?;
+                format!("{}.{}({})", target, func.name(db), args(params)?)
+            }
+            Expr::Variant { variant, params } => {
+                let name = path(ModuleDef::Variant(*variant))?;
+                let fields = variant.fields(db);
+                constructor(db, name, variant.kind(db), &fields, params, sema_scope)?
+            }
+            Expr::Struct { strukt, params } => {
+                let name = path(ModuleDef::Adt(Adt::Struct(*strukt)))?;
+                let fields = strukt.fields(db);
+                constructor(db, name, strukt.kind(db), &fields, params, sema_scope)?
+            }
+            Expr::Field { expr, field } => {
+                format!("{}.{}", expr.gen_source_code(sema_scope)?, field.name(db))
+            }
+        };
+        Some(res)
+    }
+
+    /// The type of the expression.
+    pub fn ty(&self, db: &dyn HirDatabase) -> Type {
+        match self {
+            Expr::Local(it) => it.ty(db),
+            Expr::Const(it) => it.ty(db),
+            Expr::Static(it) => it.ty(db),
+            Expr::Function { func, .. } | Expr::Method { func, .. } => func.ret_type(db),
+            Expr::Variant { variant, .. } => Adt::Enum(variant.parent_enum(db)).ty(db),
+            Expr::Struct { strukt, .. } => Adt::Struct(*strukt).ty(db),
+            Expr::Field { field, .. } => field.ty(db),
+        }
+    }
+
+    /// The number of nodes in the expression, used to prefer simpler terms.
+    pub fn size(&self) -> usize {
+        match self {
+            Expr::Local(_) | Expr::Const(_) | Expr::Static(_) => 1,
+            Expr::Function { params, .. }
+            | Expr::Variant { params, .. }
+            | Expr::Struct { params, .. } => 1 + params.iter().map(Expr::size).sum::<usize>(),
+            Expr::Method { target, params, .. } => {
+                1 + target.size() + params.iter().map(Expr::size).sum::<usize>()
+            }
+            Expr::Field { expr, .. } => 1 + expr.size(),
+        }
+    }
+}
+
+fn constructor(
+    db: &dyn HirDatabase,
+    name: String,
+    kind: StructKind,
+    fields: &[Field],
+    params: &[Expr],
+    sema_scope: &SemanticsScope<'_>,
+) -> Option<String> {
+    let res = match kind {
+        StructKind::Unit => name,
+        StructKind::Tuple => {
+            let params = params
+                .iter()
+                .map(|it| it.gen_source_code(sema_scope))
+                .collect::<Option<Vec<_>>>()?;
+            format!("{}({})", name, params.join(", "))
+        }
+        StructKind::Record => {
+            let fields = fields
+                .iter()
+                .zip(params)
+                .map(|(field, param)| {
+                    let param = param.gen_source_code(sema_scope)?;
+                    let field = field.name(db).to_string();
+                    Some(if field == param { field } else { format!("{}: {}", field, param) })
+                })
+                .collect::<Option<Vec<_>>>()?;
+            format!("{} {{ {} }}", name, fields.join(", "))
+        }
+    };
+    Some(res)
+}
+
+/// Limits of the term search.
+#[derive(Debug, Clone, Copy)]
+pub struct TermSearchConfig {
+    /// The number of rounds of building new terms from known ones.
+    pub depth: usize,
+    /// The number of terms kept for a single type.
+    pub max_alternatives: usize,
+}
+
+impl Default for TermSearchConfig {
+    fn default() -> Self {
+        TermSearchConfig { depth: 3, max_alternatives: 4 }
+    }
+}
+
+/// Searches for expressions that could unify with `goal`, simplest ones first.
+pub fn term_search(
+    sema_scope: &SemanticsScope<'_>,
+    goal: &Type,
+    config: TermSearchConfig,
+) -> Vec<Expr> {
+    let db = sema_scope.db;
+    let module = sema_scope.module();
+
+    let mut lookup = Lookup { terms: Vec::new(), max_alternatives: config.max_alternatives };
+    let mut functions = Vec::new();
+    let mut adts = Vec::new();
+    sema_scope.process_all_names(&mut |_, def| match def {
+        ScopeDef::Local(local) => lookup.insert(local.ty(db), Expr::Local(local)),
+        ScopeDef::ModuleDef(ModuleDef::Const(it)) => lookup.insert(it.ty(db), Expr::Const(it)),
+        ScopeDef::ModuleDef(ModuleDef::Static(it)) => lookup.insert(it.ty(db), Expr::Static(it)),
+        ScopeDef::ModuleDef(ModuleDef::Function(it)) => functions.push(it),
+        ScopeDef::ModuleDef(ModuleDef::Adt(it)) => adts.push(it),
+        _ => (),
+    });
+    // FIXME: Generic ADTs and functions are skipped, as their type parameters would have to be
+    // instantiated to match the goal, which means that e.g. `Vec::new()` is never suggested.
+    adts.retain(|adt| GenericDef::Adt(*adt).type_params(db).is_empty());
+    for adt in &adts {
+        for impl_ in Impl::all_for_type(db, adt.ty(db)) {
+            if impl_.trait_(db).is_some() || !GenericDef::Impl(impl_).type_params(db).is_empty() {
+                continue;
+            }
+            functions.extend(impl_.items(db).into_iter().filter_map(|it| match it {
+                AssocItem::Function(f) => Some(f),
+                _ => None,
+            }));
+        }
+    }
+    // Calling the function the search happens in would just recurse.
+    let enclosing_fn = match sema_scope.resolver().body_owner() {
+        Some(DefWithBodyId::FunctionId(it)) => Some(Function::from(it)),
+        _ => None,
+    };
+    functions.retain(|f| {
+        Some(*f) != enclosing_fn
+            && GenericDef::Function(*f).type_params(db).is_empty()
+            && !f.is_unsafe_to_call(db)
+            && !f.is_async(db)
+            && f.is_visible_from(db, module)
+            && !f.ret_type(db).is_unit()
+    });
+
+    for _ in 0..config.depth {
+        let mut new_terms = Vec::new();
+
+        // Field projections.
+        for (ty, exprs) in &lookup.terms {
+            for (field, field_ty) in ty.fields(db) {
+                if !field.is_visible_from(db, module) {
+                    continue;
+                }
+                for expr in exprs {
+                    let expr = Expr::Field { expr: Box::new(expr.clone()), field };
+                    new_terms.push((field_ty.clone(), expr));
+                }
+            }
+        }
+
+        // Constructors.
+        for adt in &adts {
+            if !adt.is_visible_from(db, module) {
+                continue;
+            }
+            match adt {
+                Adt::Struct(strukt) => {
+                    let fields = strukt.fields(db);
+                    if fields.iter().any(|it| !it.is_visible_from(db, module)) {
+                        continue;
+                    }
+                    if let Some(params) = lookup.find_all(db, fields.iter().map(|it| it.ty(db))) {
+                        new_terms.push((adt.ty(db), Expr::Struct { strukt: *strukt, params }));
+                    }
+                }
+                Adt::Enum(enum_) => {
+                    for variant in enum_.variants(db) {
+                        let field_tys =
+                            variant.fields(db).iter().map(|it| it.ty(db)).collect::<Vec<_>>();
+                        if let Some(params) = lookup.find_all(db, field_tys) {
+                            new_terms.push((adt.ty(db), Expr::Variant { variant, params }));
+                        }
+                    }
+                }
+                Adt::Union(_) => (),
+            }
+        }
+
+        // Function and method calls.
+        for &func in &functions {
+            let ret_ty = func.ret_type(db);
+            let param_tys = func.params_without_self(db).into_iter().map(|it| it.ty().clone());
+            let params = match lookup.find_all(db, param_tys) {
+                Some(it) => it,
+                None => continue,
+            };
+            match func.self_param(db) {
+                None => new_terms.push((ret_ty, Expr::Function { func, params })),
+                Some(self_param) => {
+                    let self_ty = self_param.ty(db);
+                    let self_ty = match self_param.access(db) {
+                        crate::Access::Owned => self_ty,
+                        _ => self_ty.remove_ref().unwrap_or(self_ty),
+                    };
+                    let targets = lookup.find(db, &self_ty).to_vec();
+                    for target in targets {
+                        let expr =
+                            Expr::Method { func, target: Box::new(target), params: params.clone() };
+                        new_terms.push((ret_ty.clone(), expr));
+                    }
+                }
+            }
+        }
+
+        let known = lookup.len();
+        for (ty, expr) in new_terms {
+            lookup.insert(ty, expr);
+        }
+        if lookup.len() == known {
+            break;
+        }
+    }
+
+    let mut res = lookup
+        .terms
+        .into_iter()
+        .filter(|(ty, _)| ty.could_unify_with(db, goal))
+        .flat_map(|(_, exprs)| exprs)
+        .collect::<Vec<_>>();
+    res.sort_by_key(Expr::size);
+    res
+}
+
+/// The known terms, grouped by their type.
+struct Lookup {
+    terms: Vec<(Type, Vec<Expr>)>,
+    max_alternatives: usize,
+}
+
+impl Lookup {
+    fn insert(&mut self, ty: Type, expr: Expr) {
+        if ty.is_unknown() || ty.is_unit() {
+            return;
+        }
+        match self.terms.iter_mut().find(|(it, _)| *it == ty) {
+            Some((_, exprs)) => {
+                if exprs.len() < self.max_alternatives && !exprs.contains(&expr) {
+                    exprs.push(expr);
+                }
+            }
+            None => self.terms.push((ty, vec![expr])),
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.terms.iter().map(|(_, exprs)| exprs.len()).sum()
+    }
+
+    fn find(&self, db: &dyn HirDatabase, ty: &Type) -> &[Expr] {
+        self.terms
+            .iter()
+            .find(|(it, _)| it == ty)
+            .or_else(|| self.terms.iter().find(|(it, _)| it.could_unify_with(db, ty)))
+            .map_or(&[], |(_, exprs)| exprs)
+    }
+
+    /// Finds one term for each of `tys`.
+    fn find_all(
+        &self,
+        db: &dyn HirDatabase,
+        tys: impl IntoIterator<Item = Type>,
+    ) -> Option<Vec<Expr>> {
+        tys.into_iter().map(|ty| self.find(db, &ty).first().cloned()).collect()
+    }
+}
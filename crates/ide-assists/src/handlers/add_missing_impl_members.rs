@@ -2,13 +2,16 @@ use hir::HasSource;
 use ide_db::{
     syntax_helpers::insert_whitespace_into_node::insert_ws_into, traits::resolve_target_trait,
 };
-use syntax::ast::{self, make, AstNode};
+use syntax::{
+    ast::{self, make, AstNode},
+    Direction,
+};
 
 use crate::{
     assist_context::{AssistContext, Assists},
     utils::{
         add_trait_assoc_items_to_impl, filter_assoc_items, gen_trait_fn_body, render_snippet,
-        Cursor, DefaultMethods,
+        Cursor, DefaultMethods, ImplTypes,
     },
     AssistId, AssistKind,
 };
@@ -140,6 +143,13 @@ fn add_missing_impl_members_inner(
             impl_def.clone(),
             target_scope,
         );
+        if let DefaultMethods::No = mode {
+            first_new_item.syntax().siblings(Direction::Next).filter_map(ast::Fn::cast).for_each(
+                |func| {
+                    let _ = try_gen_trait_body(ctx, &func, &trait_, &impl_def);
+                },
+            );
+        }
         match ctx.config.snippet_cap {
             None => builder.replace(target, new_impl_def.to_string()),
             Some(cap) => {
@@ -147,14 +157,11 @@ fn add_missing_impl_members_inner(
                 let placeholder;
                 if let DefaultMethods::No = mode {
                     if let ast::AssocItem::Fn(func) = &first_new_item {
-                        if try_gen_trait_body(ctx, func, &trait_, &impl_def).is_none() {
-                            if let Some(m) =
-                                func.syntax().descendants().find_map(ast::MacroCall::cast)
-                            {
-                                if m.syntax().text() == "todo!()" {
-                                    placeholder = m;
-                                    cursor = Cursor::Replace(placeholder.syntax());
-                                }
+                        if let Some(m) = func.syntax().descendants().find_map(ast::MacroCall::cast)
+                        {
+                            if m.syntax().text() == "todo!()" {
+                                placeholder = m;
+                                cursor = Cursor::Replace(placeholder.syntax());
                             }
                         }
                     }
@@ -178,7 +185,14 @@ fn try_gen_trait_body(
     let trait_path = make::ext::ident_path(&trait_.name(ctx.db()).to_string());
     let hir_ty = ctx.sema.resolve_type(&impl_def.self_ty()?)?;
     let adt = hir_ty.as_adt()?.source(ctx.db())?;
-    gen_trait_fn_body(func, &trait_path, &adt.value)
+    let trait_arg = match impl_def.trait_()? {
+        ast::Type::PathType(it) => it.path()?.segment()?.generic_arg_list(),
+        _ => None,
+    }
+    .and_then(|it| it.generic_args().find_map(|it| ast::TypeArg::cast(it.syntax().clone())))
+    .and_then(|it| ctx.sema.resolve_type(&it.ty()?));
+    let impl_types = ImplTypes { db: ctx.db(), self_ty: hir_ty, trait_arg };
+    gen_trait_fn_body(func, &trait_path, &adt.value, Some(&impl_types))
 }
 
 #[cfg(test)]
@@ -1334,6 +1348,169 @@ impl PartialEq for SomeStruct {
             !self.eq(other)
         }
 }
+"#,
+        );
+    }
+
+    #[test]
+    fn test_from_impl_body() {
+        check_assist(
+            add_missing_impl_members,
+            r#"
+//- minicore: from
+struct Wrapper(u32);
+impl From<u32> for Wrapper {$0}
+"#,
+            r#"
+struct Wrapper(u32);
+impl From<u32> for Wrapper {
+    $0fn from(value: u32) -> Self {
+        Self(value)
+    }
+}
+"#,
+        );
+        check_assist(
+            add_missing_impl_members,
+            r#"
+//- minicore: from
+enum Error {
+    Io { code: i32 },
+    Parse(u8, u8),
+    Other(String),
+}
+impl From<i32> for Error {$0}
+"#,
+            r#"
+enum Error {
+    Io { code: i32 },
+    Parse(u8, u8),
+    Other(String),
+}
+impl From<i32> for Error {
+    $0fn from(value: i32) -> Self {
+        Self::Io { code: value }
+    }
+}
+"#,
+        );
+        check_assist(
+            add_missing_impl_members,
+            r#"
+//- minicore: from
+type Id = u32;
+struct Key { id: Id }
+struct Wrapper<T>(T);
+impl From<u32> for Key {}
+impl<T> From<T> for Wrapper<T> {$0}
+"#,
+            r#"
+type Id = u32;
+struct Key { id: Id }
+struct Wrapper<T>(T);
+impl From<u32> for Key {}
+impl<T> From<T> for Wrapper<T> {
+    $0fn from(value: T) -> Self {
+        Self(value)
+    }
+}
+"#,
+        );
+        check_assist(
+            add_missing_impl_members,
+            r#"
+//- minicore: from
+type Id = u32;
+struct Key { id: Id }
+impl From<u32> for Key {$0}
+"#,
+            r#"
+type Id = u32;
+struct Key { id: Id }
+impl From<u32> for Key {
+    $0fn from(value: u32) -> Self {
+        Self { id: value }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_display_impl_body() {
+        check_assist(
+            add_missing_impl_members,
+            r#"
+struct Formatter;
+type Result = core::result::Result<(), ()>;
+trait Display {
+    fn fmt(&self, f: &mut Formatter) -> Result;
+}
+enum Shape {
+    Empty,
+    Circle(f32),
+    Rect { w: f32, h: f32 },
+}
+impl Display for Shape {$0}
+"#,
+            r#"
+struct Formatter;
+type Result = core::result::Result<(), ()>;
+trait Display {
+    fn fmt(&self, f: &mut Formatter) -> Result;
+}
+enum Shape {
+    Empty,
+    Circle(f32),
+    Rect { w: f32, h: f32 },
+}
+impl Display for Shape {
+    $0fn fmt(&self, f: &mut Formatter) -> Result {
+        match self {
+            Self::Empty => write!(f, "Empty"),
+            Self::Circle(arg0) => write!(f, "{}", arg0),
+            Self::Rect { w, h } => write!(f, "{} {}", w, h),
+        }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_deref_impl_body() {
+        check_assist(
+            add_missing_impl_members,
+            r#"
+//- minicore: deref_mut
+struct Meters { value: f64 }
+impl core::ops::Deref for Meters {$0}
+"#,
+            r#"
+struct Meters { value: f64 }
+impl core::ops::Deref for Meters {
+    $0type Target = f64;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+"#,
+        );
+        check_assist(
+            add_missing_impl_members,
+            r#"
+//- minicore: deref_mut
+struct Meters(f64);
+impl core::ops::DerefMut for Meters {$0}
+"#,
+            r#"
+struct Meters(f64);
+impl core::ops::DerefMut for Meters {
+    $0fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
 "#,
         );
     }
@@ -0,0 +1,580 @@
+use either::Either;
+use hir::{HirDisplay, PathResolution};
+use ide_db::{defs::Definition, FxHashMap};
+use itertools::Itertools;
+use stdx::format_to;
+use syntax::{
+    ast::{self, edit::IndentLevel, AstNode, HasArgList, HasGenericParams, HasName},
+    match_ast, TextRange,
+};
+
+use crate::{AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: convert_closure_to_fn
+//
+// Converts a closure bound to a local variable into a nested function. Variables captured by the
+// closure become additional parameters, which are passed at every call.
+//
+// ```
+// # //- minicore: copy
+// fn main() {
+//     let n = 2;
+//     let add$0 = |x: u32| x + n;
+//     add(1);
+// }
+// ```
+// ->
+// ```
+// fn main() {
+//     let n = 2;
+//     fn add(x: u32, n: u32) -> u32 {
+//         x + n
+//     }
+//     add(1, n);
+// }
+// ```
+pub(crate) fn convert_closure_to_fn(acc: &mut Assists, ctx: &AssistContext<'_>) -> Option<()> {
+    let let_stmt = ctx.find_node_at_offset::<ast::LetStmt>()?;
+    let closure = match let_stmt.initializer()? {
+        ast::Expr::ClosureExpr(it) => it,
+        _ => return None,
+    };
+    let body = closure.body()?;
+    if ctx.offset() >= body.syntax().text_range().start() || closure.async_token().is_some() {
+        return None;
+    }
+    let pat = match let_stmt.pat()? {
+        ast::Pat::IdentPat(it) if it.pat().is_none() && it.ref_token().is_none() => it,
+        _ => return None,
+    };
+    let name = pat.name()?;
+    let local = ctx.sema.to_def(&pat)?;
+    let module = ctx.sema.scope(closure.syntax())?.module();
+    let db = ctx.db();
+
+    // Nested functions can't refer to the generic parameters of the enclosing items.
+    let outer_generics = outer_generic_names(&let_stmt);
+    let check_generics = |text: String| {
+        let mentions_outer_generic = text
+            .split(|c: char| !c.is_alphanumeric() && c != '_')
+            .any(|word| outer_generics.iter().any(|it| it == word));
+        (!mentions_outer_generic).then(|| text)
+    };
+    let render_ty = |ty: &hir::Type| {
+        if ty.contains_unknown() {
+            return None;
+        }
+        check_generics(ty.display_source_code(db, module.into()).ok()?)
+    };
+
+    let callable = ctx.sema.type_of_expr(&closure.clone().into())?.original.as_callable(db)?;
+    let mut params = Vec::new();
+    for (param, (_, ty)) in closure.param_list()?.params().zip(callable.params(db)) {
+        let ty = match param.ty() {
+            Some(it) => check_generics(it.to_string())?,
+            None => render_ty(&ty)?,
+        };
+        params.push(format!("{}: {}", param.pat()?, ty));
+    }
+    let ret_ty = match closure.ret_type() {
+        Some(it) => Some(check_generics(it.ty()?.to_string())?),
+        None => {
+            let ty = callable.return_type();
+            if ty.is_unit() {
+                None
+            } else {
+                Some(render_ty(&ty)?)
+            }
+        }
+    };
+
+    let captures = captures(ctx, &closure, &body)?;
+    let mut capture_args = Vec::new();
+    for capture in &captures {
+        let ty = render_ty(&capture.local.ty(db))?;
+        let name = capture.local.name(db);
+        let (param_ty, arg) = match capture.mode {
+            CaptureMode::Value => (ty, name.to_string()),
+            CaptureMode::Shared => (format!("&{}", ty), format!("&{}", name)),
+            CaptureMode::Exclusive => (format!("&mut {}", ty), format!("&mut {}", name)),
+        };
+        params.push(format!("{}: {}", name, param_ty));
+        capture_args.push(arg);
+    }
+
+    // Without captures, the function can be used in place of the closure anywhere.
+    let mut calls = Vec::new();
+    for (file_id, refs) in Definition::Local(local).usages(&ctx.sema).all() {
+        for reference in refs {
+            let call = reference
+                .name
+                .as_name_ref()
+                .and_then(|it| it.syntax().parent())
+                .and_then(ast::PathSegment::cast)
+                .map(|it| it.parent_path())
+                .and_then(|path| {
+                    let path_expr = ast::PathExpr::cast(path.syntax().parent()?)?;
+                    let call = ast::CallExpr::cast(path_expr.syntax().parent()?)?;
+                    (call.expr()?.syntax() == path_expr.syntax()).then(|| call.arg_list())?
+                });
+            match call {
+                Some(arg_list) => calls.push((file_id, arg_list)),
+                None if !captures.is_empty() => {
+                    cov_mark::hit!(convert_closure_to_fn_non_call_usage);
+                    return None;
+                }
+                None => (),
+            }
+        }
+    }
+
+    let target = closure.syntax().text_range();
+    acc.add(
+        AssistId("convert_closure_to_fn", AssistKind::RefactorRewrite),
+        "Convert closure to fn",
+        target,
+        |builder| {
+            let indent = IndentLevel::from_node(let_stmt.syntax());
+            let body_text = render_body(&body, &captures);
+            let mut buf = format!("fn {}({})", name, params.join(", "));
+            if let Some(ret_ty) = &ret_ty {
+                format_to!(buf, " -> {}", ret_ty);
+            }
+            match &body {
+                ast::Expr::BlockExpr(it) if it.modifier().is_none() => {
+                    format_to!(buf, " {}", body_text)
+                }
+                _ => {
+                    let body_text = body_text.replace('\n', &format!("\n{}", IndentLevel(1)));
+                    format_to!(buf, " {{\n{}{}\n{}}}", indent + 1, body_text, indent)
+                }
+            }
+            builder.replace(let_stmt.syntax().text_range(), buf);
+
+            if capture_args.is_empty() {
+                return;
+            }
+            for (file_id, arg_list) in calls {
+                builder.edit_file(file_id);
+                let has_args = arg_list.args().next().is_some();
+                let text =
+                    format!("{}{}", if has_args { ", " } else { "" }, capture_args.join(", "));
+                if let Some(r_paren) = arg_list.r_paren_token() {
+                    builder.insert(r_paren.text_range().start(), text);
+                }
+            }
+        },
+    )
+}
+
+fn outer_generic_names(let_stmt: &ast::LetStmt) -> Vec<String> {
+    let mut names = Vec::new();
+    for node in let_stmt.syntax().ancestors() {
+        let generic_param_list = match_ast! {
+            match node {
+                ast::Fn(it) => it.generic_param_list(),
+                ast::Impl(it) => {
+                    names.push("Self".to_owned());
+                    it.generic_param_list()
+                },
+                ast::Trait(it) => {
+                    names.push("Self".to_owned());
+                    it.generic_param_list()
+                },
+                _ => continue,
+            }
+        };
+        names.extend(
+            generic_param_list
+                .into_iter()
+                .flat_map(|it| it.generic_params())
+                .filter_map(|it| match it {
+                    ast::GenericParam::TypeParam(it) => it.name(),
+                    ast::GenericParam::ConstParam(it) => it.name(),
+                    ast::GenericParam::LifetimeParam(_) => None,
+                })
+                .map(|it| it.to_string()),
+        );
+    }
+    names
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum CaptureMode {
+    Shared,
+    Value,
+    Exclusive,
+}
+
+struct Capture {
+    local: hir::Local,
+    mode: CaptureMode,
+    usages: Vec<ast::PathExpr>,
+}
+
+/// Finds the locals the closure captures, and how it would have to capture them if it were a
+/// function.
+fn captures(
+    ctx: &AssistContext<'_>,
+    closure: &ast::ClosureExpr,
+    body: &ast::Expr,
+) -> Option<Vec<Capture>> {
+    let db = ctx.db();
+    let closure_range = closure.syntax().text_range();
+    let is_move = closure.move_token().is_some();
+    let mut captures: FxHashMap<hir::Local, Capture> = FxHashMap::default();
+    for path_expr in body.syntax().descendants().filter_map(ast::PathExpr::cast) {
+        let local = match ctx.sema.resolve_path(&path_expr.path()?) {
+            Some(PathResolution::Local(it)) => it,
+            _ => continue,
+        };
+        let defined_inside = match local.source(db).value {
+            Either::Left(pat) => closure_range.contains_range(pat.syntax().text_range()),
+            Either::Right(_) => {
+                cov_mark::hit!(convert_closure_to_fn_captures_self);
+                return None;
+            }
+        };
+        if defined_inside {
+            continue;
+        }
+        let is_copy = local.ty(db).is_copy(db);
+        // The function can borrow what a `move` closure takes ownership of, unless the closure
+        // mutates its own copy of the value.
+        let mode = match usage_access(ctx, &path_expr) {
+            hir::Access::Exclusive if is_move && is_copy => {
+                cov_mark::hit!(convert_closure_to_fn_mutated_move_copy);
+                return None;
+            }
+            hir::Access::Exclusive => CaptureMode::Exclusive,
+            hir::Access::Owned => CaptureMode::Value,
+            hir::Access::Shared if is_copy => CaptureMode::Value,
+            hir::Access::Shared => CaptureMode::Shared,
+        };
+        let capture = captures.entry(local).or_insert(Capture { local, mode, usages: Vec::new() });
+        capture.mode = capture.mode.max(mode);
+        capture.usages.push(path_expr);
+    }
+    Some(
+        captures
+            .into_values()
+            .sorted_by_key(|it| it.usages.first().map(|it| it.syntax().text_range().start()))
+            .collect(),
+    )
+}
+
+/// How the expression is used, looking through field accesses.
+fn usage_access(ctx: &AssistContext<'_>, path_expr: &ast::PathExpr) -> hir::Access {
+    let mut expr = ast::Expr::PathExpr(path_expr.clone());
+    loop {
+        let parent = match expr.syntax().parent() {
+            Some(it) => it,
+            None => return hir::Access::Owned,
+        };
+        if let Some(field_expr) = ast::FieldExpr::cast(parent.clone()) {
+            expr = field_expr.into();
+            continue;
+        }
+        if let Some(bin_expr) = ast::BinExpr::cast(parent.clone()) {
+            let is_assignee = matches!(bin_expr.op_kind(), Some(ast::BinaryOp::Assignment { .. }))
+                && bin_expr.lhs().map_or(false, |it| it.syntax() == expr.syntax());
+            return if is_assignee { hir::Access::Exclusive } else { hir::Access::Shared };
+        }
+        if let Some(ref_expr) = ast::RefExpr::cast(parent.clone()) {
+            return if ref_expr.mut_token().is_some() {
+                hir::Access::Exclusive
+            } else {
+                hir::Access::Shared
+            };
+        }
+        if let Some(method_call) = ast::MethodCallExpr::cast(parent) {
+            if method_call.receiver().map_or(false, |it| it.syntax() == expr.syntax()) {
+                return ctx
+                    .sema
+                    .resolve_method_call(&method_call)
+                    .and_then(|it| it.self_param(ctx.db()))
+                    .map_or(hir::Access::Shared, |it| it.access(ctx.db()));
+            }
+        }
+        // Field accesses only move out of the local if the field is used by value.
+        return match &expr {
+            ast::Expr::FieldExpr(_) => hir::Access::Shared,
+            _ => hir::Access::Owned,
+        };
+    }
+}
+
+/// Renders the closure body, dereferencing the captures that are passed by reference.
+fn render_body(body: &ast::Expr, captures: &[Capture]) -> String {
+    let body_range = body.syntax().text_range();
+    let mut edits: Vec<(TextRange, String)> = Vec::new();
+    for capture in captures.iter().filter(|it| it.mode != CaptureMode::Value) {
+        for usage in &capture.usages {
+            let parent = usage.syntax().parent();
+            // Method calls and field accesses auto-dereference.
+            if parent.as_ref().map_or(false, |it| {
+                ast::FieldExpr::can_cast(it.kind()) || ast::MethodCallExpr::can_cast(it.kind())
+            }) {
+                continue;
+            }
+            if let Some(ref_expr) = parent.and_then(ast::RefExpr::cast) {
+                let is_mut = ref_expr.mut_token().is_some();
+                if is_mut == (capture.mode == CaptureMode::Exclusive) {
+                    edits.push((ref_expr.syntax().text_range(), usage.to_string()));
+                    continue;
+                }
+            }
+            edits.push((usage.syntax().text_range(), format!("*{}", usage)));
+        }
+    }
+    edits.sort_by_key(|(range, _)| std::cmp::Reverse(range.start()));
+    let mut text = body.syntax().text().to_string();
+    for (range, replacement) in edits {
+        let range = range - body_range.start();
+        text.replace_range(std::ops::Range::<usize>::from(range), &replacement);
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn convert_closure_without_captures() {
+        check_assist(
+            convert_closure_to_fn,
+            r#"
+fn main() {
+    let double$0 = |x: i32| x * 2;
+    let g = double;
+    double(3);
+}
+"#,
+            r#"
+fn main() {
+    fn double(x: i32) -> i32 {
+        x * 2
+    }
+    let g = double;
+    double(3);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_closure_with_inferred_types_and_block_body() {
+        check_assist(
+            convert_closure_to_fn,
+            r#"
+fn main() {
+    let $0log = |msg| {
+        let _ = msg;
+    };
+    log("hello");
+}
+"#,
+            r#"
+fn main() {
+    fn log(msg: &str) {
+        let _ = msg;
+    }
+    log("hello");
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_closure_with_captures() {
+        check_assist(
+            convert_closure_to_fn,
+            r#"
+//- minicore: copy
+struct S { n: u32 }
+impl S {
+    fn bump(&mut self) {}
+    fn get(&self) -> u32 { self.n }
+}
+
+fn main() {
+    let offset = 1u32;
+    let mut total = 0u32;
+    let mut s = S { n: 0 };
+    let shared = S { n: 2 };
+    let f$0 = |x: u32| {
+        total += x + offset + shared.get();
+        s.bump();
+        let _ = &shared;
+    };
+    f(2);
+}
+"#,
+            r#"
+struct S { n: u32 }
+impl S {
+    fn bump(&mut self) {}
+    fn get(&self) -> u32 { self.n }
+}
+
+fn main() {
+    let offset = 1u32;
+    let mut total = 0u32;
+    let mut s = S { n: 0 };
+    let shared = S { n: 2 };
+    fn f(x: u32, total: &mut u32, offset: u32, shared: &S, s: &mut S) {
+        *total += x + offset + shared.get();
+        s.bump();
+        let _ = shared;
+    }
+    f(2, &mut total, offset, &shared, &mut s);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_move_closure() {
+        check_assist(
+            convert_closure_to_fn,
+            r#"
+struct S;
+fn consume(_: S) {}
+
+fn main() {
+    let s = S;
+    let f$0 = move || consume(s);
+    f();
+}
+"#,
+            r#"
+struct S;
+fn consume(_: S) {}
+
+fn main() {
+    let s = S;
+    fn f(s: S) {
+        consume(s)
+    }
+    f(s);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_move_closure_borrowing_captures() {
+        check_assist(
+            convert_closure_to_fn,
+            r#"
+//- minicore: copy
+struct S;
+impl S {
+    fn len(&self) -> usize { 0 }
+}
+
+fn main() {
+    let s = S;
+    let n = 1;
+    let f$0 = move || s.len() + n;
+    f();
+    f();
+}
+"#,
+            r#"
+struct S;
+impl S {
+    fn len(&self) -> usize { 0 }
+}
+
+fn main() {
+    let s = S;
+    let n = 1;
+    fn f(s: &S, n: usize) -> usize {
+        s.len() + n
+    }
+    f(&s, n);
+    f(&s, n);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_when_move_closure_mutates_copy() {
+        cov_mark::check!(convert_closure_to_fn_mutated_move_copy);
+        check_assist_not_applicable(
+            convert_closure_to_fn,
+            r#"
+//- minicore: copy
+fn main() {
+    let mut n = 0;
+    let mut f$0 = move || { n += 1; n };
+    f();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_with_captures_and_non_call_usages() {
+        cov_mark::check!(convert_closure_to_fn_non_call_usage);
+        check_assist_not_applicable(
+            convert_closure_to_fn,
+            r#"
+fn apply(f: impl Fn()) { f() }
+fn main() {
+    let n = 1;
+    let f$0 = || { let _ = n; };
+    apply(f);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_when_capturing_self() {
+        cov_mark::check!(convert_closure_to_fn_captures_self);
+        check_assist_not_applicable(
+            convert_closure_to_fn,
+            r#"
+struct S;
+impl S {
+    fn foo(&self) {
+        let f$0 = || self;
+        f();
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_with_outer_generics() {
+        check_assist_not_applicable(
+            convert_closure_to_fn,
+            r#"
+fn bar<T: Copy>(t: T) {
+    let f$0 = || t;
+    f();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_in_body() {
+        check_assist_not_applicable(
+            convert_closure_to_fn,
+            r#"
+fn main() {
+    let f = |x: i32| x$0 * 2;
+    f(1);
+}
+"#,
+        );
+    }
+}
@@ -0,0 +1,345 @@
+use hir::{Local, PathResolution};
+use ide_db::FxHashMap;
+use syntax::{
+    ast::{
+        self,
+        edit::{AstNodeEdit, IndentLevel},
+        AstNode, HasName,
+    },
+    TextRange,
+};
+
+use crate::{AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: convert_match_to_let_else
+//
+// Converts a `let` with a `match` whose other arm diverges into a let-else statement.
+//
+// ```
+// # //- minicore: option
+// fn foo(opt: Option<()>) {
+//     let val$0 = match opt {
+//         Some(it) => it,
+//         None => return,
+//     };
+// }
+// ```
+// ->
+// ```
+// fn foo(opt: Option<()>) {
+//     let Some(val) = opt else { return };
+// }
+// ```
+pub(crate) fn convert_match_to_let_else(acc: &mut Assists, ctx: &AssistContext<'_>) -> Option<()> {
+    let let_stmt = ctx.find_node_at_offset::<ast::LetStmt>()?;
+    let initializer = match let_stmt.initializer()? {
+        ast::Expr::MatchExpr(it) => it,
+        _ => return None,
+    };
+    if ctx.offset() > initializer.syntax().text_range().start()
+        || let_stmt.let_else().is_some()
+        || let_stmt.ty().is_some()
+    {
+        return None;
+    }
+    let let_binders = let_binders(&let_stmt.pat()?)?;
+    let scrutinee = initializer.expr()?;
+
+    let arms = initializer.match_arm_list()?.arms().collect::<Vec<_>>();
+    let (extracting_arm, diverging_arm) = match arms.as_slice() {
+        [first, second] => {
+            if diverges(ctx, second) {
+                (first, second)
+            } else if diverges(ctx, first) {
+                (second, first)
+            } else {
+                return None;
+            }
+        }
+        _ => return None,
+    };
+    if extracting_arm.guard().is_some() || diverging_arm.guard().is_some() {
+        return None;
+    }
+    let extracting_pat = extracting_arm.pat()?;
+    let diverging_pat = diverging_arm.pat()?;
+    if !pat_bindings(ctx, &diverging_pat)?.is_empty() {
+        cov_mark::hit!(convert_match_to_let_else_diverging_arm_binds);
+        return None;
+    }
+
+    // The extracting arm has to hand out each of its bindings exactly once, in the shape of the
+    // `let` pattern, so the bindings can simply be renamed.
+    let bindings = pat_bindings(ctx, &extracting_pat)?;
+    let extracted = extracted_locals(ctx, &extracting_arm.expr()?)?;
+    if extracted.len() != let_binders.len() || extracted.len() != bindings.len() {
+        cov_mark::hit!(convert_match_to_let_else_unsupported_arm);
+        return None;
+    }
+    let mut renames = FxHashMap::default();
+    for (local, let_binder) in extracted.into_iter().zip(let_binders) {
+        if renames.insert(local, let_binder).is_some() {
+            return None;
+        }
+    }
+
+    let target = let_stmt.syntax().text_range();
+    acc.add(
+        AssistId("convert_match_to_let_else", AssistKind::RefactorRewrite),
+        "Convert match to let-else",
+        target,
+        |builder| {
+            let mut pat = extracting_pat.syntax().to_string();
+            let pat_start = extracting_pat.syntax().text_range().start();
+            let mut replacements = bindings
+                .iter()
+                .filter_map(|(local, ident_pat)| {
+                    let let_binder = renames.get(local)?;
+                    let name = ident_pat.name()?;
+                    let start = ident_pat.syntax().text_range().start();
+                    let range = TextRange::new(start, name.syntax().text_range().end());
+                    let mut text = String::new();
+                    if ident_pat.ref_token().is_some() {
+                        text.push_str("ref ");
+                        if ident_pat.mut_token().is_some() {
+                            text.push_str("mut ");
+                        }
+                    } else if let_binder.mut_token().is_some() {
+                        text.push_str("mut ");
+                    }
+                    text.push_str(&let_binder.name()?.to_string());
+                    Some((range - pat_start, text))
+                })
+                .collect::<Vec<_>>();
+            replacements.sort_by_key(|(range, _)| std::cmp::Reverse(range.start()));
+            for (range, text) in replacements {
+                pat.replace_range(std::ops::Range::<usize>::from(range), &text);
+            }
+
+            // A let-else initializer must not end with a `}`.
+            let scrutinee = match scrutinee.is_block_like() {
+                true => format!("({})", scrutinee),
+                false => scrutinee.to_string(),
+            };
+            let else_block = match diverging_arm.expr() {
+                Some(ast::Expr::BlockExpr(block)) => {
+                    let arm_indent = IndentLevel::from_node(diverging_arm.syntax()).0;
+                    let stmt_indent = let_stmt.indent_level().0;
+                    block.dedent(IndentLevel(arm_indent.saturating_sub(stmt_indent))).to_string()
+                }
+                Some(expr) => format!("{{ {} }}", expr),
+                None => return,
+            };
+            builder.replace(target, format!("let {} = {} else {};", pat, scrutinee, else_block));
+        },
+    )
+}
+
+/// Returns the identifier patterns bound by the `let`, in the order of a tuple.
+fn let_binders(pat: &ast::Pat) -> Option<Vec<ast::IdentPat>> {
+    match pat {
+        ast::Pat::IdentPat(it) if it.pat().is_none() && it.ref_token().is_none() => {
+            Some(vec![it.clone()])
+        }
+        ast::Pat::TuplePat(it) => it
+            .fields()
+            .map(|field| match field {
+                ast::Pat::IdentPat(it) if it.pat().is_none() && it.ref_token().is_none() => {
+                    Some(it)
+                }
+                _ => None,
+            })
+            .collect(),
+        _ => None,
+    }
+}
+
+/// Returns the locals an arm hands out, in the order of a tuple.
+fn extracted_locals(ctx: &AssistContext<'_>, expr: &ast::Expr) -> Option<Vec<Local>> {
+    let to_local = |expr: &ast::Expr| match expr {
+        ast::Expr::PathExpr(path) => match ctx.sema.resolve_path(&path.path()?)? {
+            PathResolution::Local(local) => Some(local),
+            _ => None,
+        },
+        _ => None,
+    };
+    match expr {
+        ast::Expr::TupleExpr(tuple) => tuple.fields().map(|it| to_local(&it)).collect(),
+        _ => Some(vec![to_local(expr)?]),
+    }
+}
+
+/// Returns the locals bound by a pattern, ignoring identifiers that resolve to constants or unit
+/// variants.
+fn pat_bindings(ctx: &AssistContext<'_>, pat: &ast::Pat) -> Option<Vec<(Local, ast::IdentPat)>> {
+    let mut bindings = Vec::new();
+    for ident_pat in pat.syntax().descendants().filter_map(ast::IdentPat::cast) {
+        if ctx.sema.resolve_bind_pat_to_const(&ident_pat).is_some() {
+            continue;
+        }
+        bindings.push((ctx.sema.to_def(&ident_pat)?, ident_pat));
+    }
+    Some(bindings)
+}
+
+fn diverges(ctx: &AssistContext<'_>, arm: &ast::MatchArm) -> bool {
+    arm.expr().map_or(false, |expr| expr_diverges(ctx, &expr))
+}
+
+fn expr_diverges(ctx: &AssistContext<'_>, expr: &ast::Expr) -> bool {
+    if ctx.sema.type_of_expr(expr).map_or(false, |ty| ty.original.is_never()) {
+        return true;
+    }
+    // Blocks ending with a diverging statement are typed as `()`.
+    match expr {
+        ast::Expr::BlockExpr(block) => match block.stmt_list() {
+            Some(stmt_list) if stmt_list.tail_expr().is_none() => {
+                match stmt_list.statements().last() {
+                    Some(ast::Stmt::ExprStmt(stmt)) => {
+                        stmt.expr().map_or(false, |it| expr_diverges(ctx, &it))
+                    }
+                    _ => false,
+                }
+            }
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn convert_option_match() {
+        check_assist(
+            convert_match_to_let_else,
+            r#"
+//- minicore: option
+fn foo(opt: Option<u32>) -> u32 {
+    let mut $0val = match opt {
+        Some(it) => it,
+        None => return 0,
+    };
+    val += 1;
+    val
+}
+"#,
+            r#"
+fn foo(opt: Option<u32>) -> u32 {
+    let Some(mut val) = opt else { return 0 };
+    val += 1;
+    val
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_match_with_diverging_first_arm_and_block() {
+        check_assist(
+            convert_match_to_let_else,
+            r#"
+//- minicore: result
+fn foo(res: Result<(u32, u32), ()>) {
+    loop {
+        let$0 (a, b) = match res {
+            Err(_) => {
+                let _ = 0;
+                continue;
+            }
+            Ok((x, y)) => (y, x),
+        };
+    }
+}
+"#,
+            r#"
+fn foo(res: Result<(u32, u32), ()>) {
+    loop {
+        let Ok((b, a)) = res else {
+            let _ = 0;
+            continue;
+        };
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_match_keeps_constants_and_wraps_blocks() {
+        check_assist(
+            convert_match_to_let_else,
+            r#"
+//- minicore: option
+const ZERO: u32 = 0;
+fn foo(opt: Option<(u32, u32)>) {
+    let $0n = match { opt } {
+        Some((ZERO, ref n)) => n,
+        _ => return,
+    };
+}
+"#,
+            r#"
+const ZERO: u32 = 0;
+fn foo(opt: Option<(u32, u32)>) {
+    let Some((ZERO, ref n)) = ({ opt }) else { return };
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_without_diverging_arm() {
+        check_assist_not_applicable(
+            convert_match_to_let_else,
+            r#"
+//- minicore: option
+fn foo(opt: Option<u32>) {
+    let $0val = match opt {
+        Some(it) => it,
+        None => 0,
+    };
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_when_diverging_arm_binds() {
+        cov_mark::check!(convert_match_to_let_else_diverging_arm_binds);
+        check_assist_not_applicable(
+            convert_match_to_let_else,
+            r#"
+//- minicore: result
+fn foo(res: Result<u32, u32>) -> u32 {
+    let $0val = match res {
+        Ok(it) => it,
+        Err(e) => return e,
+    };
+    val
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_when_arm_computes_value() {
+        cov_mark::check!(convert_match_to_let_else_unsupported_arm);
+        check_assist_not_applicable(
+            convert_match_to_let_else,
+            r#"
+//- minicore: option
+fn foo(opt: Option<(u32, u32)>) {
+    let $0val = match opt {
+        Some((a, _b)) => a,
+        None => return,
+    };
+}
+"#,
+        );
+    }
+}
@@ -0,0 +1,569 @@
+use either::Either;
+use ide_db::{base_db::FileId, defs::Definition, FxHashMap};
+use itertools::Itertools;
+use syntax::{
+    ast::{self, AstNode, HasAttrs, HasGenericParams, HasName, HasVisibility},
+    match_ast, SyntaxNode, TextRange,
+};
+
+use crate::{assist_context::SourceChangeBuilder, AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: convert_named_struct_to_tuple_struct
+//
+// Converts struct with named fields to tuple struct, and analogously for enum variants with named
+// fields.
+//
+// ```
+// struct Point$0 { x: f32, y: f32 }
+//
+// impl Point {
+//     pub fn new(x: f32, y: f32) -> Self {
+//         Point { x, y }
+//     }
+//
+//     pub fn x(&self) -> f32 {
+//         self.x
+//     }
+//
+//     pub fn y(&self) -> f32 {
+//         self.y
+//     }
+// }
+// ```
+// ->
+// ```
+// struct Point(f32, f32);
+//
+// impl Point {
+//     pub fn new(x: f32, y: f32) -> Self {
+//         Point(x, y)
+//     }
+//
+//     pub fn x(&self) -> f32 {
+//         self.0
+//     }
+//
+//     pub fn y(&self) -> f32 {
+//         self.1
+//     }
+// }
+// ```
+pub(crate) fn convert_named_struct_to_tuple_struct(
+    acc: &mut Assists,
+    ctx: &AssistContext<'_>,
+) -> Option<()> {
+    let strukt = ctx
+        .find_node_at_offset::<ast::Struct>()
+        .map(Either::Left)
+        .or_else(|| ctx.find_node_at_offset::<ast::Variant>().map(Either::Right))?;
+    let field_list = strukt.as_ref().either(|s| s.field_list(), |v| v.field_list())?;
+    let record_fields = match field_list {
+        ast::FieldList::RecordFieldList(it) => it,
+        ast::FieldList::TupleFieldList(_) => return None,
+    };
+    let strukt_def = match &strukt {
+        Either::Left(s) => Either::Left(ctx.sema.to_def(s)?),
+        Either::Right(v) => Either::Right(ctx.sema.to_def(v)?),
+    };
+    let names = record_fields
+        .fields()
+        .map(|it| it.name().map(|it| it.to_string()))
+        .collect::<Option<Vec<_>>>()?;
+    let target = strukt.as_ref().either(|s| s.syntax(), |v| v.syntax()).text_range();
+
+    let records = struct_records(ctx, strukt_def);
+    if records.iter().any(|(_, node)| {
+        ast::RecordExpr::cast(node.clone())
+            .map_or(false, |it| changes_evaluation_order(&it, &names))
+    }) {
+        cov_mark::hit!(convert_named_struct_evaluation_order);
+        return None;
+    }
+
+    acc.add(
+        AssistId("convert_named_struct_to_tuple_struct", AssistKind::RefactorRewrite),
+        "Convert to tuple struct",
+        target,
+        |edit| {
+            let mut edits = field_reference_edits(ctx, record_fields.fields());
+            edit_struct_references(&mut edits, records, &names);
+            for (file_id, edits) in edits {
+                edit.edit_file(file_id);
+                for (range, text) in edits {
+                    edit.replace(range, text);
+                }
+            }
+            edit_struct_def(ctx, edit, &strukt, record_fields);
+        },
+    )
+}
+
+fn edit_struct_def(
+    ctx: &AssistContext<'_>,
+    edit: &mut SourceChangeBuilder,
+    strukt: &Either<ast::Struct, ast::Variant>,
+    record_fields: ast::RecordFieldList,
+) {
+    let tuple_fields = record_fields
+        .fields()
+        .filter_map(|f| {
+            let attrs = f.attrs().map(|it| format!("{} ", it)).join("");
+            let visibility = f.visibility().map(|it| format!("{} ", it)).unwrap_or_default();
+            Some(format!("{}{}{}", attrs, visibility, f.ty()?))
+        })
+        .join(", ");
+    let tuple_fields = format!("({})", tuple_fields);
+    let record_fields_text_range = record_fields.syntax().text_range();
+
+    edit.edit_file(ctx.file_id());
+
+    match strukt {
+        Either::Left(strukt) => {
+            // Tuple structs put the where clause after the fields and end with a semicolon.
+            let start = match (strukt.generic_param_list(), strukt.name()) {
+                (Some(params), _) => params.syntax().text_range().end(),
+                (None, Some(name)) => name.syntax().text_range().end(),
+                (None, None) => record_fields_text_range.start(),
+            };
+            let where_clause = strukt
+                .where_clause()
+                .map(|w| {
+                    let text = w.syntax().text().to_string();
+                    format!(" {}", text.split_whitespace().join(" ").trim_end_matches(','))
+                })
+                .unwrap_or_default();
+            edit.replace(
+                TextRange::new(start, record_fields_text_range.end()),
+                format!("{}{};", tuple_fields, where_clause),
+            );
+        }
+        Either::Right(variant) => {
+            let start = variant
+                .name()
+                .map_or(record_fields_text_range.start(), |it| it.syntax().text_range().end());
+            edit.replace(TextRange::new(start, record_fields_text_range.end()), tuple_fields);
+        }
+    }
+}
+
+/// The record expressions and patterns of the struct, which need to be rewritten as a whole.
+fn struct_records(
+    ctx: &AssistContext<'_>,
+    strukt: Either<hir::Struct, hir::Variant>,
+) -> Vec<(FileId, SyntaxNode)> {
+    let strukt_def = match strukt {
+        Either::Left(s) => Definition::Adt(hir::Adt::Struct(s)),
+        Either::Right(v) => Definition::Variant(v),
+    };
+    let usages = strukt_def.usages(&ctx.sema).include_self_refs().all();
+    let mut records = Vec::new();
+    for (file_id, refs) in usages {
+        for r in refs {
+            // Only rewrite record expressions and patterns whose path ends in the reference.
+            let path = r
+                .name
+                .syntax()
+                .parent()
+                .and_then(ast::PathSegment::cast)
+                .map(|it| it.parent_path())
+                .filter(|it| it.parent_path().is_none());
+            let record = path.and_then(|it| it.syntax().parent()).filter(|it| {
+                ast::RecordExpr::can_cast(it.kind()) || ast::RecordPat::can_cast(it.kind())
+            });
+            if let Some(record) = record {
+                records.push((file_id, record));
+            }
+        }
+    }
+    records
+}
+
+/// Whether turning the fields of `record_expr` into arguments in declaration
+/// order could change what the program does.
+fn changes_evaluation_order(record_expr: &ast::RecordExpr, names: &[String]) -> bool {
+    let fields = record_expr.record_expr_field_list().into_iter().flat_map(|it| it.fields());
+    let (positions, exprs): (Vec<_>, Vec<_>) = fields
+        .map(|field| {
+            let name = field.field_name().map(|it| it.to_string());
+            (name.and_then(|name| names.iter().position(|it| *it == name)), field.expr())
+        })
+        .unzip();
+    if positions.iter().tuple_windows().all(|(a, b)| a < b) {
+        return false;
+    }
+    // Literals can go anywhere, other expressions may depend on each other.
+    let reads = exprs.iter().filter(|it| !matches!(it, Some(ast::Expr::Literal(_)))).count();
+    let has_side_effects =
+        exprs.iter().any(|it| !matches!(it, Some(ast::Expr::Literal(_) | ast::Expr::PathExpr(_))));
+    has_side_effects && reads > 1
+}
+
+fn edit_struct_references(
+    edits: &mut FxHashMap<FileId, Vec<(TextRange, String)>>,
+    mut records: Vec<(FileId, SyntaxNode)>,
+    names: &[String],
+) {
+    // Nested records are rewritten first, so that the outer ones can take the rewritten text.
+    records.sort_by_key(|(_, node)| node.text_range().len());
+    for (file_id, node) in records {
+        let edits = edits.entry(file_id).or_default();
+        if let Some(edit) = edit_record(edits, &node, names) {
+            edits.push(edit);
+        }
+    }
+}
+
+fn edit_record(
+    edits: &mut Vec<(TextRange, String)>,
+    node: &SyntaxNode,
+    names: &[String],
+) -> Option<(TextRange, String)> {
+    match_ast! {
+        match node {
+            ast::RecordPat(record_pat) => {
+                let field_list = record_pat.record_pat_field_list()?;
+                let mut fields = field_list
+                    .fields()
+                    .map(|field| {
+                        let name = match field.field_name()? {
+                            ast::NameOrNameRef::Name(it) => it.to_string(),
+                            ast::NameOrNameRef::NameRef(it) => it.to_string(),
+                        };
+                        Some((name, field.pat()?))
+                    })
+                    .collect::<Option<Vec<_>>>()?;
+                let has_rest = field_list.rest_pat().is_some();
+                let mut pats = names
+                    .iter()
+                    .map(|name| match fields.iter().position(|(it, _)| it == name) {
+                        Some(idx) => Some(take_rewritten(edits, fields.swap_remove(idx).1.syntax())),
+                        None if has_rest => Some("_".to_owned()),
+                        None => None,
+                    })
+                    .collect::<Option<Vec<_>>>()?;
+                if has_rest {
+                    let explicit = pats.iter().rposition(|it| it != "_").map_or(0, |it| it + 1);
+                    if explicit < pats.len() {
+                        pats.truncate(explicit);
+                        pats.push("..".to_owned());
+                    }
+                }
+                Some((
+                    record_pat.syntax().text_range(),
+                    format!("{}({})", record_pat.path()?, pats.join(", ")),
+                ))
+            },
+            ast::RecordExpr(record_expr) => {
+                let field_list = record_expr.record_expr_field_list()?;
+                let mut fields = field_list
+                    .fields()
+                    .map(|field| Some((field.field_name()?.to_string(), field.expr()?)))
+                    .collect::<Option<Vec<_>>>()?;
+                let spread = match field_list.spread() {
+                    // The base would be evaluated once per missing field.
+                    Some(ast::Expr::PathExpr(it)) => Some(take_rewritten(edits, it.syntax())),
+                    Some(_) => return None,
+                    None => None,
+                };
+                let args = names
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, name)| match fields.iter().position(|(it, _)| it == name) {
+                        Some(pos) => Some(take_rewritten(edits, fields.swap_remove(pos).1.syntax())),
+                        None => spread.as_ref().map(|base| format!("{}.{}", base, idx)),
+                    })
+                    .collect::<Option<Vec<_>>>()?;
+                Some((
+                    record_expr.syntax().text_range(),
+                    format!("{}({})", record_expr.path()?, args.join(", ")),
+                ))
+            },
+            _ => None,
+        }
+    }
+}
+
+/// Returns the text of `node` with the `edits` inside of it applied, which
+/// are then dropped as the node is moved as a whole.
+fn take_rewritten(edits: &mut Vec<(TextRange, String)>, node: &SyntaxNode) -> String {
+    let range = node.text_range();
+    let (mut inner, outer): (Vec<_>, Vec<_>) =
+        edits.drain(..).partition(|(it, _)| range.contains_range(*it));
+    *edits = outer;
+    inner.sort_by_key(|(it, _)| it.start());
+    let mut text = node.to_string();
+    for (edit_range, new_text) in inner.into_iter().rev() {
+        text.replace_range(std::ops::Range::<usize>::from(edit_range - range.start()), &new_text);
+    }
+    text
+}
+
+/// Replaces field accesses with tuple indices. Record expressions and patterns
+/// mentioning the fields are rewritten as a whole.
+fn field_reference_edits(
+    ctx: &AssistContext<'_>,
+    fields: impl Iterator<Item = ast::RecordField>,
+) -> FxHashMap<FileId, Vec<(TextRange, String)>> {
+    let mut edits: FxHashMap<_, Vec<_>> = FxHashMap::default();
+    for (index, field) in fields.enumerate() {
+        let field = match ctx.sema.to_def(&field) {
+            Some(it) => it,
+            None => continue,
+        };
+        let def = Definition::Field(field);
+        let usages = def.usages(&ctx.sema).all();
+        for (file_id, refs) in usages {
+            for r in refs {
+                if let Some(name_ref) = r.name.as_name_ref() {
+                    if name_ref.syntax().parent().and_then(ast::FieldExpr::cast).is_some() {
+                        edits
+                            .entry(file_id)
+                            .or_default()
+                            .push((name_ref.syntax().text_range(), index.to_string()));
+                    }
+                }
+            }
+        }
+    }
+    edits
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn not_applicable_other_than_record_struct() {
+        check_assist_not_applicable(convert_named_struct_to_tuple_struct, r#"struct Foo$0(u32)"#);
+        check_assist_not_applicable(convert_named_struct_to_tuple_struct, r#"struct Foo$0;"#);
+    }
+
+    #[test]
+    fn convert_simple_struct() {
+        check_assist(
+            convert_named_struct_to_tuple_struct,
+            r#"
+struct Inner;
+struct A$0 { inner: Inner }
+
+impl A {
+    fn new(inner: Inner) -> A {
+        A { inner }
+    }
+
+    fn new_with_default() -> A {
+        A::new(Inner)
+    }
+
+    fn into_inner(self) -> Inner {
+        self.inner
+    }
+}"#,
+            r#"
+struct Inner;
+struct A(Inner);
+
+impl A {
+    fn new(inner: Inner) -> A {
+        A(inner)
+    }
+
+    fn new_with_default() -> A {
+        A::new(Inner)
+    }
+
+    fn into_inner(self) -> Inner {
+        self.0
+    }
+}"#,
+        );
+    }
+
+    #[test]
+    fn convert_struct_with_patterns_and_spread() {
+        check_assist(
+            convert_named_struct_to_tuple_struct,
+            r#"
+struct Point$0 { pub x: i32, #[allow(dead_code)] y: i32, z: i32 }
+
+fn f(p: Point) -> Point {
+    let Point { x, .. } = p;
+    let Point { z: last, y, .. } = p;
+    match p {
+        Point { y: 0, x: 0, z } => Point { x: 1, y: 1, z },
+        Point { .. } => Point { z: 2, ..p },
+    }
+}"#,
+            r#"
+struct Point(pub i32, #[allow(dead_code)] i32, i32);
+
+fn f(p: Point) -> Point {
+    let Point(x, ..) = p;
+    let Point(_, y, last) = p;
+    match p {
+        Point(0, 0, z) => Point(1, 1, z),
+        Point(..) => Point(p.0, p.1, 2),
+    }
+}"#,
+        );
+    }
+
+    #[test]
+    fn convert_field_accesses_inside_records() {
+        check_assist(
+            convert_named_struct_to_tuple_struct,
+            r#"
+struct Point$0 { x: i32, y: i32 }
+
+fn swap(p: Point) -> Point {
+    Point { x: p.y, y: Point { x: p.x, y: 0 }.x }
+}
+
+fn flip(p: Point) -> i32 {
+    match p {
+        Point { x: 0, y } => y,
+        Point { y, .. } => p.x + y,
+    }
+}"#,
+            r#"
+struct Point(i32, i32);
+
+fn swap(p: Point) -> Point {
+    Point(p.1, Point(p.0, 0).0)
+}
+
+fn flip(p: Point) -> i32 {
+    match p {
+        Point(0, y) => y,
+        Point(_, y) => p.0 + y,
+    }
+}"#,
+        );
+    }
+
+    #[test]
+    fn keeps_evaluation_order() {
+        check_assist(
+            convert_named_struct_to_tuple_struct,
+            r#"
+struct S$0 { a: i32, b: i32, c: i32 }
+
+fn f(x: i32) -> i32 { x }
+
+fn g() -> S {
+    S { c: f(3), b: 2, a: 1 }
+}"#,
+            r#"
+struct S(i32, i32, i32);
+
+fn f(x: i32) -> i32 { x }
+
+fn g() -> S {
+    S(1, 2, f(3))
+}"#,
+        );
+        cov_mark::check!(convert_named_struct_evaluation_order);
+        check_assist_not_applicable(
+            convert_named_struct_to_tuple_struct,
+            r#"
+struct S$0 { a: i32, b: i32 }
+
+fn f() -> i32 { 1 }
+fn g() -> i32 { 2 }
+
+fn h() -> S {
+    S { b: f(), a: g() }
+}"#,
+        );
+    }
+
+    #[test]
+    fn convert_struct_with_where_clause() {
+        check_assist(
+            convert_named_struct_to_tuple_struct,
+            r#"
+struct Wrap$0<T>
+where
+    T: Clone,
+{
+    field: T,
+}
+"#,
+            r#"
+struct Wrap<T>(T) where T: Clone;
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_variant() {
+        check_assist(
+            convert_named_struct_to_tuple_struct,
+            r#"
+enum Shape {
+    Circle { radius: f64 },
+    Rect$0 { w: f64, h: f64 },
+}
+
+fn area(s: &Shape) -> f64 {
+    match s {
+        Shape::Circle { radius } => radius * radius,
+        Shape::Rect { w, h } => w * h,
+    }
+}
+
+fn square(side: f64) -> Shape {
+    Shape::Rect { h: side, w: side }
+}
+"#,
+            r#"
+enum Shape {
+    Circle { radius: f64 },
+    Rect(f64, f64),
+}
+
+fn area(s: &Shape) -> f64 {
+    match s {
+        Shape::Circle { radius } => radius * radius,
+        Shape::Rect(w, h) => w * h,
+    }
+}
+
+fn square(side: f64) -> Shape {
+    Shape::Rect(side, side)
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_across_files() {
+        check_assist(
+            convert_named_struct_to_tuple_struct,
+            r#"
+//- /main.rs
+mod foo;
+
+fn main() {
+    let f = foo::Foo { value: 1 };
+    let _ = f.value;
+}
+//- /foo.rs
+pub struct $0Foo { pub value: u32 }
+"#,
+            r#"
+//- /main.rs
+mod foo;
+
+fn main() {
+    let f = foo::Foo(1);
+    let _ = f.0;
+}
+//- /foo.rs
+pub struct Foo(pub u32);
+"#,
+        );
+    }
+}
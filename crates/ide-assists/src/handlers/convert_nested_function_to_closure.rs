@@ -0,0 +1,226 @@
+use ide_db::defs::Definition;
+use stdx::format_to;
+use syntax::{
+    ast::{self, AstNode, HasAttrs, HasDocComments, HasGenericParams, HasName, HasVisibility},
+    AstToken, TextRange, TextSize,
+};
+
+use crate::{AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: convert_nested_function_to_closure
+//
+// Converts a function that is defined within the body of another function into a closure.
+//
+// ```
+// fn main() {
+//     fn fo$0o(label: &str, number: u64) {
+//         println!("{}: {}", label, number);
+//     }
+//
+//     foo("Bar", 100);
+// }
+// ```
+// ->
+// ```
+// fn main() {
+//     let foo = |label: &str, number: u64| {
+//         println!("{}: {}", label, number);
+//     };
+//
+//     foo("Bar", 100);
+// }
+// ```
+pub(crate) fn convert_nested_function_to_closure(
+    acc: &mut Assists,
+    ctx: &AssistContext<'_>,
+) -> Option<()> {
+    let fn_ = ctx.find_node_at_offset::<ast::Fn>()?;
+    let body = fn_.body()?;
+    if body.syntax().text_range().contains_inclusive(ctx.offset()) {
+        return None;
+    }
+    let stmt_list = fn_.syntax().parent().and_then(ast::StmtList::cast)?;
+    if fn_.generic_param_list().is_some()
+        || fn_.where_clause().is_some()
+        || fn_.visibility().is_some()
+        || fn_.const_token().is_some()
+        || fn_.async_token().is_some()
+        || fn_.unsafe_token().is_some()
+        || fn_.abi().is_some()
+        || fn_.attrs().next().is_some()
+    {
+        return None;
+    }
+    let name = fn_.name()?;
+    let param_list = fn_.param_list()?;
+    let function = ctx.sema.to_def(&fn_)?;
+
+    // Unlike the function, the closure is only visible after its definition, and can't be used
+    // from nested items.
+    let fn_end = fn_.syntax().text_range().end();
+    for (file_id, refs) in Definition::Function(function).usages(&ctx.sema).all() {
+        for reference in refs {
+            let in_nested_item = reference
+                .name
+                .syntax()
+                .ancestors()
+                .take_while(|it| it != stmt_list.syntax())
+                .any(|it| ast::Item::can_cast(it.kind()));
+            if file_id != ctx.file_id() || reference.range.start() < fn_end || in_nested_item {
+                cov_mark::hit!(convert_nested_function_to_closure_bad_usage);
+                return None;
+            }
+        }
+    }
+
+    let fn_token = fn_.fn_token()?;
+    let target =
+        TextRange::new(fn_token.text_range().start(), param_list.syntax().text_range().end());
+    acc.add(
+        AssistId("convert_nested_function_to_closure", AssistKind::RefactorRewrite),
+        "Convert nested function to closure",
+        target,
+        |builder| {
+            let params = param_list.params().map(|it| it.to_string()).collect::<Vec<_>>();
+            let mut buf = format!("let {} = |{}|", name, params.join(", "));
+            if let Some(ret_type) = fn_.ret_type() {
+                format_to!(buf, " {}", ret_type);
+            }
+            format_to!(buf, " {};", body);
+            builder.replace(TextRange::new(fn_token.text_range().start(), fn_end), buf);
+
+            // Doc comments on statements are unused.
+            for comment in fn_.doc_comments() {
+                let start = comment.syntax().text_range().start();
+                let prefix = if comment.kind().shape.is_block() { "/*" } else { "//" };
+                builder.replace(TextRange::at(start, TextSize::of(comment.prefix())), prefix);
+            }
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn convert_nested_function() {
+        check_assist(
+            convert_nested_function_to_closure,
+            r#"
+fn main() {
+    /// Adds two numbers.
+    fn $0add(a: u32, b: u32) -> u32 {
+        a + b
+    }
+
+    let _ = add(1, 2);
+}
+"#,
+            r#"
+fn main() {
+    // Adds two numbers.
+    let add = |a: u32, b: u32| -> u32 {
+        a + b
+    };
+
+    let _ = add(1, 2);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_nested_function_with_block_doc_comment() {
+        check_assist(
+            convert_nested_function_to_closure,
+            r#"
+fn main() {
+    /** Does nothing. */
+    fn $0foo() {}
+    foo();
+}
+"#,
+            r#"
+fn main() {
+    /* Does nothing. */
+    let foo = || {};
+    foo();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_nested_function_without_params() {
+        check_assist(
+            convert_nested_function_to_closure,
+            r#"
+fn main() {
+    fn$0 foo() {}
+    foo();
+}
+"#,
+            r#"
+fn main() {
+    let foo = || {};
+    foo();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_top_level_or_generic_functions() {
+        check_assist_not_applicable(convert_nested_function_to_closure, r#"fn $0foo() {}"#);
+        check_assist_not_applicable(
+            convert_nested_function_to_closure,
+            r#"
+fn main() {
+    fn $0foo<T>(t: T) {}
+}
+"#,
+        );
+        check_assist_not_applicable(
+            convert_nested_function_to_closure,
+            r#"
+fn main() {
+    fn foo() { $0 }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_when_used_before_definition_or_recursively() {
+        cov_mark::check_count!(convert_nested_function_to_closure_bad_usage, 3);
+        check_assist_not_applicable(
+            convert_nested_function_to_closure,
+            r#"
+fn main() {
+    foo();
+    fn $0foo() {}
+}
+"#,
+        );
+        check_assist_not_applicable(
+            convert_nested_function_to_closure,
+            r#"
+fn main() {
+    fn $0fact(n: u32) -> u32 { if n == 0 { 1 } else { n * fact(n - 1) } }
+}
+"#,
+        );
+        check_assist_not_applicable(
+            convert_nested_function_to_closure,
+            r#"
+fn main() {
+    fn $0foo() {}
+    fn bar() { foo() }
+}
+"#,
+        );
+    }
+}
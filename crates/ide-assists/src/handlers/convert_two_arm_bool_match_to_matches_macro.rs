@@ -0,0 +1,228 @@
+use syntax::ast::{self, AstNode};
+
+use crate::{AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: convert_two_arm_bool_match_to_matches_macro
+//
+// Converts a `match` that evaluates to `true` for some patterns and to `false` otherwise into a
+// `matches!` invocation.
+//
+// ```
+// # //- minicore: option
+// fn is_positive(opt: Option<i32>) -> bool {
+//     match opt$0 {
+//         Some(val) if val > 0 => true,
+//         _ => false,
+//     }
+// }
+// ```
+// ->
+// ```
+// fn is_positive(opt: Option<i32>) -> bool {
+//     matches!(opt, Some(val) if val > 0)
+// }
+// ```
+pub(crate) fn convert_two_arm_bool_match_to_matches_macro(
+    acc: &mut Assists,
+    ctx: &AssistContext<'_>,
+) -> Option<()> {
+    let match_expr = ctx.find_node_at_offset::<ast::MatchExpr>()?;
+    let match_arm_list = match_expr.match_arm_list()?;
+    let mut arms = match_arm_list.arms().collect::<Vec<_>>();
+    if arms.len() < 2 {
+        return None;
+    }
+    if match_arm_list.syntax().text_range().contains(ctx.offset()) {
+        return None;
+    }
+
+    let last_arm = arms.pop()?;
+    if !matches!(last_arm.pat()?, ast::Pat::WildcardPat(_)) || last_arm.guard().is_some() {
+        return None;
+    }
+    let last_value = bool_literal(&last_arm.expr()?)?;
+    for arm in &arms {
+        if bool_literal(&arm.expr()?)? == last_value {
+            return None;
+        }
+    }
+    // Several guards can't be merged into a single pattern.
+    if arms.len() > 1 && arms.iter().any(|arm| arm.guard().is_some()) {
+        return None;
+    }
+    if !ctx.sema.type_of_expr(&ast::Expr::MatchExpr(match_expr.clone()))?.original.is_bool() {
+        return None;
+    }
+    let scrutinee = match_expr.expr()?;
+
+    let target = match_expr.syntax().text_range();
+    acc.add(
+        AssistId("convert_two_arm_bool_match_to_matches_macro", AssistKind::RefactorRewrite),
+        "Convert to matches!",
+        target,
+        |builder| {
+            let pats = arms
+                .iter()
+                .filter_map(|arm| arm.pat())
+                .map(|it| it.to_string())
+                .collect::<Vec<_>>()
+                .join(" | ");
+            let guard = arms
+                .iter()
+                .find_map(|arm| arm.guard())
+                .map_or_else(String::new, |it| format!(" {}", it));
+            let negation = if last_value { "!" } else { "" };
+            builder
+                .replace(target, format!("{}matches!({}, {}{})", negation, scrutinee, pats, guard));
+        },
+    )
+}
+
+fn bool_literal(expr: &ast::Expr) -> Option<bool> {
+    match expr {
+        ast::Expr::Literal(lit) => match lit.kind() {
+            ast::LiteralKind::Bool(value) => Some(value),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    use super::*;
+
+    #[test]
+    fn convert_simple_match() {
+        check_assist(
+            convert_two_arm_bool_match_to_matches_macro,
+            r#"
+//- minicore: option
+fn foo(a: Option<u32>) -> bool {
+    $0match a {
+        Some(_) => true,
+        _ => false,
+    }
+}
+"#,
+            r#"
+fn foo(a: Option<u32>) -> bool {
+    matches!(a, Some(_))
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_inverted_match_with_guard() {
+        check_assist(
+            convert_two_arm_bool_match_to_matches_macro,
+            r#"
+//- minicore: option
+fn foo(a: Option<u32>) -> bool {
+    match a$0 {
+        Some(val) if val > 3 => false,
+        _ => true,
+    }
+}
+"#,
+            r#"
+fn foo(a: Option<u32>) -> bool {
+    !matches!(a, Some(val) if val > 3)
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_match_with_several_arms() {
+        check_assist(
+            convert_two_arm_bool_match_to_matches_macro,
+            r#"
+enum E { A, B, C }
+fn foo(e: E) -> bool {
+    mat$0ch e {
+        E::A => true,
+        E::B => true,
+        _ => false,
+    }
+}
+"#,
+            r#"
+enum E { A, B, C }
+fn foo(e: E) -> bool {
+    matches!(e, E::A | E::B)
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_match_target() {
+        check_assist_target(
+            convert_two_arm_bool_match_to_matches_macro,
+            r#"
+fn foo(a: u32) -> bool {
+    let _ = $0match a { 1 => true, _ => false };
+}
+"#,
+            "match a { 1 => true, _ => false }",
+        );
+    }
+
+    #[test]
+    fn not_applicable_without_bool_arms() {
+        check_assist_not_applicable(
+            convert_two_arm_bool_match_to_matches_macro,
+            r#"
+fn foo(a: u32) -> bool {
+    $0match a {
+        1 => true,
+        2 => false,
+        _ => a > 3,
+    }
+}
+"#,
+        );
+        check_assist_not_applicable(
+            convert_two_arm_bool_match_to_matches_macro,
+            r#"
+fn foo(a: u32) -> bool {
+    $0match a {
+        1 => true,
+        _ => true,
+    }
+}
+"#,
+        );
+        check_assist_not_applicable(
+            convert_two_arm_bool_match_to_matches_macro,
+            r#"
+fn foo(a: u32) -> bool {
+    $0match a {
+        1 if a > 0 => true,
+        2 => true,
+        _ => false,
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_inside_arms() {
+        check_assist_not_applicable(
+            convert_two_arm_bool_match_to_matches_macro,
+            r#"
+fn foo(a: u32) -> bool {
+    match a {
+        1 => $0true,
+        _ => false,
+    }
+}
+"#,
+        );
+    }
+}
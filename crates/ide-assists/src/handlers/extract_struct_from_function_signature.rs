@@ -0,0 +1,757 @@
+use hir::{Module, ModuleDef};
+use ide_db::{
+    defs::Definition,
+    helpers::mod_path_to_ast,
+    imports::insert_use::{insert_use, ImportScope},
+    search::FileReference,
+    FxHashSet,
+};
+use itertools::Itertools;
+use stdx::{to_camel_case, to_lower_snake_case};
+use syntax::{
+    ast::{
+        self,
+        edit::IndentLevel,
+        edit_in_place::{GenericParamsOwnerEdit, Indent},
+        make, AstNode, HasArgList, HasGenericParams, HasName, HasVisibility,
+    },
+    match_ast, ted, SyntaxKind,
+};
+
+use crate::{assist_context::SourceChangeBuilder, AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: extract_struct_from_function_signature
+//
+// Extracts a struct from the selected parameters of a function, and passes it in their place.
+//
+// ```
+// fn draw_line($0x: f32, y: f32$0, color: u32) {}
+//
+// fn main() {
+//     draw_line(1.0, 2.0, 0xff);
+// }
+// ```
+// ->
+// ```
+// struct DrawLineParams { x: f32, y: f32 }
+//
+// fn draw_line(draw_line_params: DrawLineParams, color: u32) {
+//     let DrawLineParams { x, y } = draw_line_params;
+// }
+//
+// fn main() {
+//     draw_line(DrawLineParams { x: 1.0, y: 2.0 }, 0xff);
+// }
+// ```
+pub(crate) fn extract_struct_from_function_signature(
+    acc: &mut Assists,
+    ctx: &AssistContext<'_>,
+) -> Option<()> {
+    if ctx.has_empty_selection() {
+        return None;
+    }
+    let param_list = ctx.find_node_at_offset::<ast::ParamList>()?;
+    let selection = ctx.selection_trimmed();
+    if !param_list.syntax().text_range().contains_range(selection) {
+        return None;
+    }
+    let fn_ = ast::Fn::cast(param_list.syntax().parent()?)?;
+    fn_.body()?;
+    if is_trait_item(&fn_) {
+        return None;
+    }
+
+    // Indices into the non-self parameters, which is how call arguments are matched up.
+    let selected = param_list
+        .params()
+        .enumerate()
+        .filter(|(_, param)| param.syntax().text_range().intersect(selection).is_some())
+        .collect_vec();
+    let (first_idx, _) = selected.first()?;
+    let (last_idx, _) = selected.last()?;
+    let fields = selected
+        .iter()
+        .map(|(_, param)| {
+            let pat = match param.pat()? {
+                ast::Pat::IdentPat(it) if it.ref_token().is_none() && it.pat().is_none() => it,
+                _ => return None,
+            };
+            let ty = param.ty()?;
+            if ty.syntax().descendants().any(|it| ast::ImplTraitType::can_cast(it.kind())) {
+                return None;
+            }
+            Some((pat, ty))
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    let fn_name = fn_.name()?;
+    let strukt_name = format!("{}Params", to_camel_case(&fn_name.text()));
+    let function = ctx.sema.to_def(&fn_)?;
+    let module = function.module(ctx.db());
+    if module
+        .scope(ctx.db(), None)
+        .into_iter()
+        .any(|(name, _)| name.to_smol_str() == strukt_name.as_str())
+    {
+        cov_mark::hit!(extract_struct_from_function_signature_name_taken);
+        return None;
+    }
+
+    let target = fields
+        .iter()
+        .map(|(pat, _)| pat.syntax().text_range())
+        .fold(selection, |acc, it| acc.cover(it));
+    acc.add(
+        AssistId("extract_struct_from_function_signature", AssistKind::RefactorExtract),
+        "Extract struct from function parameters",
+        target,
+        |builder| {
+            let usages = Definition::Function(function).usages(&ctx.sema).all();
+            let has_self_param = param_list.self_param().is_some();
+            let field_names = fields
+                .iter()
+                .filter_map(|(pat, _)| pat.name().map(|it| it.text().to_string()))
+                .collect_vec();
+            let range = *first_idx..*last_idx + 1;
+
+            let mut visited_modules = FxHashSet::default();
+            visited_modules.insert(module);
+            // The defining file is edited last, so that the builder only switches to it once.
+            let mut def_file_references = None;
+            for (file_id, references) in usages {
+                if file_id == ctx.file_id() {
+                    def_file_references = Some(references);
+                    continue;
+                }
+                builder.edit_file(file_id);
+                let processed = process_references(
+                    ctx,
+                    builder,
+                    &mut visited_modules,
+                    module,
+                    &strukt_name,
+                    references,
+                );
+                for (call, scope) in processed {
+                    update_call(
+                        ctx,
+                        call,
+                        scope,
+                        &strukt_name,
+                        &field_names,
+                        &range,
+                        has_self_param,
+                    );
+                }
+            }
+
+            builder.edit_file(ctx.file_id());
+            let fn_ = builder.make_mut(fn_.clone());
+            let processed = def_file_references
+                .map(|references| {
+                    process_references(
+                        ctx,
+                        builder,
+                        &mut visited_modules,
+                        module,
+                        &strukt_name,
+                        references,
+                    )
+                })
+                .unwrap_or_default();
+            for (call, scope) in processed {
+                update_call(ctx, call, scope, &strukt_name, &field_names, &range, has_self_param);
+            }
+
+            let item = fn_
+                .syntax()
+                .ancestors()
+                .take_while(|it| !ast::SourceFile::can_cast(it.kind()))
+                .filter(|it| ast::Fn::can_cast(it.kind()) || ast::Impl::can_cast(it.kind()))
+                .last()
+                .unwrap_or_else(|| fn_.syntax().clone());
+            let item = match ast::Item::cast(item.clone()) {
+                Some(it) => it,
+                None => return,
+            };
+            let indent = item.indent_level();
+            let (strukt, generic_args) = create_struct_def(&fn_, &strukt_name, &fields);
+            strukt.reindent_to(indent);
+            ted::insert_all_raw(
+                ted::Position::before(item.syntax()),
+                vec![
+                    strukt.syntax().clone().into(),
+                    make::tokens::whitespace(&format!("\n\n{}", indent)).into(),
+                ],
+            );
+
+            update_fn(&fn_, &strukt_name, generic_args, &fields, &range);
+        },
+    )
+}
+
+fn is_trait_item(fn_: &ast::Fn) -> bool {
+    let container = fn_.syntax().parent().and_then(ast::AssocItemList::cast);
+    match container.and_then(|it| it.syntax().parent()) {
+        Some(parent) => match_ast! {
+            match parent {
+                ast::Trait(_it) => true,
+                ast::Impl(it) => it.trait_().is_some(),
+                _ => false,
+            }
+        },
+        None => false,
+    }
+}
+
+/// Creates the struct definition, returning it together with the generic arguments the function
+/// has to pass to it.
+fn create_struct_def(
+    fn_: &ast::Fn,
+    strukt_name: &str,
+    fields: &[(ast::IdentPat, ast::Type)],
+) -> (ast::Struct, Vec<String>) {
+    let fn_generics = fn_.generic_param_list();
+    let known = fn_generics.iter().flat_map(|it| it.generic_params()).collect_vec();
+    let used = known
+        .into_iter()
+        .filter(|param| fields.iter().any(|(_, ty)| mentions_generic_param(ty, param)))
+        .collect_vec();
+
+    // Struct fields can't have elided lifetimes, so those get a fresh lifetime parameter.
+    let fresh_lifetime = fields
+        .iter()
+        .any(|(_, ty)| has_elided_lifetime(ty))
+        .then(|| fresh_lifetime_name(fn_generics.as_ref()));
+
+    let mut params = Vec::new();
+    let mut args = Vec::new();
+    if let Some(lifetime) = &fresh_lifetime {
+        params
+            .push(ast::GenericParam::LifetimeParam(make::lifetime_param(make::lifetime(lifetime))));
+        args.push("'_".to_owned());
+    }
+    for param in &used {
+        let arg = match param {
+            ast::GenericParam::LifetimeParam(it) => it.lifetime().map(|it| it.to_string()),
+            ast::GenericParam::TypeParam(it) => it.name().map(|it| it.to_string()),
+            ast::GenericParam::ConstParam(it) => it.name().map(|it| it.to_string()),
+        };
+        args.extend(arg);
+        params.push(param.clone());
+    }
+
+    let visibility = fn_.visibility();
+    let record_fields = fields.iter().filter_map(|(pat, ty)| {
+        let ty = match &fresh_lifetime {
+            Some(lifetime) => fill_elided_lifetimes(ty, lifetime),
+            None => ty.clone(),
+        };
+        Some(make::record_field(visibility.clone(), pat.name()?, ty))
+    });
+    let field_list = make::record_field_list(record_fields);
+    let generic_param_list = (!params.is_empty()).then(|| make::generic_param_list(params));
+    let strukt =
+        make::struct_(visibility, make::name(strukt_name), generic_param_list, field_list.into())
+            .clone_for_update();
+    if let Some(field_list) = strukt.field_list() {
+        ted::insert(ted::Position::before(field_list.syntax()), make::tokens::single_space());
+    }
+
+    let predicates = fn_
+        .where_clause()
+        .into_iter()
+        .flat_map(|it| it.predicates())
+        .filter(|pred| {
+            let bounded = pred
+                .ty()
+                .map(|it| it.syntax().text().to_string())
+                .or_else(|| pred.lifetime().map(|it| it.to_string()));
+            used.iter().any(|param| Some(generic_param_text(param)) == bounded)
+        })
+        .collect_vec();
+    if !predicates.is_empty() {
+        let where_clause = strukt.get_or_create_where_clause();
+        for pred in predicates {
+            where_clause.add_predicate(pred.clone_subtree().clone_for_update());
+        }
+    }
+
+    (strukt, args)
+}
+
+fn generic_param_text(param: &ast::GenericParam) -> String {
+    match param {
+        ast::GenericParam::LifetimeParam(it) => {
+            it.lifetime().map(|it| it.to_string()).unwrap_or_default()
+        }
+        ast::GenericParam::TypeParam(it) => it.name().map(|it| it.to_string()).unwrap_or_default(),
+        ast::GenericParam::ConstParam(it) => it.name().map(|it| it.to_string()).unwrap_or_default(),
+    }
+}
+
+fn mentions_generic_param(ty: &ast::Type, param: &ast::GenericParam) -> bool {
+    let text = generic_param_text(param);
+    match param {
+        ast::GenericParam::LifetimeParam(_) => ty
+            .syntax()
+            .descendants()
+            .filter_map(ast::Lifetime::cast)
+            .any(|it| it.text() == text.as_str()),
+        ast::GenericParam::TypeParam(_) | ast::GenericParam::ConstParam(_) => ty
+            .syntax()
+            .descendants()
+            .filter_map(ast::Path::cast)
+            .filter(|it| it.qualifier().is_none())
+            .filter_map(|it| it.segment()?.name_ref())
+            .any(|it| it.text() == text.as_str()),
+    }
+}
+
+fn has_elided_lifetime(ty: &ast::Type) -> bool {
+    ty.syntax().descendants().any(|node| {
+        match_ast! {
+            match node {
+                ast::RefType(it) => it.lifetime().is_none(),
+                ast::Lifetime(it) => it.text() == "'_",
+                _ => false,
+            }
+        }
+    })
+}
+
+fn fresh_lifetime_name(generics: Option<&ast::GenericParamList>) -> String {
+    let taken = generics
+        .into_iter()
+        .flat_map(|it| it.lifetime_params())
+        .filter_map(|it| it.lifetime())
+        .map(|it| it.to_string())
+        .collect::<FxHashSet<_>>();
+    ('a'..='z')
+        .map(|c| format!("'{}", c))
+        .find(|it| !taken.contains(it))
+        .unwrap_or_else(|| "'params".to_owned())
+}
+
+fn fill_elided_lifetimes(ty: &ast::Type, lifetime: &str) -> ast::Type {
+    let ty = ty.clone_subtree().clone_for_update();
+    let nodes = ty.syntax().descendants().collect_vec();
+    for node in nodes {
+        if let Some(ref_type) = ast::RefType::cast(node.clone()) {
+            if ref_type.lifetime().is_none() {
+                if let Some(amp) = ref_type.amp_token() {
+                    ted::insert_all_raw(
+                        ted::Position::after(amp),
+                        vec![
+                            make::lifetime(lifetime).clone_for_update().syntax().clone().into(),
+                            make::tokens::single_space().into(),
+                        ],
+                    );
+                }
+            }
+        } else if let Some(it) = ast::Lifetime::cast(node) {
+            if it.text() == "'_" {
+                ted::replace(it.syntax(), make::lifetime(lifetime).clone_for_update().syntax());
+            }
+        }
+    }
+    ty
+}
+
+fn update_fn(
+    fn_: &ast::Fn,
+    strukt_name: &str,
+    generic_args: Vec<String>,
+    fields: &[(ast::IdentPat, ast::Type)],
+    range: &std::ops::Range<usize>,
+) -> Option<()> {
+    let param_list = fn_.param_list()?;
+    let param_name = to_lower_snake_case(strukt_name);
+    let ty = if generic_args.is_empty() {
+        make::ty(strukt_name)
+    } else {
+        make::ty(&format!("{}<{}>", strukt_name, generic_args.join(", ")))
+    };
+    let new_param = make::param(make::ext::simple_ident_pat(make::name(&param_name)).into(), ty);
+    let params = param_list.params().enumerate().filter_map(|(idx, param)| {
+        if idx == range.start {
+            Some(new_param.clone())
+        } else if range.contains(&idx) {
+            None
+        } else {
+            Some(param.clone_subtree())
+        }
+    });
+    let new_param_list =
+        make::param_list(param_list.self_param().map(|it| it.clone_subtree()), params);
+    ted::replace(param_list.syntax(), new_param_list.clone_for_update().syntax());
+
+    let field_pats = fields.iter().filter_map(|(pat, _)| {
+        let name = pat.name()?;
+        let field =
+            make::record_pat_field_shorthand(make::name_ref(&name.text())).clone_for_update();
+        if pat.mut_token().is_some() {
+            let mut_pat = make::ident_pat(false, true, name).clone_for_update();
+            ted::replace(field.pat()?.syntax(), mut_pat.syntax());
+        }
+        Some(field)
+    });
+    let destructure = make::let_stmt(
+        make::record_pat_with_fields(
+            make::ext::ident_path(strukt_name),
+            make::record_pat_field_list(field_pats),
+        )
+        .into(),
+        None,
+        Some(make::expr_path(make::ext::ident_path(&param_name))),
+    )
+    .clone_for_update();
+
+    let stmt_list = fn_.body()?.stmt_list()?;
+    let indent = IndentLevel::from_node(fn_.syntax());
+    let l_curly = stmt_list.l_curly_token()?;
+    let r_curly = stmt_list.r_curly_token()?;
+    // One-line bodies are spread over several lines.
+    let is_empty = stmt_list.statements().next().is_none() && stmt_list.tail_expr().is_none();
+    if !stmt_list.syntax().text().contains_char('\n') {
+        for token in [l_curly.next_token(), r_curly.prev_token()].into_iter().flatten() {
+            if token.kind() == SyntaxKind::WHITESPACE {
+                ted::remove(token);
+            }
+        }
+        ted::insert(
+            ted::Position::before(&r_curly),
+            make::tokens::whitespace(&format!("\n{}", indent)),
+        );
+        if !is_empty {
+            ted::insert(
+                ted::Position::after(&l_curly),
+                make::tokens::whitespace(&format!("\n{}", indent + 1)),
+            );
+        }
+    }
+    ted::insert_all_raw(
+        ted::Position::after(&l_curly),
+        vec![
+            make::tokens::whitespace(&format!("\n{}", indent + 1)).into(),
+            destructure.syntax().clone().into(),
+        ],
+    );
+    Some(())
+}
+
+fn process_references(
+    ctx: &AssistContext<'_>,
+    builder: &mut SourceChangeBuilder,
+    visited_modules: &mut FxHashSet<Module>,
+    target_module: Module,
+    strukt_name: &str,
+    refs: Vec<FileReference>,
+) -> Vec<(ast::ArgList, Option<(ImportScope, ast::Path)>)> {
+    // All the nodes have to be made mutable before the tree is edited.
+    refs.into_iter()
+        .filter_map(|reference| {
+            let name_ref = reference.name.as_name_ref()?;
+            let arg_list = call_arg_list(name_ref)?;
+            let module = ctx.sema.scope(arg_list.syntax())?.module();
+            let arg_list = builder.make_mut(arg_list);
+            if visited_modules.insert(module) {
+                let mod_path = module.find_use_path_prefixed(
+                    ctx.db(),
+                    ModuleDef::Module(target_module),
+                    ctx.config.insert_use.prefix_kind,
+                );
+                if let Some(mod_path) = mod_path {
+                    let path = make::path_concat(
+                        mod_path_to_ast(&mod_path),
+                        make::ext::ident_path(strukt_name),
+                    );
+                    let scope =
+                        ImportScope::find_insert_use_container(arg_list.syntax(), &ctx.sema)?;
+                    return Some((arg_list, Some((scope, path))));
+                }
+            }
+            Some((arg_list, None))
+        })
+        .collect()
+}
+
+fn call_arg_list(name_ref: &ast::NameRef) -> Option<ast::ArgList> {
+    let parent = name_ref.syntax().parent()?;
+    match_ast! {
+        match parent {
+            ast::MethodCallExpr(it) => it.arg_list(),
+            ast::PathSegment(it) => {
+                let path = it.parent_path();
+                if path.parent_path().is_some() {
+                    return None;
+                }
+                let path_expr = ast::PathExpr::cast(path.syntax().parent()?)?;
+                let call = ast::CallExpr::cast(path_expr.syntax().parent()?)?;
+                (call.expr()?.syntax() == path_expr.syntax()).then(|| call.arg_list())?
+            },
+            _ => None,
+        }
+    }
+}
+
+fn update_call(
+    ctx: &AssistContext<'_>,
+    arg_list: ast::ArgList,
+    import: Option<(ImportScope, ast::Path)>,
+    strukt_name: &str,
+    field_names: &[String],
+    range: &std::ops::Range<usize>,
+    has_self_param: bool,
+) -> Option<()> {
+    // Associated function calls pass the receiver as the first argument.
+    let offset = usize::from(
+        has_self_param && arg_list.syntax().parent().and_then(ast::CallExpr::cast).is_some(),
+    );
+    let args = arg_list.args().collect_vec();
+    let selected = args.get(range.start + offset..range.end + offset)?;
+    if selected.len() != field_names.len() {
+        return None;
+    }
+
+    if let Some((scope, path)) = import {
+        insert_use(&scope, path.clone_for_update(), &ctx.config.insert_use);
+    }
+    let fields = field_names.iter().zip(selected).map(|(name, arg)| {
+        let shorthand = match arg {
+            ast::Expr::PathExpr(it) => it.path().map_or(false, |it| it.to_string() == *name),
+            _ => false,
+        };
+        let expr = (!shorthand).then(|| arg.clone_subtree());
+        make::record_expr_field(make::name_ref(name), expr)
+    });
+    let record_expr =
+        make::record_expr(make::ext::ident_path(strukt_name), make::record_expr_field_list(fields));
+    let new_args = args.iter().enumerate().filter_map(|(idx, arg)| {
+        if idx == range.start + offset {
+            Some(ast::Expr::RecordExpr(record_expr.clone()))
+        } else if (range.start + offset..range.end + offset).contains(&idx) {
+            None
+        } else {
+            Some(arg.clone_subtree())
+        }
+    });
+    ted::replace(arg_list.syntax(), make::arg_list(new_args).clone_for_update().syntax());
+    Some(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn extract_simple() {
+        check_assist(
+            extract_struct_from_function_signature,
+            r#"
+fn foo(a: u32, $0b: i32, c: bool$0) -> i32 {
+    if c { b } else { a as i32 }
+}
+
+fn main() {
+    let b = 2;
+    foo(1, b, true);
+}
+"#,
+            r#"
+struct FooParams { b: i32, c: bool }
+
+fn foo(a: u32, foo_params: FooParams) -> i32 {
+    let FooParams { b, c } = foo_params;
+    if c { b } else { a as i32 }
+}
+
+fn main() {
+    let b = 2;
+    foo(1, FooParams { b, c: true });
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn extract_mut_params_from_one_line_body() {
+        check_assist(
+            extract_struct_from_function_signature,
+            r#"
+fn bump($0mut a: u32, b: u32$0) -> u32 { a += b; a }
+"#,
+            r#"
+struct BumpParams { a: u32, b: u32 }
+
+fn bump(bump_params: BumpParams) -> u32 {
+    let BumpParams { mut a, b } = bump_params;
+    a += b; a
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn extract_with_generics_and_lifetimes() {
+        check_assist(
+            extract_struct_from_function_signature,
+            r#"
+pub fn find<'a, T: PartialEq, U>(
+    $0haystack: &'a [T],
+    needle: &T,$0
+    other: U,
+) -> Option<&'a T>
+where
+    T: Copy,
+    U: Copy,
+{
+    haystack.iter().find(|it| *it == needle)
+}
+"#,
+            r#"
+pub struct FindParams<'b, 'a, T: PartialEq> where T: Copy { pub haystack: &'a [T], pub needle: &'b T }
+
+pub fn find<'a, T: PartialEq, U>(find_params: FindParams<'_, 'a, T>, other: U) -> Option<&'a T>
+where
+    T: Copy,
+    U: Copy,
+{
+    let FindParams { haystack, needle } = find_params;
+    haystack.iter().find(|it| *it == needle)
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn extract_from_method() {
+        check_assist(
+            extract_struct_from_function_signature,
+            r#"
+struct S;
+
+impl S {
+    fn set(&mut self, $0x: i32, y: i32$0) {}
+}
+
+fn main() {
+    let mut s = S;
+    s.set(1, 2);
+    S::set(&mut s, 3, 4);
+}
+"#,
+            r#"
+struct S;
+
+struct SetParams { x: i32, y: i32 }
+
+impl S {
+    fn set(&mut self, set_params: SetParams) {
+        let SetParams { x, y } = set_params;
+    }
+}
+
+fn main() {
+    let mut s = S;
+    s.set(SetParams { x: 1, y: 2 });
+    S::set(&mut s, SetParams { x: 3, y: 4 });
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn extract_imports_struct_in_other_modules() {
+        check_assist(
+            extract_struct_from_function_signature,
+            r#"
+//- /main.rs
+mod foo;
+
+fn main() {
+    foo::area(1, 2);
+}
+//- /foo.rs
+pub fn area($0w: u32, h: u32$0) -> u32 {
+    w * h
+}
+"#,
+            r#"
+//- /main.rs
+use foo::AreaParams;
+
+mod foo;
+
+fn main() {
+    foo::area(AreaParams { w: 1, h: 2 });
+}
+//- /foo.rs
+pub struct AreaParams { pub w: u32, pub h: u32 }
+
+pub fn area(area_params: AreaParams) -> u32 {
+    let AreaParams { w, h } = area_params;
+    w * h
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_without_selection() {
+        check_assist_not_applicable(
+            extract_struct_from_function_signature,
+            r#"fn foo(a$0: u32) {}"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_trait_items() {
+        check_assist_not_applicable(
+            extract_struct_from_function_signature,
+            r#"
+trait Tr {
+    fn foo(&self, $0a: u32$0) {}
+}
+"#,
+        );
+        check_assist_not_applicable(
+            extract_struct_from_function_signature,
+            r#"
+trait Tr {
+    fn foo(&self, a: u32);
+}
+struct S;
+impl Tr for S {
+    fn foo(&self, $0a: u32$0) {}
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_patterns_and_impl_trait() {
+        check_assist_not_applicable(
+            extract_struct_from_function_signature,
+            r#"fn foo($0(a, b): (u32, u32)$0) {}"#,
+        );
+        check_assist_not_applicable(
+            extract_struct_from_function_signature,
+            r#"fn foo($0a: impl Copy$0) {}"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_if_name_taken() {
+        cov_mark::check!(extract_struct_from_function_signature_name_taken);
+        check_assist_not_applicable(
+            extract_struct_from_function_signature,
+            r#"
+struct FooParams;
+fn foo($0a: u32$0) {}
+"#,
+        );
+    }
+}
@@ -137,7 +137,7 @@ fn add_vis_to_referenced_record_field(acc: &mut Assists, ctx: &AssistContext<'_>
     })
 }
 
-fn target_data_for_def(
+pub(crate) fn target_data_for_def(
     db: &dyn HirDatabase,
     def: hir::ModuleDef,
 ) -> Option<(TextSize, Option<ast::Visibility>, TextRange, FileId, Option<hir::Name>)> {
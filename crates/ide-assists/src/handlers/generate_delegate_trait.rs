@@ -0,0 +1,510 @@
+use hir::{self, HasVisibility, ModuleDef};
+use ide_db::{helpers::mod_path_to_ast, path_transform::PathTransform, FxHashSet};
+use syntax::{
+    ast::{self, edit::AstNodeEdit, make, AstNode, HasGenericParams, HasName, SelfParamKind},
+    ted, SyntaxNode,
+};
+
+use crate::{
+    utils::{convert_param_list_to_arg_list, generate_trait_impl_text, render_snippet, Cursor},
+    AssistContext, AssistId, AssistKind, Assists, GroupLabel,
+};
+
+// Assist: generate_delegate_trait
+//
+// Generate an implementation of a trait that forwards every item to the
+// implementation of the selected field.
+//
+// ```
+// trait Describe {
+//     const KIND: &'static str;
+//     fn describe(&self, verbose: bool) -> String;
+// }
+//
+// struct Age(u8);
+// impl Describe for Age {
+//     const KIND: &'static str = "age";
+//     fn describe(&self, verbose: bool) -> String {
+//         String::new()
+//     }
+// }
+//
+// struct Person {
+//     ag$0e: Age,
+// }
+// ```
+// ->
+// ```
+// trait Describe {
+//     const KIND: &'static str;
+//     fn describe(&self, verbose: bool) -> String;
+// }
+//
+// struct Age(u8);
+// impl Describe for Age {
+//     const KIND: &'static str = "age";
+//     fn describe(&self, verbose: bool) -> String {
+//         String::new()
+//     }
+// }
+//
+// struct Person {
+//     age: Age,
+// }
+//
+// $0impl Describe for Person {
+//     const KIND: &'static str = <Age as Describe>::KIND;
+//
+//     fn describe(&self, verbose: bool) -> String {
+//         <Age as Describe>::describe(&self.age, verbose)
+//     }
+// }
+// ```
+pub(crate) fn generate_delegate_trait(acc: &mut Assists, ctx: &AssistContext<'_>) -> Option<()> {
+    let strukt = ctx.find_node_at_offset::<ast::Struct>()?;
+    // The name is needed to write the impl header.
+    strukt.name()?;
+
+    let (field_name, field_ty, target) = match ctx.find_node_at_offset::<ast::RecordField>() {
+        Some(field) => {
+            let field_name = field.name()?;
+            let field_ty = field.ty()?;
+            (format!("{}", field_name), field_ty, field.syntax().text_range())
+        }
+        None => {
+            let field = ctx.find_node_at_offset::<ast::TupleField>()?;
+            let field_list = ctx.find_node_at_offset::<ast::TupleFieldList>()?;
+            let field_list_index = field_list.fields().position(|it| it == field)?;
+            let field_ty = field.ty()?;
+            (format!("{}", field_list_index), field_ty, field.syntax().text_range())
+        }
+    };
+
+    let sema_strukt = ctx.sema.to_def(&strukt)?;
+    let current_module = sema_strukt.module(ctx.db());
+    let sema_field_ty = ctx.sema.resolve_type(&field_ty)?;
+    let implemented_traits: FxHashSet<hir::Trait> =
+        hir::Impl::all_for_type(ctx.db(), sema_strukt.ty(ctx.db()))
+            .into_iter()
+            .filter_map(|impl_| impl_.trait_(ctx.db()))
+            .collect();
+
+    for impl_def in hir::Impl::all_for_type(ctx.db(), sema_field_ty) {
+        let trait_ = match impl_def.trait_(ctx.db()) {
+            Some(it) => it,
+            None => continue,
+        };
+        if implemented_traits.contains(&trait_)
+            || impl_def.is_negative(ctx.db())
+            || impl_def.is_builtin_derive(ctx.db()).is_some()
+            || trait_.is_unsafe(ctx.db())
+            || !trait_.is_visible_from(ctx.db(), current_module)
+        {
+            continue;
+        }
+        let impl_ = match ctx.sema.source(impl_def) {
+            Some(it) => it.value,
+            None => continue,
+        };
+        // Blanket and other generic impls would need their parameters carried
+        // over to the new impl, which we don't attempt.
+        if impl_.generic_param_list().is_some() {
+            continue;
+        }
+        let trait_path = match current_module.find_use_path(ctx.db(), ModuleDef::Trait(trait_)) {
+            Some(it) => mod_path_to_ast(&it),
+            None => continue,
+        };
+        let trait_args = match impl_.trait_() {
+            Some(ast::Type::PathType(ty)) => {
+                ty.path().and_then(|it| it.segment()).and_then(|it| it.generic_arg_list())
+            }
+            _ => None,
+        };
+        let trait_path = match trait_args {
+            Some(args) => match make::ty(&format!("{}{}", trait_path, args)) {
+                ast::Type::PathType(ty) => match ty.path() {
+                    Some(it) => it,
+                    None => continue,
+                },
+                _ => continue,
+            },
+            None => trait_path,
+        };
+        let items =
+            match delegate_items(ctx, &strukt, trait_, &impl_, &trait_path, &field_name, &field_ty)
+            {
+                Some(items) if !items.is_empty() => items,
+                _ => continue,
+            };
+
+        acc.add_group(
+            &GroupLabel("Generate delegate trait impls…".to_owned()),
+            AssistId("generate_delegate_trait", AssistKind::Generate),
+            format!("Generate delegate impl of `{}` for `{}`", trait_.name(ctx.db()), field_name),
+            target,
+            |builder| {
+                // The parameters of the struct go on the `impl`, only their names on the type.
+                let adt = ast::Adt::Struct(strukt.clone());
+                let impl_text = generate_trait_impl_text(&adt, &trait_path.to_string(), "");
+                let impl_def = match ast::SourceFile::parse(&impl_text)
+                    .tree()
+                    .syntax()
+                    .descendants()
+                    .find_map(ast::Impl::cast)
+                {
+                    Some(it) => it.clone_for_update(),
+                    None => return,
+                };
+                let assoc_items = impl_def.get_or_create_assoc_item_list();
+                for item in items {
+                    assoc_items.add_item(item);
+                }
+
+                let offset = strukt.syntax().text_range().end();
+                match ctx.config.snippet_cap {
+                    Some(cap) => {
+                        let cursor = Cursor::Before(impl_def.syntax());
+                        let snippet = render_snippet(cap, impl_def.syntax(), cursor);
+                        builder.insert_snippet(cap, offset, format!("\n\n{}", snippet));
+                    }
+                    None => builder.insert(offset, format!("\n\n{}", impl_def.syntax())),
+                }
+            },
+        )?;
+    }
+    Some(())
+}
+
+/// Creates the items of the delegating impl, or `None` if some item of the
+/// trait can't be forwarded, e.g. because it takes or returns `Self`.
+fn delegate_items(
+    ctx: &AssistContext<'_>,
+    strukt: &ast::Struct,
+    trait_: hir::Trait,
+    impl_: &ast::Impl,
+    trait_path: &ast::Path,
+    field_name: &str,
+    field_ty: &ast::Type,
+) -> Option<Vec<ast::AssocItem>> {
+    let target_scope = ctx.sema.scope(strukt.syntax())?;
+    let source_scope = ctx.sema.scope_for_def(trait_);
+    let transform = PathTransform::trait_impl(&target_scope, &source_scope, trait_, impl_.clone());
+    let qualified = |name: &str| format!("<{} as {}>::{}", field_ty, trait_path, name);
+
+    let mut items = Vec::new();
+    for item in trait_.items(ctx.db()) {
+        let item: ast::AssocItem = match item {
+            hir::AssocItem::Function(f) => {
+                let source = ctx.sema.source(f)?.value.clone_subtree().clone_for_update();
+                transform.apply(source.syntax());
+                let name = source.name()?;
+                let params = source.param_list()?;
+                let mentions_self = params.params().any(|it| mentions_self(it.syntax()))
+                    || source.ret_type().map_or(false, |it| mentions_self(it.syntax()))
+                    || source.generic_param_list().map_or(false, |it| mentions_self(it.syntax()));
+                if mentions_self {
+                    return None;
+                }
+
+                for (idx, param) in params.params().enumerate() {
+                    match param.pat() {
+                        Some(ast::Pat::IdentPat(_)) => (),
+                        Some(pat) => {
+                            let name = make::name(&format!("arg{}", idx));
+                            let new_pat = make::ident_pat(false, false, name).clone_for_update();
+                            ted::replace(pat.syntax(), new_pat.syntax());
+                        }
+                        None => return None,
+                    }
+                }
+
+                let receiver = match params.self_param() {
+                    Some(self_param) if self_param.ty().is_some() => return None,
+                    Some(self_param) => {
+                        let field = make::ext::field_from_idents(["self", field_name])?;
+                        Some(match self_param.kind() {
+                            SelfParamKind::Owned => field,
+                            SelfParamKind::Ref => make::expr_ref(field, false),
+                            SelfParamKind::MutRef => make::expr_ref(field, true),
+                        })
+                    }
+                    None => None,
+                };
+                let args = convert_param_list_to_arg_list(params.clone());
+                let args = make::arg_list(receiver.into_iter().chain(args.args()));
+                let mut call = make::expr_call(
+                    make::expr_path(make::path_from_text(&qualified(&name.to_string()))),
+                    args,
+                );
+                let is_async = source.async_token().is_some();
+                if is_async {
+                    call = make::expr_await(call);
+                }
+                let body = make::block_expr([], Some(call));
+                make::fn_(
+                    None,
+                    name,
+                    source.generic_param_list(),
+                    params,
+                    body,
+                    source.ret_type(),
+                    is_async,
+                )
+                .indent(ast::edit::IndentLevel(1))
+                .into()
+            }
+            hir::AssocItem::TypeAlias(type_alias) => {
+                let source = ctx.sema.source(type_alias)?.value.clone_subtree().clone_for_update();
+                // Generic associated types would need their arguments forwarded.
+                if source.generic_param_list().is_some() {
+                    return None;
+                }
+                transform.apply(source.syntax());
+                let name = source.name()?;
+                let ty = make::ty(&qualified(&name.to_string()));
+                make::ty_alias(name, ty).into()
+            }
+            hir::AssocItem::Const(konst) => {
+                let source = ctx.sema.source(konst)?.value.clone_subtree().clone_for_update();
+                transform.apply(source.syntax());
+                let name = source.name()?;
+                let ty = source.ty()?;
+                let expr = make::expr_path(make::path_from_text(&qualified(&name.to_string())));
+                make::item_const(None, name, ty, expr).into()
+            }
+        };
+        items.push(item.clone_for_update());
+    }
+    Some(items)
+}
+
+/// Whether `node` refers to `Self` itself, which would change meaning in the
+/// new impl. Associated items like `Self::Item` are fine.
+fn mentions_self(node: &SyntaxNode) -> bool {
+    node.descendants().filter_map(ast::Path::cast).any(|path| {
+        path.qualifier().is_none()
+            && path.parent_path().is_none()
+            && path.segment().map_or(false, |it| it.self_type_token().is_some())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn delegates_all_items() {
+        check_assist(
+            generate_delegate_trait,
+            r#"
+trait Shape {
+    type Unit;
+    const SIDES: u32;
+    fn area(&self) -> f64;
+    fn scale(&mut self, factor: f64);
+    fn into_unit(self) -> Self::Unit;
+    fn name() -> &'static str;
+}
+
+struct Square(f64);
+impl Shape for Square {
+    type Unit = f64;
+    const SIDES: u32 = 4;
+    fn area(&self) -> f64 { self.0 * self.0 }
+    fn scale(&mut self, factor: f64) { self.0 *= factor }
+    fn into_unit(self) -> f64 { self.0 }
+    fn name() -> &'static str { "square" }
+}
+
+struct Labeled {
+    label: String,
+    sha$0pe: Square,
+}
+"#,
+            r#"
+trait Shape {
+    type Unit;
+    const SIDES: u32;
+    fn area(&self) -> f64;
+    fn scale(&mut self, factor: f64);
+    fn into_unit(self) -> Self::Unit;
+    fn name() -> &'static str;
+}
+
+struct Square(f64);
+impl Shape for Square {
+    type Unit = f64;
+    const SIDES: u32 = 4;
+    fn area(&self) -> f64 { self.0 * self.0 }
+    fn scale(&mut self, factor: f64) { self.0 *= factor }
+    fn into_unit(self) -> f64 { self.0 }
+    fn name() -> &'static str { "square" }
+}
+
+struct Labeled {
+    label: String,
+    shape: Square,
+}
+
+$0impl Shape for Labeled {
+    type Unit = <Square as Shape>::Unit;
+
+    const SIDES: u32 = <Square as Shape>::SIDES;
+
+    fn area(&self) -> f64 {
+        <Square as Shape>::area(&self.shape)
+    }
+
+    fn scale(&mut self, factor: f64) {
+        <Square as Shape>::scale(&mut self.shape, factor)
+    }
+
+    fn into_unit(self) -> Self::Unit {
+        <Square as Shape>::into_unit(self.shape)
+    }
+
+    fn name() -> &'static str {
+        <Square as Shape>::name()
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn substitutes_trait_generics() {
+        check_assist(
+            generate_delegate_trait,
+            r#"
+mod convert {
+    pub trait Convert<T> {
+        fn convert(&self, value: T) -> Option<T>;
+    }
+}
+
+struct Id;
+impl convert::Convert<u32> for Id {
+    fn convert(&self, value: u32) -> Option<u32> { Some(value) }
+}
+
+struct Wrapper(I$0d);
+"#,
+            r#"
+mod convert {
+    pub trait Convert<T> {
+        fn convert(&self, value: T) -> Option<T>;
+    }
+}
+
+struct Id;
+impl convert::Convert<u32> for Id {
+    fn convert(&self, value: u32) -> Option<u32> { Some(value) }
+}
+
+struct Wrapper(Id);
+
+$0impl convert::Convert<u32> for Wrapper {
+    fn convert(&self, value: u32) -> Option<u32> {
+        <Id as convert::Convert<u32>>::convert(&self.0, value)
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn bounded_generic_struct() {
+        check_assist(
+            generate_delegate_trait,
+            r#"
+trait Describe {
+    fn describe(&self) -> u8;
+}
+
+struct Age(u8);
+impl Describe for Age {
+    fn describe(&self) -> u8 { self.0 }
+}
+
+struct Person<'a, T: Clone, const N: usize>
+where
+    T: Copy,
+{
+    ag$0e: Age,
+    names: &'a [T; N],
+}
+"#,
+            r#"
+trait Describe {
+    fn describe(&self) -> u8;
+}
+
+struct Age(u8);
+impl Describe for Age {
+    fn describe(&self) -> u8 { self.0 }
+}
+
+struct Person<'a, T: Clone, const N: usize>
+where
+    T: Copy,
+{
+    age: Age,
+    names: &'a [T; N],
+}
+
+$0impl<'a, T: Clone, const N: usize> Describe for Person<'a, T, N>
+where
+    T: Copy,
+{
+    fn describe(&self) -> u8 {
+        <Age as Describe>::describe(&self.age)
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_for_self_returning_or_implemented_traits() {
+        check_assist_not_applicable(
+            generate_delegate_trait,
+            r#"
+trait Duplicate {
+    fn duplicate(&self) -> Self;
+}
+
+struct Age(u8);
+impl Duplicate for Age {
+    fn duplicate(&self) -> Self { Age(self.0) }
+}
+
+struct Person {
+    ag$0e: Age,
+}
+"#,
+        );
+        check_assist_not_applicable(
+            generate_delegate_trait,
+            r#"
+trait Describe {
+    fn describe(&self) -> u8;
+}
+
+struct Age(u8);
+impl Describe for Age {
+    fn describe(&self) -> u8 { self.0 }
+}
+
+struct Person {
+    ag$0e: Age,
+}
+impl Describe for Person {
+    fn describe(&self) -> u8 { 0 }
+}
+"#,
+        );
+    }
+}
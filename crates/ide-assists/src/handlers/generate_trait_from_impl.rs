@@ -0,0 +1,482 @@
+use hir::ModuleDef;
+use ide_db::{
+    defs::Definition,
+    helpers::mod_path_to_ast,
+    imports::insert_use::{insert_use, ImportScope},
+    search::FileReference,
+    FxHashMap, FxHashSet,
+};
+use itertools::Itertools;
+use syntax::{
+    ast::{
+        self, edit::IndentLevel, edit_in_place::Indent, make, AstNode, HasGenericParams, HasName,
+        HasVisibility,
+    },
+    ted, SyntaxKind, SyntaxNode, T,
+};
+
+use crate::{AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: generate_trait_from_impl
+//
+// Generates a trait from the items of an inherent impl and turns the impl into
+// an implementation of that trait.
+//
+// ```
+// struct Foo(u8);
+//
+// impl$0 Foo {
+//     pub fn get(&self) -> u8 {
+//         self.0
+//     }
+// }
+// ```
+// ->
+// ```
+// struct Foo(u8);
+//
+// pub trait FooTrait {
+//     fn get(&self) -> u8;
+// }
+//
+// impl FooTrait for Foo {
+//     fn get(&self) -> u8 {
+//         self.0
+//     }
+// }
+// ```
+pub(crate) fn generate_trait_from_impl(acc: &mut Assists, ctx: &AssistContext<'_>) -> Option<()> {
+    let impl_ = ctx.find_node_at_offset::<ast::Impl>()?;
+    let assoc_item_list = impl_.assoc_item_list()?;
+    // Only offer the assist on the impl header, not on the items.
+    if ctx.offset() > assoc_item_list.syntax().text_range().start() {
+        return None;
+    }
+    if impl_.trait_().is_some() {
+        return None;
+    }
+
+    let items: Vec<ast::AssocItem> = assoc_item_list.assoc_items().collect();
+    if items.is_empty() || items.iter().any(|it| matches!(it, ast::AssocItem::MacroCall(_))) {
+        return None;
+    }
+
+    let impl_def = ctx.sema.to_def(&impl_)?;
+    let adt = impl_def.self_ty(ctx.db()).as_adt()?;
+    let trait_name = format!("{}Trait", adt.name(ctx.db()));
+    let impl_module = impl_def.module(ctx.db());
+    if impl_module.scope(ctx.db(), None).iter().any(|(name, _)| name.to_string() == trait_name) {
+        cov_mark::hit!(generate_trait_from_impl_name_taken);
+        return None;
+    }
+
+    let defs: Vec<Definition> = items
+        .iter()
+        .filter_map(|item| match item {
+            ast::AssocItem::Fn(it) => ctx.sema.to_def(it).map(Definition::Function),
+            ast::AssocItem::Const(it) => ctx.sema.to_def(it).map(Definition::Const),
+            ast::AssocItem::TypeAlias(it) => ctx.sema.to_def(it).map(Definition::TypeAlias),
+            ast::AssocItem::MacroCall(_) => None,
+        })
+        .collect();
+
+    let target = impl_.syntax().text_range();
+    acc.add(
+        AssistId("generate_trait_from_impl", AssistKind::Generate),
+        "Generate trait from impl",
+        target,
+        |builder| {
+            let mut usages: FxHashMap<_, Vec<FileReference>> = FxHashMap::default();
+            for def in defs {
+                for (file_id, refs) in def.usages(&ctx.sema).all() {
+                    usages.entry(file_id).or_default().extend(refs);
+                }
+            }
+
+            // Every module that refers to one of the items now needs the trait in scope.
+            let mut visited_modules = FxHashSet::default();
+            visited_modules.insert(impl_module);
+            let mut def_file_references = None;
+            for (file_id, references) in usages {
+                if file_id == ctx.file_id() {
+                    def_file_references = Some(references);
+                    continue;
+                }
+                builder.edit_file(file_id);
+                let imports = references
+                    .into_iter()
+                    .filter_map(|reference| {
+                        import_for_reference(ctx, &mut visited_modules, impl_module, reference)
+                    })
+                    .collect::<Vec<_>>();
+                let imports = imports
+                    .into_iter()
+                    .filter_map(|(node, path)| {
+                        import_scope(ctx, builder.make_syntax_mut(node), path)
+                    })
+                    .collect::<Vec<_>>();
+                for (scope, path) in imports {
+                    add_import(ctx, &scope, path, &trait_name);
+                }
+            }
+            builder.edit_file(ctx.file_id());
+
+            let imports = def_file_references
+                .into_iter()
+                .flatten()
+                .filter_map(|reference| {
+                    import_for_reference(ctx, &mut visited_modules, impl_module, reference)
+                })
+                .collect::<Vec<_>>();
+            let imports = imports
+                .into_iter()
+                .filter_map(|(node, path)| import_scope(ctx, builder.make_syntax_mut(node), path))
+                .collect::<Vec<_>>();
+            let impl_ = builder.make_mut(impl_);
+            for (scope, path) in imports {
+                add_import(ctx, &scope, path, &trait_name);
+            }
+
+            let indent = impl_.indent_level();
+            let visibility = items.iter().find_map(visibility);
+            let trait_items = make::assoc_item_list().clone_for_update();
+            for item in &items {
+                let trait_item = item.clone_subtree().clone_for_update();
+                remove_visibility(&trait_item);
+                strip_definition(&trait_item);
+                trait_item.dedent(IndentLevel::from_node(item.syntax()));
+                trait_item.indent(IndentLevel(1));
+                trait_items.add_item(trait_item);
+            }
+            let trait_ = make::trait_(
+                visibility,
+                make::name(&trait_name),
+                impl_.generic_param_list(),
+                impl_.where_clause(),
+                trait_items,
+            )
+            .clone_for_update();
+            trait_.indent(indent);
+
+            for item in impl_.assoc_item_list().into_iter().flat_map(|it| it.assoc_items()) {
+                remove_visibility(&item);
+            }
+            let generic_args =
+                impl_.generic_param_list().map_or_else(String::new, |it| generic_args(&it));
+            let trait_ty = make::ty(&format!("{}{}", trait_name, generic_args)).clone_for_update();
+            let header = make::impl_trait(
+                make::ext::ident_path(&trait_name),
+                make::ext::ident_path(&trait_name),
+                None,
+            )
+            .clone_for_update();
+            if let (Some(self_ty), Some(for_kw)) = (impl_.self_ty(), header.for_token()) {
+                for_kw.detach();
+                ted::insert_all_raw(
+                    ted::Position::before(self_ty.syntax()),
+                    vec![
+                        trait_ty.syntax().clone().into(),
+                        make::tokens::single_space().into(),
+                        for_kw.into(),
+                        make::tokens::single_space().into(),
+                    ],
+                );
+            }
+
+            ted::insert_all_raw(
+                ted::Position::before(impl_.syntax()),
+                vec![
+                    trait_.syntax().clone().into(),
+                    make::tokens::whitespace(&format!("\n\n{}", indent)).into(),
+                ],
+            );
+        },
+    )
+}
+
+fn import_for_reference(
+    ctx: &AssistContext<'_>,
+    visited_modules: &mut FxHashSet<hir::Module>,
+    impl_module: hir::Module,
+    reference: FileReference,
+) -> Option<(SyntaxNode, ast::Path)> {
+    let node = reference.name.syntax().clone();
+    let module = ctx.sema.scope(&node)?.module();
+    if !visited_modules.insert(module) {
+        return None;
+    }
+    let mod_path = module.find_use_path_prefixed(
+        ctx.db(),
+        ModuleDef::Module(impl_module),
+        ctx.config.insert_use.prefix_kind,
+    )?;
+    Some((node, mod_path_to_ast(&mod_path)))
+}
+
+fn import_scope(
+    ctx: &AssistContext<'_>,
+    node: SyntaxNode,
+    path: ast::Path,
+) -> Option<(ImportScope, ast::Path)> {
+    Some((ImportScope::find_insert_use_container(&node, &ctx.sema)?, path))
+}
+
+fn add_import(ctx: &AssistContext<'_>, scope: &ImportScope, path: ast::Path, trait_name: &str) {
+    let path = make::path_concat(path, make::ext::ident_path(trait_name));
+    insert_use(scope, path, &ctx.config.insert_use);
+}
+
+fn visibility(item: &ast::AssocItem) -> Option<ast::Visibility> {
+    match item {
+        ast::AssocItem::Fn(it) => it.visibility(),
+        ast::AssocItem::Const(it) => it.visibility(),
+        ast::AssocItem::TypeAlias(it) => it.visibility(),
+        ast::AssocItem::MacroCall(_) => None,
+    }
+}
+
+fn remove_visibility(item: &ast::AssocItem) {
+    if let Some(visibility) = visibility(item) {
+        if let Some(ws) = visibility
+            .syntax()
+            .next_sibling_or_token()
+            .filter(|it| it.kind() == SyntaxKind::WHITESPACE)
+        {
+            ted::remove(ws);
+        }
+        ted::remove(visibility.syntax());
+    }
+}
+
+/// Turns an impl item into the corresponding trait item declaration by
+/// dropping its body, value or aliased type.
+fn strip_definition(item: &ast::AssocItem) {
+    let (first, last) = match item {
+        ast::AssocItem::Fn(it) => match it.body() {
+            Some(body) => (body.syntax().clone().into(), body.syntax().clone().into()),
+            None => return,
+        },
+        ast::AssocItem::Const(it) => match (it.eq_token(), it.body()) {
+            (Some(eq), Some(body)) => (eq.into(), body.syntax().clone().into()),
+            _ => return,
+        },
+        ast::AssocItem::TypeAlias(it) => match (it.eq_token(), it.ty()) {
+            (Some(eq), Some(ty)) => (eq.into(), ty.syntax().clone().into()),
+            _ => return,
+        },
+        ast::AssocItem::MacroCall(_) => return,
+    };
+    let first = match first {
+        syntax::SyntaxElement::Node(ref node) => node.prev_sibling_or_token(),
+        syntax::SyntaxElement::Token(ref token) => token.prev_sibling_or_token(),
+    }
+    .filter(|it| it.kind() == SyntaxKind::WHITESPACE)
+    .unwrap_or(first);
+    let is_fn = matches!(item, ast::AssocItem::Fn(_));
+    ted::replace_all(first..=last, vec![make::token(T![;]).into()]);
+    if !is_fn {
+        // `const X: u8 = 1;` already ends with a semicolon.
+        if let Some(semi) = item.syntax().last_token().filter(|it| it.kind() == T![;]) {
+            if semi.prev_token().map_or(false, |it| it.kind() == T![;]) {
+                ted::remove(semi);
+            }
+        }
+    }
+}
+
+fn generic_args(params: &ast::GenericParamList) -> String {
+    let args = params
+        .generic_params()
+        .filter_map(|param| match param {
+            ast::GenericParam::LifetimeParam(it) => it.lifetime().map(|it| it.to_string()),
+            ast::GenericParam::TypeParam(it) => it.name().map(|it| it.to_string()),
+            ast::GenericParam::ConstParam(it) => it.name().map(|it| it.to_string()),
+        })
+        .join(", ");
+    format!("<{}>", args)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn generates_trait_and_impl() {
+        check_assist(
+            generate_trait_from_impl,
+            r#"
+struct Foo(u8);
+
+impl$0 Foo {
+    /// Returns the value.
+    pub fn get(&self) -> u8 {
+        self.0
+    }
+
+    pub(crate) const ID: u32 = 1;
+
+    fn new() -> Self {
+        Foo(0)
+    }
+}
+"#,
+            r#"
+struct Foo(u8);
+
+pub trait FooTrait {
+    /// Returns the value.
+    fn get(&self) -> u8;
+
+    const ID: u32;
+
+    fn new() -> Self;
+}
+
+impl FooTrait for Foo {
+    /// Returns the value.
+    fn get(&self) -> u8 {
+        self.0
+    }
+
+    const ID: u32 = 1;
+
+    fn new() -> Self {
+        Foo(0)
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn generates_generic_trait() {
+        check_assist(
+            generate_trait_from_impl,
+            r#"
+struct Wrapper<T>(T);
+
+impl<T: Clone> $0Wrapper<T> {
+    fn inner(&self) -> T {
+        self.0.clone()
+    }
+}
+"#,
+            r#"
+struct Wrapper<T>(T);
+
+trait WrapperTrait<T: Clone> {
+    fn inner(&self) -> T;
+}
+
+impl<T: Clone> WrapperTrait<T> for Wrapper<T> {
+    fn inner(&self) -> T {
+        self.0.clone()
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn imports_trait_at_call_sites() {
+        check_assist(
+            generate_trait_from_impl,
+            r#"
+//- /main.rs
+mod foo;
+
+fn main() {
+    foo::Foo.get();
+}
+
+mod bar {
+    fn bar() {
+        crate::foo::Foo::make();
+    }
+}
+//- /foo.rs
+pub struct Foo;
+
+impl$0 Foo {
+    pub fn get(&self) {}
+    pub fn make() -> Foo {
+        Foo.get();
+        Foo
+    }
+}
+"#,
+            r#"
+//- /main.rs
+use foo::FooTrait;
+
+mod foo;
+
+fn main() {
+    foo::Foo.get();
+}
+
+mod bar {
+    use crate::foo::FooTrait;
+
+    fn bar() {
+        crate::foo::Foo::make();
+    }
+}
+//- /foo.rs
+pub struct Foo;
+
+pub trait FooTrait {
+    fn get(&self);
+
+    fn make() -> Foo;
+}
+
+impl FooTrait for Foo {
+    fn get(&self) {}
+    fn make() -> Foo {
+        Foo.get();
+        Foo
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_trait_impls_or_items() {
+        check_assist_not_applicable(
+            generate_trait_from_impl,
+            r#"
+trait Bar {}
+struct Foo;
+impl$0 Bar for Foo {}
+"#,
+        );
+        check_assist_not_applicable(
+            generate_trait_from_impl,
+            r#"
+struct Foo;
+impl Foo {
+    fn $0foo(&self) {}
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_if_name_is_taken() {
+        cov_mark::check!(generate_trait_from_impl_name_taken);
+        check_assist_not_applicable(
+            generate_trait_from_impl,
+            r#"
+struct Foo;
+trait FooTrait {}
+impl$0 Foo {
+    fn foo(&self) {}
+}
+"#,
+        );
+    }
+}
@@ -0,0 +1,462 @@
+use ide_db::syntax_helpers::format_string::{
+    format_placeholders, is_format_string, FormatArg, FormatPlaceholder,
+};
+use itertools::Itertools;
+use syntax::{
+    ast, AstNode, AstToken, NodeOrToken, SyntaxKind, SyntaxNode, SyntaxToken, TextRange, TextSize,
+    T,
+};
+
+use crate::{AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: inline_format_args
+//
+// Moves positional arguments that are plain identifiers into the format string.
+//
+// ```
+// macro_rules! format_args {
+//     ($lit:literal $(tt:tt)*) => { 0 },
+// }
+// macro_rules! print {
+//     ($($arg:tt)*) => (std::io::_print(format_args!($($arg)*)));
+// }
+//
+// fn main() {
+//     print!("{} + {:?}$0", x, y + 1);
+// }
+// ```
+// ->
+// ```
+// macro_rules! format_args {
+//     ($lit:literal $(tt:tt)*) => { 0 },
+// }
+// macro_rules! print {
+//     ($($arg:tt)*) => (std::io::_print(format_args!($($arg)*)));
+// }
+//
+// fn main() {
+//     print!("{x} + {:?}", y + 1);
+// }
+// ```
+pub(crate) fn inline_format_args(acc: &mut Assists, ctx: &AssistContext<'_>) -> Option<()> {
+    let call = FormatCall::at_cursor(ctx)?;
+    let placeholders = call.placeholders()?;
+    let refs = positional_refs(&placeholders);
+    if refs.iter().any(|it| it.index >= call.positional.len()) {
+        return None;
+    }
+
+    // Inlined identifiers must not be shadowed by named arguments.
+    let inlined = call
+        .positional
+        .iter()
+        .enumerate()
+        .map(|(index, arg)| {
+            is_plain_identifier(&arg.text)
+                && refs.iter().any(|it| it.index == index)
+                && !call.named.iter().any(|named| named == &arg.text)
+        })
+        .collect_vec();
+    if !inlined.contains(&true) {
+        return None;
+    }
+
+    let target = call.string.syntax().text_range();
+    acc.add(
+        AssistId("inline_format_args", AssistKind::RefactorRewrite),
+        "Inline format arguments",
+        target,
+        |builder| {
+            let new_index = |old: usize| inlined[..old].iter().filter(|&&it| !it).count();
+            // Implicit references can stay as they are if they still take the arguments in order.
+            let implicit = refs
+                .iter()
+                .filter(|it| it.kind != RefKind::Index && !inlined[it.index])
+                .map(|it| new_index(it.index))
+                .collect_vec();
+            let sequential = implicit.iter().copied().eq(0..implicit.len());
+
+            let offset = call.string.syntax().text_range().start();
+            for arg_ref in &refs {
+                let arg = if inlined[arg_ref.index] {
+                    call.positional[arg_ref.index].text.clone()
+                } else if sequential && arg_ref.kind != RefKind::Index {
+                    continue;
+                } else {
+                    new_index(arg_ref.index).to_string()
+                };
+                let text = match arg_ref.kind {
+                    RefKind::Star => format!("{}$", arg),
+                    RefKind::Next | RefKind::Index => arg,
+                };
+                builder.replace(arg_ref.range + offset, text);
+            }
+            for (arg, _) in call.positional.iter().zip(&inlined).filter(|(_, &it)| it) {
+                builder.delete(TextRange::new(arg.preceding_comma, arg.range.end()));
+            }
+        },
+    )
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum RefKind {
+    /// `{}`, taking the next argument.
+    Next,
+    /// `{0}`, or `0$` as width or precision.
+    Index,
+    /// The precision `.*`, which takes the next argument before the value does.
+    Star,
+}
+
+/// A reference to a positional argument in a format string.
+struct PositionalRef {
+    /// The range to replace to refer to another argument, relative to the string literal.
+    range: TextRange,
+    index: usize,
+    kind: RefKind,
+}
+
+/// Collects the references of the placeholders to positional arguments, in the order in which
+/// they take arguments.
+fn positional_refs(placeholders: &[FormatPlaceholder]) -> Vec<PositionalRef> {
+    let mut refs = Vec::new();
+    let mut next = 0;
+    let mut take_next = || {
+        next += 1;
+        next - 1
+    };
+    for placeholder in placeholders {
+        let spec = placeholder.spec.as_deref().unwrap_or_default();
+        let spec_start = placeholder.range.end() - TextSize::of('}') - TextSize::of(spec);
+        if let Some(star) = spec.find(".*") {
+            let range = TextRange::at(spec_start + TextSize::from(star as u32 + 1), 1.into());
+            refs.push(PositionalRef { range, index: take_next(), kind: RefKind::Star });
+        }
+        match placeholder.arg {
+            FormatArg::Next => refs.push(PositionalRef {
+                range: placeholder.arg_range,
+                index: take_next(),
+                kind: RefKind::Next,
+            }),
+            FormatArg::Index(index) => refs.push(PositionalRef {
+                range: placeholder.arg_range,
+                index,
+                kind: RefKind::Index,
+            }),
+            FormatArg::Name(_) | FormatArg::Expr(_) => (),
+        }
+        // Widths and precisions like `1$`, named ones like `width$` don't refer to positional
+        // arguments.
+        for (dollar, _) in spec.match_indices('$') {
+            let digits = spec[..dollar].bytes().rev().take_while(u8::is_ascii_digit).count();
+            let start = dollar - digits;
+            let is_name = spec[..start].ends_with(|c: char| c.is_alphanumeric() || c == '_');
+            if digits == 0 || is_name {
+                continue;
+            }
+            let range = TextRange::new(
+                spec_start + TextSize::from(start as u32),
+                spec_start + TextSize::from(dollar as u32),
+            );
+            refs.push(PositionalRef {
+                range,
+                index: spec[start..dollar].parse().unwrap(),
+                kind: RefKind::Index,
+            });
+        }
+    }
+    refs
+}
+
+// Assist: extract_expressions_from_format_string
+//
+// Moves expressions out of the placeholders of a format string into positional arguments.
+//
+// ```
+// macro_rules! format_args {
+//     ($lit:literal $(tt:tt)*) => { 0 },
+// }
+// macro_rules! print {
+//     ($($arg:tt)*) => (std::io::_print(format_args!($($arg)*)));
+// }
+//
+// fn main() {
+//     print!("{x + 1} {y}$0");
+// }
+// ```
+// ->
+// ```
+// macro_rules! format_args {
+//     ($lit:literal $(tt:tt)*) => { 0 },
+// }
+// macro_rules! print {
+//     ($($arg:tt)*) => (std::io::_print(format_args!($($arg)*)));
+// }
+//
+// fn main() {
+//     print!("{} {y}", x + 1);
+// }
+// ```
+pub(crate) fn extract_expressions_from_format_string(
+    acc: &mut Assists,
+    ctx: &AssistContext<'_>,
+) -> Option<()> {
+    let call = FormatCall::at_cursor(ctx)?;
+    let placeholders = call.placeholders()?;
+    let exprs = placeholders
+        .iter()
+        .filter_map(|it| match &it.arg {
+            FormatArg::Expr(expr) => Some((it, expr)),
+            _ => None,
+        })
+        .collect_vec();
+    if exprs.is_empty() {
+        return None;
+    }
+
+    // Implicit indices can only be used if no other placeholder refers to a positional argument.
+    let has_positional = !call.positional.is_empty() || !positional_refs(&placeholders).is_empty();
+
+    let target = call.string.syntax().text_range();
+    acc.add(
+        AssistId("extract_expressions_from_format_string", AssistKind::RefactorExtract),
+        "Extract format expressions",
+        target,
+        |builder| {
+            let offset = call.string.syntax().text_range().start();
+            for (index, (placeholder, _)) in exprs.iter().enumerate() {
+                let text = match has_positional {
+                    true => (call.positional.len() + index).to_string(),
+                    false => String::new(),
+                };
+                builder.replace(placeholder.arg_range + offset, text);
+            }
+            let insert_at = call.positional.last().map_or(target.end(), |it| it.range.end());
+            let args = exprs.iter().map(|(_, expr)| format!(", {}", expr)).collect::<String>();
+            builder.insert(insert_at, args);
+        },
+    )
+}
+
+struct MacroArg {
+    range: TextRange,
+    /// The start of the comma before the argument.
+    preceding_comma: TextSize,
+    text: String,
+}
+
+/// The format string of a format-like macro call and the arguments following it.
+struct FormatCall {
+    string: ast::String,
+    positional: Vec<MacroArg>,
+    named: Vec<String>,
+}
+
+impl FormatCall {
+    fn at_cursor(ctx: &AssistContext<'_>) -> Option<FormatCall> {
+        let string = ctx.find_token_at_offset::<ast::String>()?;
+        let token_tree = string.syntax().parent().and_then(ast::TokenTree::cast)?;
+        if !token_tree.syntax().parent().map_or(false, |it| ast::MacroCall::can_cast(it.kind())) {
+            return None;
+        }
+        // Only macros that pass the string on to `format_args!` are format-like.
+        let is_format_like = ctx
+            .sema
+            .descend_into_macros(string.syntax().clone())
+            .into_iter()
+            .filter_map(ast::String::cast)
+            .any(|it| is_format_string(&it));
+        if !is_format_like {
+            return None;
+        }
+
+        let mut tokens = token_tree
+            .syntax()
+            .children_with_tokens()
+            .skip_while(|it| it.as_token() != Some(string.syntax()))
+            .skip(1)
+            .filter(|it| !it.kind().is_trivia())
+            .peekable();
+        let mut positional = Vec::new();
+        let mut named = Vec::new();
+        loop {
+            let comma = match tokens.next() {
+                Some(NodeOrToken::Token(it)) if it.kind() == T![,] => it,
+                Some(NodeOrToken::Token(it)) if it.kind() == T![')'] => break,
+                _ => return None,
+            };
+            let arg =
+                tokens.peeking_take_while(|it| !matches!(it.kind(), T![,] | T![')'])).collect_vec();
+            let (first, last) = match (arg.first(), arg.last()) {
+                (Some(first), Some(last)) => (first, last),
+                // Trailing comma.
+                _ => continue,
+            };
+            let range = first.text_range().cover(last.text_range());
+            let text = token_tree
+                .syntax()
+                .text()
+                .slice(range - token_tree.syntax().text_range().start())
+                .to_string();
+            match &arg[..] {
+                [NodeOrToken::Token(name), NodeOrToken::Token(eq), rest @ ..]
+                    if name.kind() == SyntaxKind::IDENT
+                        && eq.kind() == T![=]
+                        && !starts_with_eq(rest) =>
+                {
+                    named.push(name.text().to_owned())
+                }
+                _ if !named.is_empty() => return None,
+                _ => positional.push(MacroArg {
+                    range,
+                    preceding_comma: comma.text_range().start(),
+                    text,
+                }),
+            }
+        }
+        Some(FormatCall { string, positional, named })
+    }
+
+    fn placeholders(&self) -> Option<Vec<FormatPlaceholder>> {
+        format_placeholders(&self.string)
+    }
+}
+
+fn starts_with_eq(tokens: &[NodeOrToken<SyntaxNode, SyntaxToken>]) -> bool {
+    matches!(tokens.first(), Some(NodeOrToken::Token(it)) if it.kind() == T![=])
+}
+
+fn is_plain_identifier(text: &str) -> bool {
+    let mut chars = text.chars();
+    chars.next().map_or(false, |c| c.is_alphabetic() || c == '_')
+        && chars.all(|c| c.is_alphanumeric() || c == '_')
+        && text != "_"
+        && text != "self"
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    const MACROS: &str = r#"
+macro_rules! format_args {
+    ($lit:literal $(tt:tt)*) => { 0 },
+}
+macro_rules! format {
+    ($($arg:tt)*) => (std::fmt::format(format_args!($($arg)*)));
+}
+macro_rules! other {
+    ($($arg:tt)*) => { 0 };
+}
+"#;
+
+    fn check_inline(before: &str, after: &str) {
+        check_assist(
+            inline_format_args,
+            &format!("{}{}", MACROS, before),
+            &format!("{}{}", MACROS, after),
+        );
+    }
+
+    fn check_extract(before: &str, after: &str) {
+        check_assist(
+            extract_expressions_from_format_string,
+            &format!("{}{}", MACROS, before),
+            &format!("{}{}", MACROS, after),
+        );
+    }
+
+    #[test]
+    fn inline_positional_identifiers() {
+        check_inline(
+            r#"fn f() { format!("$0{} and {:?}, {}", a, b, c + 1); }"#,
+            r#"fn f() { format!("{a} and {b:?}, {}", c + 1); }"#,
+        );
+        check_inline(
+            r#"fn f() { format!("$0{1} {0} {1}", a, b); }"#,
+            r#"fn f() { format!("{b} {a} {b}"); }"#,
+        );
+    }
+
+    #[test]
+    fn inline_renumbers_remaining_arguments() {
+        check_inline(
+            r#"fn f() { format!("$0{} {} {}", a.x, b, c()); }"#,
+            r#"fn f() { format!("{} {b} {}", a.x, c()); }"#,
+        );
+        check_inline(
+            r#"fn f() { format!("$0{2} {0} {1}", a(), b, c()); }"#,
+            r#"fn f() { format!("{1} {0} {b}", a(), c()); }"#,
+        );
+    }
+
+    #[test]
+    fn inline_keeps_named_arguments() {
+        check_inline(
+            r#"fn f() { format!("$0{} {x} {}", a, b, x = 1); }"#,
+            r#"fn f() { format!("{a} {x} {b}", x = 1); }"#,
+        );
+        check_assist_not_applicable(
+            inline_format_args,
+            &format!("{}{}", MACROS, r#"fn f() { format!("$0{} {x}", x, x = 1); }"#),
+        );
+    }
+
+    #[test]
+    fn inline_width_and_precision_arguments() {
+        check_inline(
+            r#"fn f() { format!("$0{:3$} {:.*}", a, p, b, w); }"#,
+            r#"fn f() { format!("{a:w$} {b:.p$}"); }"#,
+        );
+        check_inline(
+            r#"fn f() { format!("$0{:.*} {}", p(), a, b); }"#,
+            r#"fn f() { format!("{a:.*} {b}", p()); }"#,
+        );
+        check_inline(
+            r#"fn f() { format!("$0{:.*} {:1$}", p, a(), b()); }"#,
+            r#"fn f() { format!("{:.p$} {:0$}", a(), b()); }"#,
+        );
+        check_inline(
+            r#"fn f() { format!("$0{:width$.1$}", a, p, width = 2); }"#,
+            r#"fn f() { format!("{a:width$.p$}", width = 2); }"#,
+        );
+    }
+
+    #[test]
+    fn inline_not_applicable() {
+        check_assist_not_applicable(
+            inline_format_args,
+            &format!("{}{}", MACROS, r#"fn f() { other!("$0{}", a); }"#),
+        );
+        check_assist_not_applicable(
+            inline_format_args,
+            &format!("{}{}", MACROS, r#"fn f() { format!("$0{} {{}}", a.b); }"#),
+        );
+    }
+
+    #[test]
+    fn extract_expressions() {
+        check_extract(
+            r#"fn f() { format!("$0{x + 1} {y} {f(a, b):?}"); }"#,
+            r#"fn f() { format!("{} {y} {:?}", x + 1, f(a, b)); }"#,
+        );
+    }
+
+    #[test]
+    fn extract_expressions_after_positional_arguments() {
+        check_extract(
+            r#"fn f() { format!("$0{} {x.len()} {y}", a, y = 2); }"#,
+            r#"fn f() { format!("{} {1} {y}", a, x.len(), y = 2); }"#,
+        );
+    }
+
+    #[test]
+    fn extract_not_applicable_without_expressions() {
+        check_assist_not_applicable(
+            extract_expressions_from_format_string,
+            &format!("{}{}", MACROS, r#"fn f() { format!("$0{} {x} {{y + 1}}", a); }"#),
+        );
+    }
+}
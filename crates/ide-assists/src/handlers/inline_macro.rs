@@ -0,0 +1,556 @@
+use hir::{HasVisibility, MacroKind, PathResolution};
+use ide_db::{syntax_helpers::insert_whitespace_into_node::insert_ws_into, FxHashMap, FxHashSet};
+use itertools::Itertools;
+use syntax::{
+    ast::{self, edit::IndentLevel, make, AstNode, BinaryOp, HasModuleItem, HasName},
+    ted, SyntaxKind, SyntaxNode, SyntaxToken, TextRange,
+};
+
+use crate::{AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: inline_macro
+//
+// Replaces a `macro_rules!` macro call with its expansion.
+//
+// ```
+// macro_rules! double {
+//     ($e:expr) => { $e * 2 };
+// }
+//
+// fn main() {
+//     let x = double$0!(1 + 2);
+// }
+// ```
+// ->
+// ```
+// macro_rules! double {
+//     ($e:expr) => { $e * 2 };
+// }
+//
+// fn main() {
+//     let x = (1 + 2) * 2;
+// }
+// ```
+pub(crate) fn inline_macro(acc: &mut Assists, ctx: &AssistContext<'_>) -> Option<()> {
+    let macro_call = ctx.find_node_at_offset::<ast::MacroCall>()?;
+    // Don't offer the assist inside of the macro's input, where other assists apply.
+    if let Some(tt) = macro_call.token_tree() {
+        if tt.syntax().text_range().contains(ctx.offset()) {
+            return None;
+        }
+    }
+    let mac = ctx.sema.resolve_macro_call(&macro_call)?;
+    if mac.kind(ctx.db()) != MacroKind::Declarative {
+        return None;
+    }
+    let expanded = ctx.sema.expand(&macro_call)?;
+
+    let call_scope = ctx.sema.scope(macro_call.syntax())?;
+    let call_module = call_scope.module();
+    let call_krate = call_module.krate();
+    let macro_krate = mac.module(ctx.db()).krate();
+    let dollar_crate = if macro_krate == call_krate {
+        "crate".to_owned()
+    } else {
+        let dep = call_krate.dependencies(ctx.db()).into_iter().find(|it| it.krate == macro_krate);
+        dep.map(|it| it.name.to_string())?
+    };
+
+    // Every item the expansion refers to must be reachable under the same path
+    // from the call site.
+    for path in expanded.descendants().filter_map(ast::Path::cast) {
+        let is_dollar_crate = starts_with_dollar_crate(&path);
+        // Check each module along `$crate` paths, others are resolved at the call site as a whole.
+        if path.parent_path().is_some() && !is_dollar_crate {
+            continue;
+        }
+        let def = match ctx.sema.resolve_path(&path) {
+            Some(PathResolution::Def(def)) => def,
+            _ => continue,
+        };
+        if is_dollar_crate {
+            if !def.visibility(ctx.db()).is_visible_from(ctx.db(), call_module.into()) {
+                cov_mark::hit!(inline_macro_private_item);
+                return None;
+            }
+        } else if call_scope.speculative_resolve(&path) != Some(PathResolution::Def(def)) {
+            return None;
+        }
+    }
+
+    let renames = hygienic_renames(ctx, &macro_call, &call_scope, &expanded)?;
+    let format = |node: &SyntaxNode| format_node(node, &dollar_crate, &renames);
+
+    let (replace_node, replacement) = match ast::MacroItems::cast(expanded.clone()) {
+        Some(items) => {
+            let items = items.items().map(|it| format(it.syntax()));
+            (macro_call.syntax().clone(), items.collect::<Vec<_>>())
+        }
+        None => match ast::MacroStmts::cast(expanded.clone()) {
+            Some(stmts) => {
+                let mut parts = stmts.statements().map(|it| format(it.syntax())).collect_vec();
+                let stmt = macro_call
+                    .syntax()
+                    .parent()
+                    .filter(|it| ast::MacroExpr::can_cast(it.kind()))
+                    .and_then(|it| it.parent())
+                    .and_then(ast::ExprStmt::cast);
+                let replace_node = match &stmt {
+                    Some(stmt) => stmt.syntax().clone(),
+                    None => macro_call.syntax().clone(),
+                };
+                if let Some(expr) = stmts.expr() {
+                    let expr = format(expr.syntax());
+                    let semicolon = if stmt.is_some() { ";" } else { "" };
+                    parts.push(format!("{}{}", expr, semicolon));
+                }
+                (replace_node, parts)
+            }
+            None => {
+                let expr = ast::Expr::cast(expanded.clone())?;
+                let call_expr = macro_call.syntax().parent().filter(|it| {
+                    ast::MacroExpr::can_cast(it.kind()) || ast::MacroStmts::can_cast(it.kind())
+                });
+                let call_expr = call_expr.unwrap_or_else(|| macro_call.syntax().clone());
+                let mut text = format(expr.syntax());
+                if let Some(parent) = call_expr.parent().and_then(ast::Expr::cast) {
+                    if needs_parens(precedence(&expr), &call_expr, &parent) {
+                        text = format!("({})", text);
+                    }
+                }
+                (call_expr, vec![text])
+            }
+        },
+    };
+
+    let indent = IndentLevel::from_node(&replace_node);
+    let separator = if ast::MacroItems::can_cast(expanded.kind()) { "\n\n" } else { "\n" };
+    let replacement = replacement
+        .into_iter()
+        .map(|it| reindent(&it, indent))
+        .join(&format!("{}{}", separator, indent));
+
+    acc.add(
+        AssistId("inline_macro", AssistKind::RefactorInline),
+        format!("Inline macro `{}`", mac.name(ctx.db())),
+        macro_call.syntax().text_range(),
+        |builder| builder.replace(replace_node.text_range(), replacement),
+    )
+}
+
+fn starts_with_dollar_crate(path: &ast::Path) -> bool {
+    path.first_segment()
+        .and_then(|it| it.syntax().first_token())
+        .map_or(false, |it| it.text() == "$crate")
+}
+
+/// Bindings introduced by the macro itself are hygienic: they can neither see
+/// nor shadow the names of the call site, which they could once inlined.
+/// Returns new names for the macro's identifiers (by their range in the
+/// expansion) that would clash with names used at the call site.
+fn hygienic_renames(
+    ctx: &AssistContext<'_>,
+    macro_call: &ast::MacroCall,
+    call_scope: &hir::SemanticsScope<'_>,
+    expanded: &SyntaxNode,
+) -> Option<FxHashMap<TextRange, String>> {
+    let input_range = macro_call.token_tree()?.syntax().text_range();
+    let from_input = |node: &SyntaxNode| {
+        let range = ctx.sema.original_range(node);
+        range.file_id == ctx.file_id() && input_range.contains_range(range.range)
+    };
+    let idents = expanded
+        .descendants_with_tokens()
+        .filter_map(|it| it.into_token())
+        .filter(|it| it.kind() == SyntaxKind::IDENT)
+        .filter_map(|it| it.parent())
+        .collect_vec();
+
+    let mut taken = FxHashSet::default();
+    call_scope.process_all_names(&mut |name, _| {
+        taken.insert(name.to_string());
+    });
+    taken.extend(idents.iter().filter(|it| from_input(it)).map(|it| it.text().to_string()));
+    let bindings: FxHashSet<String> = expanded
+        .descendants()
+        .filter_map(ast::IdentPat::cast)
+        .filter(|it| !from_input(it.syntax()))
+        .filter_map(|it| it.name())
+        .map(|it| it.text().to_string())
+        .collect();
+
+    let mut new_names = FxHashMap::default();
+    for binding in bindings.iter().filter(|it| taken.contains(*it)).sorted() {
+        let new_name = (1..)
+            .map(|idx| format!("{}{}", binding, idx))
+            .find(|it| !taken.contains(it) && !bindings.contains(it))?;
+        taken.insert(new_name.clone());
+        new_names.insert(binding.clone(), new_name);
+    }
+    // Identifiers written in the macro share the hygiene context of its
+    // bindings, those from the macro input refer to the call site.
+    let renames = idents
+        .iter()
+        .filter(|it| !from_input(it))
+        .filter_map(|it| Some((it.text_range(), new_names.get(&it.text().to_string())?.clone())))
+        .collect();
+    Some(renames)
+}
+
+/// Formats an expanded node, turning `$crate` into a path usable at the call
+/// site and applying the `renames` of identifiers.
+fn format_node(
+    node: &SyntaxNode,
+    dollar_crate: &str,
+    renames: &FxHashMap<TextRange, String>,
+) -> String {
+    let offset = node.text_range().start();
+    let node = node.clone_subtree().clone_for_update();
+    let idents = node
+        .descendants()
+        .filter(|it| matches!(it.kind(), SyntaxKind::NAME | SyntaxKind::NAME_REF))
+        .filter_map(|it| Some((it.clone(), renames.get(&(it.text_range() + offset))?)))
+        .collect_vec();
+    for (ident, new_name) in idents {
+        let replacement = match ident.kind() {
+            SyntaxKind::NAME => make::name(new_name).clone_for_update().syntax().clone(),
+            _ => make::name_ref(new_name).clone_for_update().syntax().clone(),
+        };
+        ted::replace(ident, replacement);
+    }
+    fix_parens(&node);
+    let node = insert_ws_into(node);
+    space_binary_operators(&node);
+    node.to_string().trim().replace("$crate", dollar_crate)
+}
+
+/// `insert_ws_into` leaves the operators of binary expressions unspaced.
+fn space_binary_operators(node: &SyntaxNode) {
+    for op in node.descendants().filter_map(ast::BinExpr::cast).filter_map(|it| it.op_token()) {
+        let is_ws =
+            |it: Option<SyntaxToken>| it.map_or(true, |it| it.kind() == SyntaxKind::WHITESPACE);
+        if !is_ws(op.prev_token()) {
+            ted::insert(ted::Position::before(&op), make::tokens::single_space());
+        }
+        if !is_ws(op.next_token()) {
+            ted::insert(ted::Position::after(&op), make::tokens::single_space());
+        }
+    }
+}
+
+/// `insert_ws_into` indents by two spaces and leaves stray blank lines in
+/// front of closing braces, so re-derive the indentation from the braces and
+/// shift everything to the indentation of the call site.
+fn reindent(text: &str, indent: IndentLevel) -> String {
+    let lines = text.lines().map(str::trim).collect_vec();
+    let mut depth = 0usize;
+    let mut res = Vec::new();
+    for (idx, line) in lines.iter().enumerate() {
+        if line.is_empty() {
+            if lines.get(idx + 1).map_or(false, |next| !next.starts_with('}')) {
+                res.push(String::new());
+            }
+            continue;
+        }
+        let closing = usize::from(line.starts_with('}'));
+        depth = depth.saturating_sub(closing);
+        match idx {
+            0 => res.push(line.to_string()),
+            _ => res.push(format!("{}{}{}", indent, "    ".repeat(depth), line)),
+        }
+        depth =
+            (depth + closing + line.matches('{').count()).saturating_sub(line.matches('}').count());
+    }
+    res.join("\n")
+}
+
+/// Expression fragments are substituted into macro bodies as opaque groups,
+/// so the expanded tree can differ from what its text would parse as. Adds
+/// parentheses wherever that is the case.
+fn fix_parens(node: &SyntaxNode) {
+    let exprs = node.descendants().filter_map(ast::Expr::cast).collect_vec();
+    for child in exprs.into_iter().rev() {
+        let parent = match child.syntax().parent().and_then(ast::Expr::cast) {
+            Some(it) => it,
+            None => continue,
+        };
+        if needs_parens(precedence(&child), child.syntax(), &parent) {
+            let paren = make::expr_paren(child.clone()).clone_for_update();
+            ted::replace(child.syntax(), paren.syntax());
+        }
+    }
+}
+
+/// Whether an expression with precedence `prec` needs to be parenthesized
+/// when it is the `child` of `parent`.
+fn needs_parens(prec: u8, child: &SyntaxNode, parent: &ast::Expr) -> bool {
+    let is = |it: Option<ast::Expr>| it.map_or(false, |it| it.syntax() == child);
+    match parent {
+        ast::Expr::BinExpr(bin) => {
+            let parent_prec = precedence(parent);
+            let right_assoc = matches!(bin.op_kind(), Some(BinaryOp::Assignment { .. }));
+            prec < parent_prec || (prec == parent_prec && is(bin.rhs()) != right_assoc)
+        }
+        ast::Expr::PrefixExpr(_) | ast::Expr::RefExpr(_) => prec < PREFIX,
+        ast::Expr::CastExpr(_) => prec < CAST,
+        ast::Expr::RangeExpr(_) => prec <= RANGE,
+        ast::Expr::MethodCallExpr(call) => prec < ATOM && is(call.receiver()),
+        ast::Expr::CallExpr(call) => prec < ATOM && is(call.expr()),
+        ast::Expr::IndexExpr(index) => prec < ATOM && is(index.base()),
+        ast::Expr::FieldExpr(_) | ast::Expr::TryExpr(_) | ast::Expr::AwaitExpr(_) => prec < ATOM,
+        _ => false,
+    }
+}
+
+const JUMP: u8 = 0;
+const RANGE: u8 = 2;
+const CAST: u8 = 14;
+const PREFIX: u8 = 15;
+const ATOM: u8 = 16;
+
+fn precedence(expr: &ast::Expr) -> u8 {
+    match expr {
+        ast::Expr::ClosureExpr(_)
+        | ast::Expr::ReturnExpr(_)
+        | ast::Expr::BreakExpr(_)
+        | ast::Expr::YieldExpr(_) => JUMP,
+        ast::Expr::RangeExpr(_) => RANGE,
+        ast::Expr::BinExpr(bin) => match bin.op_kind() {
+            Some(BinaryOp::Assignment { .. }) => 1,
+            Some(BinaryOp::LogicOp(ast::LogicOp::Or)) => 3,
+            Some(BinaryOp::LogicOp(ast::LogicOp::And)) => 4,
+            Some(BinaryOp::CmpOp(_)) => 5,
+            Some(BinaryOp::ArithOp(op)) => match op {
+                ast::ArithOp::BitOr => 6,
+                ast::ArithOp::BitXor => 7,
+                ast::ArithOp::BitAnd => 8,
+                ast::ArithOp::Shl | ast::ArithOp::Shr => 9,
+                ast::ArithOp::Add | ast::ArithOp::Sub => 10,
+                ast::ArithOp::Mul | ast::ArithOp::Div | ast::ArithOp::Rem => 11,
+            },
+            None => JUMP,
+        },
+        ast::Expr::CastExpr(_) => CAST,
+        ast::Expr::PrefixExpr(_) | ast::Expr::RefExpr(_) | ast::Expr::BoxExpr(_) => PREFIX,
+        _ => ATOM,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn inlines_expression_macro() {
+        check_assist(
+            inline_macro,
+            r#"
+macro_rules! double {
+    ($e:expr) => { $e * 2 };
+}
+
+fn main() {
+    let x = double$0!(1 + 2);
+    let y = -double!(x);
+}
+"#,
+            r#"
+macro_rules! double {
+    ($e:expr) => { $e * 2 };
+}
+
+fn main() {
+    let x = (1 + 2) * 2;
+    let y = -double!(x);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn parenthesizes_in_expression_context() {
+        check_assist(
+            inline_macro,
+            r#"
+macro_rules! add {
+    ($a:expr, $b:expr) => { $a + $b };
+}
+
+fn main() {
+    let x = 3 * ad$0d!(1, 2);
+}
+"#,
+            r#"
+macro_rules! add {
+    ($a:expr, $b:expr) => { $a + $b };
+}
+
+fn main() {
+    let x = 3 * (1 + 2);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn inlines_statements() {
+        check_assist(
+            inline_macro,
+            r#"
+macro_rules! swap {
+    ($a:ident, $b:ident) => {
+        let tmp = $a;
+        $a = $b;
+        $b = tmp;
+    };
+}
+
+fn main() {
+    let mut a = 1;
+    let mut b = 2;
+    if true {
+        sw$0ap!(a, b);
+    }
+}
+"#,
+            r#"
+macro_rules! swap {
+    ($a:ident, $b:ident) => {
+        let tmp = $a;
+        $a = $b;
+        $b = tmp;
+    };
+}
+
+fn main() {
+    let mut a = 1;
+    let mut b = 2;
+    if true {
+        let tmp = a;
+        a = b;
+        b = tmp;
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn renames_hygienic_bindings() {
+        check_assist(
+            inline_macro,
+            r#"
+macro_rules! swap {
+    ($a:ident, $b:ident) => {
+        let tmp = $a;
+        $a = $b;
+        $b = tmp;
+    };
+}
+
+fn main() {
+    let mut tmp = 1;
+    let mut tmp1 = 2;
+    sw$0ap!(tmp, tmp1);
+    let _ = tmp;
+}
+"#,
+            r#"
+macro_rules! swap {
+    ($a:ident, $b:ident) => {
+        let tmp = $a;
+        $a = $b;
+        $b = tmp;
+    };
+}
+
+fn main() {
+    let mut tmp = 1;
+    let mut tmp1 = 2;
+    let tmp2 = tmp;
+    tmp = tmp1;
+    tmp1 = tmp2;
+    let _ = tmp;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn inlines_items_and_dollar_crate() {
+        check_assist(
+            inline_macro,
+            r#"
+//- /main.rs crate:main deps:helpers
+helpers::make_getter$0!(Foo);
+//- /lib.rs crate:helpers
+pub struct Value;
+#[macro_export]
+macro_rules! make_getter {
+    ($name:ident) => {
+        struct $name;
+        impl $name {
+            fn get(&self) -> $crate::Value {
+                $crate::Value
+            }
+        }
+    };
+}
+"#,
+            r#"
+struct Foo;
+
+impl Foo {
+    fn get(&self) -> helpers::Value {
+        helpers::Value
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_for_private_items() {
+        cov_mark::check!(inline_macro_private_item);
+        check_assist_not_applicable(
+            inline_macro,
+            r#"
+//- /main.rs crate:main deps:helpers
+fn main() {
+    helpers::secret$0!();
+}
+//- /lib.rs crate:helpers
+fn secret() {}
+#[macro_export]
+macro_rules! secret {
+    () => { $crate::secret() };
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_for_items_in_private_modules() {
+        cov_mark::check!(inline_macro_private_item);
+        check_assist_not_applicable(
+            inline_macro,
+            r#"
+mod m {
+    mod detail {
+        pub fn helper() {}
+    }
+    macro_rules! call_helper {
+        () => { $crate::m::detail::helper() };
+    }
+    pub(crate) use call_helper;
+}
+
+fn main() {
+    m::call_helper$0!();
+}
+"#,
+        );
+    }
+}
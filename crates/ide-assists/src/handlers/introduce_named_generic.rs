@@ -1,6 +1,7 @@
+use ide_db::defs::Definition;
 use syntax::{
-    ast::{self, edit_in_place::GenericParamsOwnerEdit, make, AstNode},
-    ted,
+    ast::{self, edit_in_place::GenericParamsOwnerEdit, make, AstNode, HasGenericParams},
+    ted, T,
 };
 
 use crate::{utils::suggest_name, AssistContext, AssistId, AssistKind, Assists};
@@ -29,21 +30,77 @@ pub(crate) fn introduce_named_generic(acc: &mut Assists, ctx: &AssistContext<'_>
         "Replace impl trait with generic",
         target,
         |edit| {
+            // Calls that spell out the generic arguments have to pass one more.
+            let mut turbofish_calls = Vec::new();
+            if let Some(function) = ctx.sema.to_def(&fn_) {
+                for (file_id, refs) in Definition::Function(function).usages(&ctx.sema).all() {
+                    let arg_lists = refs
+                        .iter()
+                        .filter_map(|it| explicit_generic_args(it.name.as_name_ref()?))
+                        .collect::<Vec<_>>();
+                    if file_id == ctx.file_id() {
+                        turbofish_calls.extend(arg_lists);
+                        continue;
+                    }
+                    edit.edit_file(file_id);
+                    arg_lists.into_iter().map(|it| edit.make_mut(it)).for_each(add_placeholder);
+                }
+            }
+            edit.edit_file(ctx.file_id());
+            let turbofish_calls =
+                turbofish_calls.into_iter().map(|it| edit.make_mut(it)).collect::<Vec<_>>();
             let impl_trait_type = edit.make_mut(impl_trait_type);
             let fn_ = edit.make_mut(fn_);
+            turbofish_calls.into_iter().for_each(add_placeholder);
 
             let type_param_name = suggest_name::for_generic_parameter(&impl_trait_type);
-
-            let type_param = make::type_param(make::name(&type_param_name), Some(type_bound_list))
-                .clone_for_update();
             let new_ty = make::ty(&type_param_name).clone_for_update();
-
             ted::replace(impl_trait_type.syntax(), new_ty.syntax());
-            fn_.get_or_create_generic_param_list().add_generic_param(type_param.into())
+
+            // Keep the bounds in the where clause if the function already has one.
+            let type_param = match fn_.where_clause() {
+                Some(where_clause) => {
+                    let pred = make::where_pred(
+                        make::ext::ident_path(&type_param_name),
+                        type_bound_list.bounds(),
+                    );
+                    where_clause.add_predicate(pred.clone_for_update());
+                    make::type_param(make::name(&type_param_name), None)
+                }
+                None => make::type_param(make::name(&type_param_name), Some(type_bound_list)),
+            };
+            fn_.get_or_create_generic_param_list()
+                .add_generic_param(type_param.clone_for_update().into())
         },
     )
 }
 
+fn explicit_generic_args(name_ref: &ast::NameRef) -> Option<ast::GenericArgList> {
+    let parent = name_ref.syntax().parent()?;
+    if let Some(method_call) = ast::MethodCallExpr::cast(parent.clone()) {
+        return method_call.generic_arg_list();
+    }
+    let segment = ast::PathSegment::cast(parent)?;
+    if segment.parent_path().parent_path().is_some() {
+        return None;
+    }
+    segment.generic_arg_list()
+}
+
+fn add_placeholder(arg_list: ast::GenericArgList) {
+    let r_angle = match arg_list.r_angle_token() {
+        Some(it) => it,
+        None => return,
+    };
+    let mut elements = Vec::new();
+    if arg_list.generic_args().next().is_some() {
+        elements.push(make::token(T![,]).into());
+        elements.push(make::tokens::single_space().into());
+    }
+    elements.push(make::ty_placeholder().clone_for_update().syntax().clone().into());
+    ted::insert_all_raw(ted::Position::before(r_angle), elements);
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -141,4 +198,62 @@ fn foo<
             r#"fn foo<F: Foo + Bar>(bar: F) {}"#,
         );
     }
+    #[test]
+    fn replace_impl_trait_with_where_clause() {
+        check_assist(
+            introduce_named_generic,
+            r#"fn foo<T>(t: T, bar: $0impl Bar + Baz) where T: Clone {}"#,
+            r#"fn foo<T, B>(t: T, bar: B) where T: Clone, B: Bar + Baz {}"#,
+        );
+    }
+
+    #[test]
+    fn replace_impl_trait_updates_turbofish_calls() {
+        check_assist(
+            introduce_named_generic,
+            r#"
+fn foo<T>(t: T, bar: $0impl Bar) {}
+struct S;
+impl S {
+    fn method<T>(&self, bar: impl Bar) {}
+}
+fn main() {
+    foo::<u32>(1, ());
+    foo(1, ());
+}
+"#,
+            r#"
+fn foo<T, B: Bar>(t: T, bar: B) {}
+struct S;
+impl S {
+    fn method<T>(&self, bar: impl Bar) {}
+}
+fn main() {
+    foo::<u32, _>(1, ());
+    foo(1, ());
+}
+"#,
+        );
+        check_assist(
+            introduce_named_generic,
+            r#"
+struct S;
+impl S {
+    fn method<T>(&self, bar: $0impl Bar) {}
+}
+fn main() {
+    S.method::<u32>(());
+}
+"#,
+            r#"
+struct S;
+impl S {
+    fn method<T, B: Bar>(&self, bar: B) {}
+}
+fn main() {
+    S.method::<u32, _>(());
+}
+"#,
+        );
+    }
 }
@@ -0,0 +1,800 @@
+use hir::{AsAssocItem, AssocItemContainer, Module, ModuleDef, ModuleSource, PathResolution};
+use ide_db::{
+    base_db::FileId,
+    defs::{Definition, NameClass, NameRefClass},
+    helpers::mod_path_to_ast,
+    imports::insert_use::{insert_use, ImportScope},
+    search::UsageSearchResult,
+    FxHashMap,
+};
+use itertools::Itertools;
+use syntax::{
+    algo,
+    ast::{self, edit::IndentLevel, make, AstNode, HasModuleItem, HasName, HasVisibility as _},
+    ted, Direction, NodeOrToken, SyntaxElement, SyntaxKind, SyntaxNode, TextRange, TextSize, T,
+};
+use text_edit::TextEdit;
+
+use crate::{
+    handlers::fix_visibility::target_data_for_def, utils::vis_offset, AssistContext, AssistId,
+    AssistKind, Assists, GroupLabel,
+};
+
+// Assist: move_item_to_module
+//
+// Moves an item into its parent, a child or a sibling module, fixing up the paths that refer to it
+// and the visibility of everything involved.
+//
+// ```
+// mod utils {}
+//
+// fn $0helper() {}
+//
+// fn main() {
+//     helper();
+// }
+// ```
+// ->
+// ```
+// mod utils {
+//     pub(crate) fn helper() {}
+// }
+//
+// fn main() {
+//     utils::helper();
+// }
+// ```
+pub(crate) fn move_item_to_module(acc: &mut Assists, ctx: &AssistContext<'_>) -> Option<()> {
+    let (item, def) = match ctx.find_node_at_offset::<ast::Name>() {
+        Some(name) => {
+            let item = name.syntax().parent().and_then(ast::Item::cast)?;
+            if !matches!(
+                item,
+                ast::Item::Fn(_)
+                    | ast::Item::Struct(_)
+                    | ast::Item::Enum(_)
+                    | ast::Item::Union(_)
+                    | ast::Item::Trait(_)
+                    | ast::Item::TypeAlias(_)
+                    | ast::Item::Const(_)
+                    | ast::Item::Static(_)
+            ) {
+                return None;
+            }
+            let def = match NameClass::classify(&ctx.sema, &name)? {
+                NameClass::Definition(it) => it,
+                _ => return None,
+            };
+            (item, Some(def))
+        }
+        None => {
+            let impl_ = ctx.find_node_at_offset::<ast::Impl>()?;
+            if impl_.assoc_item_list()?.syntax().text_range().contains(ctx.offset()) {
+                return None;
+            }
+            (ast::Item::Impl(impl_), None)
+        }
+    };
+    // Only items of a module can be moved, not the ones of blocks or impls.
+    if !matches!(item.syntax().parent()?.kind(), SyntaxKind::SOURCE_FILE | SyntaxKind::ITEM_LIST) {
+        return None;
+    }
+    let current = ctx.sema.scope(item.syntax())?.module();
+    let name = def.and_then(|it| it.name(ctx.db()));
+
+    let mut targets = current.children(ctx.db()).collect::<Vec<_>>();
+    if let Some(parent) = current.parent(ctx.db()) {
+        targets.push(parent);
+        targets.extend(parent.children(ctx.db()).filter(|&it| it != current));
+    }
+
+    // The item (or the items of a moved impl) has to stay visible from its usages, which don't
+    // depend on the target.
+    let own_defs = match (&item, def) {
+        (_, Some(def)) => vec![(item.syntax().clone(), def)],
+        (ast::Item::Impl(impl_), None) if impl_.trait_().is_none() => impl_
+            .assoc_item_list()?
+            .assoc_items()
+            .filter_map(|assoc| {
+                let name = match &assoc {
+                    ast::AssocItem::Fn(it) => it.name()?,
+                    ast::AssocItem::Const(it) => it.name()?,
+                    _ => return None,
+                };
+                let def = NameClass::classify(&ctx.sema, &name)?.defined()?;
+                Some((assoc.syntax().clone(), def))
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+    let own_usages = own_defs
+        .into_iter()
+        .map(|(node, own_def)| (node, own_def.usages(&ctx.sema).all()))
+        .collect::<Vec<_>>();
+
+    let group = GroupLabel("Move item to module".into());
+    let target_range = item.syntax().text_range();
+    for target in targets {
+        let source = target.definition_source(ctx.db());
+        if source.file_id.is_macro() || matches!(source.value, ModuleSource::BlockExpr(_)) {
+            continue;
+        }
+        if let Some(name) = &name {
+            if target.scope(ctx.db(), None).iter().any(|(it, _)| it == name) {
+                cov_mark::hit!(move_item_to_module_name_conflict);
+                continue;
+            }
+        }
+        let target_path = match current.find_use_path(ctx.db(), ModuleDef::Module(target)) {
+            Some(it) => mod_path_to_ast(&it),
+            None => continue,
+        };
+        let edits = match move_item(ctx, &item, def, &own_usages, current, target) {
+            Some(it) => it,
+            None => continue,
+        };
+        acc.add_group(
+            &group,
+            AssistId("move_item_to_module", AssistKind::Refactor),
+            format!("Move to module `{}`", target_path),
+            target_range,
+            |builder| {
+                for (file_id, mut file_edits) in edits {
+                    builder.edit_file(file_id);
+                    file_edits.sort_by_key(|(range, _)| (range.start(), range.end()));
+                    for (range, text) in file_edits {
+                        builder.replace(range, text);
+                    }
+                }
+            },
+        );
+    }
+    Some(())
+}
+
+/// Computes the edits moving `item` from `current` to `target`, given the usages of the
+/// definitions it contains.
+fn move_item(
+    ctx: &AssistContext<'_>,
+    item: &ast::Item,
+    def: Option<Definition>,
+    own_usages: &[(SyntaxNode, UsageSearchResult)],
+    current: Module,
+    target: Module,
+) -> Option<FxHashMap<FileId, Vec<(TextRange, String)>>> {
+    let db = ctx.db();
+    let item_file = ctx.file_id();
+    let item_range = item.syntax().text_range();
+    let mut edits: FxHashMap<FileId, Vec<(TextRange, String)>> = FxHashMap::default();
+
+    // Edits inside of the moved item, relative to the file it currently lives in.
+    let mut inner_edits = Vec::new();
+    let mut imports = Vec::new();
+    let mut referenced = Vec::new();
+    for name_ref in item.syntax().descendants().filter_map(ast::NameRef::cast) {
+        let referenced_def = match NameRefClass::classify(&ctx.sema, &name_ref) {
+            Some(NameRefClass::Definition(it)) => it,
+            Some(NameRefClass::FieldShorthand { field_ref, .. }) => Definition::Field(field_ref),
+            None => continue,
+        };
+        referenced.push(referenced_def);
+
+        if let Some(call) = name_ref.syntax().parent().and_then(ast::MethodCallExpr::cast) {
+            if call.name_ref().as_ref() != Some(&name_ref) {
+                continue;
+            }
+            let trait_ = match referenced_def {
+                Definition::Function(f) => match f.as_assoc_item(db).map(|it| it.container(db)) {
+                    Some(AssocItemContainer::Trait(it)) => Some(it),
+                    Some(AssocItemContainer::Impl(it)) => it.trait_(db),
+                    None => None,
+                },
+                _ => None,
+            };
+            if let Some(trait_) = trait_ {
+                referenced.push(Definition::Trait(trait_));
+                let path = path_from(ctx, target, ModuleDef::Trait(trait_));
+                imports.extend(path.filter(|it| it.qualifier().is_some()));
+            }
+            continue;
+        }
+
+        let segment = match name_ref.syntax().parent().and_then(ast::PathSegment::cast) {
+            Some(it) => it,
+            None => continue,
+        };
+        let path = segment.parent_path();
+        if path.qualifier().is_some() {
+            continue;
+        }
+        match segment.kind() {
+            Some(ast::PathSegmentKind::SelfKw | ast::PathSegmentKind::SuperKw) => {
+                if let Some(PathResolution::Def(ModuleDef::Module(module))) =
+                    ctx.sema.resolve_path(&path)
+                {
+                    let new_path = target.find_use_path(db, ModuleDef::Module(module))?;
+                    inner_edits.push((
+                        segment.syntax().text_range(),
+                        mod_path_to_ast(&new_path).to_string(),
+                    ));
+                }
+            }
+            Some(ast::PathSegmentKind::Name(_)) => {
+                let module_def = match ctx.sema.resolve_path(&path) {
+                    Some(PathResolution::Def(it)) => it,
+                    _ => continue,
+                };
+                if matches!(module_def, ModuleDef::BuiltinType(_) | ModuleDef::Macro(_))
+                    || Some(Definition::from(module_def)) == def
+                    || is_block_local(db, module_def)
+                {
+                    continue;
+                }
+                if let Some(path) = path_from(ctx, target, module_def) {
+                    let is_renamed = path
+                        .segment()
+                        .and_then(|it| it.name_ref())
+                        .map_or(true, |it| it.text() != name_ref.text());
+                    // Items imported under another name are referred to by their path instead.
+                    if is_renamed {
+                        inner_edits.push((name_ref.syntax().text_range(), path.to_string()));
+                    } else if path.qualifier().is_some() {
+                        imports.push(path);
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+    imports.sort_by_key(|it| it.to_string());
+    imports.dedup_by_key(|it| it.to_string());
+
+    // Everything the item refers to has to stay visible from its new location.
+    referenced.sort_by_key(|it| format!("{:?}", it));
+    referenced.dedup();
+    for referenced_def in referenced {
+        if referenced_def.visibility(db).map_or(true, |it| it.is_visible_from(db, target.into())) {
+            continue;
+        }
+        if let Some((file_id, range, offset, vis)) = vis_target(ctx, referenced_def) {
+            if file_id == item_file && item_range.contains_range(range) {
+                continue;
+            }
+            edits.entry(file_id).or_default().push(make_pub_crate(offset, vis));
+        }
+    }
+
+    // The item (or the items of a moved impl) has to stay visible from its usages.
+    for (node, usages) in own_usages {
+        let vis = node.children().find_map(ast::Visibility::cast);
+        if vis.as_ref().map_or(false, |it| matches!(it.to_string().as_str(), "pub" | "pub(crate)"))
+        {
+            continue;
+        }
+        let used_outside = usages.iter().any(|(file_id, refs)| {
+            refs.iter().any(|r| {
+                if *file_id == item_file && item_range.contains_range(r.range) {
+                    return false;
+                }
+                ctx.sema
+                    .scope(r.name.syntax())
+                    .map_or(false, |scope| !scope.module().path_to_root(db).contains(&target))
+            })
+        });
+        if used_outside {
+            inner_edits.push(make_pub_crate(vis_offset(node), vis));
+        }
+    }
+
+    // Paths pointing to the old location now have to point to the new one.
+    if let (Some((_, usages)), Some(name)) = (own_usages.first(), def.and_then(|it| it.name(db))) {
+        for (&file_id, refs) in usages.iter() {
+            for r in refs {
+                if file_id == item_file && item_range.contains_range(r.range) {
+                    continue;
+                }
+                let name_ref = match &r.name {
+                    ast::NameLike::NameRef(it) => it,
+                    _ => continue,
+                };
+                if ctx.sema.hir_file_for(name_ref.syntax()).is_macro() {
+                    continue;
+                }
+                if let Some(edit) = rewrite_usage(ctx, name_ref, &name, current, target) {
+                    edits.entry(file_id).or_default().extend(edit);
+                }
+            }
+        }
+    }
+
+    // Finally, move the text over.
+    let deleted_range = match item.syntax().prev_sibling_or_token() {
+        Some(ws) if ws.kind() == SyntaxKind::WHITESPACE => {
+            TextRange::new(ws.text_range().start(), item_range.end())
+        }
+        _ => match item.syntax().next_sibling_or_token() {
+            Some(ws) if ws.kind() == SyntaxKind::WHITESPACE => {
+                TextRange::new(item_range.start(), ws.text_range().end())
+            }
+            _ => item_range,
+        },
+    };
+    edits.entry(item_file).or_default().push((deleted_range, String::new()));
+
+    let mut item_text = item.syntax().to_string();
+    inner_edits.sort_by_key(|(range, _)| std::cmp::Reverse(range.start()));
+    for (range, text) in inner_edits {
+        let range = range - item_range.start();
+        item_text.replace_range(std::ops::Range::<usize>::from(range), &text);
+    }
+
+    let source = target.definition_source(db);
+    let target_file = source.file_id.original_file(db);
+    let (scope, module_indent) = match &source.value {
+        ModuleSource::SourceFile(it) => (ImportScope::File(it.clone()), None),
+        ModuleSource::Module(it) => {
+            (ImportScope::Module(it.item_list()?), Some(IndentLevel::from_node(it.syntax())))
+        }
+        ModuleSource::BlockExpr(_) => return None,
+    };
+    let indent = module_indent.map_or(IndentLevel(0), |it| it + 1);
+    let item_text = reindent(&item_text, IndentLevel::from_node(item.syntax()), indent);
+    let new_item = ast::SourceFile::parse(&item_text).tree().items().next()?.clone_for_update();
+
+    // The item and its imports are added to a copy of the target module, which is then diffed
+    // against the original.
+    let new_scope = scope.clone_for_update();
+    let (position, items) = match &new_scope {
+        ImportScope::File(it) => (ted::Position::first_child_of(it.syntax()), it.items()),
+        ImportScope::Module(it) => (ted::Position::after(it.l_curly_token()?), it.items()),
+        ImportScope::Block(_) => return None,
+    };
+    match items.last() {
+        Some(last) => ted::insert_all(
+            ted::Position::after(last.syntax()),
+            vec![
+                make::tokens::whitespace(&format!("\n\n{}", indent)).into(),
+                new_item.syntax().clone().into(),
+            ],
+        ),
+        None => {
+            let inner_ws = new_scope
+                .as_syntax_node()
+                .children_with_tokens()
+                .filter(|it| it.kind() == SyntaxKind::WHITESPACE)
+                .collect::<Vec<_>>();
+            inner_ws.into_iter().for_each(ted::remove);
+            let (before, after) = match module_indent {
+                Some(module_indent) => (format!("\n{}", indent), format!("\n{}", module_indent)),
+                None => (String::new(), "\n".to_owned()),
+            };
+            let mut elements = vec![new_item.syntax().clone().into()];
+            if !before.is_empty() {
+                elements.insert(0, make::tokens::whitespace(&before).into());
+            }
+            elements.push(make::tokens::whitespace(&after).into());
+            ted::insert_all(position, elements);
+        }
+    }
+    for import in imports {
+        insert_use(&new_scope, import.clone_for_update(), &ctx.config.insert_use);
+    }
+    let mut diff = TextEdit::builder();
+    algo::diff(scope.as_syntax_node(), new_scope.as_syntax_node()).into_text_edit(&mut diff);
+    let target_edits = edits.entry(target_file).or_default();
+    target_edits.extend(diff.finish().into_iter().map(|it| (it.delete, it.insert)));
+
+    Some(edits)
+}
+
+/// Returns the path to refer to `def` with from `module`, which is a single segment if it is in
+/// scope there already.
+fn path_from(ctx: &AssistContext<'_>, module: Module, def: ModuleDef) -> Option<ast::Path> {
+    let db = ctx.db();
+    let prefix_kind = ctx.config.insert_use.prefix_kind;
+    match module.find_use_path(db, def) {
+        Some(path) if path.kind == hir::PathKind::Plain && path.is_ident() => {
+            Some(mod_path_to_ast(&path))
+        }
+        Some(_) => Some(mod_path_to_ast(&module.find_use_path_prefixed(db, def, prefix_kind)?)),
+        // The item isn't visible from the target yet, it will be made `pub(crate)`.
+        None => {
+            let parent = def.module(db)?;
+            let parent_path =
+                module.find_use_path_prefixed(db, ModuleDef::Module(parent), prefix_kind)?;
+            let name = make::name_ref(&def.name(db)?.to_string());
+            Some(make::path_qualified(mod_path_to_ast(&parent_path), make::path_segment(name)))
+        }
+    }
+}
+
+fn is_block_local(db: &ide_db::RootDatabase, def: ModuleDef) -> bool {
+    def.module(db).map_or(false, |module| {
+        matches!(module.definition_source(db).value, ModuleSource::BlockExpr(_))
+    })
+}
+
+/// Finds the file, range, visibility offset and current visibility of a definition.
+fn vis_target(
+    ctx: &AssistContext<'_>,
+    def: Definition,
+) -> Option<(FileId, TextRange, TextSize, Option<ast::Visibility>)> {
+    let db = ctx.db();
+    match def {
+        Definition::Field(field) => {
+            let source = hir::HasSource::source(field, db)?;
+            let file_id = source.file_id.original_file(db);
+            let (syntax, vis) = match source.value {
+                hir::FieldSource::Named(it) => (it.syntax().clone(), it.visibility()),
+                hir::FieldSource::Pos(it) => (it.syntax().clone(), it.visibility()),
+            };
+            Some((file_id, syntax.text_range(), vis_offset(&syntax), vis))
+        }
+        Definition::Function(f) if f.as_assoc_item(db).is_some() => {
+            let assoc = f.as_assoc_item(db)?;
+            // Trait items and the items of trait impls have no visibility of their own.
+            if assoc.containing_trait_or_trait_impl(db).is_some() {
+                return None;
+            }
+            let (offset, vis, range, file_id, _) = target_data_for_def(db, f.into())?;
+            Some((file_id, range, offset, vis))
+        }
+        Definition::Const(c) if c.as_assoc_item(db).is_some() => {
+            let assoc = c.as_assoc_item(db)?;
+            if assoc.containing_trait_or_trait_impl(db).is_some() {
+                return None;
+            }
+            let (offset, vis, range, file_id, _) = target_data_for_def(db, c.into())?;
+            Some((file_id, range, offset, vis))
+        }
+        _ => {
+            let module_def = match def {
+                Definition::Module(it) => ModuleDef::Module(it),
+                Definition::Function(it) => ModuleDef::Function(it),
+                Definition::Adt(it) => ModuleDef::Adt(it),
+                Definition::Const(it) => ModuleDef::Const(it),
+                Definition::Static(it) => ModuleDef::Static(it),
+                Definition::Trait(it) => ModuleDef::Trait(it),
+                Definition::TypeAlias(it) => ModuleDef::TypeAlias(it),
+                _ => return None,
+            };
+            let (offset, vis, range, file_id, _) = target_data_for_def(db, module_def)?;
+            Some((file_id, range, offset, vis))
+        }
+    }
+}
+
+fn make_pub_crate(offset: TextSize, vis: Option<ast::Visibility>) -> (TextRange, String) {
+    match vis {
+        Some(vis) => (vis.syntax().text_range(), "pub(crate)".to_owned()),
+        None => (TextRange::empty(offset), "pub(crate) ".to_owned()),
+    }
+}
+
+/// Rewrites a reference to the moved item, returning the edits in its file.
+fn rewrite_usage(
+    ctx: &AssistContext<'_>,
+    name_ref: &ast::NameRef,
+    name: &hir::Name,
+    current: Module,
+    target: Module,
+) -> Option<Vec<(TextRange, String)>> {
+    let ref_module = ctx.sema.scope(name_ref.syntax())?.module();
+    let prefix = match ref_module == target {
+        true => None,
+        false => {
+            Some(mod_path_to_ast(&ref_module.find_use_path(ctx.db(), ModuleDef::Module(target))?))
+        }
+    };
+    let qualified = |prefix: &Option<ast::Path>| match prefix {
+        Some(prefix) => format!("{}::{}", prefix, name),
+        None => name.to_string(),
+    };
+
+    let segment = name_ref.syntax().parent().and_then(ast::PathSegment::cast)?;
+    let path = segment.parent_path();
+    if let Some(use_tree) = path.syntax().parent().and_then(ast::UseTree::cast) {
+        let use_tree_list = use_tree.syntax().parent().and_then(ast::UseTreeList::cast);
+        let use_ = use_tree.syntax().ancestors().find_map(ast::Use::cast)?;
+        return match use_tree_list {
+            None if prefix.is_none() => {
+                let range = use_.syntax().text_range();
+                let end = use_
+                    .syntax()
+                    .next_sibling_or_token()
+                    .filter(|it| it.kind() == SyntaxKind::WHITESPACE)
+                    .map_or(range.end(), |it| it.text_range().end());
+                Some(vec![(TextRange::new(range.start(), end), String::new())])
+            }
+            None => Some(vec![(path.syntax().text_range(), qualified(&prefix))]),
+            Some(use_tree_list) => {
+                let others =
+                    use_tree_list.use_trees().filter(|it| it != &use_tree).collect::<Vec<_>>();
+                let is_self =
+                    |it: &ast::UseTree| it.path().map_or(false, |path| path.to_string() == "self");
+                // => `use a::{Foo, Other};` becomes `use a::Other;`
+                let removal = match &others[..] {
+                    [other] if !is_self(other) => {
+                        (use_tree_list.syntax().text_range(), other.syntax().to_string())
+                    }
+                    _ => (list_item_range(use_tree.syntax()), String::new()),
+                };
+                let mut edits = vec![removal];
+                if prefix.is_some() {
+                    let vis = use_.visibility().map_or_else(String::new, |it| format!("{} ", it));
+                    let rename =
+                        use_tree.rename().map_or_else(String::new, |it| format!(" {}", it));
+                    let indent = IndentLevel::from_node(use_.syntax());
+                    edits.push((
+                        TextRange::empty(use_.syntax().text_range().end()),
+                        format!("\n{}{}use {}{};", indent, vis, qualified(&prefix), rename),
+                    ));
+                }
+                Some(edits)
+            }
+        };
+    }
+
+    let range =
+        TextRange::new(path.syntax().text_range().start(), name_ref.syntax().text_range().end());
+    if path.qualifier().is_some() || (ref_module == current && prefix.is_some()) {
+        Some(vec![(range, qualified(&prefix))])
+    } else {
+        None
+    }
+}
+
+/// Returns the range of an item of a comma separated list, together with its comma.
+fn list_item_range(node: &SyntaxNode) -> TextRange {
+    let non_trivia = |it: &SyntaxElement| !it.kind().is_trivia();
+    let next = node.siblings_with_tokens(Direction::Next).skip(1).find(non_trivia);
+    match next {
+        Some(NodeOrToken::Token(comma)) if comma.kind() == T![,] => {
+            let end = comma
+                .next_token()
+                .filter(|it| it.kind() == SyntaxKind::WHITESPACE)
+                .map_or(comma.text_range().end(), |it| it.text_range().end());
+            TextRange::new(node.text_range().start(), end)
+        }
+        _ => {
+            let prev = node.siblings_with_tokens(Direction::Prev).skip(1).find(non_trivia);
+            match prev {
+                Some(NodeOrToken::Token(comma)) if comma.kind() == T![,] => {
+                    TextRange::new(comma.text_range().start(), node.text_range().end())
+                }
+                _ => node.text_range(),
+            }
+        }
+    }
+}
+
+fn reindent(text: &str, from: IndentLevel, to: IndentLevel) -> String {
+    let (from, to) = (from.to_string(), to.to_string());
+    text.lines()
+        .enumerate()
+        .map(|(idx, line)| match line.strip_prefix(&from) {
+            Some(rest) if idx > 0 && !line.trim().is_empty() => format!("{}{}", to, rest),
+            _ => line.to_owned(),
+        })
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_by_label, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn move_fn_to_child_module() {
+        check_assist(
+            move_item_to_module,
+            r#"
+struct Bar;
+
+mod inner {
+    fn other() {}
+}
+
+fn $0foo(bar: Bar) -> Bar {
+    bar
+}
+
+fn main() {
+    foo(Bar);
+}
+"#,
+            r#"
+struct Bar;
+
+mod inner {
+    use crate::Bar;
+
+    fn other() {}
+
+    pub(crate) fn foo(bar: Bar) -> Bar {
+        bar
+    }
+}
+
+fn main() {
+    inner::foo(Bar);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn move_struct_to_sibling_file_module() {
+        check_assist(
+            move_item_to_module,
+            r#"
+//- /main.rs
+mod a;
+mod b;
+pub use a::{Foo, Other};
+
+fn make() -> Foo {
+    crate::a::Foo
+}
+//- /a.rs
+pub struct Other;
+pub struct $0Foo;
+//- /b.rs
+"#,
+            r#"
+//- /main.rs
+mod a;
+mod b;
+pub use a::Other;
+pub use b::Foo;
+
+fn make() -> Foo {
+    b::Foo
+}
+//- /a.rs
+pub struct Other;
+//- /b.rs
+pub struct Foo;
+"#,
+        );
+    }
+
+    #[test]
+    fn move_fn_to_parent_module() {
+        check_assist(
+            move_item_to_module,
+            r#"
+mod outer {
+    fn helper() -> u32 { 0 }
+
+    pub fn $0run() -> u32 {
+        self::helper() + helper()
+    }
+
+    pub fn caller() -> u32 {
+        run()
+    }
+}
+"#,
+            r#"
+use outer::helper;
+
+mod outer {
+    pub(crate) fn helper() -> u32 { 0 }
+
+    pub fn caller() -> u32 {
+        crate::run()
+    }
+}
+
+pub fn run() -> u32 {
+    outer::helper() + helper()
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn move_impl() {
+        check_assist(
+            move_item_to_module,
+            r#"
+mod m {}
+struct S;
+$0impl S {
+    fn new() -> S { S }
+}
+fn main() {
+    S::new();
+}
+"#,
+            r#"
+mod m {
+    use crate::S;
+
+    impl S {
+        pub(crate) fn new() -> S { S }
+    }
+}
+struct S;
+fn main() {
+    S::new();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn move_fn_using_renamed_import() {
+        check_assist_by_label(
+            move_item_to_module,
+            r#"
+mod m {}
+mod n {
+    pub struct S;
+}
+use n::S as T;
+fn $0foo(_: T) {}
+"#,
+            r#"
+mod m {
+    fn foo(_: crate::n::S) {}
+}
+mod n {
+    pub struct S;
+}
+use n::S as T;
+"#,
+            "Move to module `m`",
+        );
+        check_assist_by_label(
+            move_item_to_module,
+            r#"
+mod m {}
+mod n {
+    pub struct S;
+}
+use n::S as T;
+fn $0foo(_: T) {}
+"#,
+            r#"
+mod m {}
+mod n {
+    pub struct S;
+
+    fn foo(_: S) {}
+}
+use n::S as T;
+"#,
+            "Move to module `n`",
+        );
+    }
+
+    #[test]
+    fn not_applicable_in_fn_body() {
+        check_assist_not_applicable(
+            move_item_to_module,
+            r#"
+mod m {}
+fn main() {
+    fn $0inner() {}
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_on_name_conflict() {
+        cov_mark::check!(move_item_to_module_name_conflict);
+        check_assist_not_applicable(
+            move_item_to_module,
+            r#"
+mod m {
+    pub fn foo() {}
+}
+fn $0foo() {}
+"#,
+        );
+    }
+}
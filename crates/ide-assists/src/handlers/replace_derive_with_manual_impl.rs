@@ -6,6 +6,7 @@ use ide_db::{
 use itertools::Itertools;
 use syntax::{
     ast::{self, AstNode, HasName},
+    Direction,
     SyntaxKind::WHITESPACE,
 };
 
@@ -215,10 +216,12 @@ fn impl_def_from_trait(
     let (impl_def, first_assoc_item) =
         add_trait_assoc_items_to_impl(sema, trait_items, trait_, impl_def, target_scope);
 
-    // Generate a default `impl` function body for the derived trait.
-    if let ast::AssocItem::Fn(ref func) = first_assoc_item {
-        let _ = gen_trait_fn_body(func, trait_path, adt);
-    };
+    // Generate default `impl` function bodies for the derived trait.
+    first_assoc_item.syntax().siblings(Direction::Next).filter_map(ast::Fn::cast).for_each(
+        |func| {
+            let _ = gen_trait_fn_body(&func, trait_path, adt, None);
+        },
+    );
 
     Some((impl_def, first_assoc_item))
 }
@@ -813,6 +816,143 @@ impl PartialOrd for Foo {
         )
     }
 
+    #[test]
+    fn add_custom_impl_partial_ord_empty_struct() {
+        check_assist(
+            replace_derive_with_manual_impl,
+            r#"
+//- minicore: ord, derive
+#[derive(Partial$0Ord)]
+struct Foo;
+"#,
+            r#"
+struct Foo;
+
+impl PartialOrd for Foo {
+    $0fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(core::cmp::Ordering::Equal)
+    }
+}
+"#,
+        )
+    }
+
+    #[test]
+    fn add_custom_impl_ord_record_struct() {
+        check_assist(
+            replace_derive_with_manual_impl,
+            r#"
+//- minicore: ord, derive
+#[derive($0Ord)]
+struct Foo {
+    bin: usize,
+    bar: usize,
+}
+"#,
+            r#"
+struct Foo {
+    bin: usize,
+    bar: usize,
+}
+
+impl Ord for Foo {
+    $0fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        match self.bin.cmp(&other.bin) {
+            core::cmp::Ordering::Equal => {}
+            ord => return ord,
+        }
+        self.bar.cmp(&other.bar)
+    }
+}
+"#,
+        )
+    }
+
+    #[test]
+    fn add_custom_impl_ord_enum() {
+        check_assist(
+            replace_derive_with_manual_impl,
+            r#"
+//- minicore: ord, derive
+#[derive($0Ord)]
+enum Foo {
+    Bar,
+    Baz(usize, usize),
+    Qux { bin: usize },
+}
+"#,
+            r#"
+enum Foo {
+    Bar,
+    Baz(usize, usize),
+    Qux { bin: usize },
+}
+
+impl Ord for Foo {
+    $0fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        let ordinal = |it: &Self| match it {
+            Self::Bar => 0,
+            Self::Baz(_, _) => 1,
+            Self::Qux { bin: _ } => 2,
+        };
+        match (self, other) {
+            (Self::Baz(l0, l1), Self::Baz(r0, r1)) => {
+                match l0.cmp(&r0) {
+                    core::cmp::Ordering::Equal => {}
+                    ord => return ord,
+                }
+                l1.cmp(&r1)
+            }
+            (Self::Qux { bin: l_bin }, Self::Qux { bin: r_bin }) => l_bin.cmp(&r_bin),
+            _ => ordinal(self).cmp(&ordinal(other)),
+        }
+    }
+}
+"#,
+        )
+    }
+
+    #[test]
+    fn add_custom_impl_ord_empty_struct() {
+        check_assist(
+            replace_derive_with_manual_impl,
+            r#"
+//- minicore: ord, derive
+#[derive($0Ord)]
+struct Foo();
+"#,
+            r#"
+struct Foo();
+
+impl Ord for Foo {
+    $0fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        core::cmp::Ordering::Equal
+    }
+}
+"#,
+        )
+    }
+
+    #[test]
+    fn add_custom_impl_eq() {
+        check_assist(
+            replace_derive_with_manual_impl,
+            r#"
+//- minicore: eq, derive
+#[derive(PartialEq, $0Eq)]
+struct Foo;
+"#,
+            r#"
+#[derive(PartialEq)]
+struct Foo;
+
+impl Eq for Foo {
+    $0
+}
+"#,
+        )
+    }
+
     #[test]
     fn add_custom_impl_partial_eq_record_struct() {
         check_assist(
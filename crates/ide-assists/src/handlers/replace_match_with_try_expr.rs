@@ -0,0 +1,393 @@
+use hir::{Local, ModuleDef, PathResolution};
+use ide_db::{famous_defs::FamousDefs, ty_filter::TryEnum};
+use syntax::ast::{self, AstNode, HasArgList};
+
+use crate::{AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: replace_match_with_try_expr
+//
+// Replaces a `match` that unwraps the happy case and returns the sad case with a `?` expression.
+//
+// ```
+// # //- minicore: option
+// fn handle(opt: Option<u32>) -> Option<u32> {
+//     let val = $0match opt {
+//         Some(it) => it,
+//         None => return None,
+//     };
+//     Some(val + 1)
+// }
+// ```
+// ->
+// ```
+// fn handle(opt: Option<u32>) -> Option<u32> {
+//     let val = opt?;
+//     Some(val + 1)
+// }
+// ```
+pub(crate) fn replace_match_with_try_expr(
+    acc: &mut Assists,
+    ctx: &AssistContext<'_>,
+) -> Option<()> {
+    let match_expr = ctx.find_node_at_offset::<ast::MatchExpr>()?;
+    let match_arm_list = match_expr.match_arm_list()?;
+    if match_arm_list.syntax().text_range().contains(ctx.offset()) {
+        return None;
+    }
+    let scrutinee = match_expr.expr()?;
+    let scrutinee_ty = ctx.sema.type_of_expr(&scrutinee)?.original;
+    let try_enum = TryEnum::from_ty(&ctx.sema, &scrutinee_ty)?;
+    let enum_ = match scrutinee_ty.as_adt()? {
+        hir::Adt::Enum(it) => it,
+        _ => return None,
+    };
+    let is_variant = |res: Option<PathResolution>, name: &str| match res {
+        Some(PathResolution::Def(ModuleDef::Variant(variant))) => {
+            variant.parent_enum(ctx.db()) == enum_ && variant.name(ctx.db()).to_smol_str() == name
+        }
+        _ => false,
+    };
+
+    let arms = match_arm_list.arms().collect::<Vec<_>>();
+    let (happy_arm, sad_arm) = match arms.as_slice() {
+        [first, second] => match is_happy_arm(ctx, first, try_enum, &is_variant) {
+            true => (first, second),
+            false => (second, first),
+        },
+        _ => return None,
+    };
+    if !is_happy_arm(ctx, happy_arm, try_enum, &is_variant) || sad_arm.guard().is_some() {
+        return None;
+    }
+    let returned = match sad_arm.expr()? {
+        ast::Expr::ReturnExpr(it) => it.expr()?,
+        _ => return None,
+    };
+
+    match try_enum {
+        TryEnum::Option => {
+            let pat_res = match sad_arm.pat()? {
+                ast::Pat::IdentPat(it) => {
+                    ctx.sema.resolve_bind_pat_to_const(&it).map(PathResolution::Def)
+                }
+                ast::Pat::PathPat(it) => ctx.sema.resolve_path(&it.path()?),
+                _ => return None,
+            };
+            let expr_res = match &returned {
+                ast::Expr::PathExpr(it) => ctx.sema.resolve_path(&it.path()?),
+                _ => return None,
+            };
+            if !is_variant(pat_res, "None") || !is_variant(expr_res, "None") {
+                return None;
+            }
+        }
+        TryEnum::Result => {
+            let (err_local, err_pat) = match sad_arm.pat()? {
+                ast::Pat::TupleStructPat(it) if is_variant(resolve(ctx, it.path()), "Err") => {
+                    single_binding(ctx, &it)?
+                }
+                _ => return None,
+            };
+            let call = match &returned {
+                ast::Expr::CallExpr(it) => it.clone(),
+                _ => return None,
+            };
+            let callee = match call.expr()? {
+                ast::Expr::PathExpr(it) => it.path(),
+                _ => return None,
+            };
+            let ret_ty = ctx.sema.type_of_expr(&returned)?.original;
+            if !is_variant(resolve(ctx, callee), "Err")
+                || TryEnum::from_ty(&ctx.sema, &ret_ty).is_none()
+            {
+                return None;
+            }
+            let arg = match call.arg_list()?.args().collect::<Vec<_>>().as_slice() {
+                [arg] => arg.clone(),
+                _ => return None,
+            };
+            let (converted, is_conversion) = conversion_arg(&arg)?;
+            if local_of(ctx, &converted)? != err_local {
+                return None;
+            }
+
+            // `?` converts the error with `From`, so the original conversion has to be the same.
+            let from_err_ty = ctx.sema.type_of_pat(&err_pat)?.original;
+            let to_err_ty = ret_ty.type_arguments().nth(1)?;
+            let converts = if is_conversion {
+                let krate = ctx.sema.scope(match_expr.syntax())?.krate();
+                FamousDefs(&ctx.sema, krate)
+                    .core_convert_From()
+                    .map_or(false, |from| to_err_ty.impls_trait(ctx.db(), from, &[from_err_ty]))
+            } else {
+                from_err_ty == to_err_ty
+            };
+            if !converts {
+                cov_mark::hit!(replace_match_with_try_expr_no_from_impl);
+                return None;
+            }
+        }
+    }
+
+    let target = match_expr.syntax().text_range();
+    acc.add(
+        AssistId("replace_match_with_try_expr", AssistKind::RefactorRewrite),
+        "Replace match with try expression",
+        target,
+        |builder| {
+            let replacement = match scrutinee {
+                ast::Expr::PathExpr(_)
+                | ast::Expr::CallExpr(_)
+                | ast::Expr::MethodCallExpr(_)
+                | ast::Expr::FieldExpr(_)
+                | ast::Expr::IndexExpr(_)
+                | ast::Expr::ParenExpr(_)
+                | ast::Expr::MacroExpr(_)
+                | ast::Expr::TryExpr(_)
+                | ast::Expr::AwaitExpr(_) => format!("{}?", scrutinee),
+                _ => format!("({})?", scrutinee),
+            };
+            builder.replace(target, replacement);
+        },
+    )
+}
+
+/// Checks for `Some(it) => it` or `Ok(it) => it`.
+fn is_happy_arm(
+    ctx: &AssistContext<'_>,
+    arm: &ast::MatchArm,
+    try_enum: TryEnum,
+    is_variant: &dyn Fn(Option<PathResolution>, &str) -> bool,
+) -> bool {
+    let pat = match arm.pat() {
+        Some(ast::Pat::TupleStructPat(it)) => it,
+        _ => return false,
+    };
+    if arm.guard().is_some() || !is_variant(resolve(ctx, pat.path()), try_enum.happy_case()) {
+        return false;
+    }
+    match (single_binding(ctx, &pat), arm.expr()) {
+        (Some((local, _)), Some(expr)) => local_of(ctx, &expr) == Some(local),
+        _ => false,
+    }
+}
+
+fn single_binding(ctx: &AssistContext<'_>, pat: &ast::TupleStructPat) -> Option<(Local, ast::Pat)> {
+    let mut fields = pat.fields();
+    let field = fields.next()?;
+    if fields.next().is_some() {
+        return None;
+    }
+    match &field {
+        ast::Pat::IdentPat(it) if it.pat().is_none() && it.ref_token().is_none() => {
+            Some((ctx.sema.to_def(it)?, field))
+        }
+        _ => None,
+    }
+}
+
+/// Splits `e`, `e.into()`, `From::from(e)` and `Into::into(e)` into the converted expression and
+/// whether there is a conversion.
+fn conversion_arg(arg: &ast::Expr) -> Option<(ast::Expr, bool)> {
+    match arg {
+        ast::Expr::MethodCallExpr(call) => {
+            let name = call.name_ref()?;
+            if name.text() != "into" || call.arg_list()?.args().next().is_some() {
+                return None;
+            }
+            Some((call.receiver()?, true))
+        }
+        ast::Expr::CallExpr(call) => {
+            let path = match call.expr()? {
+                ast::Expr::PathExpr(it) => it.path()?,
+                _ => return None,
+            };
+            let name = path.segment()?.name_ref()?;
+            let qualifier = path.qualifier()?.segment()?.name_ref()?;
+            match (qualifier.text().as_str(), name.text().as_str()) {
+                ("From", "from") | ("Into", "into") => (),
+                _ => return None,
+            }
+            let mut args = call.arg_list()?.args();
+            let arg = args.next()?;
+            if args.next().is_some() {
+                return None;
+            }
+            Some((arg, true))
+        }
+        _ => Some((arg.clone(), false)),
+    }
+}
+
+fn resolve(ctx: &AssistContext<'_>, path: Option<ast::Path>) -> Option<PathResolution> {
+    ctx.sema.resolve_path(&path?)
+}
+
+fn local_of(ctx: &AssistContext<'_>, expr: &ast::Expr) -> Option<Local> {
+    match expr {
+        ast::Expr::PathExpr(it) => match resolve(ctx, it.path())? {
+            PathResolution::Local(local) => Some(local),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn replace_option_match() {
+        check_assist(
+            replace_match_with_try_expr,
+            r#"
+//- minicore: option
+fn foo(opt: Option<u32>) -> Option<u32> {
+    let val = $0match opt {
+        None => return None,
+        Some(x) => x,
+    };
+    Some(val)
+}
+"#,
+            r#"
+fn foo(opt: Option<u32>) -> Option<u32> {
+    let val = opt?;
+    Some(val)
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn replace_result_match() {
+        check_assist(
+            replace_match_with_try_expr,
+            r#"
+//- minicore: result
+fn foo(res: Result<u32, i32>) -> Result<u32, i32> {
+    let val = match$0 res {
+        Ok(v) => v,
+        Err(e) => return Err(e),
+    };
+    Ok(val)
+}
+"#,
+            r#"
+fn foo(res: Result<u32, i32>) -> Result<u32, i32> {
+    let val = res?;
+    Ok(val)
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn replace_result_match_with_conversion() {
+        check_assist(
+            replace_match_with_try_expr,
+            r#"
+//- minicore: result, from
+struct A;
+struct B;
+impl From<A> for B {
+    fn from(_: A) -> B { B }
+}
+fn foo(res: Result<u32, A>) -> Result<u32, B> {
+    let val = $0match res {
+        Ok(v) => v,
+        Err(e) => return Err(e.into()),
+    };
+    Ok(val)
+}
+"#,
+            r#"
+struct A;
+struct B;
+impl From<A> for B {
+    fn from(_: A) -> B { B }
+}
+fn foo(res: Result<u32, A>) -> Result<u32, B> {
+    let val = res?;
+    Ok(val)
+}
+"#,
+        );
+        check_assist(
+            replace_match_with_try_expr,
+            r#"
+//- minicore: result, from
+fn bar() -> Result<u32, u8> { Ok(0) }
+fn foo() -> Result<u32, u8> {
+    $0match bar() {
+        Ok(v) => v,
+        Err(e) => return Err(From::from(e)),
+    };
+    Ok(0)
+}
+"#,
+            r#"
+fn bar() -> Result<u32, u8> { Ok(0) }
+fn foo() -> Result<u32, u8> {
+    bar()?;
+    Ok(0)
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_without_from_impl() {
+        cov_mark::check!(replace_match_with_try_expr_no_from_impl);
+        check_assist_not_applicable(
+            replace_match_with_try_expr,
+            r#"
+//- minicore: result, from
+struct A;
+struct B;
+impl Into<B> for A {
+    fn into(self) -> B { B }
+}
+fn foo(res: Result<u32, A>) -> Result<u32, B> {
+    let val = $0match res {
+        Ok(v) => v,
+        Err(e) => return Err(e.into()),
+    };
+    Ok(val)
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_for_other_arms() {
+        check_assist_not_applicable(
+            replace_match_with_try_expr,
+            r#"
+//- minicore: option
+fn foo(opt: Option<u32>) -> Option<u32> {
+    let val = $0match opt {
+        Some(x) => x + 1,
+        None => return None,
+    };
+    Some(val)
+}
+"#,
+        );
+        check_assist_not_applicable(
+            replace_match_with_try_expr,
+            r#"
+//- minicore: option
+fn foo(opt: Option<u32>) -> u32 {
+    $0match opt {
+        Some(x) => x,
+        None => return 0,
+    }
+}
+"#,
+        );
+    }
+}
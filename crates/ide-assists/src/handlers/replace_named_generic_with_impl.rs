@@ -0,0 +1,330 @@
+use hir::GenericParam;
+use ide_db::{
+    base_db::FileRange,
+    defs::Definition,
+    search::{FileReference, SearchScope},
+};
+use itertools::Itertools;
+use syntax::{
+    ast::{self, AstNode, HasGenericParams, HasTypeBounds},
+    match_ast, Direction, NodeOrToken, SyntaxElement, SyntaxKind, SyntaxNode, TextRange, T,
+};
+
+use crate::{assist_context::SourceChangeBuilder, AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: replace_named_generic_with_impl
+//
+// Replaces a named generic that is only used as the type of one argument with `impl Trait`.
+//
+// ```
+// fn new<P$0: AsRef<str>>(location: P) {}
+// ```
+// ->
+// ```
+// fn new(location: impl AsRef<str>) {}
+// ```
+pub(crate) fn replace_named_generic_with_impl(
+    acc: &mut Assists,
+    ctx: &AssistContext<'_>,
+) -> Option<()> {
+    let type_param = ctx.find_node_at_offset::<ast::TypeParam>()?;
+    let generic_param_list = type_param.syntax().parent().and_then(ast::GenericParamList::cast)?;
+    let fn_ = generic_param_list.syntax().parent().and_then(ast::Fn::cast)?;
+    if is_in_trait_or_trait_impl(&fn_) {
+        return None;
+    }
+    let param_list = fn_.param_list()?;
+    let type_param_def = ctx.sema.to_def(&type_param)?;
+    let function = ctx.sema.to_def(&fn_)?;
+
+    // The generic parameter must be used exactly once in an argument type, and otherwise only as
+    // the bounded type of where predicates.
+    let refs = Definition::GenericParam(GenericParam::TypeParam(type_param_def))
+        .usages(&ctx.sema)
+        .in_scope(SearchScope::file_range(FileRange {
+            file_id: ctx.file_id(),
+            range: fn_.syntax().text_range(),
+        }))
+        .all();
+    let refs = refs.references.get(&ctx.file_id()).cloned().unwrap_or_default();
+    let mut arg_type = None;
+    let mut where_preds = Vec::new();
+    for reference in &refs {
+        let path_type = match path_type_of_reference(reference) {
+            Some(it) => it,
+            None => {
+                cov_mark::hit!(replace_named_generic_with_impl_other_usage);
+                return None;
+            }
+        };
+        if param_list.syntax().text_range().contains_range(path_type.syntax().text_range())
+            && arg_type.is_none()
+        {
+            arg_type = Some(path_type);
+        } else if let Some(pred) = path_type.syntax().parent().and_then(ast::WherePred::cast) {
+            if pred.generic_param_list().is_some() {
+                return None;
+            }
+            where_preds.push(pred);
+        } else {
+            cov_mark::hit!(replace_named_generic_with_impl_other_usage);
+            return None;
+        }
+    }
+    let arg_type = arg_type?;
+
+    let bounds = type_param
+        .type_bound_list()
+        .into_iter()
+        .chain(where_preds.iter().filter_map(|it| it.type_bound_list()))
+        .flat_map(|it| it.bounds())
+        .map(|it| it.to_string())
+        .collect_vec();
+    let bounds = if bounds.is_empty() { "Sized".to_owned() } else { bounds.join(" + ") };
+    // `&impl A + B` is ambiguous.
+    let needs_parens = bounds.contains('+')
+        && arg_type.syntax().parent().map_or(false, |it| {
+            ast::RefType::can_cast(it.kind()) || ast::PtrType::can_cast(it.kind())
+        });
+    let impl_trait =
+        if needs_parens { format!("(impl {})", bounds) } else { format!("impl {}", bounds) };
+
+    // Explicit generic arguments for the parameter have to be dropped at call sites.
+    let arg_index = generic_param_list
+        .generic_params()
+        .filter(|it| !matches!(it, ast::GenericParam::LifetimeParam(_)))
+        .position(|it| it.syntax() == type_param.syntax())?;
+    let arg_count = generic_param_list
+        .generic_params()
+        .filter(|it| !matches!(it, ast::GenericParam::LifetimeParam(_)))
+        .count();
+
+    let target = type_param.syntax().text_range();
+    acc.add(
+        AssistId("replace_named_generic_with_impl", AssistKind::RefactorRewrite),
+        "Replace named generic with impl trait",
+        target,
+        |edit| {
+            for (file_id, refs) in Definition::Function(function).usages(&ctx.sema).all() {
+                edit.edit_file(file_id);
+                for arg_list in refs.iter().filter_map(explicit_generic_args) {
+                    let args = arg_list
+                        .generic_args()
+                        .filter(|it| !matches!(it, ast::GenericArg::LifetimeArg(_)))
+                        .collect_vec();
+                    if args.len() != arg_count {
+                        continue;
+                    }
+                    if arg_list.generic_args().count() == 1 {
+                        edit.delete(arg_list.syntax().text_range());
+                    } else {
+                        delete_list_item(edit, args[arg_index].syntax());
+                    }
+                }
+            }
+            edit.edit_file(ctx.file_id());
+
+            edit.replace(arg_type.syntax().text_range(), impl_trait);
+            if generic_param_list.generic_params().count() == 1 {
+                edit.delete(generic_param_list.syntax().text_range());
+            } else {
+                delete_list_item(edit, type_param.syntax());
+            }
+            if let Some(where_clause) = fn_.where_clause() {
+                if where_clause.predicates().count() == where_preds.len() {
+                    delete_where_clause(edit, &where_clause);
+                } else {
+                    where_preds.iter().for_each(|it| delete_list_item(edit, it.syntax()));
+                }
+            }
+        },
+    )
+}
+
+fn is_in_trait_or_trait_impl(fn_: &ast::Fn) -> bool {
+    let container = fn_.syntax().parent().and_then(ast::AssocItemList::cast);
+    match container.and_then(|it| it.syntax().parent()) {
+        Some(parent) => match_ast! {
+            match parent {
+                ast::Trait(_it) => true,
+                ast::Impl(it) => it.trait_().is_some(),
+                _ => false,
+            }
+        },
+        None => false,
+    }
+}
+
+fn path_type_of_reference(reference: &FileReference) -> Option<ast::PathType> {
+    let segment = reference.name.syntax().parent().and_then(ast::PathSegment::cast)?;
+    let path = segment.parent_path();
+    if path.qualifier().is_some() || path.parent_path().is_some() {
+        return None;
+    }
+    path.syntax().parent().and_then(ast::PathType::cast)
+}
+
+fn explicit_generic_args(reference: &FileReference) -> Option<ast::GenericArgList> {
+    let parent = reference.name.syntax().parent()?;
+    if let Some(method_call) = ast::MethodCallExpr::cast(parent.clone()) {
+        return method_call.generic_arg_list();
+    }
+    let segment = ast::PathSegment::cast(parent)?;
+    if segment.parent_path().parent_path().is_some() {
+        return None;
+    }
+    segment.generic_arg_list()
+}
+
+/// Deletes an item of a comma separated list, together with its comma.
+fn delete_list_item(edit: &mut SourceChangeBuilder, node: &SyntaxNode) {
+    let non_trivia = |it: &SyntaxElement| !it.kind().is_trivia();
+    let next = node.siblings_with_tokens(Direction::Next).skip(1).find(non_trivia);
+    let range = match next {
+        Some(NodeOrToken::Token(comma)) if comma.kind() == T![,] => {
+            let end = comma
+                .next_token()
+                .filter(|it| it.kind() == SyntaxKind::WHITESPACE)
+                .map_or(comma.text_range().end(), |it| it.text_range().end());
+            TextRange::new(node.text_range().start(), end)
+        }
+        _ => {
+            let prev = node.siblings_with_tokens(Direction::Prev).skip(1).find(non_trivia);
+            match prev {
+                Some(NodeOrToken::Token(comma)) if comma.kind() == T![,] => {
+                    TextRange::new(comma.text_range().start(), node.text_range().end())
+                }
+                _ => node.text_range(),
+            }
+        }
+    };
+    edit.delete(range);
+}
+
+fn delete_where_clause(edit: &mut SourceChangeBuilder, where_clause: &ast::WhereClause) {
+    let syntax = where_clause.syntax();
+    let prev = syntax.prev_sibling_or_token().filter(|it| it.kind() == SyntaxKind::WHITESPACE);
+    let next = syntax.next_sibling_or_token().filter(|it| it.kind() == SyntaxKind::WHITESPACE);
+    let start = prev.map_or(syntax.text_range().start(), |it| it.text_range().start());
+    let end = next.map_or(syntax.text_range().end(), |it| it.text_range().end());
+    let followed_by_body =
+        syntax.next_sibling().map_or(false, |it| ast::BlockExpr::can_cast(it.kind()));
+    edit.replace(TextRange::new(start, end), if followed_by_body { " " } else { "" });
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn replace_generic_with_impl() {
+        check_assist(
+            replace_named_generic_with_impl,
+            r#"fn new<P$0: AsRef<str>>(location: P) {}"#,
+            r#"fn new(location: impl AsRef<str>) {}"#,
+        );
+    }
+
+    #[test]
+    fn replace_generic_among_others() {
+        check_assist(
+            replace_named_generic_with_impl,
+            r#"fn foo<'a, T: Clone, $0U: Copy, const N: usize>(t: T, u: &'a U) -> T { t }"#,
+            r#"fn foo<'a, T: Clone, const N: usize>(t: T, u: &'a impl Copy) -> T { t }"#,
+        );
+    }
+
+    #[test]
+    fn replace_generic_with_where_clause() {
+        check_assist(
+            replace_named_generic_with_impl,
+            r#"
+fn foo<$0T: Clone>(items: Vec<T>)
+where
+    T: Send + Sync,
+{
+}
+"#,
+            r#"
+fn foo(items: Vec<impl Clone + Send + Sync>) {
+}
+"#,
+        );
+        check_assist(
+            replace_named_generic_with_impl,
+            r#"fn foo<$0T, U>(t: &T, u: U) where U: Copy, T: Clone + Send {}"#,
+            r#"fn foo<U>(t: &(impl Clone + Send), u: U) where U: Copy {}"#,
+        );
+    }
+
+    #[test]
+    fn replace_generic_removes_turbofish_args() {
+        check_assist(
+            replace_named_generic_with_impl,
+            r#"
+fn foo<T: Copy, U$0: Copy>(t: T, u: U) {}
+fn bar<T: Copy>(t: T) {}
+fn main() {
+    foo::<u8, u16>(1, 2);
+    foo(1, 2);
+}
+"#,
+            r#"
+fn foo<T: Copy>(t: T, u: impl Copy) {}
+fn bar<T: Copy>(t: T) {}
+fn main() {
+    foo::<u8>(1, 2);
+    foo(1, 2);
+}
+"#,
+        );
+        check_assist(
+            replace_named_generic_with_impl,
+            r#"
+struct S;
+impl S {
+    fn foo<T$0: Copy>(&self, t: T) {}
+}
+fn main() {
+    S.foo::<u8>(1);
+}
+"#,
+            r#"
+struct S;
+impl S {
+    fn foo(&self, t: impl Copy) {}
+}
+fn main() {
+    S.foo(1);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_when_used_elsewhere() {
+        cov_mark::check!(replace_named_generic_with_impl_other_usage);
+        check_assist_not_applicable(
+            replace_named_generic_with_impl,
+            r#"fn foo<$0T: Default>(t: T) -> T { T::default() }"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_when_used_in_several_args_or_in_traits() {
+        check_assist_not_applicable(
+            replace_named_generic_with_impl,
+            r#"fn foo<$0T: Copy>(a: T, b: T) {}"#,
+        );
+        check_assist_not_applicable(
+            replace_named_generic_with_impl,
+            r#"
+trait Tr {
+    fn foo<$0T: Copy>(t: T);
+}
+"#,
+        );
+    }
+}
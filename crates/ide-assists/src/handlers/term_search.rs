@@ -0,0 +1,175 @@
+use hir::term_search::{term_search as search_terms, TermSearchConfig};
+use itertools::Itertools;
+use syntax::{
+    ast::{self, make},
+    AstNode,
+};
+
+use crate::{AssistContext, AssistId, AssistKind, Assists, GroupLabel};
+
+// Assist: term_search
+//
+// Replaces a `todo` macro call with an expression of the expected type, built from the locals, fields,
+// constructors and functions in scope.
+//
+// ```
+// macro_rules! todo { () => { loop {} } }
+// struct Config { verbose: bool }
+// fn load(verbose: bool) -> Config {
+//     let config: Config = todo$0!();
+//     config
+// }
+// ```
+// ->
+// ```
+// macro_rules! todo { () => { loop {} } }
+// struct Config { verbose: bool }
+// fn load(verbose: bool) -> Config {
+//     let config: Config = Config { verbose };
+//     config
+// }
+// ```
+pub(crate) fn term_search(acc: &mut Assists, ctx: &AssistContext<'_>) -> Option<()> {
+    let macro_call = ctx.find_node_at_offset::<ast::MacroCall>()?;
+    // Fill the holes written like the ones the other assists leave behind.
+    let todo = match make::ext::expr_todo() {
+        ast::Expr::MacroExpr(it) => it.macro_call()?,
+        _ => return None,
+    };
+    let macro_name = |it: &ast::MacroCall| it.path()?.segment()?.name_ref();
+    if macro_name(&macro_call)?.text() != macro_name(&todo)?.text() {
+        return None;
+    }
+    let expr = ast::Expr::MacroExpr(macro_call.syntax().parent().and_then(ast::MacroExpr::cast)?);
+    let goal = ctx.sema.type_of_expr(&expr)?.adjusted();
+    if goal.is_unit() || goal.is_unknown() || goal.is_never() {
+        return None;
+    }
+
+    let scope = ctx.sema.scope(expr.syntax())?;
+    let candidates = search_terms(&scope, &goal, TermSearchConfig::default())
+        .into_iter()
+        .filter_map(|it| it.gen_source_code(&scope))
+        .unique()
+        .take(MAX_SUGGESTIONS)
+        .collect::<Vec<_>>();
+    if candidates.is_empty() {
+        cov_mark::hit!(term_search_no_candidates);
+        return None;
+    }
+
+    let group = GroupLabel("Term search".into());
+    let target = expr.syntax().text_range();
+    for candidate in candidates {
+        acc.add_group(
+            &group,
+            AssistId("term_search", AssistKind::Generate),
+            format!("Replace `{}` with `{}`", todo, candidate),
+            target,
+            |builder| builder.replace(target, candidate),
+        );
+    }
+    Some(())
+}
+
+const MAX_SUGGESTIONS: usize = 8;
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_by_label, check_assist_not_applicable};
+
+    use super::*;
+
+    const HOLE_MACRO: &str = "macro_rules! todo { () => { loop {} } }\n";
+
+    fn with_todo(ra_fixture: &str) -> String {
+        format!("{}{}", HOLE_MACRO, ra_fixture)
+    }
+
+    #[test]
+    fn fills_with_local() {
+        check_assist(
+            term_search,
+            &with_todo("fn f(a: u32) -> u32 { todo$0!() }"),
+            &with_todo("fn f(a: u32) -> u32 { a }"),
+        );
+    }
+
+    #[test]
+    fn fills_with_constructor_and_field() {
+        check_assist(
+            term_search,
+            &with_todo(
+                r#"
+struct Inner(u8);
+struct Outer { inner: Inner }
+enum E { A(u8) }
+fn f(outer: Outer) -> E {
+    to$0do!()
+}
+"#,
+            ),
+            &with_todo(
+                r#"
+struct Inner(u8);
+struct Outer { inner: Inner }
+enum E { A(u8) }
+fn f(outer: Outer) -> E {
+    E::A(outer.inner.0)
+}
+"#,
+            ),
+        );
+    }
+
+    #[test]
+    fn fills_with_function_calls() {
+        check_assist_by_label(
+            term_search,
+            &with_todo(
+                r#"
+struct Config;
+impl Config {
+    fn new(name: &str) -> Config { Config }
+    fn name(&self) -> u32 { 0 }
+}
+fn f(name: &str) -> u32 {
+    todo$0!()
+}
+"#,
+            ),
+            &with_todo(
+                r#"
+struct Config;
+impl Config {
+    fn new(name: &str) -> Config { Config }
+    fn name(&self) -> u32 { 0 }
+}
+fn f(name: &str) -> u32 {
+    Config.name()
+}
+"#,
+            ),
+            &format!("Replace `{}` with `Config.name()`", make::ext::expr_todo()),
+        );
+    }
+
+    #[test]
+    fn does_not_call_enclosing_fn() {
+        cov_mark::check!(term_search_no_candidates);
+        check_assist_not_applicable(
+            term_search,
+            &with_todo(
+                r#"
+struct Config(u8);
+fn f() -> Config { todo$0!() }
+"#,
+            ),
+        );
+    }
+
+    #[test]
+    fn not_applicable_for_unit() {
+        check_assist_not_applicable(term_search, &with_todo("fn f() { todo$0!() }"));
+    }
+}
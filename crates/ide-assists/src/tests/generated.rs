@@ -275,6 +275,30 @@ fn main() {
     )
 }
 
+#[test]
+fn doctest_convert_closure_to_fn() {
+    check_doc_test(
+        "convert_closure_to_fn",
+        r#####"
+//- minicore: copy
+fn main() {
+    let n = 2;
+    let add$0 = |x: u32| x + n;
+    add(1);
+}
+"#####,
+        r#####"
+fn main() {
+    let n = 2;
+    fn add(x: u32, n: u32) -> u32 {
+        x + n
+    }
+    add(1, n);
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_convert_for_loop_with_for_each() {
     check_doc_test(
@@ -407,6 +431,93 @@ fn main() {
     )
 }
 
+#[test]
+fn doctest_convert_match_to_let_else() {
+    check_doc_test(
+        "convert_match_to_let_else",
+        r#####"
+//- minicore: option
+fn foo(opt: Option<()>) {
+    let val$0 = match opt {
+        Some(it) => it,
+        None => return,
+    };
+}
+"#####,
+        r#####"
+fn foo(opt: Option<()>) {
+    let Some(val) = opt else { return };
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_convert_named_struct_to_tuple_struct() {
+    check_doc_test(
+        "convert_named_struct_to_tuple_struct",
+        r#####"
+struct Point$0 { x: f32, y: f32 }
+
+impl Point {
+    pub fn new(x: f32, y: f32) -> Self {
+        Point { x, y }
+    }
+
+    pub fn x(&self) -> f32 {
+        self.x
+    }
+
+    pub fn y(&self) -> f32 {
+        self.y
+    }
+}
+"#####,
+        r#####"
+struct Point(f32, f32);
+
+impl Point {
+    pub fn new(x: f32, y: f32) -> Self {
+        Point(x, y)
+    }
+
+    pub fn x(&self) -> f32 {
+        self.0
+    }
+
+    pub fn y(&self) -> f32 {
+        self.1
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_convert_nested_function_to_closure() {
+    check_doc_test(
+        "convert_nested_function_to_closure",
+        r#####"
+fn main() {
+    fn fo$0o(label: &str, number: u64) {
+        println!("{}: {}", label, number);
+    }
+
+    foo("Bar", 100);
+}
+"#####,
+        r#####"
+fn main() {
+    let foo = |label: &str, number: u64| {
+        println!("{}: {}", label, number);
+    };
+
+    foo("Bar", 100);
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_convert_to_guarded_return() {
     check_doc_test(
@@ -472,6 +583,27 @@ impl Point {
     )
 }
 
+#[test]
+fn doctest_convert_two_arm_bool_match_to_matches_macro() {
+    check_doc_test(
+        "convert_two_arm_bool_match_to_matches_macro",
+        r#####"
+//- minicore: option
+fn is_positive(opt: Option<i32>) -> bool {
+    match opt$0 {
+        Some(val) if val > 0 => true,
+        _ => false,
+    }
+}
+"#####,
+        r#####"
+fn is_positive(opt: Option<i32>) -> bool {
+    matches!(opt, Some(val) if val > 0)
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_convert_while_to_loop() {
     check_doc_test(
@@ -542,6 +674,37 @@ fn qux(bar: Bar, baz: Baz) {}
     )
 }
 
+#[test]
+fn doctest_extract_expressions_from_format_string() {
+    check_doc_test(
+        "extract_expressions_from_format_string",
+        r#####"
+macro_rules! format_args {
+    ($lit:literal $(tt:tt)*) => { 0 },
+}
+macro_rules! print {
+    ($($arg:tt)*) => (std::io::_print(format_args!($($arg)*)));
+}
+
+fn main() {
+    print!("{x + 1} {y}$0");
+}
+"#####,
+        r#####"
+macro_rules! format_args {
+    ($lit:literal $(tt:tt)*) => { 0 },
+}
+macro_rules! print {
+    ($($arg:tt)*) => (std::io::_print(format_args!($($arg)*)));
+}
+
+fn main() {
+    print!("{} {y}", x + 1);
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_extract_function() {
     check_doc_test(
@@ -613,6 +776,31 @@ enum A { One(One) }
     )
 }
 
+#[test]
+fn doctest_extract_struct_from_function_signature() {
+    check_doc_test(
+        "extract_struct_from_function_signature",
+        r#####"
+fn draw_line($0x: f32, y: f32$0, color: u32) {}
+
+fn main() {
+    draw_line(1.0, 2.0, 0xff);
+}
+"#####,
+        r#####"
+struct DrawLineParams { x: f32, y: f32 }
+
+fn draw_line(draw_line_params: DrawLineParams, color: u32) {
+    let DrawLineParams { x, y } = draw_line_params;
+}
+
+fn main() {
+    draw_line(DrawLineParams { x: 1.0, y: 2.0 }, 0xff);
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_extract_type_alias() {
     check_doc_test(
@@ -837,6 +1025,57 @@ impl Person {
     )
 }
 
+#[test]
+fn doctest_generate_delegate_trait() {
+    check_doc_test(
+        "generate_delegate_trait",
+        r#####"
+trait Describe {
+    const KIND: &'static str;
+    fn describe(&self, verbose: bool) -> String;
+}
+
+struct Age(u8);
+impl Describe for Age {
+    const KIND: &'static str = "age";
+    fn describe(&self, verbose: bool) -> String {
+        String::new()
+    }
+}
+
+struct Person {
+    ag$0e: Age,
+}
+"#####,
+        r#####"
+trait Describe {
+    const KIND: &'static str;
+    fn describe(&self, verbose: bool) -> String;
+}
+
+struct Age(u8);
+impl Describe for Age {
+    const KIND: &'static str = "age";
+    fn describe(&self, verbose: bool) -> String {
+        String::new()
+    }
+}
+
+struct Person {
+    age: Age,
+}
+
+$0impl Describe for Person {
+    const KIND: &'static str = <Age as Describe>::KIND;
+
+    fn describe(&self, verbose: bool) -> String {
+        <Age as Describe>::describe(&self.age, verbose)
+    }
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_generate_deref() {
     check_doc_test(
@@ -1259,6 +1498,35 @@ impl Person {
     )
 }
 
+#[test]
+fn doctest_generate_trait_from_impl() {
+    check_doc_test(
+        "generate_trait_from_impl",
+        r#####"
+struct Foo(u8);
+
+impl$0 Foo {
+    pub fn get(&self) -> u8 {
+        self.0
+    }
+}
+"#####,
+        r#####"
+struct Foo(u8);
+
+pub trait FooTrait {
+    fn get(&self) -> u8;
+}
+
+impl FooTrait for Foo {
+    fn get(&self) -> u8 {
+        self.0
+    }
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_inline_call() {
     check_doc_test(
@@ -1280,6 +1548,37 @@ fn foo(name: Option<&str>) {
     )
 }
 
+#[test]
+fn doctest_inline_format_args() {
+    check_doc_test(
+        "inline_format_args",
+        r#####"
+macro_rules! format_args {
+    ($lit:literal $(tt:tt)*) => { 0 },
+}
+macro_rules! print {
+    ($($arg:tt)*) => (std::io::_print(format_args!($($arg)*)));
+}
+
+fn main() {
+    print!("{} + {:?}$0", x, y + 1);
+}
+"#####,
+        r#####"
+macro_rules! format_args {
+    ($lit:literal $(tt:tt)*) => { 0 },
+}
+macro_rules! print {
+    ($($arg:tt)*) => (std::io::_print(format_args!($($arg)*)));
+}
+
+fn main() {
+    print!("{x} + {:?}", y + 1);
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_inline_into_callers() {
     check_doc_test(
@@ -1335,6 +1634,31 @@ fn main() {
     )
 }
 
+#[test]
+fn doctest_inline_macro() {
+    check_doc_test(
+        "inline_macro",
+        r#####"
+macro_rules! double {
+    ($e:expr) => { $e * 2 };
+}
+
+fn main() {
+    let x = double$0!(1 + 2);
+}
+"#####,
+        r#####"
+macro_rules! double {
+    ($e:expr) => { $e * 2 };
+}
+
+fn main() {
+    let x = (1 + 2) * 2;
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_inline_type_alias() {
     check_doc_test(
@@ -1616,6 +1940,31 @@ fn handle(action: Action) {
     )
 }
 
+#[test]
+fn doctest_move_item_to_module() {
+    check_doc_test(
+        "move_item_to_module",
+        r#####"
+mod utils {}
+
+fn $0helper() {}
+
+fn main() {
+    helper();
+}
+"#####,
+        r#####"
+mod utils {
+    pub(crate) fn helper() {}
+}
+
+fn main() {
+    utils::helper();
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_move_module_to_file() {
     check_doc_test(
@@ -2009,6 +2358,42 @@ fn handle(action: Action) {
     )
 }
 
+#[test]
+fn doctest_replace_match_with_try_expr() {
+    check_doc_test(
+        "replace_match_with_try_expr",
+        r#####"
+//- minicore: option
+fn handle(opt: Option<u32>) -> Option<u32> {
+    let val = $0match opt {
+        Some(it) => it,
+        None => return None,
+    };
+    Some(val + 1)
+}
+"#####,
+        r#####"
+fn handle(opt: Option<u32>) -> Option<u32> {
+    let val = opt?;
+    Some(val + 1)
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_replace_named_generic_with_impl() {
+    check_doc_test(
+        "replace_named_generic_with_impl",
+        r#####"
+fn new<P$0: AsRef<str>>(location: P) {}
+"#####,
+        r#####"
+fn new(location: impl AsRef<str>) {}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_replace_qualified_name_with_use() {
     check_doc_test(
@@ -2187,6 +2572,29 @@ use std::{collections::HashMap};
     )
 }
 
+#[test]
+fn doctest_term_search() {
+    check_doc_test(
+        "term_search",
+        r#####"
+macro_rules! todo { () => { loop {} } }
+struct Config { verbose: bool }
+fn load(verbose: bool) -> Config {
+    let config: Config = todo$0!();
+    config
+}
+"#####,
+        r#####"
+macro_rules! todo { () => { loop {} } }
+struct Config { verbose: bool }
+fn load(verbose: bool) -> Config {
+    let config: Config = Config { verbose };
+    config
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_toggle_ignore() {
     check_doc_test(
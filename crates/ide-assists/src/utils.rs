@@ -4,8 +4,8 @@ use std::ops;
 
 use itertools::Itertools;
 
-pub(crate) use gen_trait_fn_body::gen_trait_fn_body;
 use hir::{db::HirDatabase, HirDisplay, Semantics};
+pub(crate) use ide_db::syntax_helpers::gen_trait_fn_body::{gen_trait_fn_body, ImplTypes};
 use ide_db::{famous_defs::FamousDefs, path_transform::PathTransform, RootDatabase, SnippetCap};
 use stdx::format_to;
 use syntax::{
@@ -23,7 +23,6 @@ use syntax::{
 use crate::assist_context::{AssistContext, SourceChangeBuilder};
 
 pub(crate) mod suggest_name;
-mod gen_trait_fn_body;
 
 pub(crate) fn unwrap_trivial_block(block_expr: ast::BlockExpr) -> ast::Expr {
     extract_trivial_expression(&block_expr)
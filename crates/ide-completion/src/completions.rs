@@ -1,6 +1,7 @@
 //! This module defines an accumulator for completions which are going to be presented to user.
 
 pub(crate) mod attribute;
+pub(crate) mod doc_link;
 pub(crate) mod dot;
 pub(crate) mod expr;
 pub(crate) mod extern_abi;
@@ -614,6 +615,7 @@ pub(super) fn complete_name_ref(
             match &path_ctx.kind {
                 PathKind::Expr { expr_ctx } => {
                     expr::complete_expr_path(acc, ctx, path_ctx, expr_ctx);
+                    expr::complete_expr_term_search(acc, ctx, path_ctx);
 
                     dot::complete_undotted_self(acc, ctx, path_ctx, expr_ctx);
                     item_list::complete_item_list_in_expr(acc, ctx, path_ctx, expr_ctx);
@@ -629,9 +631,11 @@ pub(super) fn complete_name_ref(
                         TypeLocation::TypeAscription(ascription) => {
                             r#type::complete_ascribed_type(acc, ctx, path_ctx, ascription);
                         }
+                        TypeLocation::ImplTarget => {
+                            item_list::impl_skeleton::complete_impl_skeleton(acc, ctx, path_ctx);
+                        }
                         TypeLocation::GenericArgList(_)
                         | TypeLocation::TypeBound
-                        | TypeLocation::ImplTarget
                         | TypeLocation::ImplTrait
                         | TypeLocation::Other => (),
                     }
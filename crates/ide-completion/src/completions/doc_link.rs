@@ -0,0 +1,198 @@
+//! Completes intra-doc links in doc comments, like `/// [Vec::pu$0]`.
+
+use ide_db::{
+    defs::Definition,
+    doc_links::{doc_attributes, doc_link_candidates, parse_intra_doc_link},
+    SymbolKind,
+};
+use syntax::{ast, AstToken, TextRange, TextSize};
+
+use crate::{CompletionContext, CompletionItem, CompletionItemKind, Completions};
+
+pub(crate) fn complete_doc_link(
+    acc: &mut Completions,
+    ctx: &CompletionContext<'_>,
+    comment: &ast::Comment,
+) -> Option<()> {
+    let comment_start = comment.syntax().text_range().start();
+    let before_cursor = &comment.text()[..usize::from(ctx.position.offset - comment_start)];
+
+    let link_start = before_cursor.rfind('[')?;
+    // `a[i` is an index expression in some code, not the start of a link.
+    if before_cursor[..link_start]
+        .chars()
+        .next_back()
+        .map_or(false, |c| c.is_alphanumeric() || c == '_')
+    {
+        return None;
+    }
+    let (link, ns) = parse_intra_doc_link(&before_cursor[link_start + 1..]);
+    if !link.chars().all(|c| c.is_alphanumeric() || c == '_' || c == ':') {
+        return None;
+    }
+    let (qualifier, name) = match link.rsplit_once("::") {
+        Some((qualifier, name)) => (Some(qualifier), name),
+        None => (None, link),
+    };
+    if qualifier.map_or(false, |it| it.is_empty() || it.ends_with(':')) || name.contains(':') {
+        return None;
+    }
+
+    let (_, def) =
+        ctx.original_token.parent_ancestors().find_map(|node| doc_attributes(&ctx.sema, &node))?;
+    let range = TextRange::new(ctx.position.offset - TextSize::of(name), ctx.position.offset);
+    for (name, candidate) in doc_link_candidates(ctx.db, &ctx.scope, def, qualifier, ns) {
+        let kind = match candidate {
+            Definition::BuiltinType(_) => CompletionItemKind::BuiltinType,
+            _ => CompletionItemKind::SymbolKind(match candidate {
+                Definition::Module(_) => SymbolKind::Module,
+                Definition::Function(_) => SymbolKind::Function,
+                Definition::Adt(hir::Adt::Struct(_)) => SymbolKind::Struct,
+                Definition::Adt(hir::Adt::Union(_)) => SymbolKind::Union,
+                Definition::Adt(hir::Adt::Enum(_)) => SymbolKind::Enum,
+                Definition::Variant(_) => SymbolKind::Variant,
+                Definition::Const(_) => SymbolKind::Const,
+                Definition::Static(_) => SymbolKind::Static,
+                Definition::Trait(_) => SymbolKind::Trait,
+                Definition::TypeAlias(_) => SymbolKind::TypeAlias,
+                Definition::Field(_) => SymbolKind::Field,
+                Definition::Macro(it) => SymbolKind::from(it.kind(ctx.db)),
+                _ => continue,
+            }),
+        };
+        CompletionItem::new(kind, range, name.to_smol_str()).add_to(acc);
+    }
+    Some(())
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::{expect, Expect};
+
+    use crate::tests::{check_edit, completion_list};
+
+    fn check(ra_fixture: &str, expect: Expect) {
+        let actual = completion_list(ra_fixture);
+        expect.assert_eq(&actual);
+    }
+
+    #[test]
+    fn completes_items_in_scope() {
+        check(
+            r#"
+struct Foo;
+fn bar() {}
+macro_rules! baz { () => {} }
+/// See [$0
+fn documented() {
+    let local = 0;
+}
+"#,
+            expect![[r#"
+                fn bar
+                fn documented
+                ma baz
+                st Foo
+                bt u32
+            "#]],
+        );
+    }
+
+    #[test]
+    fn completes_with_disambiguator() {
+        check(
+            r#"
+struct Foo;
+fn bar() {}
+macro_rules! baz { () => {} }
+/// See [`fn@$0
+fn documented() {}
+"#,
+            expect![[r#"
+                fn bar
+                fn documented
+            "#]],
+        );
+        check(
+            r#"
+struct Foo;
+fn bar() {}
+macro_rules! baz { () => {} }
+/// See [macro@$0
+fn documented() {}
+"#,
+            expect![[r#"
+                ma baz
+            "#]],
+        );
+    }
+
+    #[test]
+    fn completes_members_of_types() {
+        check(
+            r#"
+struct Foo { field: u32 }
+impl Foo {
+    fn method(&self) {}
+    const CONST: u32 = 0;
+}
+enum Bar { Variant }
+/// [Foo::$0]
+fn documented() {}
+"#,
+            expect![[r#"
+                ct CONST
+                fd field
+                fn method
+            "#]],
+        );
+        check_edit(
+            "Variant",
+            r#"
+enum Bar { Variant }
+/// [Bar::Va$0]
+fn documented() {}
+"#,
+            r#"
+enum Bar { Variant }
+/// [Bar::Variant]
+fn documented() {}
+"#,
+        );
+    }
+
+    #[test]
+    fn completes_module_items() {
+        check(
+            r#"
+//! The crate, see [foo::$0].
+mod foo {
+    pub struct Public;
+    struct Private;
+}
+"#,
+            expect![[r#"
+                st Private
+                st Public
+            "#]],
+        );
+    }
+
+    #[test]
+    fn no_completions_outside_of_links() {
+        check(
+            r#"
+/// See arr[$0
+fn documented() {}
+"#,
+            expect![[r#""#]],
+        );
+        check(
+            r#"
+// [$0
+fn documented() {}
+"#,
+            expect![[r#""#]],
+        );
+    }
+}
@@ -1,12 +1,17 @@
 //! Completion of names from the current scope in expression position.
 
-use hir::ScopeDef;
+use hir::{
+    term_search::{term_search, Expr, TermSearchConfig},
+    HirDisplay, ScopeDef,
+};
+use itertools::Itertools;
 use syntax::ast;
 
 use crate::{
     completions::record::add_default_update,
     context::{ExprCtx, PathCompletionCtx, Qualified},
-    CompletionContext, Completions,
+    item::CompletionRelevanceTypeMatch,
+    CompletionContext, CompletionItem, CompletionItemKind, CompletionRelevance, Completions,
 };
 
 pub(crate) fn complete_expr_path(
@@ -298,3 +303,44 @@ pub(crate) fn complete_expr_path(
         }
     }
 }
+
+/// Completes whole expressions of the expected type, found by a type-directed search.
+pub(crate) fn complete_expr_term_search(
+    acc: &mut Completions,
+    ctx: &CompletionContext<'_>,
+    path_ctx: &PathCompletionCtx,
+) {
+    let _p = profile::span("complete_expr_term_search");
+    if !ctx.config.enable_term_search || !matches!(path_ctx.qualified, Qualified::No) {
+        return;
+    }
+    let goal = match &ctx.expected_type {
+        Some(ty) if !ty.is_unit() && !ty.is_unknown() => ty,
+        _ => return,
+    };
+
+    term_search(&ctx.scope, goal, TermSearchConfig::default())
+        .into_iter()
+        // Plain names and unit constructors are completed from the scope already.
+        .filter(|expr| match expr {
+            Expr::Local(_) | Expr::Const(_) | Expr::Static(_) => false,
+            Expr::Struct { params, .. } | Expr::Variant { params, .. } => !params.is_empty(),
+            _ => true,
+        })
+        .filter_map(|expr| Some((expr.gen_source_code(&ctx.scope)?, expr.ty(ctx.db))))
+        .unique_by(|(code, _)| code.clone())
+        .for_each(|(code, ty)| {
+            let type_match = if ty == *goal {
+                CompletionRelevanceTypeMatch::Exact
+            } else {
+                CompletionRelevanceTypeMatch::CouldUnify
+            };
+            let mut item =
+                CompletionItem::new(CompletionItemKind::Expression, ctx.source_range(), code);
+            item.detail(ty.display(ctx.db).to_string()).set_relevance(CompletionRelevance {
+                type_match: Some(type_match),
+                ..Default::default()
+            });
+            item.add_to(acc);
+        });
+}
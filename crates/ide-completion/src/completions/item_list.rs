@@ -5,6 +5,7 @@ use crate::{
     CompletionContext, Completions,
 };
 
+pub(crate) mod impl_skeleton;
 pub(crate) mod trait_impl;
 
 pub(crate) fn complete_item_list_in_expr(
@@ -0,0 +1,502 @@
+//! Completion of whole `impl Trait for Type` blocks.
+//!
+//! When typing the self type of an `impl`, this offers complete trait
+//! implementations for the local types of the crate, with the missing items
+//! already filled in:
+//!
+//! - traits a type is required to implement, because it is passed to a
+//!   function whose parameter is bounded by them;
+//! - commonly implemented standard library traits the type doesn't implement yet.
+//!
+//! ```ignore
+//! struct Foo { bar: u32 }
+//!
+//! impl Foo$0
+//! ```
+//!
+//! may result in
+//!
+//! ```ignore
+//! struct Foo { bar: u32 }
+//!
+//! impl Clone for Foo {
+//!     fn clone(&self) -> Self {
+//!         Self { bar: self.bar.clone() }
+//!     }
+//! }
+//! ```
+
+use hir::{HasSource, Semantics};
+use ide_db::{
+    helpers::mod_path_to_ast,
+    path_transform::PathTransform,
+    syntax_helpers::{gen_trait_fn_body::gen_trait_fn_body, insert_whitespace_into_node},
+    FxHashSet, RootDatabase, SymbolKind,
+};
+use syntax::{
+    ast::{
+        self,
+        edit::{AstNodeEdit, IndentLevel},
+        edit_in_place::AttrsOwnerEdit,
+        make, HasArgList, HasGenericParams, HasTypeBounds,
+    },
+    ted, AstNode, SyntaxKind, TextRange,
+};
+use text_edit::TextEdit;
+
+use crate::{
+    context::PathCompletionCtx, CompletionContext, CompletionItem, CompletionItemKind,
+    CompletionRelevance, Completions,
+};
+
+pub(crate) fn complete_impl_skeleton(
+    acc: &mut Completions,
+    ctx: &CompletionContext<'_>,
+    path_ctx: &PathCompletionCtx,
+) -> Option<()> {
+    if !path_ctx.is_trivial_path() {
+        return None;
+    }
+    let token = &ctx.original_token;
+    let impl_ =
+        if token.kind() == SyntaxKind::WHITESPACE { token.prev_token()? } else { token.clone() }
+            .parent_ancestors()
+            .find_map(ast::Impl::cast)?;
+    if impl_.for_token().is_some()
+        || impl_.assoc_item_list().is_some()
+        || impl_.generic_param_list().is_some()
+    {
+        return None;
+    }
+    let replacement_range =
+        TextRange::new(impl_.syntax().text_range().start(), ctx.source_range().end());
+    let indent = IndentLevel::from_node(impl_.syntax());
+
+    // Skeletons are only built for the types and traits matching what was typed so far.
+    let typed = match token.kind() {
+        SyntaxKind::IDENT => token.text().to_lowercase(),
+        _ => String::new(),
+    };
+    let is_wanted = |adt: hir::Adt, trait_: hir::Trait| {
+        [adt.name(ctx.db), trait_.name(ctx.db)]
+            .iter()
+            .any(|name| name.to_smol_str().to_lowercase().starts_with(&typed))
+    };
+
+    let mut seen = FxHashSet::default();
+    for (adt, trait_, required_by) in required_impls(&ctx.sema, ctx.krate, impl_.syntax()) {
+        if is_wanted(adt, trait_) && seen.insert((adt, trait_)) {
+            add_impl_skeleton(acc, ctx, replacement_range, indent, adt, trait_, Some(required_by));
+        }
+    }
+
+    let famous_defs = ctx.famous_defs();
+    let common_traits = [
+        famous_defs.core_fmt_Debug(),
+        famous_defs.core_clone_Clone(),
+        famous_defs.core_default_Default(),
+        famous_defs.core_cmp_PartialEq(),
+        famous_defs.core_cmp_Eq(),
+        famous_defs.core_hash_Hash(),
+        famous_defs.core_cmp_PartialOrd(),
+        famous_defs.core_cmp_Ord(),
+        famous_defs.core_fmt_Display(),
+    ];
+    let local_adts = ctx.module.declarations(ctx.db).into_iter().filter_map(|def| match def {
+        hir::ModuleDef::Adt(adt) if is_implementable(ctx.db, adt) => Some(adt),
+        _ => None,
+    });
+    for adt in local_adts {
+        for &trait_ in common_traits.iter().flatten() {
+            if is_wanted(adt, trait_)
+                && !implements(ctx.db, adt, trait_)
+                && seen.insert((adt, trait_))
+            {
+                add_impl_skeleton(acc, ctx, replacement_range, indent, adt, trait_, None);
+            }
+        }
+    }
+    Some(())
+}
+
+/// Finds the local types that are passed to a function by the items next to
+/// the `impl`, whose parameter is bounded by a trait they don't implement.
+///
+/// Nested modules are skipped, to keep the number of calls that need to be
+/// inferred small.
+fn required_impls(
+    sema: &Semantics<'_, RootDatabase>,
+    krate: hir::Crate,
+    impl_: &syntax::SyntaxNode,
+) -> Vec<(hir::Adt, hir::Trait, hir::Function)> {
+    let db = sema.db;
+    let calls = impl_
+        .parent()
+        .into_iter()
+        .flat_map(|container| container.children())
+        .filter(|item| item.kind() != SyntaxKind::MODULE)
+        .flat_map(|item| item.descendants())
+        .filter_map(|node| call_with_args(sema, node));
+
+    let mut res = Vec::new();
+    for (func, params, args) in calls {
+        for (param, arg) in params.iter().zip(args) {
+            let param_ty = param.ty().remove_ref().unwrap_or_else(|| param.ty().clone());
+            let type_param = match param_ty.as_type_param(db) {
+                Some(it) => it,
+                None => continue,
+            };
+            let adt = match sema
+                .type_of_expr(&arg)
+                .and_then(|it| it.original.strip_references().as_adt())
+            {
+                Some(adt) if adt.module(db).krate() == krate && is_implementable(db, adt) => adt,
+                _ => continue,
+            };
+            for trait_ in type_param.trait_bounds(db) {
+                if trait_.type_or_const_param_count(db, true) == 0 && !implements(db, adt, trait_) {
+                    res.push((adt, trait_, func));
+                }
+            }
+        }
+    }
+    res
+}
+
+fn call_with_args(
+    sema: &Semantics<'_, RootDatabase>,
+    node: syntax::SyntaxNode,
+) -> Option<(hir::Function, Vec<hir::Param>, Vec<ast::Expr>)> {
+    let db = sema.db;
+    if let Some(call) = ast::CallExpr::cast(node.clone()) {
+        let func = match sema.type_of_expr(&call.expr()?)?.original.as_callable(db)?.kind() {
+            hir::CallableKind::Function(it) => it,
+            _ => return None,
+        };
+        return Some((func, func.assoc_fn_params(db), call.arg_list()?.args().collect()));
+    }
+    let call = ast::MethodCallExpr::cast(node)?;
+    let func = sema.resolve_method_call(&call)?;
+    Some((func, func.params_without_self(db), call.arg_list()?.args().collect()))
+}
+
+/// Only non-generic types get skeletons, as we can't guess the bounds of an
+/// `impl` over generic parameters.
+fn is_implementable(db: &RootDatabase, adt: hir::Adt) -> bool {
+    hir::GenericDef::from(adt).params(db).is_empty()
+}
+
+fn implements(db: &RootDatabase, adt: hir::Adt, trait_: hir::Trait) -> bool {
+    let ty = adt.ty(db);
+    // Defaulted parameters like `Rhs` of `PartialEq` default to `Self`.
+    let args = vec![ty.clone(); trait_.type_or_const_param_count(db, false)];
+    ty.impls_trait(db, trait_, &args)
+}
+
+fn add_impl_skeleton(
+    acc: &mut Completions,
+    ctx: &CompletionContext<'_>,
+    replacement_range: TextRange,
+    indent: IndentLevel,
+    adt: hir::Adt,
+    trait_: hir::Trait,
+    required_by: Option<hir::Function>,
+) -> Option<()> {
+    let db = ctx.db;
+    let trait_path = mod_path_to_ast(&ctx.module.find_use_path(db, hir::ModuleDef::from(trait_))?);
+    let adt_path = mod_path_to_ast(&ctx.module.find_use_path(db, hir::ModuleDef::from(adt))?);
+    let adt_source = adt.source(db)?;
+
+    let impl_def = make::impl_trait(trait_path.clone(), adt_path.clone(), None).clone_for_update();
+    let source_scope = ctx.sema.scope_for_def(trait_);
+    let transform = PathTransform::trait_impl(&ctx.scope, &source_scope, trait_, impl_def.clone());
+
+    let assoc_item_list = impl_def.get_or_create_assoc_item_list();
+    for item in missing_items(&ctx.sema, trait_) {
+        transform.apply(item.syntax());
+        item.remove_attrs_and_docs();
+        match &item {
+            ast::AssocItem::Fn(func) => {
+                let body =
+                    make::block_expr(None, Some(make::ext::expr_todo())).indent(IndentLevel(1));
+                ted::replace(func.get_or_create_body().syntax(), body.clone_for_update().syntax());
+                let _ = gen_trait_fn_body(func, &trait_path, &adt_source.value, None);
+            }
+            ast::AssocItem::TypeAlias(type_alias) => {
+                if let Some(type_bound_list) = type_alias.type_bound_list() {
+                    type_bound_list.remove()
+                }
+            }
+            _ => {}
+        }
+        assoc_item_list.add_item(item);
+    }
+    let text = impl_def.indent(indent).to_string();
+
+    let label = format!("impl {} for {}", trait_path, adt_path);
+    let mut item = CompletionItem::new(
+        CompletionItemKind::SymbolKind(SymbolKind::Impl),
+        replacement_range,
+        label,
+    );
+    if let Some(func) = required_by {
+        item.detail(format!("required by {}", func.name(db)));
+        item.set_relevance(CompletionRelevance { is_definite: true, ..Default::default() });
+    }
+    match ctx.config.snippet_cap {
+        Some(cap) => {
+            let snippet = text.replace('\\', "\\\\").replace('$', "\\$");
+            let snippet = match snippet.find("todo!()") {
+                Some(idx) => format!("{}${{0:todo!()}}{}", &snippet[..idx], &snippet[idx + 7..]),
+                None => format!("{}$0", snippet),
+            };
+            item.snippet_edit(cap, TextEdit::replace(replacement_range, snippet));
+        }
+        None => {
+            item.text_edit(TextEdit::replace(replacement_range, text));
+        }
+    };
+    item.add_to(acc);
+    Some(())
+}
+
+/// The items of `trait_` without a default, ready to be inserted into an `impl`.
+fn missing_items(sema: &Semantics<'_, RootDatabase>, trait_: hir::Trait) -> Vec<ast::AssocItem> {
+    trait_
+        .items(sema.db)
+        .into_iter()
+        .filter_map(|item| {
+            let (item, file_id) = match item {
+                hir::AssocItem::Function(it) => {
+                    let source = sema.source(it)?;
+                    if source.value.body().is_some() {
+                        return None;
+                    }
+                    (ast::AssocItem::Fn(source.value), source.file_id)
+                }
+                hir::AssocItem::TypeAlias(it) => {
+                    let source = sema.source(it)?;
+                    (ast::AssocItem::TypeAlias(source.value), source.file_id)
+                }
+                hir::AssocItem::Const(it) => {
+                    let source = sema.source(it)?;
+                    if source.value.body().is_some() {
+                        return None;
+                    }
+                    (ast::AssocItem::Const(source.value), source.file_id)
+                }
+            };
+            if file_id.is_macro() {
+                ast::AssocItem::cast(insert_whitespace_into_node::insert_ws_into(
+                    item.syntax().clone(),
+                ))
+            } else {
+                Some(item.clone_for_update())
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::{expect, Expect};
+    use ide_db::SymbolKind;
+    use itertools::Itertools;
+
+    use crate::{
+        tests::{check_edit, do_completion},
+        CompletionItemKind,
+    };
+
+    fn check(ra_fixture: &str, expect: Expect) {
+        let actual = do_completion(ra_fixture, CompletionItemKind::SymbolKind(SymbolKind::Impl))
+            .into_iter()
+            .map(|it| match it.detail() {
+                Some(detail) => format!("{} ({})\n", it.label(), detail),
+                None => format!("{}\n", it.label()),
+            })
+            .join("");
+        expect.assert_eq(&actual)
+    }
+
+    #[test]
+    fn completes_common_traits() {
+        check(
+            r#"
+//- minicore: clone, fmt, default, eq, ord, hash, derive
+#[derive(Clone, PartialEq)]
+struct Foo;
+struct Generic<T>(T);
+
+impl $0
+"#,
+            expect![[r#"
+                impl Default for Foo
+                impl Eq for Foo
+                impl Ord for Foo
+                impl PartialOrd for Foo
+                impl core::fmt::Debug for Foo
+                impl core::fmt::Display for Foo
+                impl core::hash::Hash for Foo
+            "#]],
+        );
+    }
+
+    #[test]
+    fn completes_traits_required_by_calls() {
+        check(
+            r#"
+trait Speak {
+    fn speak(&self) -> u32;
+}
+fn talk(it: &impl Speak) {}
+fn shout<T: Speak>(it: T) {}
+
+struct Dog;
+
+fn main() {
+    talk(&Dog);
+    shout(Dog);
+}
+
+impl Do$0
+"#,
+            expect![[r#"
+                impl Speak for Dog (required by talk)
+            "#]],
+        );
+    }
+
+    #[test]
+    fn only_completes_what_was_typed() {
+        check(
+            r#"
+//- minicore: clone, fmt, default, eq, ord, hash, derive
+#[derive(Clone, PartialEq)]
+struct Foo;
+struct Bar;
+
+impl Fo$0
+"#,
+            expect![[r#"
+                impl Default for Foo
+                impl Eq for Foo
+                impl Ord for Foo
+                impl PartialOrd for Foo
+                impl core::fmt::Debug for Foo
+                impl core::fmt::Display for Foo
+                impl core::hash::Hash for Foo
+            "#]],
+        );
+        check(
+            r#"
+//- minicore: clone, fmt, default, eq, ord, hash, derive
+#[derive(Clone, PartialEq)]
+struct Foo;
+struct Bar;
+
+impl Def$0
+"#,
+            expect![[r#"
+                impl Default for Bar
+                impl Default for Foo
+            "#]],
+        );
+    }
+
+    #[test]
+    fn skips_calls_in_nested_modules() {
+        check(
+            r#"
+trait Speak {
+    fn speak(&self) -> u32;
+}
+fn talk(it: &impl Speak) {}
+
+struct Dog;
+
+mod m {
+    fn f() { super::talk(&super::Dog); }
+}
+
+impl Do$0
+"#,
+            expect![[""]],
+        );
+    }
+
+    #[test]
+    fn no_skeletons_in_trait_impl() {
+        check(
+            r#"
+//- minicore: clone
+struct Foo;
+impl Clone for $0
+"#,
+            expect![[""]],
+        );
+    }
+
+    #[test]
+    fn fills_in_derivable_bodies() {
+        check_edit(
+            "impl Clone for Foo",
+            r#"
+//- minicore: clone
+struct Foo {
+    bar: u32,
+}
+
+impl Fo$0
+"#,
+            r#"
+struct Foo {
+    bar: u32,
+}
+
+impl Clone for Foo {
+    fn clone(&self) -> Self {
+        Self { bar: self.bar.clone() }
+    }
+}$0
+"#,
+        );
+    }
+
+    #[test]
+    fn leaves_todo_for_other_traits() {
+        check_edit(
+            "impl Speak for Dog",
+            r#"
+trait Speak {
+    fn speak(&self) -> u32;
+    fn twice(&self) -> u32 { self.speak() * 2 }
+}
+fn talk<T: Speak>(it: T) {}
+
+struct Dog;
+
+fn f() { talk(Dog); }
+
+impl $0
+"#,
+            r#"
+trait Speak {
+    fn speak(&self) -> u32;
+    fn twice(&self) -> u32 { self.speak() * 2 }
+}
+fn talk<T: Speak>(it: T) {}
+
+struct Dog;
+
+fn f() { talk(Dog); }
+
+impl Speak for Dog {
+    fn speak(&self) -> u32 {
+        ${0:todo!()}
+    }
+}
+"#,
+        );
+    }
+}
@@ -1,11 +1,17 @@
 //! Completes constants and paths in unqualified patterns.
 
-use hir::{db::DefDatabase, AssocItem, ScopeDef};
-use syntax::ast::Pat;
+use hir::{db::DefDatabase, AssocItem, ScopeDef, StructKind};
+use ide_db::helpers::mod_path_to_ast;
+use itertools::Itertools;
+use stdx::format_to;
+use syntax::{
+    ast::{self, edit::IndentLevel, make, Pat},
+    AstNode,
+};
 
 use crate::{
     context::{PathCompletionCtx, PatternContext, PatternRefutability, Qualified},
-    CompletionContext, Completions,
+    CompletionContext, CompletionItem, CompletionItemKind, Completions,
 };
 
 /// Completes constants and paths in unqualified patterns.
@@ -46,6 +52,21 @@ pub(crate) fn complete_pattern(
     let refutable = pattern_ctx.refutability == PatternRefutability::Refutable;
     let single_variant_enum = |enum_: hir::Enum| ctx.db.enum_data(enum_.into()).variants.len() == 1;
 
+    // In a match arm, only offer the variants that aren't covered by the other arms yet.
+    let missing_patterns =
+        if pattern_ctx.parent_pat.is_none() { missing_match_patterns(ctx) } else { None };
+    if let Some((match_expr, missing)) = &missing_patterns {
+        add_missing_match_arms(acc, ctx, match_expr, missing);
+    }
+    let is_uncovered = |variant: hir::Variant| match &missing_patterns {
+        Some((_, missing)) => missing.iter().any(|pat| match pat {
+            hir::MissingPat::Variant(hir::VariantDef::Variant(it), _) => *it == variant,
+            hir::MissingPat::Wild => true,
+            _ => false,
+        }),
+        None => true,
+    };
+
     if let Some(hir::Adt::Enum(e)) =
         ctx.expected_type.as_ref().and_then(|ty| ty.strip_references().as_adt())
     {
@@ -56,7 +77,9 @@ pub(crate) fn complete_pattern(
                 e,
                 &pattern_ctx.impl_,
                 |acc, ctx, variant, path| {
-                    acc.add_qualified_variant_pat(ctx, pattern_ctx, variant, path);
+                    if is_uncovered(variant) {
+                        acc.add_qualified_variant_pat(ctx, pattern_ctx, variant, path);
+                    }
                 },
             );
         }
@@ -72,7 +95,8 @@ pub(crate) fn complete_pattern(
                     true
                 }
                 hir::ModuleDef::Variant(variant)
-                    if refutable || single_variant_enum(variant.parent_enum(ctx.db)) =>
+                    if (refutable || single_variant_enum(variant.parent_enum(ctx.db)))
+                        && is_uncovered(variant) =>
                 {
                     acc.add_variant_pat(ctx, pattern_ctx, None, variant, Some(name.clone()));
                     true
@@ -105,6 +129,121 @@ pub(crate) fn complete_pattern(
     });
 }
 
+/// If completing the pattern of a match arm, returns the `match` and the patterns
+/// not covered by its other arms.
+fn missing_match_patterns(
+    ctx: &CompletionContext<'_>,
+) -> Option<(ast::MatchExpr, Vec<hir::MissingPat>)> {
+    let token = &ctx.original_token;
+    let arm_list = token.parent_ancestors().find_map(ast::MatchArmList::cast)?;
+    let arm = token
+        .parent_ancestors()
+        .take_while(|it| it != arm_list.syntax())
+        .find_map(ast::MatchArm::cast);
+    if let Some(arm) = &arm {
+        if !arm.pat()?.syntax().text_range().contains_range(token.text_range()) {
+            return None;
+        }
+    }
+    let match_expr = ast::MatchExpr::cast(arm_list.syntax().parent()?)?;
+    let missing = ctx.sema.missing_match_patterns(&match_expr, arm.as_ref())?;
+    Some((match_expr, missing))
+}
+
+/// Whether the pattern being completed already belongs to an arm with a body,
+/// in which case there is no room to fill in more arms.
+fn in_arm_with_body(ctx: &CompletionContext<'_>) -> bool {
+    let token = &ctx.original_token;
+    token
+        .parent_ancestors()
+        .take_while(|it| !ast::MatchArmList::can_cast(it.kind()))
+        .find_map(ast::MatchArm::cast)
+        .map_or(false, |arm| arm.fat_arrow_token().is_some())
+}
+
+/// Adds a snippet filling in an arm for each of the missing patterns at once.
+fn add_missing_match_arms(
+    acc: &mut Completions,
+    ctx: &CompletionContext<'_>,
+    match_expr: &ast::MatchExpr,
+    missing: &[hir::MissingPat],
+) -> Option<()> {
+    if missing.len() < 2 || in_arm_with_body(ctx) {
+        return None;
+    }
+    let cap = ctx.config.snippet_cap?;
+    let indent = IndentLevel::from_node(match_expr.syntax()) + 1;
+    let pats =
+        missing.iter().map(|pat| render_missing_pat(ctx, pat)).collect::<Option<Vec<_>>>()?;
+
+    let body = make::ext::expr_todo();
+    let mut snippet = String::new();
+    for (idx, pat) in pats.iter().enumerate() {
+        if idx > 0 {
+            format_to!(snippet, "\n{}", indent);
+        }
+        format_to!(snippet, "{} => ${{{}:{}}},", pat, idx + 1, body);
+    }
+    snippet.push_str("$0");
+
+    let mut item =
+        CompletionItem::new(CompletionItemKind::Snippet, ctx.source_range(), "fill match arms");
+    item.detail(pats.iter().join(" | ")).insert_snippet(cap, snippet);
+    item.add_to(acc);
+    Some(())
+}
+
+fn render_missing_pat(ctx: &CompletionContext<'_>, pat: &hir::MissingPat) -> Option<String> {
+    let db = ctx.db;
+    let res = match pat {
+        hir::MissingPat::Wild => "_".to_owned(),
+        hir::MissingPat::Bool(it) => it.to_string(),
+        hir::MissingPat::Ref(it) => format!("&{}", render_missing_pat(ctx, it)?),
+        hir::MissingPat::Tuple(fields) => {
+            let fields =
+                fields.iter().map(|it| render_missing_pat(ctx, it)).collect::<Option<Vec<_>>>()?;
+            match &*fields {
+                [field] => format!("({},)", field),
+                _ => format!("({})", fields.join(", ")),
+            }
+        }
+        hir::MissingPat::Variant(variant, fields) => {
+            let (def, kind) = match *variant {
+                hir::VariantDef::Struct(it) => (hir::ModuleDef::from(it), it.kind(db)),
+                hir::VariantDef::Union(it) => (hir::ModuleDef::from(it), StructKind::Record),
+                hir::VariantDef::Variant(it) => (hir::ModuleDef::from(it), it.kind(db)),
+            };
+            let path = mod_path_to_ast(&ctx.module.find_use_path(db, def)?);
+            match kind {
+                StructKind::Unit => path.to_string(),
+                StructKind::Tuple => {
+                    let fields = fields
+                        .iter()
+                        .map(|it| render_missing_pat(ctx, it))
+                        .collect::<Option<Vec<_>>>()?;
+                    format!("{}({})", path, fields.join(", "))
+                }
+                StructKind::Record => {
+                    let mut fields = variant
+                        .fields(db)
+                        .into_iter()
+                        .zip(fields)
+                        .filter(|(_, pat)| **pat != hir::MissingPat::Wild)
+                        .map(|(field, pat)| {
+                            Some(format!("{}: {}", field.name(db), render_missing_pat(ctx, pat)?))
+                        })
+                        .collect::<Option<Vec<_>>>()?;
+                    if fields.len() < variant.fields(db).len() {
+                        fields.push("..".to_owned());
+                    }
+                    format!("{} {{ {} }}", path, fields.join(", "))
+                }
+            }
+        }
+    };
+    Some(res)
+}
+
 pub(crate) fn complete_pattern_path(
     acc: &mut Completions,
     ctx: &CompletionContext<'_>,
@@ -2,7 +2,7 @@
 
 mod format_like;
 
-use hir::{Documentation, HasAttrs};
+use hir::{Documentation, HasAttrs, HirDisplay};
 use ide_db::{imports::insert_use::ImportScope, ty_filter::TryEnum, SnippetCap};
 use syntax::{
     ast::{self, AstNode, AstToken},
@@ -70,7 +70,15 @@ pub(crate) fn complete_postfix(
     }
 
     if !ctx.config.snippets.is_empty() {
-        add_custom_postfix_completions(acc, ctx, &postfix_snippet, &receiver_text);
+        add_custom_postfix_completions(
+            acc,
+            ctx,
+            cap,
+            &postfix_snippet,
+            dot_receiver,
+            receiver_ty,
+            &receiver_text,
+        );
     }
 
     let try_enum = TryEnum::from_ty(&ctx.sema, &receiver_ty.strip_references());
@@ -235,56 +243,98 @@ fn build_postfix_snippet_builder<'ctx>(
 
     // Wrapping impl Fn in an option ruins lifetime inference for the parameters in a way that
     // can't be annotated for the closure, hence fix it by constructing it without the Option first
-    fn build<'ctx>(
-        ctx: &'ctx CompletionContext<'_>,
-        cap: SnippetCap,
-        delete_range: TextRange,
-    ) -> impl Fn(&str, &str, &str) -> Builder + 'ctx {
-        move |label, detail, snippet| {
-            let edit = TextEdit::replace(delete_range, snippet.to_string());
-            let mut item =
-                CompletionItem::new(CompletionItemKind::Snippet, ctx.source_range(), label);
-            item.detail(detail).snippet_edit(cap, edit);
-            let postfix_match = if ctx.original_token.text() == label {
-                cov_mark::hit!(postfix_exact_match_is_high_priority);
-                Some(CompletionRelevancePostfixMatch::Exact)
-            } else {
-                cov_mark::hit!(postfix_inexact_match_is_low_priority);
-                Some(CompletionRelevancePostfixMatch::NonExact)
-            };
-            let relevance = CompletionRelevance { postfix_match, ..Default::default() };
-            item.set_relevance(relevance);
-            item
-        }
+    Some(build_postfix_snippet(ctx, cap, delete_range))
+}
+
+fn build_postfix_snippet<'ctx>(
+    ctx: &'ctx CompletionContext<'_>,
+    cap: SnippetCap,
+    delete_range: TextRange,
+) -> impl Fn(&str, &str, &str) -> Builder + 'ctx {
+    move |label, detail, snippet| {
+        let edit = TextEdit::replace(delete_range, snippet.to_string());
+        let mut item = CompletionItem::new(CompletionItemKind::Snippet, ctx.source_range(), label);
+        item.detail(detail).snippet_edit(cap, edit);
+        let postfix_match = if ctx.original_token.text() == label {
+            cov_mark::hit!(postfix_exact_match_is_high_priority);
+            Some(CompletionRelevancePostfixMatch::Exact)
+        } else {
+            cov_mark::hit!(postfix_inexact_match_is_low_priority);
+            Some(CompletionRelevancePostfixMatch::NonExact)
+        };
+        let relevance = CompletionRelevance { postfix_match, ..Default::default() };
+        item.set_relevance(relevance);
+        item
     }
-    Some(build(ctx, cap, delete_range))
+}
+
+/// Builds the postfix snippet builder for snippets replacing the whole statement `receiver` is
+/// the expression of, returning `None` if the receiver is not in statement position.
+fn build_stmt_postfix_snippet_builder<'ctx>(
+    ctx: &'ctx CompletionContext<'_>,
+    cap: SnippetCap,
+    receiver: &ast::Expr,
+) -> Option<impl Fn(&str, &str, &str) -> Builder + 'ctx> {
+    let parent = receiver.syntax().parent()?.parent()?;
+    if !matches!(parent.kind(), STMT_LIST | EXPR_STMT) {
+        return None;
+    }
+    let receiver_range = ctx.sema.original_range_opt(receiver.syntax())?.range;
+    if ctx.source_range().end() < receiver_range.start() {
+        return None;
+    }
+    // Swallow the rest of the statement, including its semicolon, if there is one.
+    let end = ctx
+        .original_token
+        .parent_ancestors()
+        .find_map(ast::ExprStmt::cast)
+        .filter(|stmt| stmt.syntax().text_range().start() == receiver_range.start())
+        .map_or(ctx.source_range().end(), |stmt| stmt.syntax().text_range().end());
+    let delete_range = TextRange::new(receiver_range.start(), end.max(ctx.source_range().end()));
+    Some(build_postfix_snippet(ctx, cap, delete_range))
 }
 
 fn add_custom_postfix_completions(
     acc: &mut Completions,
     ctx: &CompletionContext<'_>,
+    cap: SnippetCap,
     postfix_snippet: impl Fn(&str, &str, &str) -> Builder,
+    receiver: &ast::Expr,
+    receiver_ty: &hir::Type,
     receiver_text: &str,
 ) -> Option<()> {
     if ImportScope::find_insert_use_container(&ctx.token.parent()?, &ctx.sema).is_none() {
         return None;
     }
-    ctx.config.postfix_snippets().filter(|(_, snip)| snip.scope == SnippetScope::Expr).for_each(
-        |(trigger, snippet)| {
-            let imports = match snippet.imports(ctx) {
-                Some(imports) => imports,
+    let stmt_postfix_snippet = build_stmt_postfix_snippet_builder(ctx, cap, receiver);
+    let receiver_ty_text = receiver_ty
+        .display_source_code(ctx.db, ctx.module.into())
+        .unwrap_or_else(|_| "_".to_owned());
+    ctx.config.postfix_snippets().for_each(|(trigger, snippet)| {
+        let postfix_snippet: &dyn Fn(&str, &str, &str) -> Builder = match snippet.scope {
+            SnippetScope::Expr => &postfix_snippet,
+            SnippetScope::Stmt => match &stmt_postfix_snippet {
+                Some(it) => it,
                 None => return,
-            };
-            let body = snippet.postfix_snippet(receiver_text);
-            let mut builder =
-                postfix_snippet(trigger, snippet.description.as_deref().unwrap_or_default(), &body);
-            builder.documentation(Documentation::new(format!("```rust\n{}\n```", body)));
-            for import in imports.into_iter() {
-                builder.add_import(import);
-            }
-            builder.add_to(acc);
-        },
-    );
+            },
+            SnippetScope::Item | SnippetScope::Type => return,
+        };
+        if !snippet.receiver_matches(ctx, receiver_ty) {
+            return;
+        }
+        let imports = match snippet.imports(ctx) {
+            Some(imports) => imports,
+            None => return,
+        };
+        let body = snippet.postfix_snippet(receiver_text, &receiver_ty_text);
+        let mut builder =
+            postfix_snippet(trigger, snippet.description.as_deref().unwrap_or_default(), &body);
+        builder.documentation(Documentation::new(format!("```rust\n{}\n```", body)));
+        for import in imports.into_iter() {
+            builder.add_import(import);
+        }
+        builder.add_to(acc);
+    });
     None
 }
 
@@ -509,6 +559,7 @@ fn main() {
                 &["ControlFlow::Break(${receiver})".into()],
                 "",
                 &["core::ops::ControlFlow".into()],
+                &[],
                 crate::SnippetScope::Expr,
             )
             .unwrap()],
@@ -573,6 +624,164 @@ fn main() {
         );
     }
 
+    #[test]
+    fn custom_postfix_completion_receiver_constraints() {
+        let config = CompletionConfig {
+            snippets: vec![
+                Snippet::new(
+                    &[],
+                    &["okexpr".into()],
+                    &["${receiver}.unwrap_or_default()".into()],
+                    "",
+                    &[],
+                    &["core::result::Result".into()],
+                    crate::SnippetScope::Expr,
+                )
+                .unwrap(),
+                Snippet::new(
+                    &[],
+                    &["cmp".into()],
+                    &["${receiver} == ${receiver}".into()],
+                    "",
+                    &[],
+                    &["core::cmp::PartialEq".into()],
+                    crate::SnippetScope::Expr,
+                )
+                .unwrap(),
+            ],
+            ..TEST_CONFIG
+        };
+
+        let list = |ra_fixture| {
+            crate::tests::completion_list_with_config(config.clone(), ra_fixture, true, None)
+        };
+        let actual = list(
+            r#"
+//- minicore: result, eq
+struct S;
+fn main() { S.$0 }
+"#,
+        );
+        assert!(!actual.contains("okexpr") && !actual.contains("cmp"), "{}", actual);
+        let actual = list(
+            r#"
+//- minicore: result, eq
+struct S;
+impl PartialEq for S {
+    fn eq(&self, other: &S) -> bool { true }
+}
+fn main() { S.$0 }
+"#,
+        );
+        assert!(!actual.contains("okexpr") && actual.contains("cmp"), "{}", actual);
+        let actual = list(
+            r#"
+//- minicore: result, eq
+fn main() { let r: Result<u32, ()> = Ok(0); (&r).$0 }
+"#,
+        );
+        assert!(actual.contains("okexpr"), "{}", actual);
+    }
+
+    #[test]
+    fn custom_postfix_completion_receiver_type() {
+        check_edit_with_config(
+            CompletionConfig {
+                snippets: vec![Snippet::new(
+                    &[],
+                    &["letty".into()],
+                    &["let ${1:value}: ${receiver_type} = ${receiver};".into()],
+                    "",
+                    &[],
+                    &[],
+                    crate::SnippetScope::Expr,
+                )
+                .unwrap()],
+                ..TEST_CONFIG
+            },
+            "letty",
+            r#"
+//- minicore: option
+fn main() { Some(0u8).$0 }
+"#,
+            r#"
+fn main() { let ${1:value}: Option<u8> = Some(0u8); }
+"#,
+        );
+    }
+
+    #[test]
+    fn custom_postfix_completion_replaces_statement() {
+        let config = CompletionConfig {
+            snippets: vec![Snippet::new(
+                &[],
+                &["letok".into()],
+                &["let Ok(${1:value}) = ${receiver} else {".into(), "    $0".into(), "};".into()],
+                "",
+                &[],
+                &["core::result::Result".into()],
+                crate::SnippetScope::Stmt,
+            )
+            .unwrap()],
+            ..TEST_CONFIG
+        };
+
+        check_edit_with_config(
+            config.clone(),
+            "letok",
+            r#"
+//- minicore: result
+fn foo() -> Result<u32, ()> { Ok(0) }
+fn main() {
+    foo().$0;
+    let _ = 92;
+}
+"#,
+            r#"
+fn foo() -> Result<u32, ()> { Ok(0) }
+fn main() {
+    let Ok(${1:value}) = foo() else {
+    $0
+};
+    let _ = 92;
+}
+"#,
+        );
+        check_edit_with_config(
+            config.clone(),
+            "letok",
+            r#"
+//- minicore: result
+fn foo() -> Result<u32, ()> { Ok(0) }
+fn main() {
+    foo().$0
+}
+"#,
+            r#"
+fn foo() -> Result<u32, ()> { Ok(0) }
+fn main() {
+    let Ok(${1:value}) = foo() else {
+    $0
+};
+}
+"#,
+        );
+
+        let actual = crate::tests::completion_list_with_config(
+            config,
+            r#"
+//- minicore: result
+fn foo() -> Result<u32, ()> { Ok(0) }
+fn main() {
+    let _ = foo().$0;
+}
+"#,
+            true,
+            None,
+        );
+        assert!(!actual.contains("letok"), "{}", actual);
+    }
+
     #[test]
     fn postfix_completion_for_format_like_strings() {
         check_edit(
@@ -169,6 +169,7 @@ mod tests {
                     &["ControlFlow::Break(())".into()],
                     "",
                     &["core::ops::ControlFlow".into()],
+                    &[],
                     crate::SnippetScope::Expr,
                 )
                 .unwrap()],
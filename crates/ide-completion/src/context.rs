@@ -311,6 +311,11 @@ pub(super) enum CompletionAnalysis {
         /// fake token
         expanded: Option<ast::String>,
     },
+    /// The doc comment the cursor is currently inside
+    DocComment {
+        /// original token
+        original: ast::Comment,
+    },
     /// Set if we are currently completing in an unexpanded attribute, this usually implies a builtin attribute like `allow($0)`
     UnexpandedAttrTT {
         colon_prefix: bool,
@@ -318,6 +323,34 @@ pub(super) enum CompletionAnalysis {
     },
 }
 
+impl CompletionAnalysis {
+    /// A short name for the kind of position, used to learn from accepted completions.
+    pub(super) fn kind_name(&self) -> &'static str {
+        match self {
+            CompletionAnalysis::Name(_) => "name",
+            CompletionAnalysis::NameRef(NameRefContext { kind, .. }) => match kind {
+                NameRefKind::Path(PathCompletionCtx { kind, .. }) => match kind {
+                    PathKind::Expr { .. } => "expr",
+                    PathKind::Type { .. } => "type",
+                    PathKind::Attr { .. } | PathKind::Derive { .. } => "attr",
+                    PathKind::Item { .. } => "item",
+                    PathKind::Pat { .. } => "pat",
+                    PathKind::Vis { .. } => "vis",
+                    PathKind::Use => "use",
+                },
+                NameRefKind::DotAccess(_) => "dot",
+                NameRefKind::Keyword(_) => "keyword",
+                NameRefKind::RecordExpr { .. } => "record",
+                NameRefKind::Pattern(_) => "pat",
+            },
+            CompletionAnalysis::Lifetime(_) => "lifetime",
+            CompletionAnalysis::String { .. } => "string",
+            CompletionAnalysis::DocComment { .. } => "doc",
+            CompletionAnalysis::UnexpandedAttrTT { .. } => "attr",
+        }
+    }
+}
+
 /// Information about the field or method access we are completing.
 #[derive(Debug)]
 pub(super) struct DotAccess {
@@ -400,6 +400,10 @@ impl<'a> CompletionContext<'a> {
                             original,
                             expanded: ast::String::cast(self.token.clone()),
                         }
+                    } else if let Some(original) = ast::Comment::cast(self.original_token.clone())
+                        .filter(|it| it.kind().doc.is_some())
+                    {
+                        CompletionAnalysis::DocComment { original }
                     } else {
                         // Fix up trailing whitespace problem
                         // #[attr(foo = $0
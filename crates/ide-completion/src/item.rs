@@ -72,6 +72,9 @@ pub struct CompletionItem {
 
     /// The import data to add to completion's edits.
     import_to_add: SmallVec<[LocatedImport; 1]>,
+
+    /// The kind of position this item was completed at, like `expr` or `type`.
+    context_kind: &'static str,
 }
 
 // We use custom debug for CompletionItem to make snapshot tests more readable.
@@ -151,6 +154,8 @@ pub struct CompletionRelevance {
     pub postfix_match: Option<CompletionRelevancePostfixMatch>,
     /// This is set for type inference results
     pub is_definite: bool,
+    /// How often the user accepted this item at this kind of position before.
+    pub accepted_count: u32,
 }
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
@@ -215,6 +220,7 @@ impl CompletionRelevance {
             is_private_editable,
             postfix_match,
             is_definite,
+            accepted_count,
         } = self;
 
         // lower rank private things
@@ -256,6 +262,8 @@ impl CompletionRelevance {
         if is_definite {
             score += 10;
         }
+        // Items accepted often outweigh an exact name match, but not an exact postfix match.
+        score += accepted_count.min(ACCEPTED_COUNT_CAP) * 2;
         score
     }
 
@@ -267,12 +275,16 @@ impl CompletionRelevance {
     }
 }
 
+/// The number of acceptances after which an item doesn't rank any higher.
+const ACCEPTED_COUNT_CAP: u32 = 5;
+
 /// The type of the completion item.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum CompletionItemKind {
     SymbolKind(SymbolKind),
     Binding,
     BuiltinType,
+    Expression,
     InferredType,
     Keyword,
     Method,
@@ -316,6 +328,7 @@ impl CompletionItemKind {
             },
             CompletionItemKind::Binding => "bn",
             CompletionItemKind::BuiltinType => "bt",
+            CompletionItemKind::Expression => "ex",
             CompletionItemKind::InferredType => "it",
             CompletionItemKind::Keyword => "kw",
             CompletionItemKind::Method => "me",
@@ -409,6 +422,29 @@ impl CompletionItem {
     pub fn imports_to_add(&self) -> &[LocatedImport] {
         &self.import_to_add
     }
+
+    /// The kind of position this item was completed at, like `expr` or `type`.
+    pub fn context_kind(&self) -> &'static str {
+        self.context_kind
+    }
+
+    /// Identifies what this item completes across completion requests: the path of the import it
+    /// adds, or what it is looked up by if it doesn't need one.
+    pub fn item_path(&self) -> String {
+        match self.import_to_add.first() {
+            Some(import) => import.import_path.to_string(),
+            None => self.lookup().to_owned(),
+        }
+    }
+
+    /// Records how often this item was accepted before, to rank it accordingly.
+    pub fn set_accepted_count(&mut self, count: u32) {
+        self.relevance.accepted_count = count;
+    }
+
+    pub(crate) fn set_context_kind(&mut self, kind: &'static str) {
+        self.context_kind = kind;
+    }
 }
 
 /// A helper to make `CompletionItem`s.
@@ -478,6 +514,7 @@ impl Builder {
             relevance: self.relevance,
             ref_match: self.ref_match,
             import_to_add: self.imports_to_add,
+            context_kind: "",
         }
     }
     pub(crate) fn lookup_by(&mut self, lookup: impl Into<SmolStr>) -> &mut Builder {
@@ -614,9 +651,14 @@ mod tests {
             vec![Cr { is_private_editable: true, ..default }],
             vec![default],
             vec![Cr { is_local: true, ..default }],
+            vec![Cr { accepted_count: 1, ..default }],
             vec![Cr { type_match: Some(CompletionRelevanceTypeMatch::CouldUnify), ..default }],
             vec![Cr { type_match: Some(CompletionRelevanceTypeMatch::Exact), ..default }],
-            vec![Cr { exact_name_match: true, ..default }],
+            vec![
+                Cr { exact_name_match: true, ..default },
+                Cr { accepted_count: 5, ..default },
+                Cr { accepted_count: 20, ..default },
+            ],
             vec![Cr { exact_name_match: true, is_local: true, ..default }],
             vec![Cr {
                 exact_name_match: true,
@@ -184,6 +184,9 @@ pub fn completions(
                 completions::extern_abi::complete_extern_abi(acc, ctx, expanded);
                 completions::format_string::format_string(acc, ctx, original, expanded);
             }
+            CompletionAnalysis::DocComment { original } => {
+                completions::doc_link::complete_doc_link(acc, ctx, original);
+            }
             CompletionAnalysis::UnexpandedAttrTT {
                 colon_prefix,
                 fake_attribute_under_caret: Some(attr),
@@ -199,7 +202,10 @@ pub fn completions(
         }
     }
 
-    Some(completions.into())
+    let mut items: Vec<CompletionItem> = completions.into();
+    let kind = analysis.kind_name();
+    items.iter_mut().for_each(|it| it.set_context_kind(kind));
+    Some(items)
 }
 
 /// Resolves additional completion data at the position given.
@@ -745,6 +745,7 @@ fn main() { let _: m::Spam = S$0 }
                             is_private_editable: false,
                             postfix_match: None,
                             is_definite: false,
+                            accepted_count: 0,
                         },
                     },
                     CompletionItem {
@@ -770,6 +771,7 @@ fn main() { let _: m::Spam = S$0 }
                             is_private_editable: false,
                             postfix_match: None,
                             is_definite: false,
+                            accepted_count: 0,
                         },
                     },
                 ]
@@ -847,6 +849,7 @@ fn foo() { A { the$0 } }
                             is_private_editable: false,
                             postfix_match: None,
                             is_definite: false,
+                            accepted_count: 0,
                         },
                     },
                 ]
@@ -42,9 +42,15 @@
 // On failure of resolution the snippet won't be applicable, otherwise the snippet will insert an import for the items on insertion if
 // the items aren't yet in scope.
 //
+// * `receiver` is an optional list of trait or type paths the receiver of a postfix snippet has to implement or be of respectively.
+// Traits with generic parameters are only matched if all of them have defaults, which are taken to be the receiver's type.
+//
 // * `scope` is an optional filter for when the snippet should be applicable. Possible values are:
 // ** for Snippet-Scopes: `expr`, `item` (default: `item`)
-// ** for Postfix-Snippet-Scopes: `expr`, `type` (default: `expr`)
+// ** for Postfix-Snippet-Scopes: `expr`, `type`, `stmt` (default: `expr`)
+//
+// A postfix snippet with the `stmt` scope is only applicable if the receiver makes up an entire expression statement,
+// in which case the whole statement, including its trailing semicolon, is replaced by the snippet.
 //
 // The `body` field also has access to placeholders as visible in the example as `$0`.
 // These placeholders take the form of `$number` or `${number:placeholder_text}` which can be traversed as tabstop in ascending order starting from 1,
@@ -52,6 +58,25 @@
 //
 // There is also a special placeholder, `${receiver}`, which will be replaced by the receiver expression for postfix snippets, or a `$0` tabstop in case of normal snippets.
 // This replacement for normal snippets allows you to reuse a snippet for both post- and prefix in a single definition.
+// Similarly, `${receiver_type}` will be replaced by the inferred type of the receiver for postfix snippets, or `_` in case of normal snippets.
+//
+// As an example, the following postfix snippet turns `foo().letok` into a `let ... else` statement for `Result` receivers:
+// [source,json]
+// ----
+// {
+//   "let Ok else": {
+//     "postfix": "letok",
+//     "body": [
+//       "let Ok(${1:value}) = ${receiver} else {",
+//       "\t$0",
+//       "};"
+//     ],
+//     "receiver": "core::result::Result",
+//     "description": "Destructure a `Result` with `let ... else`",
+//     "scope": "stmt"
+//   }
+// }
+// ----
 //
 // For the VSCode editor, rust-analyzer also ships with a small set of defaults which can be removed
 // by overwriting the settings object mentioned above, the defaults are:
@@ -113,6 +138,8 @@ pub enum SnippetScope {
     Item,
     Expr,
     Type,
+    /// A postfix snippet replacing the whole statement its receiver is the expression of.
+    Stmt,
 }
 
 /// A user supplied snippet.
@@ -127,6 +154,9 @@ pub struct Snippet {
     // and reconstruct them on demand instead. This is cheaper than reparsing them
     // from strings
     requires: Box<[GreenNode]>,
+    /// The traits the receiver of a postfix snippet has to implement, or the
+    /// types it has to be of.
+    receiver: Box<[GreenNode]>,
 }
 
 impl Snippet {
@@ -136,12 +166,14 @@ impl Snippet {
         snippet: &[String],
         description: &str,
         requires: &[String],
+        receiver: &[String],
         scope: SnippetScope,
     ) -> Option<Self> {
         if prefix_triggers.is_empty() && postfix_triggers.is_empty() {
             return None;
         }
         let (requires, snippet, description) = validate_snippet(snippet, description, requires)?;
+        let receiver = receiver.iter().map(|it| parse_path(it)).collect::<Option<_>>()?;
         Some(Snippet {
             // Box::into doesn't work as that has a Copy bound 😒
             postfix_triggers: postfix_triggers.iter().map(String::as_str).map(Into::into).collect(),
@@ -150,6 +182,7 @@ impl Snippet {
             snippet,
             description,
             requires,
+            receiver,
         })
     }
 
@@ -158,12 +191,40 @@ impl Snippet {
         import_edits(ctx, &self.requires)
     }
 
+    /// Returns whether a postfix snippet applies to a receiver of type `ty`.
+    pub(crate) fn receiver_matches(&self, ctx: &CompletionContext<'_>, ty: &hir::Type) -> bool {
+        self.receiver.iter().all(|path| {
+            let path = match ast::Path::cast(SyntaxNode::new_root(path.clone())) {
+                Some(it) => it,
+                None => return false,
+            };
+            let ty_without_refs = ty.strip_references();
+            match ctx.scope.speculative_resolve(&path) {
+                Some(hir::PathResolution::Def(hir::ModuleDef::Trait(trait_))) => {
+                    if trait_.type_or_const_param_count(ctx.db, true) != 0 {
+                        return false;
+                    }
+                    // Defaulted parameters like `Rhs` of `PartialEq` usually default to `Self`.
+                    let args = vec![ty.clone(); trait_.type_or_const_param_count(ctx.db, false)];
+                    ty.impls_trait(ctx.db, trait_, &args)
+                }
+                Some(hir::PathResolution::Def(hir::ModuleDef::Adt(adt))) => {
+                    ty_without_refs.as_adt() == Some(adt)
+                }
+                Some(hir::PathResolution::Def(hir::ModuleDef::BuiltinType(builtin))) => {
+                    ty_without_refs.as_builtin() == Some(builtin)
+                }
+                _ => false,
+            }
+        })
+    }
+
     pub fn snippet(&self) -> String {
-        self.snippet.replace("${receiver}", "$0")
+        self.snippet.replace("${receiver}", "$0").replace("${receiver_type}", "_")
     }
 
-    pub fn postfix_snippet(&self, receiver: &str) -> String {
-        self.snippet.replace("${receiver}", receiver)
+    pub fn postfix_snippet(&self, receiver: &str, receiver_type: &str) -> String {
+        self.snippet.replace("${receiver}", receiver).replace("${receiver_type}", receiver_type)
     }
 }
 
@@ -195,15 +256,7 @@ fn validate_snippet(
 ) -> Option<(Box<[GreenNode]>, String, Option<Box<str>>)> {
     let mut imports = Vec::with_capacity(requires.len());
     for path in requires.iter() {
-        let use_path = ast::SourceFile::parse(&format!("use {};", path))
-            .syntax_node()
-            .descendants()
-            .find_map(ast::Path::cast)?;
-        if use_path.syntax().text() != path.as_str() {
-            return None;
-        }
-        let green = use_path.syntax().green().into_owned();
-        imports.push(green);
+        imports.push(parse_path(path)?);
     }
     let snippet = snippet.iter().join("\n");
     let description = (!description.is_empty())
@@ -212,3 +265,14 @@ fn validate_snippet(
         .map(Into::into);
     Some((imports.into_boxed_slice(), snippet, description))
 }
+
+fn parse_path(path: &str) -> Option<GreenNode> {
+    let use_path = ast::SourceFile::parse(&format!("use {};", path))
+        .syntax_node()
+        .descendants()
+        .find_map(ast::Path::cast)?;
+    if use_path.syntax().text() != path {
+        return None;
+    }
+    Some(use_path.syntax().green().into_owned())
+}
@@ -1,7 +1,10 @@
 //! Completion tests for expressions.
 use expect_test::{expect, Expect};
 
-use crate::tests::{completion_list, BASE_ITEMS_FIXTURE};
+use crate::{
+    tests::{completion_list, get_all_items, BASE_ITEMS_FIXTURE, TEST_CONFIG},
+    CompletionConfig, CompletionItemKind,
+};
 
 fn check(ra_fixture: &str, expect: Expect) {
     let actual = completion_list(&format!("{}{}", BASE_ITEMS_FIXTURE, ra_fixture));
@@ -670,3 +673,47 @@ fn main() {
         "#]],
     );
 }
+
+fn check_term_search(ra_fixture: &str, expect: Expect) {
+    let config = CompletionConfig { enable_term_search: true, ..TEST_CONFIG };
+    let actual = get_all_items(config, ra_fixture, None)
+        .into_iter()
+        .filter(|it| it.kind() == CompletionItemKind::Expression)
+        .map(|it| format!("{} {:?}\n", it.label(), it.relevance().type_match))
+        .collect::<String>();
+    expect.assert_eq(&actual);
+}
+
+#[test]
+fn complete_term_search_expressions() {
+    check_term_search(
+        r#"
+struct Port(u16);
+struct Config { port: Port, verbose: bool }
+impl Config {
+    fn new(port: Port) -> Config { Config { port, verbose: false } }
+}
+fn port_of(config: &Config) -> u16 { 0 }
+fn main(port: u16, verbose: bool) {
+    let config: Config = $0;
+}
+"#,
+        expect![[r#"
+            Config::new(Port(port)) Some(Exact)
+            Config { port: Port(port), verbose } Some(Exact)
+        "#]],
+    );
+}
+
+#[test]
+fn term_search_is_disabled_by_default() {
+    let actual = completion_list(
+        r#"
+struct Port(u16);
+fn main(port: u16) {
+    let p: Port = $0;
+}
+"#,
+    );
+    assert!(!actual.contains("Port(port)"));
+}
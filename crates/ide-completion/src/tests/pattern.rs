@@ -1,5 +1,6 @@
 //! Completion tests for pattern position.
 use expect_test::{expect, Expect};
+use syntax::ast::make;
 
 use crate::tests::{check_edit, completion_list, BASE_ITEMS_FIXTURE};
 
@@ -13,6 +14,12 @@ fn check(ra_fixture: &str, expect: Expect) {
     expect.assert_eq(&actual)
 }
 
+/// Checks the "fill match arms" snippet, with `HOLE` standing for the arm bodies.
+fn check_fill_match_arms(ra_fixture_before: &str, ra_fixture_after: &str) {
+    let after = ra_fixture_after.replace("HOLE", &make::ext::expr_todo().to_string());
+    check_edit("fill match arms", ra_fixture_before, &after);
+}
+
 #[test]
 fn wildcard() {
     check(
@@ -516,6 +523,7 @@ fn foo() {
             bn Enum::type       Enum::r#type$0
             kw mut
             kw ref
+            sn fill match arms  Enum::A | Enum::B { .. } | Enum::r#type | Enum::r#struct { .. }
         "#]],
     );
 
@@ -714,3 +722,127 @@ impl Ty {
         "#]],
     );
 }
+
+#[test]
+fn match_arm_variants_skip_covered_ones() {
+    check_empty(
+        r#"
+enum Msg {
+    Quit,
+    Move { x: i32, y: i32 },
+    Write(u32),
+}
+fn foo(msg: Msg) {
+    match msg {
+        Msg::Quit => (),
+        W$0
+    }
+}
+"#,
+        expect![[r#"
+            en Msg
+            bn Msg::Move {…}   Msg::Move { x$1, y$2 }$0
+            bn Msg::Write(…)   Msg::Write($1)$0
+            kw mut
+            kw ref
+            sn fill match arms Msg::Move { .. } | Msg::Write(_)
+        "#]],
+    );
+    check_empty(
+        r#"
+enum Msg {
+    Quit,
+    Move { x: i32, y: i32 },
+}
+fn foo(msg: Msg) {
+    match msg {
+        Msg::Move { .. } => (),
+        $0
+    }
+}
+"#,
+        expect![[r#"
+            en Msg
+            bn Msg::Quit Msg::Quit$0
+            kw mut
+            kw ref
+        "#]],
+    );
+}
+
+#[test]
+fn match_arm_no_fill_in_arm_with_body() {
+    check_empty(
+        r#"
+enum Msg {
+    Quit,
+    Move { x: i32, y: i32 },
+    Write(u32),
+}
+fn foo(msg: Msg) {
+    match msg {
+        Msg::Quit => (),
+        W$0 => (),
+    }
+}
+"#,
+        expect![[r#"
+            en Msg
+            bn Msg::Move {…} Msg::Move { x$1, y$2 }$0
+            bn Msg::Write(…) Msg::Write($1)$0
+            kw mut
+            kw ref
+        "#]],
+    );
+}
+
+#[test]
+fn match_arm_fill_in_nested_patterns() {
+    check_fill_match_arms(
+        r#"
+//- minicore: option
+enum Dir {
+    Left,
+    Right,
+}
+fn foo(dir: Option<Dir>) {
+    match dir {
+        Some(Dir::Left) => (),
+        $0
+    }
+}
+"#,
+        r#"
+enum Dir {
+    Left,
+    Right,
+}
+fn foo(dir: Option<Dir>) {
+    match dir {
+        Some(Dir::Left) => (),
+        None => ${1:HOLE},
+        Some(Dir::Right) => ${2:HOLE},$0
+    }
+}
+"#,
+    );
+    check_fill_match_arms(
+        r#"
+fn foo(pair: (bool, bool)) {
+    match pair {
+        (true, true) => (),
+        $0
+    }
+}
+"#,
+        r#"
+fn foo(pair: (bool, bool)) {
+    match pair {
+        (true, true) => (),
+        (false, _) => ${1:HOLE},
+        (true, false) => ${2:HOLE},$0
+    }
+}
+"#,
+    );
+}
@@ -0,0 +1,220 @@
+//! Extraction and resolution of intra-doc links in documentation.
+
+mod intra_doc_links;
+
+use hir::{db::HirDatabase, HasAttrs, Namespace, ScopeDef, Semantics, SemanticsScope};
+use pulldown_cmark::{BrokenLink, CowStr, Event, Options, Parser, Tag};
+use syntax::{ast, match_ast, AstNode, SyntaxNode, TextRange};
+
+use crate::{defs::Definition, RootDatabase};
+
+pub use self::intra_doc_links::{parse_intra_doc_link, strip_prefixes_suffixes};
+
+pub const MARKDOWN_OPTIONS: Options =
+    Options::ENABLE_FOOTNOTES.union(Options::ENABLE_TABLES).union(Options::ENABLE_TASKLISTS);
+
+pub fn broken_link_clone_cb<'a>(link: BrokenLink<'a>) -> Option<(CowStr<'a>, CowStr<'a>)> {
+    Some((/*url*/ link.reference.clone(), /*title*/ link.reference))
+}
+
+/// Extracts all links from a given markdown text returning the definition text range, link-text
+/// and the namespace if known.
+pub fn extract_definitions_from_docs(
+    docs: &hir::Documentation,
+) -> Vec<(TextRange, String, Option<Namespace>)> {
+    Parser::new_with_broken_link_callback(
+        docs.as_str(),
+        MARKDOWN_OPTIONS,
+        Some(&mut broken_link_clone_cb),
+    )
+    .into_offset_iter()
+    .filter_map(|(event, range)| match event {
+        Event::Start(Tag::Link(_, target, _)) => {
+            let (link, ns) = parse_intra_doc_link(&target);
+            Some((
+                TextRange::new(range.start.try_into().ok()?, range.end.try_into().ok()?),
+                link.to_string(),
+                ns,
+            ))
+        }
+        _ => None,
+    })
+    .collect()
+}
+
+/// Resolves the intra-doc `link` in the documentation of `def`.
+///
+/// On top of what the compiler resolves, links to inherent associated items and fields like
+/// `Type::method` are resolved as well.
+pub fn resolve_doc_path_for_def(
+    db: &dyn HirDatabase,
+    def: Definition,
+    link: &str,
+    ns: Option<Namespace>,
+) -> Option<Definition> {
+    let resolved = match def {
+        Definition::Module(it) => it.resolve_doc_path(db, link, ns),
+        Definition::Function(it) => it.resolve_doc_path(db, link, ns),
+        Definition::Adt(it) => it.resolve_doc_path(db, link, ns),
+        Definition::Variant(it) => it.resolve_doc_path(db, link, ns),
+        Definition::Const(it) => it.resolve_doc_path(db, link, ns),
+        Definition::Static(it) => it.resolve_doc_path(db, link, ns),
+        Definition::Trait(it) => it.resolve_doc_path(db, link, ns),
+        Definition::TypeAlias(it) => it.resolve_doc_path(db, link, ns),
+        Definition::Macro(it) => it.resolve_doc_path(db, link, ns),
+        Definition::Field(it) => it.resolve_doc_path(db, link, ns),
+        Definition::SelfType(it) => it.resolve_doc_path(db, link, ns),
+        Definition::BuiltinAttr(_)
+        | Definition::ToolModule(_)
+        | Definition::BuiltinType(_)
+        | Definition::Local(_)
+        | Definition::GenericParam(_)
+        | Definition::Label(_)
+        | Definition::DeriveHelper(_) => return None,
+    };
+    match resolved {
+        Some(it) => Some(it.into()),
+        None => {
+            let (qualifier, name) = link.rsplit_once("::")?;
+            let qualifier = resolve_doc_path_for_def(db, def, qualifier, Some(Namespace::Types))?;
+            members(db, qualifier)
+                .into_iter()
+                .find(|(it, def)| it.to_smol_str() == name && matches_namespace(*def, ns))
+                .map(|(_, def)| def)
+        }
+    }
+}
+
+/// Lists the items an intra-doc link in the documentation of `def` may refer to in its last
+/// segment, given the already written `qualifier` of the link and its namespace disambiguator.
+pub fn doc_link_candidates(
+    db: &RootDatabase,
+    scope: &SemanticsScope<'_>,
+    def: Definition,
+    qualifier: Option<&str>,
+    ns: Option<Namespace>,
+) -> Vec<(hir::Name, Definition)> {
+    let mut candidates = match qualifier {
+        Some(qualifier) => {
+            match resolve_doc_path_for_def(db, def, qualifier, Some(Namespace::Types)) {
+                Some(it) => members(db, it),
+                None => return Vec::new(),
+            }
+        }
+        None => {
+            let mut candidates = Vec::new();
+            scope.process_all_names(&mut |name, scope_def| {
+                let candidate = match scope_def {
+                    ScopeDef::ModuleDef(it) => Definition::from(it),
+                    _ => return,
+                };
+                // Names visible from the documented item's scope aren't necessarily visible to
+                // rustdoc, think of function locals, so double check.
+                let link = name.to_smol_str();
+                if resolve_doc_path_for_def(db, def, &link, None) == Some(candidate)
+                    || resolve_doc_path_for_def(db, def, &link, Some(Namespace::Macros))
+                        == Some(candidate)
+                {
+                    candidates.push((name, candidate));
+                }
+            });
+            candidates
+        }
+    };
+    candidates.retain(|&(_, it)| matches_namespace(it, ns));
+    candidates
+}
+
+/// Returns the attributes and the definition of the item `node` documents.
+pub fn doc_attributes(
+    sema: &Semantics<'_, RootDatabase>,
+    node: &SyntaxNode,
+) -> Option<(hir::AttrsWithOwner, Definition)> {
+    match_ast! {
+        match node {
+            ast::SourceFile(it)  => sema.to_def(&it).map(|def| (def.attrs(sema.db), Definition::Module(def))),
+            ast::Module(it)      => sema.to_def(&it).map(|def| (def.attrs(sema.db), Definition::Module(def))),
+            ast::Fn(it)          => sema.to_def(&it).map(|def| (def.attrs(sema.db), Definition::Function(def))),
+            ast::Struct(it)      => sema.to_def(&it).map(|def| (def.attrs(sema.db), Definition::Adt(hir::Adt::Struct(def)))),
+            ast::Union(it)       => sema.to_def(&it).map(|def| (def.attrs(sema.db), Definition::Adt(hir::Adt::Union(def)))),
+            ast::Enum(it)        => sema.to_def(&it).map(|def| (def.attrs(sema.db), Definition::Adt(hir::Adt::Enum(def)))),
+            ast::Variant(it)     => sema.to_def(&it).map(|def| (def.attrs(sema.db), Definition::Variant(def))),
+            ast::Trait(it)       => sema.to_def(&it).map(|def| (def.attrs(sema.db), Definition::Trait(def))),
+            ast::Static(it)      => sema.to_def(&it).map(|def| (def.attrs(sema.db), Definition::Static(def))),
+            ast::Const(it)       => sema.to_def(&it).map(|def| (def.attrs(sema.db), Definition::Const(def))),
+            ast::TypeAlias(it)   => sema.to_def(&it).map(|def| (def.attrs(sema.db), Definition::TypeAlias(def))),
+            ast::Impl(it)        => sema.to_def(&it).map(|def| (def.attrs(sema.db), Definition::SelfType(def))),
+            ast::RecordField(it) => sema.to_def(&it).map(|def| (def.attrs(sema.db), Definition::Field(def))),
+            ast::TupleField(it)  => sema.to_def(&it).map(|def| (def.attrs(sema.db), Definition::Field(def))),
+            ast::Macro(it)       => sema.to_def(&it).map(|def| (def.attrs(sema.db), Definition::Macro(def))),
+            _ => None
+        }
+    }
+}
+
+/// Returns whether a link disambiguated with `ns` may refer to `def`.
+pub fn matches_namespace(def: Definition, ns: Option<Namespace>) -> bool {
+    let ns = match ns {
+        Some(it) => it,
+        None => return true,
+    };
+    match def {
+        Definition::Macro(_) => ns == Namespace::Macros,
+        Definition::Function(_)
+        | Definition::Const(_)
+        | Definition::Static(_)
+        | Definition::Field(_) => ns == Namespace::Values,
+        Definition::Variant(_) => ns != Namespace::Macros,
+        _ => ns == Namespace::Types,
+    }
+}
+
+/// The items nested in `def` a link can refer to via a `def::item` path.
+fn members(db: &dyn HirDatabase, def: Definition) -> Vec<(hir::Name, Definition)> {
+    let mut res = Vec::new();
+    let ty = match def {
+        Definition::Module(module) => {
+            return module
+                .scope(db, None)
+                .into_iter()
+                .filter_map(|(name, def)| match def {
+                    ScopeDef::ModuleDef(it) => Some((name, it.into())),
+                    _ => None,
+                })
+                .collect();
+        }
+        Definition::Trait(trait_) => {
+            return trait_
+                .items(db)
+                .into_iter()
+                .filter_map(|item| Some((item.name(db)?, item.into())))
+                .collect();
+        }
+        Definition::Adt(adt) => {
+            match adt {
+                hir::Adt::Struct(it) => res.extend(field_members(db, it.fields(db))),
+                hir::Adt::Union(it) => res.extend(field_members(db, it.fields(db))),
+                hir::Adt::Enum(it) => res.extend(
+                    it.variants(db).into_iter().map(|it| (it.name(db), Definition::Variant(it))),
+                ),
+            }
+            adt.ty(db)
+        }
+        Definition::BuiltinType(it) => it.ty(db),
+        _ => return res,
+    };
+    let inherent_items = hir::Impl::all_for_type(db, ty)
+        .into_iter()
+        .filter(|it| it.trait_(db).is_none())
+        .flat_map(|it| it.items(db))
+        .filter_map(|item| Some((item.name(db)?, item.into())));
+    res.extend(inherent_items);
+    res
+}
+
+fn field_members(
+    db: &dyn HirDatabase,
+    fields: Vec<hir::Field>,
+) -> impl Iterator<Item = (hir::Name, Definition)> + '_ {
+    fields.into_iter().map(move |it| (it.name(db), Definition::Field(it)))
+}
@@ -42,10 +42,26 @@ impl FamousDefs<'_, '_> {
         self.find_lang_crate(LangCrateOrigin::ProcMacro)
     }
 
+    pub fn core_clone_Clone(&self) -> Option<Trait> {
+        self.find_trait("core:clone:Clone")
+    }
+
+    pub fn core_cmp_Eq(&self) -> Option<Trait> {
+        self.find_trait("core:cmp:Eq")
+    }
+
     pub fn core_cmp_Ord(&self) -> Option<Trait> {
         self.find_trait("core:cmp:Ord")
     }
 
+    pub fn core_cmp_PartialEq(&self) -> Option<Trait> {
+        self.find_trait("core:cmp:PartialEq")
+    }
+
+    pub fn core_cmp_PartialOrd(&self) -> Option<Trait> {
+        self.find_trait("core:cmp:PartialOrd")
+    }
+
     pub fn core_convert_From(&self) -> Option<Trait> {
         self.find_trait("core:convert:From")
     }
@@ -54,6 +70,10 @@ impl FamousDefs<'_, '_> {
         self.find_trait("core:convert:Into")
     }
 
+    pub fn core_convert_TryFrom(&self) -> Option<Trait> {
+        self.find_trait("core:convert:TryFrom")
+    }
+
     pub fn core_option_Option(&self) -> Option<Enum> {
         self.find_enum("core:option:Option")
     }
@@ -66,6 +86,18 @@ impl FamousDefs<'_, '_> {
         self.find_trait("core:default:Default")
     }
 
+    pub fn core_fmt_Debug(&self) -> Option<Trait> {
+        self.find_trait("core:fmt:Debug")
+    }
+
+    pub fn core_fmt_Display(&self) -> Option<Trait> {
+        self.find_trait("core:fmt:Display")
+    }
+
+    pub fn core_hash_Hash(&self) -> Option<Trait> {
+        self.find_trait("core:hash:Hash")
+    }
+
     pub fn core_iter_Iterator(&self) -> Option<Trait> {
         self.find_trait("core:iter:traits:iterator:Iterator")
     }
@@ -118,10 +150,11 @@ impl FamousDefs<'_, '_> {
     }
 
     fn find_trait(&self, path: &str) -> Option<Trait> {
-        match self.find_def(path)? {
+        // Derivable traits share their name with the derive macro, so look at all definitions.
+        self.find_defs(path)?.into_iter().find_map(|def| match def {
             hir::ScopeDef::ModuleDef(hir::ModuleDef::Trait(it)) => Some(it),
             _ => None,
-        }
+        })
     }
 
     fn find_macro(&self, path: &str) -> Option<Macro> {
@@ -158,6 +191,10 @@ impl FamousDefs<'_, '_> {
     }
 
     fn find_def(&self, path: &str) -> Option<ScopeDef> {
+        self.find_defs(path)?.into_iter().next()
+    }
+
+    fn find_defs(&self, path: &str) -> Option<Vec<ScopeDef>> {
         let db = self.0.db;
         let mut path = path.split(':');
         let trait_ = path.next_back()?;
@@ -178,8 +215,12 @@ impl FamousDefs<'_, '_> {
                 }
             })?;
         }
-        let def =
-            module.scope(db, None).into_iter().find(|(name, _def)| name.to_smol_str() == trait_)?.1;
-        Some(def)
+        let defs = module
+            .scope(db, None)
+            .into_iter()
+            .filter(|(name, _def)| name.to_smol_str() == trait_)
+            .map(|(_name, def)| def)
+            .collect();
+        Some(defs)
     }
 }
@@ -9,6 +9,7 @@ mod apply_change;
 pub mod active_parameter;
 pub mod assists;
 pub mod defs;
+pub mod doc_links;
 pub mod famous_defs;
 pub mod helpers;
 pub mod items_locator;
@@ -38,6 +39,7 @@ pub mod syntax_helpers {
     pub mod node_ext;
     pub mod insert_whitespace_into_node;
     pub mod format_string;
+    pub mod gen_trait_fn_body;
 
     pub use parser::LexedStr;
 }
@@ -306,3 +306,112 @@ pub fn lex_format_specifiers(
         chars.next();
     }
 }
+
+/// The argument a placeholder refers to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FormatArg {
+    /// `{}`, the next positional argument.
+    Next,
+    /// `{0}`
+    Index(usize),
+    /// `{name}`
+    Name(String),
+    /// Anything else, like `{x + 1}`. This is not accepted by `format_args!`.
+    Expr(String),
+}
+
+/// A `{...}` placeholder of a format string.
+#[derive(Debug, Clone)]
+pub struct FormatPlaceholder {
+    /// The range of the placeholder including its braces, relative to the start of the literal.
+    pub range: TextRange,
+    /// The range of the argument, empty for [`FormatArg::Next`].
+    pub arg_range: TextRange,
+    pub arg: FormatArg,
+    /// The text after the `:`, if there is one.
+    pub spec: Option<String>,
+}
+
+/// Collects the placeholders of a format string, or returns `None` if its braces don't match.
+///
+/// Unlike [`lex_format_specifiers`], this doesn't check the format spec, and it allows arbitrary
+/// expressions as arguments.
+pub fn format_placeholders(string: &ast::String) -> Option<Vec<FormatPlaceholder>> {
+    let mut chars = Vec::new();
+    let mut error = false;
+    string.escaped_char_ranges(&mut |range, res| match res {
+        Ok(c) => chars.push((range, c)),
+        Err(_) => error = true,
+    });
+    if error {
+        return None;
+    }
+    let text = string.text();
+
+    let mut placeholders = Vec::new();
+    let mut idx = 0;
+    while idx < chars.len() {
+        let (range, c) = chars[idx];
+        let next = chars.get(idx + 1).map(|&(_, c)| c);
+        match (c, next) {
+            ('{', Some('{')) | ('}', Some('}')) => idx += 2,
+            ('}', _) => return None,
+            ('{', _) => {
+                let mut depth = 0;
+                let mut colon = None;
+                let mut end = idx + 1;
+                loop {
+                    let &(_, c) = chars.get(end)?;
+                    let prev = chars[end - 1].1;
+                    let next = chars.get(end + 1).map(|&(_, c)| c);
+                    match c {
+                        '(' | '[' | '{' => depth += 1,
+                        ')' | ']' => depth -= 1,
+                        '}' if depth == 0 => break,
+                        '}' => depth -= 1,
+                        ':' if depth == 0
+                            && colon.is_none()
+                            && prev != ':'
+                            && next != Some(':') =>
+                        {
+                            colon = Some(end)
+                        }
+                        _ => (),
+                    }
+                    end += 1;
+                }
+                let arg_end = chars[colon.unwrap_or(end)].0.start();
+                let arg_range = TextRange::new(range.end(), arg_end);
+                let spec = colon.map(|colon| {
+                    let spec_range = TextRange::new(chars[colon].0.end(), chars[end].0.start());
+                    text[spec_range].to_owned()
+                });
+                let arg_text = &text[arg_range];
+                let arg = if arg_text.is_empty() {
+                    FormatArg::Next
+                } else if arg_text.bytes().all(|it| it.is_ascii_digit()) {
+                    FormatArg::Index(arg_text.parse().ok()?)
+                } else if is_identifier(arg_text) {
+                    FormatArg::Name(arg_text.to_owned())
+                } else {
+                    FormatArg::Expr(arg_text.trim().to_owned())
+                };
+                placeholders.push(FormatPlaceholder {
+                    range: range.cover(chars[end].0),
+                    arg_range,
+                    arg,
+                    spec,
+                });
+                idx = end + 1;
+            }
+            _ => idx += 1,
+        }
+    }
+    Some(placeholders)
+}
+
+fn is_identifier(text: &str) -> bool {
+    let mut chars = text.chars();
+    chars.next().map_or(false, |c| c.is_alphabetic() || c == '_')
+        && chars.all(|c| c.is_alphanumeric() || c == '_')
+}
@@ -2,24 +2,42 @@
 
 use syntax::{
     ast::{self, edit::AstNodeEdit, make, AstNode, BinaryOp, CmpOp, HasName, LogicOp},
-    ted,
+    ted, Direction, T,
 };
 
+use crate::RootDatabase;
+
+/// The resolved types of the impl a body is generated for.
+pub struct ImplTypes<'a> {
+    pub db: &'a RootDatabase,
+    /// The type the trait is implemented for.
+    pub self_ty: hir::Type,
+    /// The first type argument of the trait, like `T` in `From<T>`.
+    pub trait_arg: Option<hir::Type>,
+}
+
 /// Generate custom trait bodies without default implementation where possible.
 ///
 /// Returns `Option` so that we can use `?` rather than `if let Some`. Returning
 /// `None` means that generating a custom trait body failed, and the body will remain
-/// as `todo!` instead.
-pub(crate) fn gen_trait_fn_body(
+/// as `todo!` instead. Bodies which depend on the types of fields, like the one of `From`, are only
+/// generated if `impl_types` are given.
+pub fn gen_trait_fn_body(
     func: &ast::Fn,
     trait_path: &ast::Path,
     adt: &ast::Adt,
+    impl_types: Option<&ImplTypes<'_>>,
 ) -> Option<()> {
     match trait_path.segment()?.name_ref()?.text().as_str() {
         "Clone" => gen_clone_impl(adt, func),
         "Debug" => gen_debug_impl(adt, func),
         "Default" => gen_default_impl(adt, func),
+        "Deref" => gen_deref_impl(adt, func, false),
+        "DerefMut" => gen_deref_impl(adt, func, true),
+        "Display" => gen_display_impl(adt, func),
+        "From" => gen_from_impl(adt, func, impl_types?),
         "Hash" => gen_hash_impl(adt, func),
+        "Ord" => gen_ord(adt, func),
         "PartialEq" => gen_partial_eq(adt, func),
         "PartialOrd" => gen_partial_ord(adt, func),
         _ => None,
@@ -646,7 +664,10 @@ fn gen_partial_ord(adt: &ast::Adt, func: &ast::Fn) -> Option<()> {
 
             // No fields in the body means there's nothing to compare.
             None => {
-                let expr = make::expr_literal("true").into();
+                let expr = make::expr_call(
+                    make::expr_path(make::ext::ident_path("Some")),
+                    make::arg_list(Some(make_ordering_equal()?)),
+                );
                 make::block_expr(None, Some(expr)).indent(ast::edit::IndentLevel(1))
             }
         },
@@ -656,6 +677,409 @@ fn gen_partial_ord(adt: &ast::Adt, func: &ast::Fn) -> Option<()> {
     Some(())
 }
 
+/// Generate an `Ord` impl based on the fields and members of the target type.
+fn gen_ord(adt: &ast::Adt, func: &ast::Fn) -> Option<()> {
+    stdx::always!(func.name().map_or(false, |name| name.text() == "cmp"));
+    fn gen_ord_match(match_target: ast::Expr) -> Option<ast::Stmt> {
+        let arms = vec![
+            make::match_arm(
+                Some(make::path_pat(make::ext::path_from_idents([
+                    "core", "cmp", "Ordering", "Equal",
+                ])?)),
+                None,
+                make::expr_empty_block(),
+            ),
+            make::match_arm(
+                [make::ident_pat(false, false, make::name("ord")).into()],
+                None,
+                make::expr_return(Some(make::expr_path(make::ext::ident_path("ord")))),
+            ),
+        ];
+        let list = make::match_arm_list(arms).indent(ast::edit::IndentLevel(1));
+        Some(make::expr_stmt(make::expr_match(match_target, list)).into())
+    }
+
+    fn gen_cmp_call(lhs: ast::Expr, rhs: ast::Expr) -> ast::Expr {
+        let args = make::arg_list(Some(make::expr_ref(rhs, false)));
+        make::expr_method_call(lhs, make::name_ref("cmp"), args)
+    }
+
+    // => match a.cmp(&b) { Equal => {} ord => return ord } c.cmp(&d)
+    fn gen_cmp_chain(mut exprs: Vec<ast::Expr>) -> Option<ast::BlockExpr> {
+        let tail = match exprs.pop() {
+            Some(it) => it,
+            // No fields in the body means there's nothing to compare.
+            None => make_ordering_equal()?,
+        };
+        let stmts = exprs.into_iter().map(gen_ord_match).collect::<Option<Vec<ast::Stmt>>>()?;
+        Some(make::block_expr(stmts, Some(tail)))
+    }
+
+    let body = match adt {
+        // `Ord` cannot be derived for unions, so no default impl can be provided.
+        ast::Adt::Union(_) => return None,
+
+        // Variants are ordered by their declaration order, and fields of the same variant are
+        // compared in order.
+        ast::Adt::Enum(enum_) => {
+            let mut ordinal_arms = vec![];
+            let mut arms = vec![];
+            for (i, variant) in enum_.variant_list()?.variants().enumerate() {
+                let variant_path =
+                    make::ext::path_from_idents(["Self", &variant.name()?.to_string()])?;
+                // => (Self::Bar { bin: l_bin }, Self::Bar { bin: r_bin }) => l_bin.cmp(r_bin),
+                let (wildcard, pats): (ast::Pat, _) = match variant.field_list() {
+                    Some(ast::FieldList::RecordFieldList(list)) => {
+                        let names = list
+                            .fields()
+                            .map(|it| Some(it.name()?.to_string()))
+                            .collect::<Option<Vec<_>>>()?;
+                        let gen_pat = |prefix: &str| {
+                            let fields = names.iter().map(|name| {
+                                let pat = make::ext::simple_ident_pat(make::name(&format!(
+                                    "{prefix}{name}"
+                                )));
+                                make::record_pat_field(make::name_ref(name), pat.into())
+                            });
+                            make::record_pat_with_fields(
+                                variant_path.clone(),
+                                make::record_pat_field_list(fields),
+                            )
+                            .into()
+                        };
+                        let wildcard = names.iter().map(|name| {
+                            make::record_pat_field(
+                                make::name_ref(name),
+                                make::wildcard_pat().into(),
+                            )
+                        });
+                        let wildcard = make::record_pat_with_fields(
+                            variant_path.clone(),
+                            make::record_pat_field_list(wildcard),
+                        );
+                        let names = names.iter().map(|it| (format!("l_{it}"), format!("r_{it}")));
+                        (
+                            wildcard.into(),
+                            Some((gen_pat("l_"), gen_pat("r_"), names.collect::<Vec<_>>())),
+                        )
+                    }
+                    // => (Self::Baz(l0, l1), Self::Baz(r0, r1)) => ...
+                    Some(ast::FieldList::TupleFieldList(list)) => {
+                        let count = list.fields().count();
+                        let gen_pat = |prefix: &str| {
+                            let fields = (0..count).map(|i| {
+                                make::ext::simple_ident_pat(make::name(&format!("{prefix}{i}")))
+                                    .into()
+                            });
+                            make::tuple_struct_pat(variant_path.clone(), fields).into()
+                        };
+                        let wildcard = (0..count).map(|_| make::wildcard_pat().into());
+                        let wildcard = make::tuple_struct_pat(variant_path.clone(), wildcard);
+                        let names = (0..count).map(|i| (format!("l{i}"), format!("r{i}")));
+                        (
+                            wildcard.into(),
+                            Some((gen_pat("l"), gen_pat("r"), names.collect::<Vec<_>>())),
+                        )
+                    }
+                    None => (make::path_pat(variant_path), None),
+                };
+                ordinal_arms.push(make::match_arm(
+                    Some(wildcard),
+                    None,
+                    make::expr_literal(&i.to_string()).into(),
+                ));
+
+                let (left, right, names) = match pats {
+                    Some(it) => it,
+                    None => continue,
+                };
+                let exprs: Vec<_> = names
+                    .iter()
+                    .map(|(l, r)| {
+                        let lhs = make::expr_path(make::ext::ident_path(l));
+                        let rhs = make::expr_path(make::ext::ident_path(r));
+                        gen_cmp_call(lhs, rhs)
+                    })
+                    .collect();
+                let tuple = make::tuple_pat(vec![left, right]);
+                let expr = match <[_; 1]>::try_from(exprs) {
+                    Ok([expr]) => expr,
+                    Err(exprs) => gen_cmp_chain(exprs)?.indent(ast::edit::IndentLevel(1)).into(),
+                };
+                arms.push(make::match_arm(Some(tuple.into()), None, expr));
+            }
+
+            // => let ordinal = |it: &Self| match it { Self::Foo => 0, Self::Bar { bin: _ } => 1 };
+            let it = make::expr_path(make::ext::ident_path("it"));
+            let ordinal = make::expr_closure(
+                Some(make::param(
+                    make::ext::simple_ident_pat(make::name("it")).into(),
+                    make::ty_ref(make::ty("Self"), false),
+                )),
+                make::expr_match(
+                    it,
+                    make::match_arm_list(ordinal_arms).indent(ast::edit::IndentLevel(1)),
+                ),
+            );
+            let ordinal = make::let_stmt(
+                make::ext::simple_ident_pat(make::name("ordinal")).into(),
+                None,
+                Some(ordinal),
+            );
+
+            // => ordinal(self).cmp(&ordinal(other))
+            let gen_ordinal = |name| {
+                let arg = make::arg_list(Some(make::expr_path(make::ext::ident_path(name))));
+                make::expr_call(make::expr_path(make::ext::ident_path("ordinal")), arg)
+            };
+            let cmp_ordinals = gen_cmp_call(gen_ordinal("self"), gen_ordinal("other"));
+
+            let tail = match arms.len() {
+                0 => cmp_ordinals,
+                _ => {
+                    arms.push(make::match_arm(
+                        Some(make::wildcard_pat().into()),
+                        None,
+                        cmp_ordinals,
+                    ));
+                    let match_target = make::expr_tuple(vec![
+                        make::expr_path(make::ext::ident_path("self")),
+                        make::expr_path(make::ext::ident_path("other")),
+                    ]);
+                    let list = make::match_arm_list(arms).indent(ast::edit::IndentLevel(1));
+                    make::expr_match(match_target, list)
+                }
+            };
+            make::block_expr(Some(ordinal.into()), Some(tail))
+        }
+
+        ast::Adt::Struct(strukt) => {
+            let exprs = field_names(strukt.field_list())?
+                .iter()
+                .map(|field| {
+                    let lhs = make::expr_path(make::ext::ident_path("self"));
+                    let rhs = make::expr_path(make::ext::ident_path("other"));
+                    gen_cmp_call(make::expr_field(lhs, field), make::expr_field(rhs, field))
+                })
+                .collect();
+            gen_cmp_chain(exprs)?
+        }
+    };
+
+    let body = body.indent(ast::edit::IndentLevel(1));
+    ted::replace(func.body()?.syntax(), body.clone_for_update().syntax());
+    Some(())
+}
+
+/// Generate a `Display` impl which displays all fields of the target type.
+fn gen_display_impl(adt: &ast::Adt, func: &ast::Fn) -> Option<()> {
+    stdx::always!(func.name().map_or(false, |name| name.text() == "fmt"));
+    // => write!(f, "{} {}", a, b)
+    fn gen_write(name: &ast::Name, args: Vec<ast::Expr>) -> ast::Expr {
+        let fmt_string = match args.len() {
+            0 => format!("\"{}\"", name),
+            n => format!("\"{}\"", vec!["{}"; n].join(" ")),
+        };
+        let target = make::expr_path(make::ext::ident_path("f"));
+        let fmt_string = make::expr_literal(&fmt_string).into();
+        let args = make::arg_list([target, fmt_string].into_iter().chain(args));
+        make::expr_macro_call(make::expr_path(make::ext::ident_path("write")), args)
+    }
+
+    let expr = match adt {
+        ast::Adt::Union(_) => return None,
+
+        // => match self { Self::Name(arg0) => write!(f, "{}", arg0) }
+        ast::Adt::Enum(enum_) => {
+            let mut arms = vec![];
+            for variant in enum_.variant_list()?.variants() {
+                let name = variant.name()?;
+                let variant_path = make::ext::path_from_idents(["Self", &name.to_string()])?;
+                let (pat, bindings): (ast::Pat, _) = match variant.field_list() {
+                    Some(ast::FieldList::RecordFieldList(list)) => {
+                        let names =
+                            list.fields().map(|it| it.name()).collect::<Option<Vec<_>>>()?;
+                        let pats =
+                            names.iter().map(|it| make::ident_pat(false, false, it.clone()).into());
+                        (make::record_pat(variant_path, pats).into(), names)
+                    }
+                    Some(ast::FieldList::TupleFieldList(list)) => {
+                        let names = (0..list.fields().count())
+                            .map(|i| make::name(&format!("arg{}", i)))
+                            .collect::<Vec<_>>();
+                        let pats =
+                            names.iter().map(|it| make::ident_pat(false, false, it.clone()).into());
+                        (make::tuple_struct_pat(variant_path, pats).into(), names)
+                    }
+                    None => (make::path_pat(variant_path), Vec::new()),
+                };
+                let args = bindings
+                    .iter()
+                    .map(|it| make::expr_path(make::ext::ident_path(&it.to_string())))
+                    .collect();
+                arms.push(make::match_arm(Some(pat), None, gen_write(&name, args)));
+            }
+            let match_target = make::expr_path(make::ext::ident_path("self"));
+            let list = make::match_arm_list(arms).indent(ast::edit::IndentLevel(1));
+            make::expr_match(match_target, list)
+        }
+
+        // => write!(f, "{} {}", self.a, self.b)
+        ast::Adt::Struct(strukt) => {
+            let args = field_names(strukt.field_list())?
+                .iter()
+                .map(|it| make::expr_field(make::expr_path(make::ext::ident_path("self")), it))
+                .collect();
+            gen_write(&strukt.name()?, args)
+        }
+    };
+
+    let body = make::block_expr(None, Some(expr)).indent(ast::edit::IndentLevel(1));
+    ted::replace(func.body()?.syntax(), body.clone_for_update().syntax());
+    Some(())
+}
+
+/// Generate a `From` impl which wraps the single field of the target type, or of one of its
+/// variants, that has the converted type.
+fn gen_from_impl(adt: &ast::Adt, func: &ast::Fn, impl_types: &ImplTypes<'_>) -> Option<()> {
+    stdx::always!(func.name().map_or(false, |name| name.text() == "from"));
+    let db = impl_types.db;
+    let param_ty = impl_types.trait_arg.as_ref()?;
+    let param = func.param_list()?.params().next()?;
+    let param_name = match param.pat()? {
+        ast::Pat::IdentPat(it) => it.name()?.to_string(),
+        ast::Pat::WildcardPat(it) => {
+            let pat = make::ident_pat(false, false, make::name("value")).clone_for_update();
+            ted::replace(it.syntax(), pat.syntax());
+            "value".to_owned()
+        }
+        _ => return None,
+    };
+
+    // => Self(value) or Self { field: value }
+    let gen_constructor = |path: ast::Path, field_list: Option<ast::FieldList>| {
+        let value = make::expr_path(make::ext::ident_path(&param_name));
+        match field_list? {
+            ast::FieldList::RecordFieldList(list) => {
+                let field = list.fields().next()?;
+                let name = make::name_ref(&field.name()?.to_string());
+                let field = make::record_expr_field(name, Some(value));
+                Some(make::record_expr(path, make::record_expr_field_list(Some(field))).into())
+            }
+            ast::FieldList::TupleFieldList(_) => {
+                Some(make::expr_call(make::expr_path(path), make::arg_list(Some(value))))
+            }
+        }
+    };
+    let has_single_field_of_type =
+        |fields: Vec<(hir::Field, hir::Type)>| matches!(&fields[..], [(_, ty)] if ty == param_ty);
+
+    let expr = match (adt, impl_types.self_ty.as_adt()?) {
+        (ast::Adt::Enum(enum_), hir::Adt::Enum(hir_enum)) => {
+            let mut variants = hir_enum
+                .variants(db)
+                .into_iter()
+                .filter(|&it| has_single_field_of_type(impl_types.self_ty.variant_fields(db, it)));
+            let variant = variants.next()?;
+            if variants.next().is_some() {
+                return None;
+            }
+            let name = variant.name(db).to_string();
+            let variant = enum_
+                .variant_list()?
+                .variants()
+                .find(|it| it.name().map_or(false, |it| it.text() == name))?;
+            let path = make::ext::path_from_idents(["Self", &name])?;
+            gen_constructor(path, variant.field_list())?
+        }
+        (ast::Adt::Struct(strukt), hir::Adt::Struct(_)) => {
+            if !has_single_field_of_type(impl_types.self_ty.fields(db)) {
+                return None;
+            }
+            gen_constructor(make::ext::ident_path("Self"), strukt.field_list())?
+        }
+        _ => return None,
+    };
+
+    let body = make::block_expr(None, Some(expr)).indent(ast::edit::IndentLevel(1));
+    ted::replace(func.body()?.syntax(), body.clone_for_update().syntax());
+    Some(())
+}
+
+/// Generate a `Deref` or `DerefMut` impl for a struct with a single field, filling in the
+/// `Target` type as well.
+fn gen_deref_impl(adt: &ast::Adt, func: &ast::Fn, mutable: bool) -> Option<()> {
+    let expected_name = if mutable { "deref_mut" } else { "deref" };
+    stdx::always!(func.name().map_or(false, |name| name.text() == expected_name));
+    let strukt = match adt {
+        ast::Adt::Struct(it) => it,
+        _ => return None,
+    };
+    let (field_name, field_ty) = match strukt.field_list()? {
+        ast::FieldList::RecordFieldList(list) => {
+            let mut fields = list.fields();
+            let field = fields.next()?;
+            if fields.next().is_some() {
+                return None;
+            }
+            (field.name()?.to_string(), field.ty()?)
+        }
+        ast::FieldList::TupleFieldList(list) => {
+            let mut fields = list.fields();
+            let field = fields.next()?;
+            if fields.next().is_some() {
+                return None;
+            }
+            ("0".to_owned(), field.ty()?)
+        }
+    };
+
+    // => &self.field
+    let field = make::expr_field(make::expr_path(make::ext::ident_path("self")), &field_name);
+    let body = make::block_expr(None, Some(make::expr_ref(field, mutable)))
+        .indent(ast::edit::IndentLevel(1));
+    ted::replace(func.body()?.syntax(), body.clone_for_update().syntax());
+
+    // => type Target = FieldTy;
+    let target = func
+        .syntax()
+        .siblings(Direction::Prev)
+        .chain(func.syntax().siblings(Direction::Next))
+        .filter_map(ast::TypeAlias::cast)
+        .find(|it| it.name().map_or(false, |name| name.text() == "Target") && it.ty().is_none());
+    if let Some(semicolon) = target.and_then(|it| it.semicolon_token()) {
+        // The alias may be the first new item, which the cursor is placed on, so it is filled in
+        // place.
+        let alias = make::ty_alias(make::name("Target"), field_ty).clone_for_update();
+        let elements = alias
+            .name()?
+            .syntax()
+            .siblings_with_tokens(Direction::Next)
+            .skip(1)
+            .take_while(|it| it.kind() != T![;])
+            .collect::<Vec<_>>();
+        elements.iter().for_each(|it| it.detach());
+        ted::insert_all(ted::Position::before(semicolon), elements);
+    }
+    Some(())
+}
+
+fn field_names(field_list: Option<ast::FieldList>) -> Option<Vec<String>> {
+    match field_list {
+        Some(ast::FieldList::RecordFieldList(list)) => {
+            list.fields().map(|it| Some(it.name()?.to_string())).collect()
+        }
+        Some(ast::FieldList::TupleFieldList(list)) => {
+            Some((0..list.fields().count()).map(|i| i.to_string()).collect())
+        }
+        None => Some(Vec::new()),
+    }
+}
+
+fn make_ordering_equal() -> Option<ast::Expr> {
+    Some(make::expr_path(make::ext::path_from_idents(["core", "cmp", "Ordering", "Equal"])?))
+}
+
 fn make_discriminant() -> Option<ast::Expr> {
     Some(make::expr_path(make::ext::path_from_idents(["core", "mem", "discriminant"])?))
 }
@@ -0,0 +1,213 @@
+use hir::db::AstDatabase;
+use ide_db::{assists::Assist, helpers::mod_path_to_ast, source_change::SourceChange};
+use syntax::{ast, AstNode};
+use text_edit::TextEdit;
+
+use crate::{adjusted_display_range, fix, path_segment_named, Diagnostic, DiagnosticsContext};
+
+// Diagnostic: ambiguous-assoc-type
+//
+// This diagnostic is triggered if a path like `Type::Assoc` doesn't determine the trait the
+// associated type comes from. This is the case for types other than type parameters and `Self`,
+// and for type parameters bounded by several traits defining `Assoc`.
+pub(crate) fn ambiguous_assoc_type(
+    ctx: &DiagnosticsContext<'_>,
+    d: &hir::AmbiguousAssocType,
+) -> Diagnostic {
+    let range =
+        adjusted_display_range::<ast::Path>(ctx, d.path.clone().map(|it| it.into()), &|path| {
+            let segment = path_segment_named(&path, &d.name)?;
+            Some(segment.parent_path().syntax().text_range())
+        });
+    Diagnostic::new(
+        "ambiguous-assoc-type",
+        format!("ambiguous associated type `{}`", d.name),
+        range,
+    )
+    .with_fixes(fixes(ctx, d))
+}
+
+fn fixes(ctx: &DiagnosticsContext<'_>, d: &hir::AmbiguousAssocType) -> Option<Vec<Assist>> {
+    if d.path.file_id.is_macro() {
+        return None;
+    }
+    let file_id = d.path.file_id.original_file(ctx.sema.db);
+    let root = ctx.sema.db.parse_or_expand(d.path.file_id)?;
+    let path = d.path.value.to_node(&root);
+    let segment = path_segment_named(&path, &d.name)?;
+    let qualifier = segment.parent_path().qualifier()?;
+    // `<Type>::Assoc` becomes `<Type as Trait>::Assoc`, not `<<Type> as Trait>::Assoc`.
+    let qualifier = match qualifier.segment().and_then(|it| it.kind()) {
+        Some(ast::PathSegmentKind::Type { type_ref: Some(ty), trait_ref: None }) => {
+            ty.syntax().clone()
+        }
+        _ => qualifier.syntax().clone(),
+    };
+    let module = ctx.sema.scope(path.syntax())?.module();
+    let range = segment.parent_path().syntax().text_range();
+
+    let fixes = d
+        .candidates
+        .iter()
+        .filter_map(|&candidate| {
+            let trait_path = mod_path_to_ast(
+                &module.find_use_path(ctx.sema.db, hir::ModuleDef::from(candidate))?,
+            );
+            let replacement = format!("<{} as {}>::{}", qualifier, trait_path, segment);
+            Some(fix(
+                "qualify_assoc_type",
+                &format!("Qualify as `{}`", replacement),
+                SourceChange::from_text_edit(file_id, TextEdit::replace(range, replacement)),
+                range,
+            ))
+        })
+        .collect::<Vec<_>>();
+    if fixes.is_empty() {
+        None
+    } else {
+        Some(fixes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_diagnostics, check_fix, check_fixes};
+
+    #[test]
+    fn concrete_type() {
+        check_diagnostics(
+            r#"
+trait Trait {
+    type Assoc;
+}
+struct S;
+impl Trait for S {
+    type Assoc = ();
+}
+fn f(s: S::Assoc) {}
+      //^^^^^^^^ 💡 error: ambiguous associated type `Assoc`
+fn g(s: <S>::Assoc) {}
+      //^^^^^^^^^^ 💡 error: ambiguous associated type `Assoc`
+"#,
+        );
+    }
+
+    #[test]
+    fn several_bounds() {
+        check_diagnostics(
+            r#"
+trait A {
+    type Assoc;
+}
+trait B {
+    type Assoc;
+}
+fn f<T: A + B>(t: T::Assoc) {}
+                //^^^^^^^^ 💡 error: ambiguous associated type `Assoc`
+fn g<T: A>(t: T::Assoc) {}
+"#,
+        );
+    }
+
+    #[test]
+    fn nested_shorthand() {
+        check_diagnostics(
+            r#"
+trait Trait {
+    type Assoc: Trait;
+}
+fn f<T: Trait>(t: T::Assoc::Assoc) {}
+                //^^^^^^^^^^^^^^^ 💡 error: ambiguous associated type `Assoc`
+"#,
+        );
+    }
+
+    #[test]
+    fn qualify_concrete_type() {
+        check_fix(
+            r#"
+mod m {
+    pub trait Trait {
+        type Assoc;
+    }
+}
+use m::Trait;
+struct S<T>(T);
+impl<T> Trait for S<T> {
+    type Assoc = T;
+}
+fn f() {
+    let _: S<u8>::Assoc$0 = 0;
+}
+"#,
+            r#"
+mod m {
+    pub trait Trait {
+        type Assoc;
+    }
+}
+use m::Trait;
+struct S<T>(T);
+impl<T> Trait for S<T> {
+    type Assoc = T;
+}
+fn f() {
+    let _: <S<u8> as Trait>::Assoc = 0;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn qualify_type_param() {
+        check_fixes(
+            r#"
+trait A {
+    type Assoc;
+}
+trait B {
+    type Assoc;
+}
+fn f<T: A + B>(t: T::Assoc$0) {}
+"#,
+            vec![
+                r#"
+trait A {
+    type Assoc;
+}
+trait B {
+    type Assoc;
+}
+fn f<T: A + B>(t: <T as A>::Assoc) {}
+"#,
+                r#"
+trait A {
+    type Assoc;
+}
+trait B {
+    type Assoc;
+}
+fn f<T: A + B>(t: <T as B>::Assoc) {}
+"#,
+            ],
+        );
+    }
+
+    #[test]
+    fn qualify_nested_shorthand() {
+        check_fix(
+            r#"
+trait Trait {
+    type Assoc: Trait;
+}
+fn f<T: Trait>(t: T::Assoc::Assoc$0) {}
+"#,
+            r#"
+trait Trait {
+    type Assoc: Trait;
+}
+fn f<T: Trait>(t: <T::Assoc as Trait>::Assoc) {}
+"#,
+        );
+    }
+}
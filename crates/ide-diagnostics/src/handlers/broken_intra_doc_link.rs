@@ -0,0 +1,312 @@
+use hir::{InFile, Namespace, Semantics};
+use ide_db::{
+    base_db::{FileId, SourceDatabaseExt},
+    defs::Definition,
+    doc_links::{
+        doc_attributes, doc_link_candidates, extract_definitions_from_docs,
+        resolve_doc_path_for_def,
+    },
+    source_change::SourceChange,
+    RootDatabase,
+};
+use itertools::Itertools;
+use syntax::{
+    ast::{self, HasDocComments},
+    AstNode, SmolStr, SyntaxNode, TextRange, TextSize,
+};
+use text_edit::TextEdit;
+
+use crate::{fix, Diagnostic, Severity};
+
+// Diagnostic: broken-intra-doc-link
+//
+// This diagnostic is triggered if an intra-doc link in the documentation of an item does not
+// resolve to any item, or if it resolves to items of several namespaces without a disambiguator.
+pub(crate) fn broken_intra_doc_link(
+    sema: &Semantics<'_, RootDatabase>,
+    acc: &mut Vec<Diagnostic>,
+    file_id: FileId,
+    node: &SyntaxNode,
+) -> Option<()> {
+    let has_docs = ast::AnyHasDocComments::cast(node.clone())?;
+    if has_docs.doc_comments_and_attrs().next().is_none() {
+        return None;
+    }
+    let (attrs, def) = doc_attributes(sema, node)?;
+    let (docs, doc_mapping) = attrs.docs_with_rangemap(sema.db)?;
+    let file_text = sema.db.file_text(file_id);
+
+    for (range, link, ns) in extract_definitions_from_docs(&docs) {
+        let range = match doc_mapping.map(range) {
+            Some(InFile { file_id: link_file_id, value }) if link_file_id == file_id.into() => {
+                value
+            }
+            _ => continue,
+        };
+        let path = link.split('#').next().unwrap_or_default();
+        if !is_path_like(path) {
+            continue;
+        }
+        // The text range of the path within the link, if we can find it in the source.
+        let path_range = file_text[range].rfind(path).map(|offset| {
+            TextRange::at(range.start() + TextSize::from(offset as u32), TextSize::of(path))
+        });
+
+        if resolve_doc_path_for_def(sema.db, def, path, ns).is_none() {
+            let fixes = path_range.and_then(|path_range| {
+                let name = path.rsplit("::").next().unwrap_or(path);
+                let name_range =
+                    TextRange::new(path_range.end() - TextSize::of(name), path_range.end());
+                let fixes: Vec<_> = did_you_mean(sema, node, def, path, ns)
+                    .into_iter()
+                    .map(|candidate| {
+                        fix(
+                            "change_doc_link",
+                            &format!("Change link to `{}`", candidate),
+                            SourceChange::from_text_edit(
+                                file_id,
+                                TextEdit::replace(name_range, candidate.to_string()),
+                            ),
+                            range,
+                        )
+                    })
+                    .collect();
+                (!fixes.is_empty()).then(|| fixes)
+            });
+            acc.push(
+                Diagnostic::new(
+                    "broken-intra-doc-link",
+                    format!("unresolved link to `{}`", path),
+                    range,
+                )
+                .severity(Severity::WeakWarning)
+                .experimental()
+                .with_fixes(fixes),
+            );
+            continue;
+        }
+        if ns.is_some() {
+            continue;
+        }
+
+        let resolutions: Vec<_> = [Namespace::Types, Namespace::Values, Namespace::Macros]
+            .into_iter()
+            .filter_map(|ns| Some((ns, resolve_doc_path_for_def(sema.db, def, path, Some(ns))?)))
+            .unique_by(|&(_, it)| it)
+            .collect();
+        let is_derive_of_trait = |it: &Definition| matches!(it, Definition::Macro(it) if it.kind(sema.db) == hir::MacroKind::Derive);
+        // Rustdoc prefers traits over their derive macros.
+        let is_ambiguous = match &*resolutions {
+            [_] | [] => false,
+            [(_, a), (_, b)] => !(is_derive_of_trait(a) || is_derive_of_trait(b)),
+            _ => true,
+        };
+        if !is_ambiguous {
+            continue;
+        }
+
+        let fixes = path_range.map(|path_range| {
+            resolutions
+                .iter()
+                .map(|&(ns, def)| {
+                    let disambiguator = match ns {
+                        Namespace::Types => "type@",
+                        Namespace::Values => "value@",
+                        Namespace::Macros => "macro@",
+                    };
+                    fix(
+                        "disambiguate_doc_link",
+                        &format!("Link to the {} instead", def_kind_name(sema.db, def)),
+                        SourceChange::from_text_edit(
+                            file_id,
+                            TextEdit::insert(path_range.start(), disambiguator.to_owned()),
+                        ),
+                        range,
+                    )
+                })
+                .collect()
+        });
+        let kinds = resolutions.iter().map(|&(_, it)| def_kind_name(sema.db, it)).join(" and a ");
+        acc.push(
+            Diagnostic::new(
+                "broken-intra-doc-link",
+                format!("`{}` is both a {}", path, kinds),
+                range,
+            )
+            .severity(Severity::WeakWarning)
+            .experimental()
+            .with_fixes(fixes),
+        );
+    }
+    Some(())
+}
+
+/// Returns the names closest to the last segment of `path` that the link could refer to instead.
+fn did_you_mean(
+    sema: &Semantics<'_, RootDatabase>,
+    node: &SyntaxNode,
+    def: Definition,
+    path: &str,
+    ns: Option<Namespace>,
+) -> Vec<SmolStr> {
+    let (qualifier, name) = match path.rsplit_once("::") {
+        Some((qualifier, name)) => (Some(qualifier), name),
+        None => (None, path),
+    };
+    let scope = match sema.scope(node) {
+        Some(it) => it,
+        None => return Vec::new(),
+    };
+    let max_distance = (name.chars().count() / 3).max(1);
+    doc_link_candidates(sema.db, &scope, def, qualifier, ns)
+        .into_iter()
+        .map(|(candidate, _)| candidate.to_smol_str())
+        .unique()
+        .map(|candidate| (stdx::edit_distance(name, &candidate), candidate))
+        .filter(|&(distance, _)| distance <= max_distance)
+        .sorted()
+        .take(3)
+        .map(|(_, candidate)| candidate)
+        .collect()
+}
+
+fn is_path_like(link: &str) -> bool {
+    let mut segments = link.split("::");
+    let first = segments.next().unwrap_or_default();
+    // `Self` links depend on the surrounding impl, which we don't track for now.
+    first != "Self"
+        && std::iter::once(first).chain(segments).all(|segment| {
+            segment.chars().next().map_or(false, |c| c.is_alphabetic() || c == '_')
+                && segment.chars().all(|c| c.is_alphanumeric() || c == '_')
+        })
+}
+
+fn def_kind_name(db: &RootDatabase, def: Definition) -> &'static str {
+    match def {
+        Definition::Module(_) => "module",
+        Definition::Function(_) => "function",
+        Definition::Adt(hir::Adt::Struct(_)) => "struct",
+        Definition::Adt(hir::Adt::Union(_)) => "union",
+        Definition::Adt(hir::Adt::Enum(_)) => "enum",
+        Definition::Variant(_) => "variant",
+        Definition::Const(_) => "constant",
+        Definition::Static(_) => "static",
+        Definition::Trait(_) => "trait",
+        Definition::TypeAlias(_) => "type alias",
+        Definition::Field(_) => "field",
+        Definition::BuiltinType(_) => "primitive type",
+        Definition::Macro(it) if it.kind(db) == hir::MacroKind::Derive => "derive macro",
+        Definition::Macro(_) => "macro",
+        _ => "item",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_diagnostics, check_fix, check_fixes};
+
+    #[test]
+    fn resolvable_links() {
+        check_diagnostics(
+            r#"
+//- minicore: derive, clone
+struct Foo { field: u32 }
+impl Foo {
+    fn method(&self) {}
+}
+enum Bar { Variant }
+mod module {
+    pub fn function() {}
+}
+/// [Foo], [`Foo::method`], [Foo::field], [Bar::Variant], [struct@Foo],
+/// [module::function()], [the module](module), [Clone], [https://example.com],
+/// [a link](https://example.com) and [Self::method].
+fn documented() {}
+"#,
+        );
+    }
+
+    #[test]
+    fn unresolved_link() {
+        check_diagnostics(
+            r#"
+struct Foo;
+#[doc = "See [Bar] and [`Foo::nope`]."]
+           //^^^^^ weak: unresolved link to `Bar`
+                     //^^^^^^^^^^^^^ weak: unresolved link to `Foo::nope`
+fn documented() {}
+"#,
+        );
+    }
+
+    #[test]
+    fn did_you_mean() {
+        check_fix(
+            r#"
+struct Foo;
+struct Fooo;
+/// See [Fo$0].
+fn documented() {}
+"#,
+            r#"
+struct Foo;
+struct Fooo;
+/// See [Foo].
+fn documented() {}
+"#,
+        );
+        check_fix(
+            r#"
+struct Foo;
+impl Foo {
+    fn method(&self) {}
+}
+/// See [`Foo::methdo`$0].
+fn documented() {}
+"#,
+            r#"
+struct Foo;
+impl Foo {
+    fn method(&self) {}
+}
+/// See [`Foo::method`].
+fn documented() {}
+"#,
+        );
+    }
+
+    #[test]
+    fn ambiguous_link() {
+        check_diagnostics(
+            r#"
+mod foo {}
+fn foo() {}
+#[doc = "See [foo]."]
+           //^^^^^ 💡 weak: `foo` is both a module and a function
+fn documented() {}
+"#,
+        );
+        check_fixes(
+            r#"
+mod foo {}
+fn foo() {}
+/// See [foo$0].
+fn documented() {}
+"#,
+            vec![
+                r#"
+mod foo {}
+fn foo() {}
+/// See [type@foo].
+fn documented() {}
+"#,
+                r#"
+mod foo {}
+fn foo() {}
+/// See [value@foo].
+fn documented() {}
+"#,
+            ],
+        );
+    }
+}
@@ -0,0 +1,88 @@
+use hir::{db::AstDatabase, HirDisplay};
+use ide_db::{assists::Assist, source_change::SourceChange};
+use syntax::{ast, AstNode};
+use text_edit::TextEdit;
+
+use crate::{fix, Diagnostic, DiagnosticsContext};
+
+// Diagnostic: cast-to-unsized
+//
+// This diagnostic is triggered if an `as` cast targets a type without a size known at compile
+// time, like `[T]`, `str` or `dyn Trait`.
+pub(crate) fn cast_to_unsized(ctx: &DiagnosticsContext<'_>, d: &hir::CastToUnsized) -> Diagnostic {
+    let display_range =
+        ctx.sema.diagnostics_display_range(d.expr.clone().map(|it| it.into())).range;
+    Diagnostic::new(
+        "cast-to-unsized",
+        format!(
+            "cast to unsized type: `{}` as `{}`",
+            d.expr_ty.display(ctx.sema.db),
+            d.cast_ty.display(ctx.sema.db)
+        ),
+        display_range,
+    )
+    .experimental()
+    .with_fixes(fixes(ctx, d))
+}
+
+fn fixes(ctx: &DiagnosticsContext<'_>, d: &hir::CastToUnsized) -> Option<Vec<Assist>> {
+    if d.expr.file_id.is_macro() {
+        return None;
+    }
+    let (_, mutability) = d.expr_ty.as_reference()?;
+    let file_id = d.expr.file_id.original_file(ctx.sema.db);
+    let root = ctx.sema.db.parse_or_expand(d.expr.file_id)?;
+    let cast_expr = match d.expr.value.to_node(&root) {
+        ast::Expr::CastExpr(it) => it,
+        _ => return None,
+    };
+    let ty = cast_expr.ty()?;
+    let reference = format!("&{}", mutability.as_keyword_for_ref());
+    Some(vec![fix(
+        "cast_to_reference",
+        &format!("Cast to `{}{}` instead", reference, ty),
+        SourceChange::from_text_edit(
+            file_id,
+            TextEdit::insert(ty.syntax().text_range().start(), reference),
+        ),
+        cast_expr.syntax().text_range(),
+    )])
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_diagnostics, check_fix};
+
+    #[test]
+    fn unsized_targets() {
+        check_diagnostics(
+            r#"
+trait Trait {}
+fn f(s: &[u8], t: &str, d: &dyn Trait) {
+    let _ = s as [u8];
+          //^^^^^^^^^ 💡 error: cast to unsized type: `&[u8]` as `[u8]`
+    let _ = t as str;
+          //^^^^^^^^ 💡 error: cast to unsized type: `&str` as `str`
+    let _ = d as dyn Trait;
+          //^^^^^^^^^^^^^^ 💡 error: cast to unsized type: `&dyn Trait` as `dyn Trait`
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn cast_to_reference() {
+        check_fix(
+            r#"
+fn f(s: &mut [u8; 2]) {
+    let _ = s as [u8]$0;
+}
+"#,
+            r#"
+fn f(s: &mut [u8; 2]) {
+    let _ = s as &mut [u8];
+}
+"#,
+        );
+    }
+}
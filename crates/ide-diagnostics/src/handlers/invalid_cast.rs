@@ -0,0 +1,396 @@
+use hir::{db::AstDatabase, CastError, HirDisplay};
+use ide_db::{assists::Assist, famous_defs::FamousDefs, source_change::SourceChange};
+use syntax::{ast, AstNode};
+use text_edit::TextEdit;
+
+use crate::{fix, Diagnostic, DiagnosticsContext};
+
+// Diagnostic: invalid-cast
+//
+// This diagnostic is triggered if an `as` cast is not allowed between the types involved, for
+// example because they aren't primitive or because a pointer would lose or gain metadata.
+pub(crate) fn invalid_cast(ctx: &DiagnosticsContext<'_>, d: &hir::InvalidCast) -> Diagnostic {
+    let display_range =
+        ctx.sema.diagnostics_display_range(d.expr.clone().map(|it| it.into())).range;
+    let expr_ty = d.expr_ty.display(ctx.sema.db);
+    let cast_ty = d.cast_ty.display(ctx.sema.db);
+    let message = match d.error {
+        CastError::CastToBool => format!("cannot cast `{}` as `bool`", expr_ty),
+        CastError::CastToChar => format!("only `u8` can be cast as `char`, not `{}`", expr_ty),
+        CastError::DifferingKinds => {
+            format!("casting `{}` as `{}` is invalid: vtable kinds may not match", expr_ty, cast_ty)
+        }
+        CastError::SizedUnsizedCast => {
+            format!("cannot cast thin pointer `{}` to fat pointer `{}`", expr_ty, cast_ty)
+        }
+        CastError::IllegalCast => format!("casting `{}` as `{}` is invalid", expr_ty, cast_ty),
+        CastError::IntToFatCast => {
+            format!("cannot cast `{}` to a fat pointer `{}`", expr_ty, cast_ty)
+        }
+        CastError::NeedDeref => {
+            format!("casting `{}` as `{}` is invalid: needs a dereference", expr_ty, cast_ty)
+        }
+        CastError::NeedViaPtr => format!(
+            "casting `{}` as `{}` is invalid: needs casting through a raw pointer first",
+            expr_ty, cast_ty
+        ),
+        CastError::NeedViaThinPtr => format!(
+            "casting `{}` as `{}` is invalid: needs casting through a thin pointer first",
+            expr_ty, cast_ty
+        ),
+        CastError::NonScalar => format!("non-primitive cast: `{}` as `{}`", expr_ty, cast_ty),
+    };
+    Diagnostic::new("invalid-cast", message, display_range).experimental().with_fixes(fixes(ctx, d))
+}
+
+fn fixes(ctx: &DiagnosticsContext<'_>, d: &hir::InvalidCast) -> Option<Vec<Assist>> {
+    if d.expr.file_id.is_macro() {
+        return None;
+    }
+    let file_id = d.expr.file_id.original_file(ctx.sema.db);
+    let root = ctx.sema.db.parse_or_expand(d.expr.file_id)?;
+    let cast_expr = match d.expr.value.to_node(&root) {
+        ast::Expr::CastExpr(it) => it,
+        _ => return None,
+    };
+    let expr = cast_expr.expr()?;
+    let ty = cast_expr.ty()?;
+    // `Vec<u8>::from(x)` doesn't parse, the type has to be written as `<Vec<u8>>` then.
+    let qualifier = match &ty {
+        ast::Type::PathType(path)
+            if !path.syntax().descendants().any(|it| ast::GenericArgList::can_cast(it.kind())) =>
+        {
+            ty.to_string()
+        }
+        _ => format!("<{}>", ty),
+    };
+
+    let krate = ctx.sema.scope(cast_expr.syntax())?.krate();
+    let famous_defs = FamousDefs(&ctx.sema, krate);
+    let (id, label, replacement) = if famous_defs.core_convert_From().map_or(false, |from| {
+        d.cast_ty.impls_trait(ctx.sema.db, from, std::slice::from_ref(&d.expr_ty))
+    }) {
+        (
+            "replace_cast_with_from",
+            "Replace `as` with `From::from`",
+            format!("{}::from({})", qualifier, expr),
+        )
+    } else if famous_defs.core_convert_TryFrom().map_or(false, |try_from| {
+        d.cast_ty.impls_trait(ctx.sema.db, try_from, std::slice::from_ref(&d.expr_ty))
+            && returns_compatible_result(ctx, d, &famous_defs, try_from, &cast_expr) == Some(true)
+    }) {
+        // The conversion results in a `Result`, which only has the type of the cast once its
+        // error got propagated.
+        (
+            "replace_cast_with_try_from",
+            "Replace `as` with `TryFrom::try_from`",
+            format!("{}::try_from({})?", qualifier, expr),
+        )
+    } else {
+        return None;
+    };
+    let range = cast_expr.syntax().text_range();
+    Some(vec![fix(
+        id,
+        label,
+        SourceChange::from_text_edit(file_id, TextEdit::replace(range, replacement)),
+        range,
+    )])
+}
+
+/// Whether the function the cast is in returns a `Result` that the error of the `TryFrom`
+/// conversion can be propagated into with `?`.
+fn returns_compatible_result(
+    ctx: &DiagnosticsContext<'_>,
+    d: &hir::InvalidCast,
+    famous_defs: &FamousDefs<'_, '_>,
+    try_from: hir::Trait,
+    cast_expr: &ast::CastExpr,
+) -> Option<bool> {
+    let db = ctx.sema.db;
+    // `?` in closures and async blocks returns from those instead.
+    let body = cast_expr.syntax().ancestors().find(|it| {
+        ast::Fn::can_cast(it.kind())
+            || ast::ClosureExpr::can_cast(it.kind())
+            || ast::BlockExpr::cast(it.clone()).map_or(false, |it| it.async_token().is_some())
+    })?;
+    let func = ctx.sema.to_def(&ast::Fn::cast(body)?)?;
+    let ret_type = func.ret_type(db);
+    if ret_type.as_adt() != Some(hir::Adt::Enum(famous_defs.core_result_Result()?)) {
+        return Some(false);
+    }
+    let ret_error = ret_type.type_arguments().nth(1)?;
+    let error_alias = try_from.items(db).into_iter().find_map(|item| match item {
+        hir::AssocItem::TypeAlias(alias) if alias.name(db).to_smol_str() == "Error" => Some(alias),
+        _ => None,
+    })?;
+    let error =
+        d.cast_ty.normalize_trait_assoc_type(db, std::slice::from_ref(&d.expr_ty), error_alias)?;
+    Some(ret_error.impls_trait(db, famous_defs.core_convert_From()?, &[error]))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_diagnostics, check_fix, check_no_fix};
+
+    #[test]
+    fn valid_casts() {
+        check_diagnostics(
+            r#"
+//- minicore: sized, fn
+enum E { A, B = 3 }
+fn f() {}
+fn g(x: &u8, y: *const [u8], z: *mut u8, b: bool, c: char, n: u8, a: &[u8; 2]) {
+    let _ = 1 as u8;
+    let _ = 1.5 as i32;
+    let _ = n as f64;
+    let _ = E::B as u32;
+    let _ = b as i8;
+    let _ = c as u32;
+    let _ = n as char;
+    let _ = 65 as char;
+    let _ = x as *const u8;
+    let _ = z as *const u8;
+    let _ = y as *const u8;
+    let _ = y as *const [i8];
+    let _ = z as usize;
+    let _ = 0 as *const u8;
+    let _ = f as fn();
+    let _ = f as usize;
+    let _ = f as *const ();
+    let _ = a as *const u8;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn cast_to_bool_and_char() {
+        check_diagnostics(
+            r#"
+fn f(n: u32) {
+    let _ = 1 as bool;
+          //^^^^^^^^^ error: cannot cast `i32` as `bool`
+    let _ = n as char;
+          //^^^^^^^^^ error: only `u8` can be cast as `char`, not `u32`
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn non_scalar() {
+        check_diagnostics(
+            r#"
+struct S;
+enum E { A(u8) }
+fn f(s: S, e: E, x: &u8) {
+    let _ = s as u8;
+          //^^^^^^^ error: non-primitive cast: `S` as `u8`
+    let _ = e as u8;
+          //^^^^^^^ error: non-primitive cast: `E` as `u8`
+    let _ = 0 as S;
+          //^^^^^^ error: non-primitive cast: `i32` as `S`
+    let _ = x as &u16;
+          //^^^^^^^^^ error: non-primitive cast: `&u8` as `&u16`
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn references() {
+        check_diagnostics(
+            r#"
+struct S;
+fn f(x: &u8, s: &S, a: &[u8; 2]) {
+    let _ = x as u32;
+          //^^^^^^^^ error: casting `&u8` as `u32` is invalid: needs a dereference
+    let _ = s as usize;
+          //^^^^^^^^^^ error: casting `&S` as `usize` is invalid: needs casting through a raw pointer first
+    let _ = a as *mut u8;
+          //^^^^^^^^^^^^ error: casting `&[u8; 2]` as `*mut u8` is invalid
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn pointers() {
+        check_diagnostics(
+            r#"
+//- minicore: sized
+trait Trait {}
+fn f(p: *const u8, s: *const [u8], d: *const dyn Trait, b: bool) {
+    let _ = p as *const [u8];
+          //^^^^^^^^^^^^^^^^ error: cannot cast thin pointer `*const u8` to fat pointer `*const [u8]`
+    let _ = s as *const dyn Trait;
+          //^^^^^^^^^^^^^^^^^^^^^ error: casting `*const [u8]` as `*const dyn Trait` is invalid: vtable kinds may not match
+    let _ = s as usize;
+          //^^^^^^^^^^ error: casting `*const [u8]` as `usize` is invalid: needs casting through a thin pointer first
+    let _ = 0usize as *const [u8];
+          //^^^^^^^^^^^^^^^^^^^^^ error: cannot cast `usize` to a fat pointer `*const [u8]`
+    let _ = b as *const u8;
+          //^^^^^^^^^^^^^^ error: casting `bool` as `*const u8` is invalid
+    let _ = p as f32;
+          //^^^^^^^^ error: casting `*const u8` as `f32` is invalid
+    let _ = d as *const u8;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn type_params() {
+        check_diagnostics(
+            r#"
+//- minicore: sized
+fn f<T, U: ?Sized, V: ?Sized>(t: *const T, u: *const U) {
+    let _ = t as *const u8;
+    let _ = t as usize;
+    let _ = u as *const u8;
+    let _ = u as *const V;
+          //^^^^^^^^^^^^^ error: casting `*const U` as `*const V` is invalid: vtable kinds may not match
+    let _ = u as usize;
+          //^^^^^^^^^^ error: casting `*const U` as `usize` is invalid: needs casting through a thin pointer first
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn replace_with_from() {
+        check_fix(
+            r#"
+//- minicore: from
+struct S<T>(T);
+impl From<u8> for S<u8> {
+    fn from(n: u8) -> Self { S(n) }
+}
+fn f() {
+    let _ = 0u8 as S<u8>$0;
+}
+"#,
+            r#"
+struct S<T>(T);
+impl From<u8> for S<u8> {
+    fn from(n: u8) -> Self { S(n) }
+}
+fn f() {
+    let _ = <S<u8>>::from(0u8);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn replace_with_try_from() {
+        check_fix(
+            r#"
+//- minicore: try_from
+struct Small(u8);
+impl TryFrom<u32> for Small {
+    type Error = ();
+    fn try_from(n: u32) -> Result<Self, ()> { Err(()) }
+}
+fn f(n: u32) -> Result<(), ()> {
+    let _ = n as Small$0;
+    Ok(())
+}
+"#,
+            r#"
+struct Small(u8);
+impl TryFrom<u32> for Small {
+    type Error = ();
+    fn try_from(n: u32) -> Result<Self, ()> { Err(()) }
+}
+fn f(n: u32) -> Result<(), ()> {
+    let _ = Small::try_from(n)?;
+    Ok(())
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn try_from_keeps_the_cast_type() {
+        check_fix(
+            r#"
+//- minicore: try_from, try
+struct Small(u8);
+impl TryFrom<u32> for Small {
+    type Error = ();
+    fn try_from(n: u32) -> Result<Self, ()> { Err(()) }
+}
+fn f(n: u32) -> Result<Small, ()> {
+    let s: Small = n as Small$0;
+    Ok(s)
+}
+"#,
+            r#"
+struct Small(u8);
+impl TryFrom<u32> for Small {
+    type Error = ();
+    fn try_from(n: u32) -> Result<Self, ()> { Err(()) }
+}
+fn f(n: u32) -> Result<Small, ()> {
+    let s: Small = Small::try_from(n)?;
+    Ok(s)
+}
+"#,
+        );
+        check_diagnostics(
+            r#"
+//- minicore: try_from, try
+struct Small(u8);
+impl TryFrom<u32> for Small {
+    type Error = ();
+    fn try_from(n: u32) -> Result<Self, ()> { Err(()) }
+}
+fn f(n: u32) -> Result<Small, ()> {
+    let s: Small = Small::try_from(n)?;
+    Ok(s)
+}
+fn g(n: u32) -> Result<Small, ()> {
+    let s: Small = Small::try_from(n);
+                 //^^^^^^^^^^^^^^^^^^ error: expected Small, found Result<Small, ()>
+    Ok(s)
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn no_try_from_without_compatible_result() {
+        check_no_fix(
+            r#"
+//- minicore: try_from
+struct Small(u8);
+impl TryFrom<u32> for Small {
+    type Error = ();
+    fn try_from(n: u32) -> Result<Self, ()> { Err(()) }
+}
+fn f(n: u32) -> Result<(), u8> {
+    let _ = n as Small$0;
+    Ok(())
+}
+fn g(n: u32) {
+    let _ = n as Small;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn no_fix_without_impl() {
+        check_no_fix(
+            r#"
+//- minicore: from
+struct S;
+fn f() {
+    let _ = 0u8 as S$0;
+}
+"#,
+        );
+    }
+}
@@ -0,0 +1,133 @@
+use hir::{db::AstDatabase, HirDisplay};
+use ide_db::{assists::Assist, source_change::SourceChange};
+use syntax::{ast, AstNode};
+use text_edit::TextEdit;
+
+use crate::{fix, Diagnostic, DiagnosticsContext, Severity};
+
+// Diagnostic: trivial-cast
+//
+// This diagnostic is triggered if an `as` cast converts an expression to the type it already has.
+// Such casts are not reported inside of macro expansions, where they are often needed for other
+// inputs of the macro, nor when the type is written through a type alias, which may be another
+// type on other targets.
+pub(crate) fn trivial_cast(
+    ctx: &DiagnosticsContext<'_>,
+    d: &hir::TrivialCast,
+) -> Option<Diagnostic> {
+    if d.expr.file_id.is_macro() {
+        return None;
+    }
+    let root = ctx.sema.db.parse_or_expand(d.expr.file_id)?;
+    let cast_expr = match d.expr.value.to_node(&root) {
+        ast::Expr::CastExpr(it) => it,
+        _ => return None,
+    };
+    let through_alias =
+        cast_expr.ty()?.syntax().descendants().filter_map(ast::Path::cast).any(|path| {
+            matches!(
+                ctx.sema.resolve_path(&path),
+                Some(hir::PathResolution::Def(hir::ModuleDef::TypeAlias(_)))
+            )
+        });
+    if through_alias {
+        return None;
+    }
+    let display_range =
+        ctx.sema.diagnostics_display_range(d.expr.clone().map(|it| it.into())).range;
+    let ty = d.ty.display(ctx.sema.db);
+    let is_numeric =
+        d.ty.as_builtin().map_or(false, |it| it.is_int() || it.is_uint() || it.is_float());
+    let kind = if is_numeric { "trivial numeric cast" } else { "trivial cast" };
+    Some(
+        Diagnostic::new("trivial-cast", format!("{}: `{}` as `{}`", kind, ty, ty), display_range)
+            .severity(Severity::WeakWarning)
+            .experimental()
+            .with_fixes(fixes(ctx, d, &cast_expr)),
+    )
+}
+
+fn fixes(
+    ctx: &DiagnosticsContext<'_>,
+    d: &hir::TrivialCast,
+    cast_expr: &ast::CastExpr,
+) -> Option<Vec<Assist>> {
+    let file_id = d.expr.file_id.original_file(ctx.sema.db);
+    let expr = cast_expr.expr()?;
+    let range = cast_expr.syntax().text_range();
+    Some(vec![fix(
+        "remove_trivial_cast",
+        "Remove unnecessary cast",
+        SourceChange::from_text_edit(file_id, TextEdit::replace(range, expr.to_string())),
+        range,
+    )])
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_diagnostics, check_fix};
+
+    #[test]
+    fn trivial_casts() {
+        check_diagnostics(
+            r#"
+struct S;
+fn f(n: u32, x: f64, s: &S) {
+    let _ = n as u32;
+          //^^^^^^^^ 💡 weak: trivial numeric cast: `u32` as `u32`
+    let _ = x as f64;
+          //^^^^^^^^ 💡 weak: trivial numeric cast: `f64` as `f64`
+    let _ = s as &S;
+          //^^^^^^^ 💡 weak: trivial cast: `&S` as `&S`
+    let _ = 1 as u32;
+    let _ = 1.0 as f64;
+    let _ = n as u64;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn in_macro() {
+        check_diagnostics(
+            r#"
+macro_rules! to_u64 {
+    ($e:expr) => { $e as u64 };
+}
+fn f(n: u64) {
+    let _ = to_u64!(n);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn through_type_alias() {
+        check_diagnostics(
+            r#"
+type Long = i64;
+fn f(n: i64) {
+    let _ = n as Long;
+    let _ = n as i64;
+          //^^^^^^^^ 💡 weak: trivial numeric cast: `i64` as `i64`
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn remove_trivial_cast() {
+        check_fix(
+            r#"
+fn f(a: u32, b: u32) {
+    let _ = (a + b) as u32$0 * 2;
+}
+"#,
+            r#"
+fn f(a: u32, b: u32) {
+    let _ = (a + b) * 2;
+}
+"#,
+        );
+    }
+}
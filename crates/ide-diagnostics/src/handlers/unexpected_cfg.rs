@@ -0,0 +1,334 @@
+use hir::{CheckCfg, ExpectedValues, Semantics};
+use ide_db::{base_db::FileId, source_change::SourceChange, RootDatabase};
+use itertools::Itertools;
+use syntax::{
+    ast, match_ast, AstNode, AstToken, NodeOrToken, SmolStr, SyntaxKind, SyntaxNode, SyntaxToken, T,
+};
+use text_edit::TextEdit;
+
+use crate::{fix, Diagnostic, Severity};
+
+// Diagnostic: unexpected-cfg
+//
+// This diagnostic is triggered if a `cfg` condition uses a name or a value the crate doesn't
+// expect, which usually is a typo that makes the annotated code silently inactive. The expected
+// names and values are the well-known ones, the features of the crate and those declared via
+// `cargo::rustc-check-cfg` in the build script.
+pub(crate) fn unexpected_cfg(
+    sema: &Semantics<'_, RootDatabase>,
+    acc: &mut Vec<Diagnostic>,
+    file_id: FileId,
+    node: &SyntaxNode,
+) -> Option<()> {
+    let (tt, only_first) = match_ast! {
+        match node {
+            ast::Attr(it) => {
+                let only_first = match &*it.simple_name()? {
+                    "cfg" => false,
+                    "cfg_attr" => true,
+                    _ => return None,
+                };
+                (it.token_tree()?, only_first)
+            },
+            ast::MacroCall(it) => {
+                if it.path()?.as_single_name_ref()?.text() != "cfg" {
+                    return None;
+                }
+                (it.token_tree()?, false)
+            },
+            _ => return None,
+        }
+    };
+    let krate = sema.to_module_def(file_id)?.krate();
+    let cfg = krate.cfg(sema.db);
+    let expected = cfg.expected()?;
+
+    let mut predicates = split_predicates(&tt);
+    if only_first {
+        predicates.truncate(1);
+    }
+    for predicate in predicates {
+        check_predicate(acc, file_id, expected, &predicate);
+    }
+    Some(())
+}
+
+fn check_predicate(
+    acc: &mut Vec<Diagnostic>,
+    file_id: FileId,
+    expected: &CheckCfg,
+    predicate: &[NodeOrToken<ast::TokenTree, SyntaxToken>],
+) {
+    let (name, value) = match predicate {
+        [NodeOrToken::Token(name), NodeOrToken::Node(args)]
+            if matches!(name.text(), "all" | "any" | "not") =>
+        {
+            for predicate in split_predicates(args) {
+                check_predicate(acc, file_id, expected, &predicate);
+            }
+            return;
+        }
+        [NodeOrToken::Token(name)] if name.kind() == SyntaxKind::IDENT => (name, None),
+        [NodeOrToken::Token(name), NodeOrToken::Token(eq), NodeOrToken::Token(value)]
+            if name.kind() == SyntaxKind::IDENT && eq.kind() == T![=] =>
+        {
+            match ast::String::cast(value.clone()) {
+                Some(it) => (name, Some(it)),
+                None => return,
+            }
+        }
+        _ => return,
+    };
+    let value_text = match &value {
+        Some(it) => match it.value() {
+            Some(it) => Some(SmolStr::new(it)),
+            None => return,
+        },
+        None => None,
+    };
+
+    if !expected.is_expected_name(name.text()) {
+        let candidates = closest(
+            name.text(),
+            expected
+                .names()
+                .filter(|candidate| expected.is_expected(candidate, value_text.as_deref())),
+        );
+        let fixes = candidates
+            .into_iter()
+            .map(|candidate| {
+                fix(
+                    "change_cfg_name",
+                    &format!("Change to `{}`", candidate),
+                    SourceChange::from_text_edit(
+                        file_id,
+                        TextEdit::replace(name.text_range(), candidate.to_string()),
+                    ),
+                    name.text_range(),
+                )
+            })
+            .collect_vec();
+        acc.push(
+            Diagnostic::new(
+                "unexpected-cfg",
+                format!("unexpected `cfg` condition name: `{}`", name.text()),
+                name.text_range(),
+            )
+            .severity(Severity::WeakWarning)
+            .with_fixes((!fixes.is_empty()).then(|| fixes)),
+        );
+        return;
+    }
+    if expected.is_expected(name.text(), value_text.as_deref()) {
+        return;
+    }
+
+    let (range, fixes) = match (&value, &value_text) {
+        (Some(value), Some(value_text)) => {
+            let values = expected.values(name.text());
+            let values = match &values {
+                Some(ExpectedValues::Some(values)) => values.iter().flatten().collect_vec(),
+                _ => Vec::new(),
+            };
+            let fixes = closest(value_text, values.into_iter())
+                .into_iter()
+                .map(|candidate| {
+                    fix(
+                        "change_cfg_value",
+                        &format!("Change to `\"{}\"`", candidate),
+                        SourceChange::from_text_edit(
+                            file_id,
+                            TextEdit::replace(
+                                value.syntax().text_range(),
+                                format!("\"{}\"", candidate),
+                            ),
+                        ),
+                        value.syntax().text_range(),
+                    )
+                })
+                .collect_vec();
+            (value.syntax().text_range(), fixes)
+        }
+        _ => (name.text_range(), Vec::new()),
+    };
+    let value_display = match &value_text {
+        Some(it) => format!("`{}`", it),
+        None => "(none)".to_owned(),
+    };
+    acc.push(
+        Diagnostic::new(
+            "unexpected-cfg",
+            format!("unexpected `cfg` condition value: {} for `{}`", value_display, name.text()),
+            range,
+        )
+        .severity(Severity::WeakWarning)
+        .with_fixes((!fixes.is_empty()).then(|| fixes)),
+    );
+}
+
+/// Splits the contents of `tt` into its comma separated parts, skipping the delimiters and trivia.
+fn split_predicates(tt: &ast::TokenTree) -> Vec<Vec<NodeOrToken<ast::TokenTree, SyntaxToken>>> {
+    let l_delim = tt.left_delimiter_token();
+    let r_delim = tt.right_delimiter_token();
+    let mut res = vec![Vec::new()];
+    for it in tt.token_trees_and_tokens() {
+        match &it {
+            NodeOrToken::Token(token)
+                if token.kind().is_trivia()
+                    || Some(token) == l_delim.as_ref()
+                    || Some(token) == r_delim.as_ref() => {}
+            NodeOrToken::Token(token) if token.kind() == T![,] => res.push(Vec::new()),
+            _ => res.last_mut().unwrap().push(it),
+        }
+    }
+    res.retain(|it| !it.is_empty());
+    res
+}
+
+/// Returns the candidates closest to `text`, if they are close enough to likely be a typo.
+fn closest<'a>(text: &str, candidates: impl Iterator<Item = &'a SmolStr>) -> Vec<&'a SmolStr> {
+    let max_distance = (text.chars().count() / 3).max(1);
+    candidates
+        .map(|candidate| (typo_distance(text, candidate), candidate))
+        .filter(|&(distance, _)| distance <= max_distance)
+        .sorted()
+        .take(3)
+        .map(|(_, candidate)| candidate)
+        .collect()
+}
+
+/// Like [`stdx::edit_distance`], but swapping two adjacent chars counts as a
+/// single edit (optimal string alignment), so that `sered` is one typo away
+/// from `serde`.
+fn typo_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut prev_prev = vec![0; b.len() + 1];
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut cur = vec![0; b.len() + 1];
+    for i in 1..=a.len() {
+        cur[0] = i;
+        for j in 1..=b.len() {
+            let substitution = prev[j - 1] + usize::from(a[i - 1] != b[j - 1]);
+            cur[j] = substitution.min(prev[j] + 1).min(cur[j - 1] + 1);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                cur[j] = cur[j].min(prev_prev[j - 2] + 1);
+            }
+        }
+        std::mem::swap(&mut prev_prev, &mut prev);
+        std::mem::swap(&mut prev, &mut cur);
+    }
+    prev[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_diagnostics, check_fix};
+
+    #[test]
+    fn expected_cfgs() {
+        check_diagnostics(
+            r#"
+//- /lib.rs crate:foo cfg:feature=std check-cfg:feature=serde,has_foo,target_os=linux
+#[cfg(all(feature = "serde", not(feature = "std")))]
+fn serde() {}
+#[cfg(any(test, unix, target_os = "linux", has_foo))]
+fn test() {}
+#[cfg_attr(debug_assertions, derive(Whatever))]
+struct S;
+#[rustc_builtin_macro]
+macro_rules! cfg {}
+fn f() {
+    if cfg!(target_pointer_width = "64") {}
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn no_expectations() {
+        check_diagnostics(
+            r#"
+//- /lib.rs crate:foo cfg:feature=std
+#[cfg(featrue = "std")]
+fn f() {}
+"#,
+        );
+    }
+
+    #[test]
+    fn unexpected_name() {
+        check_diagnostics(
+            r#"
+//- /lib.rs crate:foo check-cfg:feature=serde
+#[cfg(featrue = "serde")]
+    //^^^^^^^ 💡 weak: unexpected `cfg` condition name: `featrue`
+fn f() {}
+#[cfg(any(unix, my_cfg))]
+              //^^^^^^ weak: unexpected `cfg` condition name: `my_cfg`
+fn g() {}
+#[rustc_builtin_macro]
+macro_rules! cfg {}
+fn h() {
+    if cfg!(tes) {}
+          //^^^ 💡 weak: unexpected `cfg` condition name: `tes`
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn unexpected_value() {
+        check_diagnostics(
+            r#"
+//- /lib.rs crate:foo check-cfg:feature=serde,target_os=linux
+#[cfg_attr(feature = "sered", derive(Whatever))]
+                   //^^^^^^^ 💡 weak: unexpected `cfg` condition value: `sered` for `feature`
+struct S;
+#[cfg(test = "yes")]
+           //^^^^^ weak: unexpected `cfg` condition value: `yes` for `test`
+fn f() {}
+#[cfg(target_os)]
+    //^^^^^^^^^ weak: unexpected `cfg` condition value: (none) for `target_os`
+fn g() {}
+"#,
+        );
+    }
+
+    #[test]
+    fn did_you_mean() {
+        check_fix(
+            r#"
+//- /lib.rs crate:foo check-cfg:feature=serde
+#[cfg(not(featrue$0 = "serde"))]
+fn f() {}
+"#,
+            r#"
+#[cfg(not(feature = "serde"))]
+fn f() {}
+"#,
+        );
+        check_fix(
+            r#"
+//- /lib.rs crate:foo check-cfg:feature=serde
+#[cfg(not(feature = "sered"$0))]
+fn f() {}
+"#,
+            r#"
+#[cfg(not(feature = "serde"))]
+fn f() {}
+"#,
+        );
+        check_fix(
+            r#"
+//- /lib.rs crate:foo check-cfg:target_os=linux,target_os=windows
+#[cfg(not(target_os = "linx"$0))]
+fn f() {}
+"#,
+            r#"
+#[cfg(not(target_os = "linux"))]
+fn f() {}
+"#,
+        );
+    }
+}
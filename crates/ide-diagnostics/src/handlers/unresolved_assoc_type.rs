@@ -0,0 +1,88 @@
+use syntax::{ast, AstNode};
+
+use crate::{adjusted_display_range, path_segment_named, Diagnostic, DiagnosticsContext};
+
+// Diagnostic: unresolved-assoc-type
+//
+// This diagnostic is triggered if a path refers to an associated type that none of the traits it
+// is looked up in define.
+pub(crate) fn unresolved_assoc_type(
+    ctx: &DiagnosticsContext<'_>,
+    d: &hir::UnresolvedAssocType,
+) -> Diagnostic {
+    let range =
+        adjusted_display_range::<ast::Path>(ctx, d.path.clone().map(|it| it.into()), &|path| {
+            Some(path_segment_named(&path, &d.name)?.syntax().text_range())
+        });
+    Diagnostic::new(
+        "unresolved-assoc-type",
+        format!("associated type `{}` not found", d.name),
+        range,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::check_diagnostics;
+
+    #[test]
+    fn type_param() {
+        check_diagnostics(
+            r#"
+trait Trait {
+    type Assoc;
+}
+fn f<T: Trait>(t: T::Assoc, u: T::Missing) {}
+                                //^^^^^^^ error: associated type `Missing` not found
+fn g<T>(t: T::Assoc) {}
+            //^^^^^ error: associated type `Assoc` not found
+"#,
+        );
+    }
+
+    #[test]
+    fn qualified_path() {
+        check_diagnostics(
+            r#"
+trait Trait {
+    type Assoc;
+}
+struct S;
+impl Trait for S {
+    type Assoc = ();
+}
+fn f(t: <S as Trait>::Assoc, u: <S as Trait>::Missing) {}
+                                            //^^^^^^^ error: associated type `Missing` not found
+"#,
+        );
+    }
+
+    #[test]
+    fn self_in_trait_and_impl() {
+        check_diagnostics(
+            r#"
+trait Super {
+    type Inherited;
+}
+trait Trait: Super {
+    type Assoc;
+    fn f(&self) -> (Self::Assoc, Self::Inherited);
+}
+struct S;
+impl Super for S {
+    type Inherited = ();
+}
+impl Trait for S {
+    type Assoc = ();
+    fn f(&self) -> (Self::Assoc, Self::Inherited) { ((), ()) }
+}
+impl S {
+    fn g(&self) {
+        let _: <Self as Trait>::Other;
+                              //^^^^^ error: associated type `Other` not found
+    }
+}
+"#,
+        );
+    }
+}
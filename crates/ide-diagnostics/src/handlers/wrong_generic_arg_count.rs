@@ -0,0 +1,174 @@
+use syntax::{ast, AstNode};
+
+use crate::{adjusted_display_range, path_segment_named, Diagnostic, DiagnosticsContext};
+
+// Diagnostic: wrong-generic-arg-count
+//
+// This diagnostic is triggered if a path segment is given more generic arguments than the item it
+// refers to declares, or fewer than it requires.
+pub(crate) fn wrong_generic_arg_count(
+    ctx: &DiagnosticsContext<'_>,
+    d: &hir::WrongGenericArgCount,
+) -> Diagnostic {
+    let s = |n| if n == 1 { "" } else { "s" };
+    let expected = if d.min == d.max {
+        format!("{}", d.max)
+    } else if d.provided < d.min {
+        format!("at least {}", d.min)
+    } else {
+        format!("at most {}", d.max)
+    };
+    let n = if d.provided < d.min { d.min } else { d.max };
+    let message = format!("expected {} generic argument{}, found {}", expected, s(n), d.provided);
+    let range =
+        adjusted_display_range::<ast::Path>(ctx, d.path.clone().map(|it| it.into()), &|path| {
+            let segment = path_segment_named(&path, &d.segment)?;
+            match segment.generic_arg_list() {
+                Some(it) => Some(it.syntax().text_range()),
+                None => Some(segment.syntax().text_range()),
+            }
+        });
+    Diagnostic::new("wrong-generic-arg-count", message, range)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::check_diagnostics;
+
+    #[test]
+    fn too_many_args() {
+        check_diagnostics(
+            r#"
+//- minicore: future
+struct S<T>(T);
+fn f(s: S<u8, u16>) {}
+       //^^^^^^^^^ error: expected 1 generic argument, found 2
+struct Wrapper {
+    field: S<u8, u8, u8>,
+          //^^^^^^^^^^^^ error: expected 1 generic argument, found 3
+}
+type Alias = S<u8, u8>;
+            //^^^^^^^^ error: expected 1 generic argument, found 2
+async fn g() -> S<u8, u8> { loop {} }
+               //^^^^^^^^ error: expected 1 generic argument, found 2
+"#,
+        );
+    }
+
+    #[test]
+    fn too_few_args() {
+        check_diagnostics(
+            r#"
+struct S<T, U, V = ()>(T, U, V);
+fn f(s: S<u8>) {}
+       //^^^^ error: expected at least 2 generic arguments, found 1
+fn g(s: S) {}
+      //^ error: expected at least 2 generic arguments, found 0
+fn h(s: S<u8, u8>, t: S<u8, u8, u8>) {}
+"#,
+        );
+    }
+
+    #[test]
+    fn defaults() {
+        check_diagnostics(
+            r#"
+struct S<T = u8>(T);
+fn f(s: S, t: S<u16>, u: S<u8, u8>) {}
+                        //^^^^^^^^ error: expected at most 1 generic argument, found 2
+"#,
+        );
+    }
+
+    #[test]
+    fn lifetimes_and_consts() {
+        check_diagnostics(
+            r#"
+struct S<'a, T, const N: usize>(&'a [T; N]);
+fn f<'a>(s: S<'a, u8, 3>) {}
+fn g<'a>(s: S<'a, u8>) {}
+           //^^^^^^^^ error: expected 2 generic arguments, found 1
+"#,
+        );
+    }
+
+    #[test]
+    fn in_body() {
+        check_diagnostics(
+            r#"
+struct S<T>(T);
+impl<T> S<T> {
+    fn new(t: T) -> Self { S(t) }
+}
+fn id<T>(t: T) -> T { t }
+fn f() {
+    let _: S<u8, u8> = S(0);
+          //^^^^^^^^ error: expected 1 generic argument, found 2
+    let _ = S::<u8, u8>::new(0);
+           //^^^^^^^^^^ error: expected 1 generic argument, found 2
+    let _ = id::<u8, u8>(0);
+            //^^^^^^^^^^ error: expected 1 generic argument, found 2
+    let _ = S::new(0);
+    let _ = id(0);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn repeated_paths() {
+        check_diagnostics(
+            r#"
+struct S<T>(T);
+struct W<T>(T);
+fn f(s: (W<S<u8, u8>>, W<S<u8>>, W<S<u8, u8>>)) {}
+          //^^^^^^^^ error: expected 1 generic argument, found 2
+                                  //^^^^^^^^ error: expected 1 generic argument, found 2
+"#,
+        );
+    }
+
+    #[test]
+    fn in_type_macro() {
+        check_diagnostics(
+            r#"
+struct S<T>(T);
+macro_rules! id {
+    ($t:ty) => { $t };
+}
+fn f(s: id!(S<u8, u8>)) {
+          //^^^^^^^^^ error: expected 1 generic argument, found 2
+    let _: id!(S<u8, u16>) = s;
+             //^^^^^^^^^^ error: expected 1 generic argument, found 2
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn in_trait_objects() {
+        check_diagnostics(
+            r#"
+//- /lib.rs edition:2015
+trait Trait<T> {}
+fn f(a: &dyn Trait<u8, u8>, b: &Trait<u8, u16>) {}
+                //^^^^^^^^ error: expected 1 generic argument, found 2
+                                   //^^^^^^^^^ error: expected 1 generic argument, found 2
+"#,
+        );
+    }
+
+    #[test]
+    fn in_impl_and_trait() {
+        check_diagnostics(
+            r#"
+struct S<T>(T);
+trait Trait<T> {}
+impl Trait<u8, u8> for S<u8> {}
+        //^^^^^^^^ error: expected 1 generic argument, found 2
+impl<T> S<T, T> {}
+       //^^^^^^ error: expected 1 generic argument, found 2
+"#,
+        );
+    }
+}
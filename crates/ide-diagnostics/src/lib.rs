@@ -26,9 +26,12 @@
 #![warn(rust_2018_idioms, unused_lifetimes, semicolon_in_expressions_from_macros)]
 
 mod handlers {
+    pub(crate) mod ambiguous_assoc_type;
     pub(crate) mod break_outside_of_loop;
+    pub(crate) mod cast_to_unsized;
     pub(crate) mod inactive_code;
     pub(crate) mod incorrect_case;
+    pub(crate) mod invalid_cast;
     pub(crate) mod invalid_derive_target;
     pub(crate) mod macro_error;
     pub(crate) mod malformed_derive;
@@ -38,16 +41,21 @@ mod handlers {
     pub(crate) mod missing_unsafe;
     pub(crate) mod no_such_field;
     pub(crate) mod replace_filter_map_next_with_find_map;
+    pub(crate) mod trivial_cast;
     pub(crate) mod type_mismatch;
     pub(crate) mod unimplemented_builtin_macro;
+    pub(crate) mod unresolved_assoc_type;
     pub(crate) mod unresolved_extern_crate;
     pub(crate) mod unresolved_import;
     pub(crate) mod unresolved_macro_call;
     pub(crate) mod unresolved_module;
     pub(crate) mod unresolved_proc_macro;
+    pub(crate) mod wrong_generic_arg_count;
 
     // The handlers below are unusual, the implement the diagnostics as well.
+    pub(crate) mod broken_intra_doc_link;
     pub(crate) mod field_shorthand;
+    pub(crate) mod unexpected_cfg;
     pub(crate) mod useless_braces;
     pub(crate) mod unlinked_file;
     pub(crate) mod json_is_not_rust;
@@ -56,6 +64,8 @@ mod handlers {
 #[cfg(test)]
 mod tests;
 
+use std::iter;
+
 use hir::{diagnostics::AnyDiagnostic, InFile, Semantics};
 use ide_db::{
     assists::{Assist, AssistId, AssistKind, AssistResolveStrategy},
@@ -65,7 +75,11 @@ use ide_db::{
     source_change::SourceChange,
     FxHashSet, RootDatabase,
 };
-use syntax::{algo::find_node_at_range, ast::AstNode, SyntaxNodePtr, TextRange};
+use syntax::{
+    algo::find_node_at_range,
+    ast::{self, AstNode},
+    SyntaxNodePtr, TextRange,
+};
 
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub struct DiagnosticCode(pub &'static str);
@@ -204,6 +218,8 @@ pub fn diagnostics(
         handlers::useless_braces::useless_braces(&mut res, file_id, &node);
         handlers::field_shorthand::field_shorthand(&mut res, file_id, &node);
         handlers::json_is_not_rust::json_in_items(&sema, &mut res, file_id, &node, &config);
+        handlers::broken_intra_doc_link::broken_intra_doc_link(&sema, &mut res, file_id, &node);
+        handlers::unexpected_cfg::unexpected_cfg(&sema, &mut res, file_id, &node);
     }
 
     let module = sema.to_module_def(file_id);
@@ -221,8 +237,11 @@ pub fn diagnostics(
     for diag in diags {
         #[rustfmt::skip]
         let d = match diag {
+            AnyDiagnostic::AmbiguousAssocType(d) => handlers::ambiguous_assoc_type::ambiguous_assoc_type(&ctx, &d),
             AnyDiagnostic::BreakOutsideOfLoop(d) => handlers::break_outside_of_loop::break_outside_of_loop(&ctx, &d),
+            AnyDiagnostic::CastToUnsized(d) => handlers::cast_to_unsized::cast_to_unsized(&ctx, &d),
             AnyDiagnostic::IncorrectCase(d) => handlers::incorrect_case::incorrect_case(&ctx, &d),
+            AnyDiagnostic::InvalidCast(d) => handlers::invalid_cast::invalid_cast(&ctx, &d),
             AnyDiagnostic::MacroError(d) => handlers::macro_error::macro_error(&ctx, &d),
             AnyDiagnostic::MalformedDerive(d) => handlers::malformed_derive::malformed_derive(&ctx, &d),
             AnyDiagnostic::MismatchedArgCount(d) => handlers::mismatched_arg_count::mismatched_arg_count(&ctx, &d),
@@ -233,17 +252,23 @@ pub fn diagnostics(
             AnyDiagnostic::ReplaceFilterMapNextWithFindMap(d) => handlers::replace_filter_map_next_with_find_map::replace_filter_map_next_with_find_map(&ctx, &d),
             AnyDiagnostic::TypeMismatch(d) => handlers::type_mismatch::type_mismatch(&ctx, &d),
             AnyDiagnostic::UnimplementedBuiltinMacro(d) => handlers::unimplemented_builtin_macro::unimplemented_builtin_macro(&ctx, &d),
+            AnyDiagnostic::UnresolvedAssocType(d) => handlers::unresolved_assoc_type::unresolved_assoc_type(&ctx, &d),
             AnyDiagnostic::UnresolvedExternCrate(d) => handlers::unresolved_extern_crate::unresolved_extern_crate(&ctx, &d),
             AnyDiagnostic::UnresolvedImport(d) => handlers::unresolved_import::unresolved_import(&ctx, &d),
             AnyDiagnostic::UnresolvedMacroCall(d) => handlers::unresolved_macro_call::unresolved_macro_call(&ctx, &d),
             AnyDiagnostic::UnresolvedModule(d) => handlers::unresolved_module::unresolved_module(&ctx, &d),
             AnyDiagnostic::UnresolvedProcMacro(d) => handlers::unresolved_proc_macro::unresolved_proc_macro(&ctx, &d, config.proc_macros_enabled, config.proc_attr_macros_enabled),
             AnyDiagnostic::InvalidDeriveTarget(d) => handlers::invalid_derive_target::invalid_derive_target(&ctx, &d),
+            AnyDiagnostic::WrongGenericArgCount(d) => handlers::wrong_generic_arg_count::wrong_generic_arg_count(&ctx, &d),
 
             AnyDiagnostic::InactiveCode(d) => match handlers::inactive_code::inactive_code(&ctx, &d) {
                 Some(it) => it,
                 None => continue,
             }
+            AnyDiagnostic::TrivialCast(d) => match handlers::trivial_cast::trivial_cast(&ctx, &d) {
+                Some(it) => it,
+                None => continue,
+            }
         };
         res.push(d)
     }
@@ -274,6 +299,14 @@ fn unresolved_fix(id: &'static str, label: &str, target: TextRange) -> Assist {
     }
 }
 
+/// Returns the segment of `path` or of one of its qualifiers that is called `name`.
+fn path_segment_named(path: &ast::Path, name: &hir::Name) -> Option<ast::PathSegment> {
+    let name = name.to_smol_str();
+    iter::successors(Some(path.clone()), |it| it.qualifier())
+        .filter_map(|it| it.segment())
+        .find(|it| it.name_ref().map_or(false, |it| it.text() == name.as_str()))
+}
+
 fn adjusted_display_range<N: AstNode>(
     ctx: &DiagnosticsContext<'_>,
     diag_ptr: InFile<SyntaxNodePtr>,
@@ -0,0 +1,587 @@
+//! Changing the parameter list of a function, together with all of its callers.
+//!
+//! Call sites are rewritten argument by argument, and arguments may themselves contain calls that
+//! need rewriting, so the edits are collected as (possibly nested) replacements first and only
+//! flattened into a [`TextEdit`] once everything has been collected.
+
+use hir::{AsAssocItem, AssocItem, Impl, Semantics};
+use ide_db::{
+    base_db::{FileId, FilePosition, SourceDatabaseExt},
+    defs::{Definition, NameRefClass},
+    rename::{bail, format_err, source_edit_from_references, IdentifierKind, RenameError},
+    search::FileReference,
+    source_change::SourceChange,
+    FxHashMap, FxHashSet, LineIndexDatabase, RootDatabase,
+};
+use itertools::Itertools;
+use syntax::{
+    algo::find_node_at_offset,
+    ast::{self, HasArgList, HasName},
+    match_ast, AstNode, TextRange,
+};
+use text_edit::TextEdit;
+
+type ChangeSignatureResult<T> = Result<T, RenameError>;
+
+/// A parameter of the new signature.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignatureParam {
+    /// Index of the parameter in the old signature, not counting `self`, or `None` if the
+    /// parameter is new.
+    pub old_index: Option<usize>,
+    pub name: String,
+    pub ty: String,
+    /// The argument to pass at existing call sites, required for new parameters.
+    pub default_value: Option<String>,
+}
+
+// Feature: Change Signature
+//
+// Reorders, adds, removes, renames and retypes the parameters of the function under the cursor.
+// The definition, the corresponding trait declaration and trait impls, and all call sites
+// (including method calls and calls in UFCS form) are updated accordingly. Renamed parameters are
+// renamed in the function bodies as well.
+//
+// Removing a parameter that is still used in a function body is an error, as is adding one without
+// a default value to pass at the existing call sites, or a call site with the wrong number of
+// arguments.
+//
+// This is exposed through the `experimental/changeSignature` LSP request.
+pub(crate) fn change_signature(
+    db: &RootDatabase,
+    position: FilePosition,
+    new_params: &[SignatureParam],
+) -> ChangeSignatureResult<SourceChange> {
+    let sema = Semantics::new(db);
+    let function = find_function(&sema, position)?;
+    let old_param_count = function.assoc_fn_params(db).len() - function.has_self_param(db) as usize;
+    validate(new_params, old_param_count)?;
+
+    let mut replacements: FxHashMap<FileId, Vec<Replacement>> = FxHashMap::default();
+    for function in related_functions(db, function) {
+        update_definition(&sema, function, new_params, old_param_count, &mut replacements)?;
+        update_call_sites(&sema, function, new_params, old_param_count, &mut replacements)?;
+    }
+
+    let mut source_change = SourceChange::default();
+    for (file_id, mut replacements) in replacements {
+        replacements.sort_by_key(|it| (it.range.start(), std::cmp::Reverse(it.range.end())));
+        replacements.dedup_by_key(|it| it.range);
+        let text = db.file_text(file_id);
+        let mut edit = TextEdit::builder();
+        let mut offset = 0.into();
+        for replacement in &replacements {
+            // Nested replacements are rendered as part of their parent.
+            if replacement.range.start() < offset {
+                continue;
+            }
+            edit.replace(replacement.range, replacement.render(&text, &replacements));
+            offset = replacement.range.end();
+        }
+        source_change.insert_source_edit(file_id, edit.finish());
+    }
+    Ok(source_change)
+}
+
+fn find_function(
+    sema: &Semantics<'_, RootDatabase>,
+    position: FilePosition,
+) -> ChangeSignatureResult<hir::Function> {
+    let source_file = sema.parse(position.file_id);
+    let syntax = source_file.syntax();
+    if let Some(name_ref) = find_node_at_offset::<ast::NameRef>(syntax, position.offset) {
+        if let Some(NameRefClass::Definition(Definition::Function(it))) =
+            NameRefClass::classify(sema, &name_ref)
+        {
+            return Ok(it);
+        }
+    }
+    find_node_at_offset::<ast::Fn>(syntax, position.offset)
+        .filter(|it| {
+            it.body().map_or(true, |body| !body.syntax().text_range().contains(position.offset))
+        })
+        .and_then(|it| sema.to_def(&it))
+        .ok_or_else(|| format_err!("No function found at position"))
+}
+
+fn validate(new_params: &[SignatureParam], old_param_count: usize) -> ChangeSignatureResult<()> {
+    let mut names = FxHashSet::default();
+    let mut old_indices = FxHashSet::default();
+    for param in new_params {
+        match IdentifierKind::classify(&param.name)? {
+            IdentifierKind::Ident | IdentifierKind::Underscore => (),
+            IdentifierKind::Lifetime => bail!("Invalid parameter name `{}`", param.name),
+        }
+        if param.name != "_" && !names.insert(param.name.as_str()) {
+            bail!("Duplicate parameter name `{}`", param.name);
+        }
+        if param.ty.trim().is_empty() {
+            bail!("Missing type for parameter `{}`", param.name);
+        }
+        if let Some(idx) = param.old_index {
+            if idx >= old_param_count {
+                bail!("Function has no parameter with index {}", idx);
+            }
+            if !old_indices.insert(idx) {
+                bail!("Parameter with index {} is used more than once", idx);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Returns the function itself, or for trait methods the declaration and all its implementations.
+fn related_functions(db: &RootDatabase, function: hir::Function) -> Vec<hir::Function> {
+    let trait_ = function
+        .as_assoc_item(db)
+        .and_then(|it| it.containing_trait(db).or_else(|| it.containing_trait_impl(db)));
+    let trait_ = match trait_ {
+        Some(it) => it,
+        None => return vec![function],
+    };
+    let name = function.name(db);
+    let find = |items: Vec<AssocItem>| {
+        items.into_iter().find_map(|it| match it {
+            AssocItem::Function(f) if f.name(db) == name => Some(f),
+            _ => None,
+        })
+    };
+    std::iter::once(find(trait_.items(db)))
+        .chain(Impl::all_for_trait(db, trait_).into_iter().map(|it| find(it.items(db))))
+        .flatten()
+        .collect()
+}
+
+fn update_definition(
+    sema: &Semantics<'_, RootDatabase>,
+    function: hir::Function,
+    new_params: &[SignatureParam],
+    old_param_count: usize,
+    replacements: &mut FxHashMap<FileId, Vec<Replacement>>,
+) -> ChangeSignatureResult<()> {
+    let db = sema.db;
+    let fn_name = function.name(db);
+    let src = sema.source(function).ok_or_else(|| format_err!("No source for `{}`", fn_name))?;
+    if src.file_id.is_macro() {
+        bail!("Cannot change the signature of `{}`, as it is defined by a macro", fn_name);
+    }
+    let file_id = src.file_id.original_file(db);
+    let param_list =
+        src.value.param_list().ok_or_else(|| format_err!("`{}` has no parameters", fn_name))?;
+    let old_params = param_list.params().collect_vec();
+    if old_params.len() != old_param_count {
+        bail!("The parameters of `{}` do not match its trait declaration", fn_name);
+    }
+
+    let mut pieces = vec![Piece::Text("(".to_owned())];
+    if let Some(self_param) = param_list.self_param() {
+        pieces.push(Piece::Source(self_param.syntax().text_range()));
+        if !new_params.is_empty() {
+            pieces.push(Piece::Text(", ".to_owned()));
+        }
+    }
+    for (idx, new_param) in new_params.iter().enumerate() {
+        if idx > 0 {
+            pieces.push(Piece::Text(", ".to_owned()));
+        }
+        let pat = match new_param.old_index.and_then(|it| old_params[it].pat()) {
+            Some(ast::Pat::IdentPat(pat)) => {
+                let mut_ = if pat.mut_token().is_some() { "mut " } else { "" };
+                format!("{}{}", mut_, new_param.name)
+            }
+            // Destructuring patterns are kept as they are.
+            Some(pat) => pat.to_string(),
+            None => new_param.name.clone(),
+        };
+        pieces.push(Piece::Text(format!("{}: {}", pat, new_param.ty)));
+    }
+    pieces.push(Piece::Text(")".to_owned()));
+    replacements
+        .entry(file_id)
+        .or_default()
+        .push(Replacement { range: param_list.syntax().text_range(), pieces });
+
+    if src.value.body().is_none() {
+        return Ok(());
+    }
+    for (idx, old_param) in old_params.iter().enumerate() {
+        let pat = match old_param.pat() {
+            Some(ast::Pat::IdentPat(it)) => it,
+            _ => continue,
+        };
+        let local = match sema.to_def(&pat) {
+            Some(it) => it,
+            None => continue,
+        };
+        let def = Definition::Local(local);
+        let new_param = new_params.iter().find(|it| it.old_index == Some(idx));
+        match new_param {
+            Some(new_param) => {
+                if pat.name().map_or(true, |it| it.text() == new_param.name.as_str()) {
+                    continue;
+                }
+                for (file_id, references) in def.usages(sema).all() {
+                    let edit = source_edit_from_references(&references, def, &new_param.name);
+                    replacements.entry(file_id).or_default().extend(edit.into_iter().map(
+                        |indel| Replacement {
+                            range: indel.delete,
+                            pieces: vec![Piece::Text(indel.insert)],
+                        },
+                    ));
+                }
+            }
+            None => {
+                if !def.usages(sema).all().is_empty() {
+                    bail!("Parameter `{}` is still used in the body of `{}`", pat, fn_name);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn update_call_sites(
+    sema: &Semantics<'_, RootDatabase>,
+    function: hir::Function,
+    new_params: &[SignatureParam],
+    old_param_count: usize,
+    replacements: &mut FxHashMap<FileId, Vec<Replacement>>,
+) -> ChangeSignatureResult<()> {
+    let has_self_param = function.has_self_param(sema.db);
+    for (file_id, references) in Definition::Function(function).usages(sema).all() {
+        for reference in references {
+            let (arg_list, is_method_call) = match call_arg_list(&reference) {
+                Some(it) => it,
+                None => continue,
+            };
+            // Calls in UFCS form pass the receiver as the first argument.
+            let receiver = has_self_param && !is_method_call;
+            let args = arg_list.args().collect_vec();
+            let expected = old_param_count + receiver as usize;
+            if args.len() != expected {
+                let line = sema.db.line_index(file_id).line_col(reference.range.start()).line;
+                bail!(
+                    "The call to `{}` on line {} passes {} arguments instead of {}",
+                    function.name(sema.db),
+                    line + 1,
+                    args.len(),
+                    expected
+                );
+            }
+            let (receiver, args) = args.split_at(receiver as usize);
+
+            let mut pieces = vec![Piece::Text("(".to_owned())];
+            if let Some(receiver) = receiver.first() {
+                pieces.push(Piece::Source(receiver.syntax().text_range()));
+                if !new_params.is_empty() {
+                    pieces.push(Piece::Text(", ".to_owned()));
+                }
+            }
+            for (idx, new_param) in new_params.iter().enumerate() {
+                if idx > 0 {
+                    pieces.push(Piece::Text(", ".to_owned()));
+                }
+                match (new_param.old_index, &new_param.default_value) {
+                    (Some(old_index), _) => {
+                        pieces.push(Piece::Source(args[old_index].syntax().text_range()))
+                    }
+                    (None, Some(default_value)) => pieces.push(Piece::Text(default_value.clone())),
+                    (None, None) => {
+                        bail!("No default value given for the new parameter `{}`", new_param.name)
+                    }
+                }
+            }
+            pieces.push(Piece::Text(")".to_owned()));
+            replacements
+                .entry(file_id)
+                .or_default()
+                .push(Replacement { range: arg_list.syntax().text_range(), pieces });
+        }
+    }
+    Ok(())
+}
+
+/// Returns the argument list of the call the reference is the callee of, and whether it is a
+/// method call.
+fn call_arg_list(reference: &FileReference) -> Option<(ast::ArgList, bool)> {
+    let name_ref = reference.name.as_name_ref()?;
+    // References from within macro calls can't be rewritten.
+    if name_ref.syntax().text_range() != reference.range {
+        return None;
+    }
+    let parent = name_ref.syntax().parent()?;
+    match_ast! {
+        match parent {
+            ast::MethodCallExpr(it) => Some((it.arg_list()?, true)),
+            ast::PathSegment(it) => {
+                let path = it.parent_path();
+                if path.parent_path().is_some() {
+                    return None;
+                }
+                let path_expr = ast::PathExpr::cast(path.syntax().parent()?)?;
+                let call = ast::CallExpr::cast(path_expr.syntax().parent()?)?;
+                if call.expr()?.syntax() != path_expr.syntax() {
+                    return None;
+                }
+                Some((call.arg_list()?, false))
+            },
+            _ => None,
+        }
+    }
+}
+
+struct Replacement {
+    range: TextRange,
+    pieces: Vec<Piece>,
+}
+
+enum Piece {
+    Text(String),
+    /// A range of the original text, with the replacements inside of it applied.
+    Source(TextRange),
+}
+
+impl Replacement {
+    fn render(&self, text: &str, replacements: &[Replacement]) -> String {
+        let mut buf = String::new();
+        for piece in &self.pieces {
+            match piece {
+                Piece::Text(it) => buf.push_str(it),
+                Piece::Source(range) => render_source(&mut buf, text, *range, replacements),
+            }
+        }
+        buf
+    }
+}
+
+fn render_source(buf: &mut String, text: &str, range: TextRange, replacements: &[Replacement]) {
+    let mut offset = range.start();
+    for replacement in replacements.iter().filter(|it| range.contains_range(it.range)) {
+        if replacement.range.start() < offset {
+            continue;
+        }
+        buf.push_str(&text[TextRange::new(offset, replacement.range.start())]);
+        buf.push_str(&replacement.render(text, replacements));
+        offset = replacement.range.end();
+    }
+    buf.push_str(&text[TextRange::new(offset, range.end())]);
+}
+
+#[cfg(test)]
+mod tests {
+    use stdx::trim_indent;
+    use test_utils::assert_eq_text;
+
+    use crate::fixture;
+
+    use super::*;
+
+    fn param(old_index: Option<usize>, name: &str, ty: &str) -> SignatureParam {
+        SignatureParam { old_index, name: name.to_owned(), ty: ty.to_owned(), default_value: None }
+    }
+
+    fn new_param(name: &str, ty: &str, default_value: &str) -> SignatureParam {
+        SignatureParam { default_value: Some(default_value.to_owned()), ..param(None, name, ty) }
+    }
+
+    fn check(params: &[SignatureParam], ra_fixture_before: &str, ra_fixture_after: &str) {
+        let ra_fixture_after = &trim_indent(ra_fixture_after);
+        let (analysis, position) = fixture::position(ra_fixture_before);
+        let result = analysis.change_signature(position, params.to_vec()).unwrap();
+        match result {
+            Ok(source_change) => {
+                let (&file_id, edit) =
+                    source_change.source_file_edits.iter().exactly_one().ok().unwrap();
+                let mut text = analysis.file_text(file_id).unwrap().to_string();
+                edit.apply(&mut text);
+                assert_eq_text!(ra_fixture_after, &*text);
+            }
+            Err(err) => {
+                let error_message = ra_fixture_after.strip_prefix("error:").unwrap_or_else(|| {
+                    panic!("Changing the signature failed unexpectedly: {}", err)
+                });
+                assert_eq!(error_message.trim(), err.to_string());
+            }
+        }
+    }
+
+    #[test]
+    fn reorder_params() {
+        check(
+            &[param(Some(1), "b", "u32"), param(Some(0), "a", "i32")],
+            r#"
+fn foo$0(a: i32, b: u32) -> i64 {
+    a as i64 + b as i64
+}
+
+fn main() {
+    foo(1, 2);
+    foo(foo(3, 4) as i32, 5);
+}
+"#,
+            r#"
+fn foo(b: u32, a: i32) -> i64 {
+    a as i64 + b as i64
+}
+
+fn main() {
+    foo(2, 1);
+    foo(5, foo(4, 3) as i32);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn add_remove_and_rename_params() {
+        check(
+            &[param(Some(0), "count", "usize"), new_param("verbose", "bool", "false")],
+            r#"
+fn run(n: usize, _unused: &str) {
+    let s = S { n };
+    for _ in 0..n {}
+}
+struct S { n: usize }
+
+fn main() {
+    $0run(10, "x");
+}
+"#,
+            r#"
+fn run(count: usize, verbose: bool) {
+    let s = S { n: count };
+    for _ in 0..count {}
+}
+struct S { n: usize }
+
+fn main() {
+    run(10, false);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn update_method_and_ufcs_calls() {
+        check(
+            &[param(Some(1), "y", "i32"), param(Some(0), "x", "i32")],
+            r#"
+struct P;
+impl P {
+    fn $0set(&mut self, x: i32, y: i32) {}
+}
+
+fn main() {
+    let mut p = P;
+    p.set(1, 2);
+    P::set(&mut p, 3, 4);
+}
+"#,
+            r#"
+struct P;
+impl P {
+    fn set(&mut self, y: i32, x: i32) {}
+}
+
+fn main() {
+    let mut p = P;
+    p.set(2, 1);
+    P::set(&mut p, 4, 3);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn update_trait_declaration_and_impls() {
+        check(
+            &[param(Some(0), "value", "u8"), new_param("scale", "u8", "1")],
+            r#"
+trait Tr {
+    fn f(&self, x: u8) -> u8;
+}
+struct A;
+impl Tr for A {
+    fn f(&self, x: u8) -> u8 { x }
+}
+struct B;
+impl Tr for B {
+    fn f$0(&self, _x: u8) -> u8 { 0 }
+}
+
+fn call(t: &dyn Tr) -> u8 {
+    t.f(2) + A.f(3)
+}
+"#,
+            r#"
+trait Tr {
+    fn f(&self, value: u8, scale: u8) -> u8;
+}
+struct A;
+impl Tr for A {
+    fn f(&self, value: u8, scale: u8) -> u8 { value }
+}
+struct B;
+impl Tr for B {
+    fn f(&self, value: u8, scale: u8) -> u8 { 0 }
+}
+
+fn call(t: &dyn Tr) -> u8 {
+    t.f(2, 1) + A.f(3, 1)
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn error_when_removing_used_param() {
+        check(
+            &[],
+            r#"
+fn foo$0(a: i32) -> i32 { a }
+"#,
+            "error: Parameter `a` is still used in the body of `foo`",
+        );
+    }
+
+    #[test]
+    fn error_when_new_param_has_no_default() {
+        check(
+            &[param(None, "a", "i32")],
+            r#"
+fn foo$0() {}
+fn main() { foo() }
+"#,
+            "error: No default value given for the new parameter `a`",
+        );
+    }
+
+    #[test]
+    fn error_on_call_with_wrong_arg_count() {
+        check(
+            &[param(Some(1), "b", "i32"), param(Some(0), "a", "i32")],
+            r#"
+fn foo$0(a: i32, b: i32) {}
+fn main() {
+    foo(1, 2);
+    foo(1);
+}
+"#,
+            "error: The call to `foo` on line 4 passes 1 arguments instead of 2",
+        );
+    }
+
+    #[test]
+    fn error_on_invalid_params() {
+        check(
+            &[param(Some(0), "a", "i32"), param(Some(0), "b", "i32")],
+            r#"fn foo$0(a: i32) {}"#,
+            "error: Parameter with index 0 is used more than once",
+        );
+        check(
+            &[param(Some(0), "a b", "i32")],
+            r#"fn foo$0(a: i32) {}"#,
+            "error: Invalid name `a b`: not an identifier",
+        );
+    }
+}
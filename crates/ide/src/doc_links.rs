@@ -3,9 +3,7 @@
 #[cfg(test)]
 mod tests;
 
-mod intra_doc_links;
-
-use pulldown_cmark::{BrokenLink, CowStr, Event, InlineStr, LinkType, Options, Parser, Tag};
+use pulldown_cmark::{BrokenLink, CowStr, Event, InlineStr, LinkType, Parser, Tag};
 use pulldown_cmark_to_cmark::{cmark_resume_with_options, Options as CMarkOptions};
 use stdx::format_to;
 use url::Url;
@@ -14,6 +12,9 @@ use hir::{db::HirDatabase, Adt, AsAssocItem, AssocItem, AssocItemContainer, HasA
 use ide_db::{
     base_db::{CrateOrigin, LangCrateOrigin, SourceDatabase},
     defs::{Definition, NameClass, NameRefClass},
+    doc_links::{
+        broken_link_clone_cb, parse_intra_doc_link, strip_prefixes_suffixes, MARKDOWN_OPTIONS,
+    },
     helpers::pick_best_token,
     RootDatabase,
 };
@@ -24,17 +25,15 @@ use syntax::{
     SyntaxNode, SyntaxToken, TextRange, TextSize, T,
 };
 
-use crate::{
-    doc_links::intra_doc_links::{parse_intra_doc_link, strip_prefixes_suffixes},
-    FilePosition, Semantics,
+use crate::{FilePosition, Semantics};
+
+pub(crate) use ide_db::doc_links::{
+    doc_attributes, extract_definitions_from_docs, resolve_doc_path_for_def,
 };
 
 /// Weblink to an item's documentation.
 pub(crate) type DocumentationLink = String;
 
-const MARKDOWN_OPTIONS: Options =
-    Options::ENABLE_FOOTNOTES.union(Options::ENABLE_TABLES).union(Options::ENABLE_TASKLISTS);
-
 /// Rewrite documentation links in markdown to point to an online host (e.g. docs.rs)
 pub(crate) fn rewrite_links(db: &RootDatabase, markdown: &str, definition: Definition) -> String {
     let mut cb = broken_link_clone_cb;
@@ -142,87 +141,6 @@ pub(crate) fn external_docs(
     get_doc_link(db, definition)
 }
 
-/// Extracts all links from a given markdown text returning the definition text range, link-text
-/// and the namespace if known.
-pub(crate) fn extract_definitions_from_docs(
-    docs: &hir::Documentation,
-) -> Vec<(TextRange, String, Option<hir::Namespace>)> {
-    Parser::new_with_broken_link_callback(
-        docs.as_str(),
-        MARKDOWN_OPTIONS,
-        Some(&mut broken_link_clone_cb),
-    )
-    .into_offset_iter()
-    .filter_map(|(event, range)| match event {
-        Event::Start(Tag::Link(_, target, _)) => {
-            let (link, ns) = parse_intra_doc_link(&target);
-            Some((
-                TextRange::new(range.start.try_into().ok()?, range.end.try_into().ok()?),
-                link.to_string(),
-                ns,
-            ))
-        }
-        _ => None,
-    })
-    .collect()
-}
-
-pub(crate) fn resolve_doc_path_for_def(
-    db: &dyn HirDatabase,
-    def: Definition,
-    link: &str,
-    ns: Option<hir::Namespace>,
-) -> Option<Definition> {
-    match def {
-        Definition::Module(it) => it.resolve_doc_path(db, link, ns),
-        Definition::Function(it) => it.resolve_doc_path(db, link, ns),
-        Definition::Adt(it) => it.resolve_doc_path(db, link, ns),
-        Definition::Variant(it) => it.resolve_doc_path(db, link, ns),
-        Definition::Const(it) => it.resolve_doc_path(db, link, ns),
-        Definition::Static(it) => it.resolve_doc_path(db, link, ns),
-        Definition::Trait(it) => it.resolve_doc_path(db, link, ns),
-        Definition::TypeAlias(it) => it.resolve_doc_path(db, link, ns),
-        Definition::Macro(it) => it.resolve_doc_path(db, link, ns),
-        Definition::Field(it) => it.resolve_doc_path(db, link, ns),
-        Definition::SelfType(it) => it.resolve_doc_path(db, link, ns),
-        Definition::BuiltinAttr(_)
-        | Definition::ToolModule(_)
-        | Definition::BuiltinType(_)
-        | Definition::Local(_)
-        | Definition::GenericParam(_)
-        | Definition::Label(_)
-        | Definition::DeriveHelper(_) => None,
-    }
-    .map(Definition::from)
-}
-
-pub(crate) fn doc_attributes(
-    sema: &Semantics<'_, RootDatabase>,
-    node: &SyntaxNode,
-) -> Option<(hir::AttrsWithOwner, Definition)> {
-    match_ast! {
-        match node {
-            ast::SourceFile(it)  => sema.to_def(&it).map(|def| (def.attrs(sema.db), Definition::Module(def))),
-            ast::Module(it)      => sema.to_def(&it).map(|def| (def.attrs(sema.db), Definition::Module(def))),
-            ast::Fn(it)          => sema.to_def(&it).map(|def| (def.attrs(sema.db), Definition::Function(def))),
-            ast::Struct(it)      => sema.to_def(&it).map(|def| (def.attrs(sema.db), Definition::Adt(hir::Adt::Struct(def)))),
-            ast::Union(it)       => sema.to_def(&it).map(|def| (def.attrs(sema.db), Definition::Adt(hir::Adt::Union(def)))),
-            ast::Enum(it)        => sema.to_def(&it).map(|def| (def.attrs(sema.db), Definition::Adt(hir::Adt::Enum(def)))),
-            ast::Variant(it)     => sema.to_def(&it).map(|def| (def.attrs(sema.db), Definition::Variant(def))),
-            ast::Trait(it)       => sema.to_def(&it).map(|def| (def.attrs(sema.db), Definition::Trait(def))),
-            ast::Static(it)      => sema.to_def(&it).map(|def| (def.attrs(sema.db), Definition::Static(def))),
-            ast::Const(it)       => sema.to_def(&it).map(|def| (def.attrs(sema.db), Definition::Const(def))),
-            ast::TypeAlias(it)   => sema.to_def(&it).map(|def| (def.attrs(sema.db), Definition::TypeAlias(def))),
-            ast::Impl(it)        => sema.to_def(&it).map(|def| (def.attrs(sema.db), Definition::SelfType(def))),
-            ast::RecordField(it) => sema.to_def(&it).map(|def| (def.attrs(sema.db), Definition::Field(def))),
-            ast::TupleField(it)  => sema.to_def(&it).map(|def| (def.attrs(sema.db), Definition::Field(def))),
-            ast::Macro(it)       => sema.to_def(&it).map(|def| (def.attrs(sema.db), Definition::Macro(def))),
-            // ast::Use(it) => sema.to_def(&it).map(|def| (Box::new(it) as _, def.attrs(sema.db))),
-            _ => None
-        }
-    }
-}
-
 pub(crate) struct DocCommentToken {
     doc_token: SyntaxToken,
     prefix_len: TextSize,
@@ -280,10 +198,6 @@ impl DocCommentToken {
     }
 }
 
-fn broken_link_clone_cb<'a>(link: BrokenLink<'a>) -> Option<(CowStr<'a>, CowStr<'a>)> {
-    Some((/*url*/ link.reference.clone(), /*title*/ link.reference))
-}
-
 // FIXME:
 // BUG: For Option::Some
 // Returns https://doc.rust-lang.org/nightly/core/prelude/v1/enum.Option.html#variant.Some
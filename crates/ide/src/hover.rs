@@ -131,7 +131,7 @@ pub(crate) fn hover(
     // FIXME: Definition should include known lints and the like instead of having this special case here
     let hovered_lint = descended.iter().find_map(|token| {
         let attr = token.parent_ancestors().find_map(ast::Attr::cast)?;
-        render::try_for_lint(&attr, token)
+        render::try_for_lint(&attr, token).or_else(|| render::try_for_cfg(sema, &attr, token))
     });
     if let Some(res) = hovered_lint {
         return Some(RangeInfo::new(original_token.text_range(), res));
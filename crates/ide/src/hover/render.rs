@@ -1,6 +1,7 @@
 //! Logic for rendering the different hover messages
 use std::fmt::Display;
 
+use cfg::{CfgAtom, CfgExpr};
 use either::Either;
 use hir::{AsAssocItem, AttributeTemplate, HasAttrs, HirDisplay, Semantics, TypeInfo};
 use ide_db::{
@@ -13,7 +14,7 @@ use ide_db::{
 use itertools::Itertools;
 use stdx::format_to;
 use syntax::{
-    algo, ast, match_ast, AstNode, Direction,
+    algo, ast, match_ast, AstNode, AstToken, Direction, NodeOrToken,
     SyntaxKind::{LET_EXPR, LET_STMT},
     SyntaxToken, T,
 };
@@ -290,6 +291,66 @@ pub(super) fn try_for_lint(attr: &ast::Attr, token: &SyntaxToken) -> Option<Hove
     })
 }
 
+pub(super) fn try_for_cfg(
+    sema: &Semantics<'_, RootDatabase>,
+    attr: &ast::Attr,
+    token: &SyntaxToken,
+) -> Option<HoverResult> {
+    let (path, tt) = attr.as_simple_call()?;
+    if token.kind() != T![ident] || !tt.syntax().text_range().contains(token.text_range().start()) {
+        return None;
+    }
+    match &*path {
+        "cfg" => (),
+        // Only the predicate of `cfg_attr` is a cfg.
+        "cfg_attr" => {
+            let predicate_end = tt
+                .syntax()
+                .children_with_tokens()
+                .find(|it| it.kind() == T![,])
+                .map_or(tt.syntax().text_range().end(), |it| it.text_range().start());
+            if token.text_range().start() >= predicate_end {
+                return None;
+            }
+        }
+        _ => return None,
+    }
+
+    let next = algo::non_trivia_sibling(token.clone().into(), Direction::Next);
+    let atom = match next {
+        // `all`, `any` and `not`
+        Some(NodeOrToken::Node(_)) => return None,
+        Some(NodeOrToken::Token(eq)) if eq.kind() == T![=] => {
+            let value = algo::non_trivia_sibling(eq.into(), Direction::Next)?.into_token()?;
+            let value = ast::String::cast(value)?;
+            CfgAtom::KeyValue { key: token.text().into(), value: value.value()?.into() }
+        }
+        _ => CfgAtom::Flag(token.text().into()),
+    };
+
+    let krate = sema.scope(attr.syntax())?.krate();
+    let enabled = krate.cfg(sema.db).check(&CfgExpr::Atom(atom.clone())) == Some(true);
+    let build_script_cfgs: Vec<CfgAtom> =
+        krate.build_script_cfg(sema.db).iter().cloned().sorted().collect();
+
+    let mut markup = format!("```\ncfg({})\n```\n___\n\n", atom);
+    if !enabled {
+        markup.push_str("Disabled for this crate.");
+    } else if build_script_cfgs.contains(&atom) {
+        markup.push_str("Enabled by the build script of this crate.");
+    } else {
+        markup.push_str("Enabled for this crate.");
+    }
+    if !build_script_cfgs.is_empty() {
+        format_to!(
+            markup,
+            "\n\nBuild script cfgs: {}",
+            build_script_cfgs.iter().map(|it| format!("`{}`", it)).join(", ")
+        );
+    }
+    Some(HoverResult { markup: Markup::from(markup), ..Default::default() })
+}
+
 pub(super) fn process_markup(
     db: &RootDatabase,
     def: Definition,
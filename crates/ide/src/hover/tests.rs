@@ -4322,6 +4322,82 @@ fn hover_clippy_lint() {
     )
 }
 
+#[test]
+fn hover_cfg() {
+    check(
+        r#"
+//- /main.rs crate:main cfg:foo
+#[cfg(all(fo$0o, not(test)))]
+fn f() {}
+"#,
+        expect![[r#"
+            *foo*
+            ```
+            cfg(foo)
+            ```
+            ___
+
+            Enabled for this crate.
+        "#]],
+    );
+    check(
+        r#"
+//- /main.rs crate:main cfg:feature=std
+#[cfg_attr(feat$0ure = "alloc", derive(Debug))]
+struct S;
+"#,
+        expect![[r#"
+            *feature*
+            ```
+            cfg(feature = "alloc")
+            ```
+            ___
+
+            Disabled for this crate.
+        "#]],
+    );
+}
+
+#[test]
+fn hover_build_script_cfg() {
+    check(
+        r#"
+//- /main.rs crate:main cfg:foo build-script-cfg:has_bar,bar_version=2
+#[cfg(has_b$0ar)]
+fn f() {}
+"#,
+        expect![[r#"
+            *has_bar*
+            ```
+            cfg(has_bar)
+            ```
+            ___
+
+            Enabled by the build script of this crate.
+
+            Build script cfgs: `has_bar`, `bar_version = "2"`
+        "#]],
+    );
+    check(
+        r#"
+//- /main.rs crate:main cfg:foo build-script-cfg:has_bar,bar_version=2
+#[cfg(fo$0o)]
+fn f() {}
+"#,
+        expect![[r#"
+            *foo*
+            ```
+            cfg(foo)
+            ```
+            ___
+
+            Enabled for this crate.
+
+            Build script cfgs: `has_bar`, `bar_version = "2"`
+        "#]],
+    );
+}
+
 #[test]
 fn hover_attr_path_qualifier() {
     check(
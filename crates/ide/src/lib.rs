@@ -25,6 +25,7 @@ mod navigation_target;
 
 mod annotations;
 mod call_hierarchy;
+mod change_signature;
 mod signature_help;
 mod doc_links;
 mod highlight_related;
@@ -76,6 +77,7 @@ use crate::navigation_target::{ToNav, TryToNav};
 pub use crate::{
     annotations::{Annotation, AnnotationConfig, AnnotationKind},
     call_hierarchy::CallItem,
+    change_signature::SignatureParam,
     expand_macro::ExpandedMacro,
     file_structure::{StructureNode, StructureNodeKind},
     folding_ranges::{Fold, FoldKind},
@@ -233,6 +235,7 @@ impl Analysis {
             None,
             cfg_options.clone(),
             cfg_options,
+            CfgOptions::default(),
             Env::default(),
             Ok(Vec::new()),
             false,
@@ -637,6 +640,14 @@ impl Analysis {
         self.with_db(|db| rename::prepare_rename(db, position))
     }
 
+    pub fn change_signature(
+        &self,
+        position: FilePosition,
+        new_params: Vec<SignatureParam>,
+    ) -> Cancellable<Result<SourceChange, RenameError>> {
+        self.with_db(|db| change_signature::change_signature(db, position, &new_params))
+    }
+
     pub fn will_rename_file(
         &self,
         file_id: FileId,
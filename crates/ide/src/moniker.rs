@@ -256,6 +256,7 @@ pub(crate) fn def_to_moniker(
             let name = krate.display_name(db)?.to_string();
             let (repo, version) = match krate.origin(db) {
                 CrateOrigin::CratesIo { repo } => (repo?, krate.version(db)?),
+                CrateOrigin::Local => return None,
                 CrateOrigin::Lang(lang) => (
                     "https://github.com/rust-lang/rust/".to_string(),
                     match lang {
@@ -32,6 +32,7 @@ pub(crate) fn shuffle_crate_graph(db: &mut RootDatabase) {
             data.version.clone(),
             data.cfg_options.clone(),
             data.potential_cfg_options.clone(),
+            data.build_script_cfg_options.clone(),
             data.env.clone(),
             data.proc_macro.clone(),
             data.is_proc_macro,
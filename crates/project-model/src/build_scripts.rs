@@ -10,7 +10,7 @@ use std::{cell::RefCell, io, path::PathBuf, process::Command};
 
 use cargo_metadata::{camino::Utf8Path, Message};
 use la_arena::ArenaMap;
-use paths::AbsPathBuf;
+use paths::{AbsPath, AbsPathBuf};
 use rustc_hash::FxHashMap;
 use semver::Version;
 use serde::Deserialize;
@@ -24,22 +24,28 @@ pub struct WorkspaceBuildScripts {
 }
 
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
-pub(crate) struct BuildScriptOutput {
+pub struct BuildScriptOutput {
     /// List of config flags defined by this package's build script.
-    pub(crate) cfgs: Vec<CfgFlag>,
+    pub cfgs: Vec<CfgFlag>,
+    /// List of `--check-cfg` specifications declared via `cargo::rustc-check-cfg`.
+    pub check_cfgs: Vec<String>,
     /// List of cargo-related environment variables with their value.
     ///
     /// If the package has a build script which defines environment variables,
     /// they can also be found here.
-    pub(crate) envs: Vec<(String, String)>,
+    pub envs: Vec<(String, String)>,
     /// Directory where a build script might place its output.
-    pub(crate) out_dir: Option<AbsPathBuf>,
+    pub out_dir: Option<AbsPathBuf>,
     /// Path to the proc-macro library file if this package exposes proc-macros.
-    pub(crate) proc_macro_dylib_path: Option<AbsPathBuf>,
+    pub proc_macro_dylib_path: Option<AbsPathBuf>,
+    /// Libraries the build script asked to link via `cargo:rustc-link-lib`.
+    pub linked_libs: Vec<String>,
+    /// Library search paths the build script added via `cargo:rustc-link-search`.
+    pub linked_paths: Vec<String>,
 }
 
 impl WorkspaceBuildScripts {
-    fn build_command(config: &CargoConfig) -> Command {
+    fn build_command(config: &CargoConfig, workspace: &CargoWorkspace) -> Command {
         if let Some([program, args @ ..]) = config.run_build_script_command.as_deref() {
             let mut cmd = Command::new(program);
             cmd.args(args);
@@ -50,6 +56,11 @@ impl WorkspaceBuildScripts {
 
         cmd.args(&["check", "--quiet", "--workspace", "--message-format=json"]);
 
+        if let Some(script) = workspace.cargo_script() {
+            cmd.arg("--manifest-path").arg(script.as_os_str());
+            cmd.arg("-Zscript").env("RUSTC_BOOTSTRAP", "1");
+        }
+
         // --all-targets includes tests, benches and examples in addition to the
         // default lib and bins. This is an independent concept from the --targets
         // flag below.
@@ -82,13 +93,13 @@ impl WorkspaceBuildScripts {
     ) -> io::Result<WorkspaceBuildScripts> {
         const RUST_1_62: Version = Version::new(1, 62, 0);
 
-        match Self::run_(Self::build_command(config), config, workspace, progress) {
+        match Self::run_(Self::build_command(config, workspace), config, workspace, progress) {
             Ok(WorkspaceBuildScripts { error: Some(error), .. })
                 if toolchain.as_ref().map_or(false, |it| *it >= RUST_1_62) =>
             {
                 // building build scripts failed, attempt to build with --keep-going so
                 // that we potentially get more build data
-                let mut cmd = Self::build_command(config);
+                let mut cmd = Self::build_command(config, workspace);
                 cmd.args(&["-Z", "unstable-options", "--keep-going"]).env("RUSTC_BOOTSTRAP", "1");
                 let mut res = Self::run_(cmd, config, workspace, progress)?;
                 res.error = Some(error);
@@ -170,14 +181,23 @@ impl WorkspaceBuildScripts {
                         // older cargos, which is not absolute, so work around that.
                         let out_dir = message.out_dir.into_os_string();
                         if !out_dir.is_empty() {
+                            let out_dir = AbsPathBuf::assert(PathBuf::from(out_dir));
                             let data = outputs[package].get_or_insert_with(Default::default);
-                            data.out_dir = Some(AbsPathBuf::assert(PathBuf::from(out_dir)));
+                            data.check_cfgs = read_check_cfgs(&out_dir);
+                            data.out_dir = Some(out_dir);
                             data.cfgs = cfgs;
                         }
                         if !message.env.is_empty() {
                             outputs[package].get_or_insert_with(Default::default).envs =
                                 message.env;
                         }
+                        if !message.linked_libs.is_empty() || !message.linked_paths.is_empty() {
+                            let data = outputs[package].get_or_insert_with(Default::default);
+                            data.linked_libs =
+                                message.linked_libs.into_iter().map(Into::into).collect();
+                            data.linked_paths =
+                                message.linked_paths.into_iter().map(Into::into).collect();
+                        }
                     }
                     Message::CompilerArtifact(message) => {
                         let package = match by_id.get(&message.package_id.repr) {
@@ -248,11 +268,32 @@ impl WorkspaceBuildScripts {
         self.error.as_deref()
     }
 
-    pub(crate) fn get_output(&self, idx: Package) -> Option<&BuildScriptOutput> {
+    pub fn get_output(&self, idx: Package) -> Option<&BuildScriptOutput> {
         self.outputs.get(idx)?.as_ref()
     }
 }
 
+/// Cargo doesn't include the `rustc-check-cfg` instructions of a build script in its messages,
+/// so read them from the saved output of the script, which lives next to its `OUT_DIR`.
+fn read_check_cfgs(out_dir: &AbsPath) -> Vec<String> {
+    let output = match out_dir.parent().map(|it| it.join("output")) {
+        Some(it) => it,
+        None => return Vec::new(),
+    };
+    let output = match std::fs::read_to_string(&output) {
+        Ok(it) => it,
+        Err(_) => return Vec::new(),
+    };
+    output
+        .lines()
+        .filter_map(|line| {
+            line.strip_prefix("cargo::rustc-check-cfg=")
+                .or_else(|| line.strip_prefix("cargo:rustc-check-cfg="))
+        })
+        .map(|it| it.trim().to_string())
+        .collect()
+}
+
 // FIXME: File a better way to know if it is a dylib.
 fn is_dylib(path: &Utf8Path) -> bool {
     match path.extension().map(|e| e.to_string().to_lowercase()) {
@@ -56,6 +56,15 @@ pub enum RustcSource {
     Discover,
 }
 
+/// Describes how to find the `Cargo.toml` to load the sysroot crates from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SysrootManifest {
+    /// Explicit path to the manifest, e.g. of the `library/` workspace of a Rust checkout.
+    Path(AbsPathBuf),
+    /// Use the manifest of the standard library sources of the current toolchain.
+    Discover,
+}
+
 /// Crates to disable `#[cfg(test)]` on.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum UnsetTestCrates {
@@ -89,6 +98,10 @@ pub struct CargoConfig {
     /// when debugging isolated issues.
     pub no_sysroot: bool,
 
+    /// Load the sysroot crates and their dependencies with `cargo metadata` instead of
+    /// using a built-in list of crates.
+    pub sysroot_manifest: Option<SysrootManifest>,
+
     /// rustc private crate source
     pub rustc_source: Option<RustcSource>,
 
@@ -259,6 +272,27 @@ impl CargoWorkspace {
         current_dir: &AbsPath,
         config: &CargoConfig,
         progress: &dyn Fn(String),
+    ) -> Result<cargo_metadata::Metadata> {
+        let unstable = cargo_toml.is_cargo_script();
+        Self::fetch_metadata_(cargo_toml, current_dir, config, progress, unstable)
+    }
+
+    /// Like [`CargoWorkspace::fetch_metadata`], but for the manifest of a standard library
+    /// source tree, which is free to use unstable cargo features.
+    pub(crate) fn fetch_sysroot_metadata(
+        cargo_toml: &ManifestPath,
+        config: &CargoConfig,
+        progress: &dyn Fn(String),
+    ) -> Result<cargo_metadata::Metadata> {
+        Self::fetch_metadata_(cargo_toml, cargo_toml.parent(), config, progress, true)
+    }
+
+    fn fetch_metadata_(
+        cargo_toml: &ManifestPath,
+        current_dir: &AbsPath,
+        config: &CargoConfig,
+        progress: &dyn Fn(String),
+        unstable: bool,
     ) -> Result<cargo_metadata::Metadata> {
         let target = config
             .target
@@ -283,17 +317,31 @@ impl CargoWorkspace {
         }
         meta.current_dir(current_dir.as_os_str());
 
+        let mut other_options = Vec::new();
         if let Some(target) = target {
-            meta.other_options(vec![String::from("--filter-platform"), target]);
+            other_options.extend([String::from("--filter-platform"), target]);
+        }
+        if cargo_toml.is_cargo_script() {
+            other_options.push(String::from("-Zscript"));
         }
+        meta.other_options(other_options);
 
         // FIXME: Fetching metadata is a slow process, as it might require
         // calling crates.io. We should be reporting progress here, but it's
         // unclear whether cargo itself supports it.
         progress("metadata".to_string());
 
-        let meta =
-            meta.exec().with_context(|| format!("Failed to run `{:?}`", meta.cargo_command()))?;
+        let meta = if unstable {
+            // Convince a stable cargo to accept unstable flags and features.
+            let mut cmd = meta.cargo_command();
+            cmd.env("RUSTC_BOOTSTRAP", "1");
+            let stdout = utf8_stdout(cmd)?;
+            let json = stdout.lines().find(|line| line.starts_with('{')).unwrap_or_default();
+            MetadataCommand::parse(json)
+                .with_context(|| format!("Failed to parse `{:?}` output", meta.cargo_command()))?
+        } else {
+            meta.exec().with_context(|| format!("Failed to run `{:?}`", meta.cargo_command()))?
+        };
 
         Ok(meta)
     }
@@ -417,6 +465,15 @@ impl CargoWorkspace {
         &self.workspace_root
     }
 
+    /// Returns the script this workspace was loaded from, if it consists of a
+    /// single-file package.
+    pub fn cargo_script(&self) -> Option<&ManifestPath> {
+        self.packages
+            .iter()
+            .map(|(_, pkg)| &pkg.manifest)
+            .find(|manifest| manifest.is_cargo_script())
+    }
+
     pub fn package_flag(&self, package: &PackageData) -> String {
         if self.is_unique(&*package.name) {
             package.name.clone()
@@ -25,6 +25,9 @@ mod sysroot;
 mod workspace;
 mod rustc_cfg;
 mod build_scripts;
+mod offline_metadata;
+mod manifest_analysis;
+mod registry_index;
 
 #[cfg(test)]
 mod tests;
@@ -40,21 +43,30 @@ use paths::{AbsPath, AbsPathBuf};
 use rustc_hash::FxHashSet;
 
 pub use crate::{
-    build_scripts::WorkspaceBuildScripts,
+    build_scripts::{BuildScriptOutput, WorkspaceBuildScripts},
     cargo_workspace::{
-        CargoConfig, CargoWorkspace, Package, PackageData, PackageDependency, RustcSource, Target,
-        TargetData, TargetKind, UnsetTestCrates,
+        CargoConfig, CargoWorkspace, Package, PackageData, PackageDependency, RustcSource,
+        SysrootManifest, Target, TargetData, TargetKind, UnsetTestCrates,
+    },
+    manifest_analysis::{
+        manifest_completions, manifest_diagnostics, manifest_hover, ManifestCompletion,
+        ManifestCompletionKind, ManifestDiagnostic, ManifestHover, ManifestInfo,
+        ResolvedDependency,
     },
     manifest_path::ManifestPath,
     project_json::{ProjectJson, ProjectJsonData},
+    registry_index::{IndexVersion, RegistryIndex},
     sysroot::Sysroot,
-    workspace::{CfgOverrides, PackageRoot, ProjectWorkspace},
+    workspace::{add_standalone_files_to_crate_graph, CfgOverrides, PackageRoot, ProjectWorkspace},
 };
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
 pub enum ProjectManifest {
     ProjectJson(ManifestPath),
     CargoToml(ManifestPath),
+    /// A single-file Cargo package (`cargo -Zscript`), that is a `.rs` file with
+    /// an embedded manifest.
+    CargoScript(ManifestPath),
 }
 
 impl ProjectManifest {
@@ -67,7 +79,18 @@ impl ProjectManifest {
         if path.file_name().unwrap_or_default() == "Cargo.toml" {
             return Ok(ProjectManifest::CargoToml(path));
         }
-        bail!("project root must point to Cargo.toml or rust-project.json: {}", path.display())
+        if path.extension().unwrap_or_default() == "rs" {
+            let text = fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read file {}", path.display()))?;
+            if has_embedded_manifest(&text) {
+                return Ok(ProjectManifest::CargoScript(path));
+            }
+            bail!("file has no embedded Cargo manifest: {}", path.display())
+        }
+        bail!(
+            "project root must point to Cargo.toml, rust-project.json or a cargo script: {}",
+            path.display()
+        )
     }
 
     pub fn discover_single(path: &AbsPath) -> Result<ProjectManifest> {
@@ -144,6 +167,47 @@ impl ProjectManifest {
     }
 }
 
+/// Checks whether `text` starts with a frontmatter block holding a Cargo manifest,
+/// as used by single-file Cargo packages:
+///
+/// ```text
+/// #!/usr/bin/env cargo
+/// ---
+/// [dependencies]
+/// regex = "1"
+/// ---
+///
+/// fn main() {}
+/// ```
+///
+/// The shebang line is optional, and the opening fence may carry a `cargo` info string.
+pub fn has_embedded_manifest(text: &str) -> bool {
+    let mut lines = text.lines().peekable();
+    if let Some(shebang) = lines.peek() {
+        // `#![inner_attribute]` is not a shebang.
+        if shebang.starts_with("#!") && !shebang[2..].trim_start().starts_with('[') {
+            lines.next();
+        }
+    }
+    let mut lines = lines.skip_while(|line| line.trim().is_empty());
+    let opening = match lines.next() {
+        Some(line) => line.trim_end(),
+        None => return false,
+    };
+    let n_dashes = opening.chars().take_while(|&c| c == '-').count();
+    if n_dashes < 3 {
+        return false;
+    }
+    let info_string = opening[n_dashes..].trim();
+    if !(info_string.is_empty() || info_string == "cargo") {
+        return false;
+    }
+    lines.any(|line| {
+        let line = line.trim_end();
+        line.len() == n_dashes && line.chars().all(|c| c == '-')
+    })
+}
+
 fn utf8_stdout(mut cmd: Command) -> Result<String> {
     let output = cmd.output().with_context(|| format!("{:?} failed", cmd))?;
     if !output.status.success() {
@@ -0,0 +1,936 @@
+//! Completion, hover and diagnostics for `Cargo.toml` files.
+//!
+//! The manifest is usually incomplete while it is edited, so instead of a
+//! proper TOML parser we use a tolerant scanner that remembers the ranges of
+//! keys and values. Crate names and versions come from the local
+//! [`RegistryIndex`], features and workspace-inherited keys from what
+//! `cargo metadata` told us about the workspace.
+
+use std::{fs, ops::Range};
+
+use paths::AbsPath;
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use crate::{CargoWorkspace, RegistryIndex};
+
+// Feature: Cargo.toml Assistance
+//
+// In `Cargo.toml` files, rust-analyzer completes:
+//
+// - names of dependencies and their versions, from the registry index cargo keeps locally,
+// - the keys of detailed dependency tables, like `default-features`,
+// - the features of a dependency in its `features` array.
+//
+// Hovering a dependency shows its resolved and latest known version, hovering a feature shows what it enables.
+//
+// Unknown features of dependencies and workspace-inherited keys (`version.workspace = true`)
+// which the workspace root doesn't define are reported as errors.
+// The registry index is never updated from the network, so only crates cargo looked up before are known.
+
+/// The maximum number of versions offered for a dependency.
+const MAX_VERSIONS: usize = 20;
+
+const DEPENDENCY_KEYS: &[&str] = &[
+    "version",
+    "features",
+    "default-features",
+    "optional",
+    "path",
+    "git",
+    "branch",
+    "tag",
+    "rev",
+    "package",
+    "registry",
+    "workspace",
+];
+
+/// What is known about a manifest besides its text.
+#[derive(Debug, Clone, Default)]
+pub struct ManifestInfo {
+    /// The resolved dependencies, by package name.
+    pub dependencies: FxHashMap<String, ResolvedDependency>,
+    /// The keys of `[workspace.package]` in the workspace root manifest, if it could be read.
+    pub workspace_package_keys: Option<FxHashSet<String>>,
+    /// The keys of `[workspace.dependencies]` in the workspace root manifest, if it could be read.
+    pub workspace_dependencies: Option<FxHashSet<String>>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ResolvedDependency {
+    pub version: String,
+    pub repository: Option<String>,
+    /// Features provided by the package, mapped to the features required by that feature.
+    pub features: FxHashMap<String, Vec<String>>,
+}
+
+impl ManifestInfo {
+    /// Collects the information about the manifest at `manifest` with the contents `text`.
+    pub fn new(cargo: &CargoWorkspace, manifest: &AbsPath, text: &str) -> ManifestInfo {
+        let mut dependencies = FxHashMap::default();
+        let resolved = |pkg: crate::Package| {
+            let data = &cargo[pkg];
+            (
+                data.name.clone(),
+                ResolvedDependency {
+                    version: data.version.to_string(),
+                    repository: data.repository.clone(),
+                    features: data.features.clone(),
+                },
+            )
+        };
+        // The dependencies of the package itself come first, so that they win
+        // over other versions of the same package in the workspace.
+        if let Some(pkg) = cargo.packages().find(|&it| &*cargo[it].manifest == manifest) {
+            for dep in &cargo[pkg].dependencies {
+                let (name, dep) = resolved(dep.pkg);
+                dependencies.entry(name).or_insert(dep);
+            }
+        }
+        for pkg in cargo.packages() {
+            let (name, dep) = resolved(pkg);
+            dependencies.entry(name).or_insert(dep);
+        }
+
+        let root_manifest = cargo.workspace_root().join("Cargo.toml");
+        let root_text = if *root_manifest == *manifest {
+            Some(text.to_owned())
+        } else {
+            fs::read_to_string(&root_manifest).ok()
+        };
+        let (workspace_package_keys, workspace_dependencies) = match root_text {
+            Some(root_text) => {
+                let root = Manifest::parse(&root_text);
+                (
+                    Some(root.keys_of(&["workspace", "package"])),
+                    Some(root.keys_of(&["workspace", "dependencies"])),
+                )
+            }
+            None => (None, None),
+        };
+
+        ManifestInfo { dependencies, workspace_package_keys, workspace_dependencies }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ManifestCompletionKind {
+    Crate,
+    Version,
+    Feature,
+    Key,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestCompletion {
+    pub label: String,
+    pub kind: ManifestCompletionKind,
+    pub detail: Option<String>,
+    /// The range of the text replaced by the completion.
+    pub range: Range<usize>,
+    pub insert: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestHover {
+    pub range: Range<usize>,
+    pub markdown: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestDiagnostic {
+    pub range: Range<usize>,
+    pub message: String,
+}
+
+/// Completes crate names, versions, dependency keys and features at `offset`.
+pub fn manifest_completions(
+    text: &str,
+    offset: usize,
+    info: &ManifestInfo,
+    index: &RegistryIndex,
+) -> Vec<ManifestCompletion> {
+    let manifest = Manifest::parse(text);
+    let mut res = Vec::new();
+    let (entry, position) = match manifest.at_offset(offset) {
+        Some(it) => it,
+        None => return res,
+    };
+    let dep = match manifest.dependency(entry) {
+        Some(it) => it,
+        None => return res,
+    };
+    match position {
+        Position::Key(key) if key == dep.table_len => {
+            let key = &entry.path[key];
+            let prefix = &text[key.range.start..offset];
+            let complete_line = !entry.is_header && entry.value.is_none();
+            for name in index.crate_names(prefix) {
+                let latest = index.latest_version(&name).map(|it| it.version.to_string());
+                let insert = match &latest {
+                    Some(version) if complete_line => format!("{} = \"{}\"", name, version),
+                    _ => name.clone(),
+                };
+                res.push(ManifestCompletion {
+                    label: name,
+                    kind: ManifestCompletionKind::Crate,
+                    detail: latest,
+                    range: key.range.clone(),
+                    insert,
+                });
+            }
+        }
+        Position::Key(key) if key == dep.table_len + 1 => {
+            let range = entry.path[key].range.clone();
+            res.extend(DEPENDENCY_KEYS.iter().map(|&it| ManifestCompletion {
+                label: it.to_owned(),
+                kind: ManifestCompletionKind::Key,
+                detail: None,
+                range: range.clone(),
+                insert: it.to_owned(),
+            }));
+        }
+        Position::Key(_) => (),
+        Position::String(range) => match dep.field {
+            None | Some("version") => {
+                let versions = index.versions(&dep.package);
+                let versions = versions.iter().filter(|it| !it.yanked).take(MAX_VERSIONS);
+                res.extend(versions.enumerate().map(|(i, it)| ManifestCompletion {
+                    label: it.version.to_string(),
+                    kind: ManifestCompletionKind::Version,
+                    detail: (i == 0).then(|| "latest".to_owned()),
+                    range: range.clone(),
+                    insert: it.version.to_string(),
+                }));
+            }
+            Some("features") => {
+                let listed = entry.value.as_ref().map(Value::strings).unwrap_or_default();
+                let features = match info.dependencies.get(&dep.package) {
+                    Some(it) => it.features.clone(),
+                    None => {
+                        index.latest_version(&dep.package).map(|it| it.features).unwrap_or_default()
+                    }
+                };
+                let mut features = features
+                    .into_iter()
+                    .filter(|(name, _)| !listed.iter().any(|(it, _)| it == name))
+                    .collect::<Vec<_>>();
+                features.sort();
+                res.extend(features.into_iter().map(|(name, enables)| ManifestCompletion {
+                    label: name.clone(),
+                    kind: ManifestCompletionKind::Feature,
+                    detail: Some(format!("[{}]", enables.join(", "))),
+                    range: range.clone(),
+                    insert: name,
+                }));
+            }
+            _ => (),
+        },
+    }
+    res
+}
+
+/// Describes the dependency or feature at `offset`.
+pub fn manifest_hover(
+    text: &str,
+    offset: usize,
+    info: &ManifestInfo,
+    index: &RegistryIndex,
+) -> Option<ManifestHover> {
+    let manifest = Manifest::parse(text);
+    let (entry, position) = manifest.at_offset(offset)?;
+    let dep = manifest.dependency(entry)?;
+    match position {
+        Position::Key(key) if key == dep.table_len => {
+            let mut markdown = format!("**{}**", dep.package);
+            if let Some(resolved) = info.dependencies.get(&dep.package) {
+                markdown += &format!("\n\nResolved version: `{}`", resolved.version);
+                if let Some(repository) = &resolved.repository {
+                    markdown += &format!("\n\nRepository: {}", repository);
+                }
+            }
+            if let Some(latest) = index.latest_version(&dep.package) {
+                markdown += &format!("\n\nLatest version in the local index: `{}`", latest.version);
+            }
+            Some(ManifestHover { range: entry.path[key].range.clone(), markdown })
+        }
+        Position::String(range) if dep.field == Some("features") => {
+            let feature = &text[range.clone()];
+            let enables = match info.dependencies.get(&dep.package) {
+                Some(resolved) => resolved.features.get(feature)?.clone(),
+                None => index.latest_version(&dep.package)?.features.get(feature)?.clone(),
+            };
+            let enables =
+                enables.iter().map(|it| format!("\"{}\"", it)).collect::<Vec<_>>().join(", ");
+            let markdown = format!("```toml\n{} = [{}]\n```", feature, enables);
+            Some(ManifestHover { range, markdown })
+        }
+        _ => None,
+    }
+}
+
+/// Reports unknown features of dependencies and workspace-inherited keys the
+/// workspace doesn't define.
+pub fn manifest_diagnostics(text: &str, info: &ManifestInfo) -> Vec<ManifestDiagnostic> {
+    let manifest = Manifest::parse(text);
+    let mut res = Vec::new();
+    for entry in &manifest.entries {
+        let keys = entry.keys();
+        if let ["package", key, "workspace"] = keys.as_slice() {
+            if entry.value.as_ref().map_or(false, Value::is_true) {
+                if let Some(defined) = &info.workspace_package_keys {
+                    if !defined.contains(*key) {
+                        res.push(ManifestDiagnostic {
+                            range: entry.path[1].range.clone(),
+                            message: format!("`{}` is not defined in `[workspace.package]`", key),
+                        });
+                    }
+                }
+            }
+            continue;
+        }
+
+        let dep = match manifest.dependency(entry) {
+            Some(it) => it,
+            None => continue,
+        };
+        match (dep.field, &entry.value) {
+            (Some("workspace"), Some(value)) if value.is_true() && !dep.is_workspace_table => {
+                let crate_key = &entry.path[dep.table_len];
+                if let Some(defined) = &info.workspace_dependencies {
+                    if !defined.contains(&crate_key.text) {
+                        res.push(ManifestDiagnostic {
+                            range: crate_key.range.clone(),
+                            message: format!(
+                                "`{}` is not defined in `[workspace.dependencies]`",
+                                crate_key.text
+                            ),
+                        });
+                    }
+                }
+            }
+            (Some("features"), Some(value)) => {
+                let resolved = match info.dependencies.get(&dep.package) {
+                    Some(it) => it,
+                    None => continue,
+                };
+                for (feature, range) in value.strings() {
+                    // `default` is always accepted and `dep/feature` refers to another package.
+                    if feature == "default" || feature.contains('/') {
+                        continue;
+                    }
+                    if !resolved.features.contains_key(&feature) {
+                        res.push(ManifestDiagnostic {
+                            range,
+                            message: format!("unknown feature `{}` of `{}`", feature, dep.package),
+                        });
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+    res
+}
+
+#[derive(Debug, Clone)]
+struct Key {
+    text: String,
+    range: Range<usize>,
+}
+
+#[derive(Debug)]
+enum Value {
+    String {
+        text: String,
+        range: Range<usize>,
+    },
+    Array(Vec<Value>),
+    Bool(bool),
+    /// Numbers, dates and inline tables, whose entries are parsed separately.
+    Other,
+}
+
+impl Value {
+    fn is_true(&self) -> bool {
+        matches!(self, Value::Bool(true))
+    }
+
+    /// The string items of an array, with their ranges.
+    fn strings(&self) -> Vec<(String, Range<usize>)> {
+        match self {
+            Value::Array(items) => items
+                .iter()
+                .filter_map(|it| match it {
+                    Value::String { text, range } => Some((text.clone(), range.clone())),
+                    _ => None,
+                })
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// The innermost string containing `offset`.
+    fn string_at(&self, offset: usize) -> Option<Range<usize>> {
+        match self {
+            Value::String { range, .. } if range.start <= offset && offset <= range.end => {
+                Some(range.clone())
+            }
+            Value::Array(items) => items.iter().find_map(|it| it.string_at(offset)),
+            _ => None,
+        }
+    }
+}
+
+/// A key-value pair or a table header.
+///
+/// The path of an entry includes the keys of the table it is in, and those of
+/// the inline tables around it: `serde = { version = "1" }` in
+/// `[dependencies]` is the entry `dependencies.serde.version`.
+#[derive(Debug)]
+struct Entry {
+    path: Vec<Key>,
+    value: Option<Value>,
+    is_header: bool,
+    /// The number of keys of `path` written in this entry.
+    own_keys: usize,
+}
+
+impl Entry {
+    fn keys(&self) -> Vec<&str> {
+        self.path.iter().map(|it| it.text.as_str()).collect()
+    }
+}
+
+enum Position {
+    /// The offset is in the key with the given index of the entry path.
+    Key(usize),
+    /// The offset is in the string with the given range.
+    String(Range<usize>),
+}
+
+/// A dependency entry, like `serde.version` in `[dependencies]`.
+struct Dependency<'a> {
+    /// The number of keys naming the dependency table.
+    table_len: usize,
+    /// The name of the package, which differs from the key for renamed dependencies.
+    package: String,
+    /// The key of the dependency the entry sets, if any.
+    field: Option<&'a str>,
+    is_workspace_table: bool,
+}
+
+struct Manifest {
+    entries: Vec<Entry>,
+}
+
+impl Manifest {
+    fn parse(text: &str) -> Manifest {
+        let mut parser = Parser { text, pos: 0, table: Vec::new(), entries: Vec::new() };
+        parser.parse();
+        Manifest { entries: parser.entries }
+    }
+
+    fn keys_of(&self, table: &[&str]) -> FxHashSet<String> {
+        self.entries
+            .iter()
+            .filter_map(|entry| {
+                let keys = entry.keys();
+                (keys.len() > table.len() && keys[..table.len()] == *table)
+                    .then(|| keys[table.len()].to_owned())
+            })
+            .collect()
+    }
+
+    fn at_offset(&self, offset: usize) -> Option<(&Entry, Position)> {
+        self.entries.iter().find_map(|entry| {
+            let own_keys = entry.path.len() - entry.own_keys..entry.path.len();
+            if let Some(key) = own_keys.into_iter().find(|&i| {
+                let range = &entry.path[i].range;
+                range.start <= offset && offset <= range.end
+            }) {
+                return Some((entry, Position::Key(key)));
+            }
+            let range = entry.value.as_ref()?.string_at(offset)?;
+            Some((entry, Position::String(range)))
+        })
+    }
+
+    fn dependency<'a>(&self, entry: &'a Entry) -> Option<Dependency<'a>> {
+        let keys = entry.keys();
+        let table_len = match keys.as_slice() {
+            [kind, ..] if is_dependency_table(kind) => 1,
+            ["target", _, kind, ..] if is_dependency_table(kind) => 3,
+            ["workspace", "dependencies", ..] => 2,
+            _ => return None,
+        };
+        let crate_key = entry.path.get(table_len)?;
+        let package = self
+            .entries
+            .iter()
+            .find_map(|other| {
+                let other_keys = other.keys();
+                if other_keys.len() == table_len + 2
+                    && other_keys[..=table_len] == keys[..=table_len]
+                    && other_keys[table_len + 1] == "package"
+                {
+                    match &other.value {
+                        Some(Value::String { text, .. }) => return Some(text.clone()),
+                        _ => (),
+                    }
+                }
+                None
+            })
+            .unwrap_or_else(|| crate_key.text.clone());
+        Some(Dependency {
+            table_len,
+            package,
+            field: entry.path.get(table_len + 1).map(|it| it.text.as_str()),
+            is_workspace_table: keys[0] == "workspace",
+        })
+    }
+}
+
+fn is_dependency_table(key: &str) -> bool {
+    matches!(key, "dependencies" | "dev-dependencies" | "build-dependencies")
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    pos: usize,
+    table: Vec<Key>,
+    entries: Vec<Entry>,
+}
+
+impl Parser<'_> {
+    fn parse(&mut self) {
+        loop {
+            self.skip_trivia(true);
+            let c = match self.peek() {
+                Some(it) => it,
+                None => break,
+            };
+            if c == '[' {
+                self.pos += 1;
+                let is_array = self.eat('[');
+                let keys = self.keys();
+                self.eat(']');
+                if is_array {
+                    self.eat(']');
+                }
+                self.table = keys.clone();
+                let own_keys = keys.len();
+                self.entries.push(Entry { path: keys, value: None, is_header: true, own_keys });
+            } else {
+                self.key_value(self.table.clone());
+            }
+            self.skip_line();
+        }
+    }
+
+    /// Parses `key = value` and returns whether anything was consumed.
+    fn key_value(&mut self, mut path: Vec<Key>) -> bool {
+        let keys = self.keys();
+        if keys.is_empty() {
+            return false;
+        }
+        let own_keys = keys.len();
+        path.extend(keys);
+        self.skip_trivia(false);
+        let value = if self.eat('=') { self.value(&path) } else { None };
+        self.entries.push(Entry { path, value, is_header: false, own_keys });
+        true
+    }
+
+    fn keys(&mut self) -> Vec<Key> {
+        let mut keys = Vec::new();
+        loop {
+            self.skip_trivia(false);
+            let key = match self.peek() {
+                Some('"' | '\'') => match self.string() {
+                    Some(Value::String { text, range }) => Key { text, range },
+                    _ => break,
+                },
+                Some(c) if is_bare_key_char(c) => {
+                    let start = self.pos;
+                    while self.peek().map_or(false, is_bare_key_char) {
+                        self.pos += 1;
+                    }
+                    Key { text: self.text[start..self.pos].to_owned(), range: start..self.pos }
+                }
+                _ => break,
+            };
+            keys.push(key);
+            self.skip_trivia(false);
+            if !self.eat('.') {
+                break;
+            }
+        }
+        keys
+    }
+
+    fn value(&mut self, path: &[Key]) -> Option<Value> {
+        self.skip_trivia(false);
+        let start = self.pos;
+        let value = match self.peek()? {
+            '"' | '\'' => return self.string(),
+            '[' => {
+                self.pos += 1;
+                let mut items = Vec::new();
+                loop {
+                    self.skip_trivia(true);
+                    match self.peek() {
+                        None => break,
+                        Some(']') => {
+                            self.pos += 1;
+                            break;
+                        }
+                        Some(',') => self.pos += 1,
+                        Some(_) => match self.value(path) {
+                            Some(it) => items.push(it),
+                            None => break,
+                        },
+                    }
+                }
+                Value::Array(items)
+            }
+            '{' => {
+                self.pos += 1;
+                loop {
+                    self.skip_trivia(false);
+                    match self.peek() {
+                        None | Some('\n') => break,
+                        Some('}') => {
+                            self.pos += 1;
+                            break;
+                        }
+                        Some(',') => self.pos += 1,
+                        Some(_) => {
+                            if !self.key_value(path.to_vec()) {
+                                break;
+                            }
+                        }
+                    }
+                }
+                Value::Other
+            }
+            _ => {
+                while self.peek().map_or(false, |c| !matches!(c, ',' | ']' | '}' | '#' | '\n')) {
+                    self.pos += 1;
+                }
+                match self.text[start..self.pos].trim_end() {
+                    "" => return None,
+                    "true" => Value::Bool(true),
+                    "false" => Value::Bool(false),
+                    _ => Value::Other,
+                }
+            }
+        };
+        Some(value)
+    }
+
+    /// Parses a string, ending it at the end of the line if it isn't closed.
+    /// The range of the value is that of its contents.
+    fn string(&mut self) -> Option<Value> {
+        let quote = self.peek()?;
+        let rest = &self.text[self.pos..];
+        let delimiter = if rest.starts_with(&format!("{0}{0}{0}", quote)) { 3 } else { 1 };
+        self.pos += delimiter;
+        let start = self.pos;
+        let mut text = String::new();
+        loop {
+            let c = match self.peek() {
+                None => break,
+                Some('\n') if delimiter == 1 => break,
+                Some(it) => it,
+            };
+            if c == quote && self.text[self.pos..].starts_with(&quote.to_string().repeat(delimiter))
+            {
+                break;
+            }
+            if c == '\\' && quote == '"' {
+                self.pos += 1;
+                if let Some(escaped) = self.peek() {
+                    text.push(escaped);
+                    self.pos += escaped.len_utf8();
+                }
+                continue;
+            }
+            text.push(c);
+            self.pos += c.len_utf8();
+        }
+        let range = start..self.pos;
+        if self.text[self.pos..].starts_with(quote) {
+            self.pos += delimiter;
+        }
+        Some(Value::String { text, range })
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.text[self.pos..].chars().next()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        let res = self.peek() == Some(c);
+        if res {
+            self.pos += c.len_utf8();
+        }
+        res
+    }
+
+    /// Skips whitespace and comments, and newlines if `newlines` is set.
+    fn skip_trivia(&mut self, newlines: bool) {
+        while let Some(c) = self.peek() {
+            match c {
+                '#' => match self.text[self.pos..].find('\n') {
+                    Some(it) => self.pos += it,
+                    None => self.pos = self.text.len(),
+                },
+                '\n' if !newlines => break,
+                c if c.is_whitespace() => self.pos += c.len_utf8(),
+                _ => break,
+            }
+        }
+    }
+
+    fn skip_line(&mut self) {
+        match self.text[self.pos..].find('\n') {
+            Some(it) => self.pos += it + 1,
+            None => self.pos = self.text.len(),
+        }
+    }
+}
+
+fn is_bare_key_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '-' || c == '_'
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, path::PathBuf};
+
+    use expect_test::{expect, Expect};
+
+    use super::*;
+
+    /// Creates an index cache with the given crates, versions and features.
+    fn index(name: &str, crates: &[(&str, &[(&str, bool)])]) -> (RegistryIndex, PathBuf) {
+        let cache = env::temp_dir().join(format!("ra-registry-{}-{}", name, std::process::id()));
+        for (krate, versions) in crates {
+            let mut bytes = vec![3, 2, 0, 0, 0];
+            bytes.extend_from_slice(b"rev\0");
+            for (version, yanked) in *versions {
+                let line = format!(
+                    r#"{{"name":"{}","vers":"{}","yanked":{},"features":{{"std":[],"derive":["dep:{}_derive"]}}}}"#,
+                    krate, version, yanked, krate
+                );
+                bytes.extend_from_slice(format!("{}\0{}\0", version, line).as_bytes());
+            }
+            let path = cache.join(&krate[..2]).join(&krate[2..4]).join(krate);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, bytes).unwrap();
+        }
+        (RegistryIndex::new(vec![cache.clone()]), cache)
+    }
+
+    fn with_offset(ra_fixture: &str) -> (String, usize) {
+        let offset = ra_fixture.find("$0").unwrap();
+        (ra_fixture.replace("$0", ""), offset)
+    }
+
+    fn info() -> ManifestInfo {
+        let features = [("std", vec![]), ("derive", vec!["serde_derive".to_owned()])];
+        let serde = ResolvedDependency {
+            version: "1.0.137".to_owned(),
+            repository: Some("https://github.com/serde-rs/serde".to_owned()),
+            features: features.into_iter().map(|(k, v)| (k.to_owned(), v)).collect(),
+        };
+        ManifestInfo {
+            dependencies: [("serde".to_owned(), serde)].into_iter().collect(),
+            workspace_package_keys: Some(["version".to_owned()].into_iter().collect()),
+            workspace_dependencies: Some(["serde".to_owned()].into_iter().collect()),
+        }
+    }
+
+    fn check_completions(ra_fixture: &str, expect: Expect) {
+        let (index, cache) = index(
+            "completions",
+            &[("serde", &[("1.0.1", false), ("1.0.2", false), ("1.0.3", true)]), ("sera", &[])],
+        );
+        let (text, offset) = with_offset(ra_fixture);
+        let completions = manifest_completions(&text, offset, &info(), &index);
+        let _ = fs::remove_dir_all(cache);
+        let actual = completions
+            .iter()
+            .map(|it| format!("{} {:?} {:?}\n", it.insert, it.range, it.detail))
+            .collect::<String>();
+        expect.assert_eq(&actual);
+    }
+
+    #[test]
+    fn completes_crate_names() {
+        check_completions(
+            r#"
+[dependencies]
+ser$0
+"#,
+            expect![[r#"
+                sera 16..19 None
+                serde = "1.0.2" 16..19 Some("1.0.2")
+            "#]],
+        );
+        check_completions(
+            r#"
+[dependencies.ser$0]
+"#,
+            expect![[r#"
+                sera 15..18 None
+                serde 15..18 Some("1.0.2")
+            "#]],
+        );
+    }
+
+    #[test]
+    fn completes_versions() {
+        check_completions(
+            r#"
+[dependencies]
+serde = "1.$0"
+"#,
+            expect![[r#"
+                1.0.2 25..27 Some("latest")
+                1.0.1 25..27 None
+            "#]],
+        );
+        check_completions(
+            r#"
+[target.'cfg(unix)'.dependencies]
+json = { package = "serde", version = "$0" }
+"#,
+            expect![[r#"
+                1.0.2 74..74 Some("latest")
+                1.0.1 74..74 None
+            "#]],
+        );
+    }
+
+    #[test]
+    fn completes_features_of_resolved_package() {
+        check_completions(
+            r#"
+[dependencies]
+serde = { version = "1", features = [
+    "std",
+    "$0",
+] }
+"#,
+            expect![[r#"
+                derive 70..70 Some("[serde_derive]")
+            "#]],
+        );
+    }
+
+    #[test]
+    fn completes_dependency_keys() {
+        check_completions(
+            r#"
+[dependencies]
+serde.feat$0
+"#,
+            expect![[r#"
+                version 22..26 None
+                features 22..26 None
+                default-features 22..26 None
+                optional 22..26 None
+                path 22..26 None
+                git 22..26 None
+                branch 22..26 None
+                tag 22..26 None
+                rev 22..26 None
+                package 22..26 None
+                registry 22..26 None
+                workspace 22..26 None
+            "#]],
+        );
+    }
+
+    #[test]
+    fn hovers_dependencies_and_features() {
+        let (index, cache) = index("hover", &[("serde", &[("1.0.152", false)])]);
+        let (text, offset) = with_offset(
+            r#"
+[dependencies]
+se$0rde = { version = "1", features = ["derive"] }
+"#,
+        );
+        let hover = manifest_hover(&text, offset, &info(), &index).unwrap();
+        let _ = fs::remove_dir_all(cache);
+        expect![[r#"
+            **serde**
+
+            Resolved version: `1.0.137`
+
+            Repository: https://github.com/serde-rs/serde
+
+            Latest version in the local index: `1.0.152`"#]]
+        .assert_eq(&hover.markdown);
+
+        let (text, offset) = with_offset(
+            r#"
+[dependencies]
+serde = { version = "1", features = ["der$0ive"] }
+"#,
+        );
+        let hover = manifest_hover(&text, offset, &info(), &RegistryIndex::default()).unwrap();
+        expect![[r#"
+            ```toml
+            derive = ["serde_derive"]
+            ```"#]]
+        .assert_eq(&hover.markdown);
+    }
+
+    #[test]
+    fn non_ascii_dependency_names() {
+        check_completions(
+            r#"
+[dependencies]
+"añb" = "$0"
+"#,
+            expect![[r#""#]],
+        );
+        let (text, offset) = with_offset(
+            r#"
+[dependencies]
+"a$0ñb" = "1"
+"#,
+        );
+        let hover = manifest_hover(&text, offset, &info(), &RegistryIndex::default());
+        assert!(hover.is_some());
+    }
+
+    #[test]
+    fn reports_unknown_features_and_workspace_keys() {
+        let text = r#"
+[package]
+name = "foo"
+version.workspace = true
+edition = { workspace = true }
+
+[dependencies]
+serde = { version = "1", features = ["derive", "sdt", "default", "other/std"] }
+regex = { workspace = true }
+serde_json.workspace = true
+"#;
+        let actual = manifest_diagnostics(text, &info())
+            .into_iter()
+            .map(|it| format!("{:?} {}\n", it.range, it.message))
+            .collect::<String>();
+        expect![[r#"
+            49..56 `edition` is not defined in `[workspace.package]`
+            144..147 unknown feature `sdt` of `serde`
+            176..181 `regex` is not defined in `[workspace.dependencies]`
+            205..215 `serde_json` is not defined in `[workspace.dependencies]`
+        "#]]
+        .assert_eq(&actual);
+    }
+}
@@ -1,8 +1,10 @@
 //! See [`ManifestPath`].
-use std::{ops, path::Path};
+use std::{fs, ops, path::Path};
 
 use paths::{AbsPath, AbsPathBuf};
 
+use crate::has_embedded_manifest;
+
 /// More or less [`AbsPathBuf`] with non-None parent.
 ///
 /// We use it to store path to Cargo.toml, as we frequently use the parent dir
@@ -34,6 +36,14 @@ impl ManifestPath {
     pub fn parent(&self) -> &AbsPath {
         self.file.parent().unwrap()
     }
+
+    /// Whether this points to a single-file Cargo package, that is a `.rs` file
+    /// with an embedded manifest, rather than to a `Cargo.toml` or
+    /// `rust-project.json`.
+    pub fn is_cargo_script(&self) -> bool {
+        self.file.extension().map_or(false, |ext| ext == "rs")
+            && fs::read_to_string(&self.file).map_or(false, |text| has_embedded_manifest(&text))
+    }
 }
 
 impl ops::Deref for ManifestPath {
@@ -0,0 +1,607 @@
+//! Reconstructs `cargo metadata` output from `Cargo.toml` and `Cargo.lock` files.
+//!
+//! `cargo metadata` fails whenever cargo can't resolve the dependency graph, for example
+//! because the network is down, a private registry asks for credentials or the manifest needs a
+//! newer cargo. Instead of leaving the user without any analysis, we then take the resolved
+//! dependency graph from `Cargo.lock` and look up the sources of the locked packages in
+//! `vendor/` and in cargo's registry cache.
+//!
+//! The result is necessarily degraded:
+//!
+//! * dependencies of all targets are included, as if `--filter-platform` wasn't passed,
+//! * features are only approximated by following the `[features]` tables,
+//! * packages without locally available sources, including all git dependencies that aren't
+//!   vendored, are skipped.
+use std::{collections::VecDeque, fs};
+
+use anyhow::{format_err, Context, Result};
+use paths::{AbsPath, AbsPathBuf};
+use rustc_hash::{FxHashMap, FxHashSet};
+use serde_json::{json, Value};
+use toml::value::Table;
+
+use crate::ManifestPath;
+
+pub(crate) fn fetch(cargo_toml: &ManifestPath) -> Result<cargo_metadata::Metadata> {
+    let (workspace_root, root_manifest) = find_workspace_root(cargo_toml)?;
+    let workspace = root_manifest.get("workspace").and_then(|it| it.as_table());
+
+    let mut members = Vec::new();
+    if root_manifest.contains_key("package") {
+        members.push(workspace_root.join("Cargo.toml"));
+    }
+    if let Some(workspace) = workspace {
+        let excluded: Vec<AbsPathBuf> =
+            strings(workspace.get("exclude")).map(|it| workspace_root.join(it)).collect();
+        for pattern in strings(workspace.get("members")) {
+            for dir in expand_member_glob(&workspace_root, pattern) {
+                let manifest = dir.join("Cargo.toml");
+                if !excluded.iter().any(|it| dir.starts_with(it)) && fs::metadata(&manifest).is_ok()
+                {
+                    members.push(manifest);
+                }
+            }
+        }
+    }
+    if !members.iter().any(|it| *it == **cargo_toml) {
+        members.push(cargo_toml.to_path_buf());
+    }
+
+    let mut resolver = Resolver {
+        workspace_root: workspace_root.clone(),
+        workspace_package: workspace.and_then(|it| it.get("package")?.as_table()),
+        workspace_dependencies: workspace.and_then(|it| it.get("dependencies")?.as_table()),
+        lockfile: read_lockfile(&workspace_root.join("Cargo.lock")),
+        cargo_home: cargo_home(),
+        packages: Vec::new(),
+        package_by_manifest: FxHashMap::default(),
+        queue: VecDeque::new(),
+    };
+    let mut members: Vec<usize> =
+        members.into_iter().filter_map(|it| resolver.enqueue(it, None, true)).collect();
+    members.sort();
+    members.dedup();
+    while let Some(idx) = resolver.queue.pop_front() {
+        resolver.resolve_dependencies(idx);
+    }
+
+    let active_features = resolver.active_features(&members);
+    let packages = &resolver.packages;
+    let meta = json!({
+        "packages": packages.iter().map(ResolvedPackage::to_json).collect::<Vec<_>>(),
+        "workspace_members": members.iter().map(|&it| &packages[it].id).collect::<Vec<_>>(),
+        "resolve": {
+            "nodes": packages.iter().zip(active_features).map(|(pkg, features)| json!({
+                "id": pkg.id,
+                "deps": pkg.dependencies.iter().map(|dep| json!({
+                    "name": dep.name,
+                    "pkg": packages[dep.package].id,
+                    "dep_kinds": [{ "kind": dep.kind, "target": null }],
+                })).collect::<Vec<_>>(),
+                "dependencies": pkg.dependencies.iter().map(|dep| &packages[dep.package].id).collect::<Vec<_>>(),
+                "features": features,
+            })).collect::<Vec<_>>(),
+            "root": null,
+        },
+        "workspace_root": workspace_root.display().to_string(),
+        "target_directory": workspace_root.join("target").display().to_string(),
+        "version": 1,
+    });
+    serde_json::from_value(meta).context("Failed to assemble Cargo metadata from manifests")
+}
+
+struct LockedPackage {
+    name: String,
+    version: String,
+    source: Option<String>,
+    dependencies: Vec<String>,
+}
+
+impl LockedPackage {
+    /// Looks up the dependency on a package called `name` in the lockfile's `dependencies`
+    /// array, whose entries look like `name`, `name version` or `name version (source)`.
+    ///
+    /// Returns the version if the lockfile pins one.
+    fn locked_dependency(&self, name: &str) -> Option<Option<&str>> {
+        self.dependencies.iter().find_map(|dep| {
+            let mut parts = dep.split(' ');
+            (parts.next()? == name).then(|| parts.next())
+        })
+    }
+}
+
+struct ResolvedPackage {
+    id: String,
+    name: String,
+    version: String,
+    source: Option<String>,
+    manifest: AbsPathBuf,
+    toml: Table,
+    edition: String,
+    is_member: bool,
+    dependencies: Vec<ResolvedDependency>,
+}
+
+struct ResolvedDependency {
+    /// The name the dependency is available under in the crate, with dashes replaced.
+    name: String,
+    package: usize,
+    /// `null`, `"dev"` or `"build"`, as in `cargo metadata`.
+    kind: Option<&'static str>,
+    features: Vec<String>,
+    default_features: bool,
+}
+
+struct Resolver<'a> {
+    workspace_root: AbsPathBuf,
+    workspace_package: Option<&'a Table>,
+    workspace_dependencies: Option<&'a Table>,
+    lockfile: Vec<LockedPackage>,
+    cargo_home: Option<AbsPathBuf>,
+    packages: Vec<ResolvedPackage>,
+    package_by_manifest: FxHashMap<AbsPathBuf, usize>,
+    queue: VecDeque<usize>,
+}
+
+impl Resolver<'_> {
+    fn enqueue(
+        &mut self,
+        manifest: AbsPathBuf,
+        source: Option<String>,
+        is_member: bool,
+    ) -> Option<usize> {
+        let manifest = manifest.normalize();
+        if let Some(&idx) = self.package_by_manifest.get(&manifest) {
+            return Some(idx);
+        }
+        let toml = match read_toml(&manifest) {
+            Ok(it) => it,
+            Err(e) => {
+                tracing::warn!("{:#}", e);
+                return None;
+            }
+        };
+        let package = toml.get("package")?.as_table()?;
+        let name = package.get("name")?.as_str()?.to_string();
+        let version = self
+            .inherit(package, "version")
+            .and_then(|it| it.as_str())
+            .filter(|it| semver::Version::parse(it).is_ok())
+            .unwrap_or("0.0.0")
+            .to_string();
+        let edition = match self.inherit(package, "edition").and_then(|it| it.as_str()) {
+            Some(edition @ ("2015" | "2018" | "2021")) => edition.to_string(),
+            Some(_) => "2021".to_string(),
+            None => "2015".to_string(),
+        };
+        let dir = manifest.parent()?;
+        let id = format!(
+            "{} {} ({})",
+            name,
+            version,
+            source.clone().unwrap_or_else(|| format!("path+file://{}", dir.display()))
+        );
+
+        let idx = self.packages.len();
+        self.packages.push(ResolvedPackage {
+            id,
+            name,
+            version,
+            source,
+            manifest: manifest.clone(),
+            toml,
+            edition,
+            is_member,
+            dependencies: Vec::new(),
+        });
+        self.package_by_manifest.insert(manifest, idx);
+        self.queue.push_back(idx);
+        Some(idx)
+    }
+
+    fn inherit<'t>(&'t self, package: &'t Table, key: &str) -> Option<&'t toml::Value> {
+        match package.get(key)? {
+            toml::Value::Table(it)
+                if it.get("workspace").and_then(|it| it.as_bool()) == Some(true) =>
+            {
+                self.workspace_package?.get(key)
+            }
+            it => Some(it),
+        }
+    }
+
+    fn resolve_dependencies(&mut self, idx: usize) {
+        let pkg = &self.packages[idx];
+        let dir = pkg.manifest.parent().unwrap().to_path_buf();
+        let locked = self.lockfile.iter().position(|it| {
+            it.name == pkg.name && it.version == pkg.version && it.source == pkg.source
+        });
+
+        let mut tables = vec![&pkg.toml];
+        if let Some(targets) = pkg.toml.get("target").and_then(|it| it.as_table()) {
+            tables.extend(targets.values().filter_map(|it| it.as_table()));
+        }
+        let mut dependencies = Vec::new();
+        for table in tables {
+            for (section, kind) in [
+                ("dependencies", None),
+                ("dev-dependencies", Some("dev")),
+                ("build-dependencies", Some("build")),
+            ] {
+                // Like cargo, we don't care about the dev-dependencies of dependencies.
+                if kind == Some("dev") && !pkg.is_member {
+                    continue;
+                }
+                let section = match table.get(section).and_then(|it| it.as_table()) {
+                    Some(it) => it,
+                    None => continue,
+                };
+                for (key, spec) in section {
+                    dependencies.push((key.clone(), kind, self.dependency_spec(spec, key)));
+                }
+            }
+        }
+
+        let mut resolved = Vec::new();
+        for (key, kind, spec) in dependencies {
+            let package_name = spec.get("package").and_then(|it| it.as_str()).unwrap_or(&key);
+            let optional = spec.get("optional").and_then(|it| it.as_bool()).unwrap_or(false);
+            let locked_version = match locked {
+                Some(locked) => match self.lockfile[locked].locked_dependency(package_name) {
+                    Some(version) => version.map(ToString::to_string),
+                    // Not in the lockfile, so either an optional dependency that isn't enabled,
+                    // or the lockfile is outdated.
+                    None => continue,
+                },
+                None if optional => continue,
+                None => None,
+            };
+
+            let target = match spec.get("path").and_then(|it| it.as_str()) {
+                Some(path) => {
+                    let base = match spec.get("workspace") {
+                        Some(_) => &self.workspace_root,
+                        None => &dir,
+                    };
+                    self.enqueue(base.join(path).join("Cargo.toml"), None, false)
+                }
+                None => self.find_registry_package(package_name, locked_version.as_deref()),
+            };
+            let package = match target {
+                Some(it) => it,
+                None => {
+                    tracing::info!("sources of dependency `{}` are not available", package_name);
+                    continue;
+                }
+            };
+            resolved.push(ResolvedDependency {
+                name: key.replace('-', "_"),
+                package,
+                kind,
+                features: strings(spec.get("features")).map(ToString::to_string).collect(),
+                default_features: spec
+                    .get("default-features")
+                    .or_else(|| spec.get("default_features"))
+                    .and_then(|it| it.as_bool())
+                    .unwrap_or(true),
+            });
+        }
+        self.packages[idx].dependencies = resolved;
+    }
+
+    /// Normalizes a dependency specification to its table form, merging in the entry from
+    /// `[workspace.dependencies]` if it's inherited.
+    fn dependency_spec(&self, spec: &toml::Value, key: &str) -> Table {
+        let mut res = Table::new();
+        match spec {
+            toml::Value::String(version) => {
+                res.insert("version".into(), version.clone().into());
+            }
+            toml::Value::Table(table) => {
+                if table.get("workspace").and_then(|it| it.as_bool()) == Some(true) {
+                    match self.workspace_dependencies.and_then(|it| it.get(key)) {
+                        Some(toml::Value::Table(inherited)) => res.extend(inherited.clone()),
+                        Some(toml::Value::String(version)) => {
+                            res.insert("version".into(), version.clone().into());
+                        }
+                        _ => (),
+                    }
+                    let mut features: Vec<toml::Value> = strings(res.get("features"))
+                        .chain(strings(table.get("features")))
+                        .map(|it| it.to_string().into())
+                        .collect();
+                    features.dedup();
+                    res.extend(table.clone());
+                    res.insert("features".into(), features.into());
+                } else {
+                    res.extend(table.clone());
+                    res.remove("workspace");
+                }
+            }
+            _ => (),
+        }
+        res
+    }
+
+    fn find_registry_package(&mut self, name: &str, version: Option<&str>) -> Option<usize> {
+        let locked = self
+            .lockfile
+            .iter()
+            .filter(|it| it.name == name && version.map_or(true, |version| it.version == version))
+            .last()?;
+        let (version, source) = (locked.version.clone(), locked.source.clone());
+
+        let dir_name = format!("{}-{}", name, version);
+        let vendor = self.workspace_root.join("vendor");
+        let mut candidates = vec![vendor.join(&dir_name), vendor.join(name)];
+        if let Some(registry) = self.cargo_home.as_ref().map(|it| it.join("registry").join("src")) {
+            if let Ok(entries) = fs::read_dir(&registry) {
+                candidates.extend(
+                    entries.flatten().map(|entry| AbsPathBuf::assert(entry.path()).join(&dir_name)),
+                );
+            }
+        }
+        let manifest = candidates.into_iter().map(|it| it.join("Cargo.toml")).find(|it| {
+            // `vendor/name` might hold a different version of the package.
+            read_toml(it).ok().map_or(false, |toml| {
+                toml.get("package").and_then(|it| it.get("version")).and_then(|it| it.as_str())
+                    == Some(&version)
+            })
+        })?;
+        self.enqueue(manifest, source, false)
+    }
+
+    /// Approximates the features cargo would enable: those requested by dependent packages,
+    /// `default` unless opted out of, and whatever these enable in turn.
+    fn active_features(&self, members: &[usize]) -> Vec<Vec<String>> {
+        let mut requested = vec![FxHashSet::default(); self.packages.len()];
+        for &member in members {
+            requested[member].insert("default".to_string());
+        }
+        for pkg in &self.packages {
+            for dep in &pkg.dependencies {
+                requested[dep.package].extend(dep.features.iter().cloned());
+                if dep.default_features {
+                    requested[dep.package].insert("default".to_string());
+                }
+            }
+        }
+
+        self.packages
+            .iter()
+            .zip(requested)
+            .map(|(pkg, requested)| {
+                let features = pkg.toml.get("features").and_then(|it| it.as_table());
+                let mut active = FxHashSet::default();
+                let mut stack: Vec<String> = requested.into_iter().collect();
+                while let Some(feature) = stack.pop() {
+                    let enables = match features.and_then(|it| it.get(&feature)) {
+                        Some(it) => it,
+                        None => continue,
+                    };
+                    if active.insert(feature) {
+                        stack.extend(strings(Some(enables)).map(ToString::to_string));
+                    }
+                }
+                let mut active: Vec<String> = active.into_iter().collect();
+                active.sort();
+                active
+            })
+            .collect()
+    }
+}
+
+impl ResolvedPackage {
+    fn to_json(&self) -> Value {
+        let package = self.toml.get("package").and_then(|it| it.as_table());
+        let features: FxHashMap<&String, Vec<&str>> = self
+            .toml
+            .get("features")
+            .and_then(|it| it.as_table())
+            .into_iter()
+            .flatten()
+            .map(|(name, enables)| (name, strings(Some(enables)).collect()))
+            .collect();
+        json!({
+            "name": self.name,
+            "version": self.version,
+            "id": self.id,
+            "source": self.source,
+            "dependencies": [],
+            "targets": self.targets(),
+            "features": features,
+            "manifest_path": self.manifest.display().to_string(),
+            "edition": self.edition,
+            "metadata": package
+                .and_then(|it| it.get("metadata"))
+                .and_then(|it| serde_json::to_value(it).ok()),
+            "repository": package.and_then(|it| it.get("repository")).and_then(|it| it.as_str()),
+        })
+    }
+
+    fn targets(&self) -> Vec<Value> {
+        let dir = self.manifest.parent().unwrap();
+        let package = self.toml.get("package").and_then(|it| it.as_table());
+        let auto = |key: &str| {
+            package.and_then(|it| it.get(key)).and_then(|it| it.as_bool()).unwrap_or(true)
+        };
+        let mut res = Vec::new();
+        let mut target = |name: &str, kind: &str, path: AbsPathBuf, table: Option<&Table>| {
+            if fs::metadata(&path).is_ok() {
+                let required_features: Vec<&str> =
+                    strings(table.and_then(|it| it.get("required-features"))).collect();
+                res.push(json!({
+                    "name": name,
+                    "kind": [kind],
+                    "crate_types": [if kind == "proc-macro" { "proc-macro" } else { "bin" }],
+                    "required-features": required_features,
+                    "src_path": path.display().to_string(),
+                    "edition": self.edition,
+                }));
+            }
+        };
+
+        let lib = self.toml.get("lib").and_then(|it| it.as_table());
+        let lib_field = |key: &str| lib.and_then(|it| it.get(key));
+        let is_proc_macro = lib_field("proc-macro")
+            .or_else(|| lib_field("proc_macro"))
+            .and_then(|it| it.as_bool())
+            .unwrap_or(false);
+        target(
+            &lib_field("name")
+                .and_then(|it| it.as_str())
+                .map_or_else(|| self.name.replace('-', "_"), ToString::to_string),
+            if is_proc_macro { "proc-macro" } else { "lib" },
+            dir.join(lib_field("path").and_then(|it| it.as_str()).unwrap_or("src/lib.rs")),
+            lib,
+        );
+
+        let mut kinds =
+            vec![("bin", "bin", "src/bin", auto("autobins"), Some(("src/main.rs", &self.name)))];
+        // Only the targets of workspace members are analyzed, see `cargo_to_crate_graph`.
+        if self.is_member {
+            kinds.extend([
+                ("example", "example", "examples", auto("autoexamples"), None),
+                ("test", "test", "tests", auto("autotests"), None),
+                ("bench", "bench", "benches", auto("autobenches"), None),
+            ]);
+        }
+        for (key, kind, auto_dir, auto, default) in kinds {
+            let mut names = FxHashSet::default();
+            let explicit = self.toml.get(key).and_then(|it| it.as_array()).into_iter().flatten();
+            for table in explicit.filter_map(|it| it.as_table()) {
+                let name = match table.get("name").and_then(|it| it.as_str()) {
+                    Some(it) => it,
+                    None => continue,
+                };
+                let path = match table.get("path").and_then(|it| it.as_str()) {
+                    Some(path) => dir.join(path),
+                    None => dir.join(auto_dir).join(format!("{}.rs", name)),
+                };
+                names.insert(name.to_string());
+                target(name, kind, path, Some(table));
+            }
+            if !auto {
+                continue;
+            }
+            if let Some((path, name)) = default {
+                if names.insert(name.clone()) {
+                    target(name, kind, dir.join(path), None);
+                }
+            }
+            let mut files: Vec<AbsPathBuf> = fs::read_dir(dir.join(auto_dir))
+                .into_iter()
+                .flatten()
+                .flatten()
+                .map(|entry| AbsPathBuf::assert(entry.path()))
+                .filter(|path| path.extension().map_or(false, |it| it == "rs"))
+                .collect();
+            files.sort();
+            for path in files {
+                let name = path.file_stem().unwrap().to_string_lossy().into_owned();
+                if names.insert(name.clone()) {
+                    target(&name, kind, path, None);
+                }
+            }
+        }
+
+        let build = match package.and_then(|it| it.get("build")) {
+            Some(toml::Value::String(path)) => Some(dir.join(path)),
+            Some(toml::Value::Boolean(false)) => None,
+            _ => Some(dir.join("build.rs")),
+        };
+        if let Some(build) = build {
+            target("build-script-build", "custom-build", build, None);
+        }
+        res
+    }
+}
+
+/// Finds the root of the workspace `cargo_toml` belongs to, like cargo does: the closest
+/// ancestor manifest with a `[workspace]` table.
+fn find_workspace_root(cargo_toml: &ManifestPath) -> Result<(AbsPathBuf, Table)> {
+    let manifest = read_toml(cargo_toml)?;
+    if !manifest.contains_key("workspace") {
+        let mut dir = cargo_toml.parent().parent();
+        while let Some(it) = dir {
+            let candidate = it.join("Cargo.toml");
+            if let Ok(toml) = read_toml(&candidate) {
+                if toml.contains_key("workspace") {
+                    return Ok((it.to_path_buf(), toml));
+                }
+            }
+            dir = it.parent();
+        }
+    }
+    Ok((cargo_toml.parent().to_path_buf(), manifest))
+}
+
+/// Expands a `[workspace] members` entry. Only `*` wildcards in the last path component are
+/// supported, which covers the common `crates/*`.
+fn expand_member_glob(workspace_root: &AbsPath, pattern: &str) -> Vec<AbsPathBuf> {
+    let (dir, last) = match pattern.rsplit_once('/') {
+        Some((dir, last)) => (workspace_root.join(dir), last),
+        None => (workspace_root.to_path_buf(), pattern),
+    };
+    let (prefix, suffix) = match last.split_once('*') {
+        Some(it) => it,
+        None => return vec![dir.join(last)],
+    };
+    let mut res: Vec<AbsPathBuf> = fs::read_dir(&dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            name.len() >= prefix.len() + suffix.len()
+                && name.starts_with(prefix)
+                && name.ends_with(suffix)
+        })
+        .map(|entry| AbsPathBuf::assert(entry.path()))
+        .collect();
+    res.sort();
+    res
+}
+
+fn read_lockfile(path: &AbsPath) -> Vec<LockedPackage> {
+    let toml = match read_toml(path) {
+        Ok(it) => it,
+        Err(e) => {
+            tracing::info!("{:#}", e);
+            return Vec::new();
+        }
+    };
+    let packages = toml.get("package").and_then(|it| it.as_array()).into_iter().flatten();
+    packages
+        .filter_map(|pkg| {
+            Some(LockedPackage {
+                name: pkg.get("name")?.as_str()?.to_string(),
+                version: pkg.get("version")?.as_str()?.to_string(),
+                source: pkg.get("source").and_then(|it| it.as_str()).map(ToString::to_string),
+                dependencies: strings(pkg.get("dependencies")).map(ToString::to_string).collect(),
+            })
+        })
+        .collect()
+}
+
+fn read_toml(path: &AbsPath) -> Result<Table> {
+    let text =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    toml::from_str(&text).map_err(|e| format_err!("Failed to parse {}: {}", path.display(), e))
+}
+
+fn cargo_home() -> Option<AbsPathBuf> {
+    let path = match std::env::var_os("CARGO_HOME") {
+        Some(it) => it.into(),
+        None => {
+            let home = std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE"))?;
+            std::path::PathBuf::from(home).join(".cargo")
+        }
+    };
+    AbsPathBuf::try_from(path).ok()
+}
+
+fn strings(value: Option<&toml::Value>) -> impl Iterator<Item = &str> {
+    value.and_then(|it| it.as_array()).into_iter().flatten().filter_map(|it| it.as_str())
+}
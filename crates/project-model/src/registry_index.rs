@@ -0,0 +1,172 @@
+//! Read-only access to the registry index cache cargo keeps in
+//! `$CARGO_HOME/registry/index`.
+//!
+//! We never touch the network: only crates cargo has already looked up are
+//! known, which in practice covers everything the user has depended on before.
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use rustc_hash::FxHashMap;
+use serde::Deserialize;
+
+/// The maximum number of crate names returned for a prefix.
+const MAX_CRATE_NAMES: usize = 100;
+
+#[derive(Debug, Clone, Default)]
+pub struct RegistryIndex {
+    /// The `.cache` directories of the known registries.
+    caches: Vec<PathBuf>,
+}
+
+/// A single published version of a crate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexVersion {
+    pub version: semver::Version,
+    pub yanked: bool,
+    pub features: FxHashMap<String, Vec<String>>,
+}
+
+impl RegistryIndex {
+    /// Finds the index caches of all registries in the cargo home directory.
+    pub fn from_cargo_home() -> RegistryIndex {
+        let cargo_home = env::var_os("CARGO_HOME")
+            .map(PathBuf::from)
+            .or_else(|| home::home_dir().map(|it| it.join(".cargo")));
+        let index = match cargo_home {
+            Some(it) => it.join("registry").join("index"),
+            None => return RegistryIndex::default(),
+        };
+        let caches = fs::read_dir(index)
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| Some(entry.ok()?.path().join(".cache")))
+            .filter(|it| it.is_dir())
+            .collect();
+        RegistryIndex { caches }
+    }
+
+    /// Creates an index reading from the given `.cache` directories.
+    pub fn new(caches: Vec<PathBuf>) -> RegistryIndex {
+        RegistryIndex { caches }
+    }
+
+    /// Returns the names of the known crates starting with `prefix`, shortest first.
+    ///
+    /// As the index is laid out by the first four characters of the name, at
+    /// least two characters are needed to avoid walking the whole cache.
+    pub fn crate_names(&self, prefix: &str) -> Vec<String> {
+        let prefix = prefix.to_ascii_lowercase();
+        if prefix.len() < 2 || !prefix.is_ascii() {
+            return Vec::new();
+        }
+        let mut res = Vec::new();
+        for cache in &self.caches {
+            let mut dirs = vec![cache.join("2"), cache.join("3").join(&prefix[..1])];
+            match prefix.len() {
+                2 | 3 => {
+                    let outer = cache.join(&prefix[..2]);
+                    dirs.extend(
+                        read_dir_names(&outer)
+                            .into_iter()
+                            .filter(|it| it.starts_with(&prefix[2..]))
+                            .map(|it| outer.join(it)),
+                    );
+                }
+                _ => dirs.push(cache.join(&prefix[..2]).join(&prefix[2..4])),
+            }
+            for dir in dirs {
+                res.extend(
+                    read_dir_names(&dir)
+                        .into_iter()
+                        .filter(|it| it.replace('_', "-").starts_with(&prefix.replace('_', "-"))),
+                );
+            }
+        }
+        res.sort_by(|a, b| a.len().cmp(&b.len()).then_with(|| a.cmp(b)));
+        res.dedup();
+        res.truncate(MAX_CRATE_NAMES);
+        res
+    }
+
+    /// Returns the published versions of `name`, newest first.
+    pub fn versions(&self, name: &str) -> Vec<IndexVersion> {
+        let name = name.to_ascii_lowercase();
+        let mut res = self
+            .caches
+            .iter()
+            .find_map(|cache| fs::read(cache.join(index_path(&name)?)).ok())
+            .map(|bytes| parse_cache_entry(&bytes))
+            .unwrap_or_default();
+        res.sort_by(|a, b| b.version.cmp(&a.version));
+        res
+    }
+
+    /// Returns the newest version of `name` that was not yanked.
+    pub fn latest_version(&self, name: &str) -> Option<IndexVersion> {
+        self.versions(name).into_iter().find(|it| !it.yanked && it.version.pre.is_empty())
+    }
+}
+
+/// The path of the index file of `name`, relative to the index root.
+///
+/// Crate names on crates.io are ASCII, anything else can't be in the index.
+fn index_path(name: &str) -> Option<PathBuf> {
+    if !name.is_ascii() {
+        return None;
+    }
+    let path = match name.len() {
+        0 => return None,
+        1 => Path::new("1").join(name),
+        2 => Path::new("2").join(name),
+        3 => Path::new("3").join(&name[..1]).join(name),
+        _ => Path::new(&name[..2]).join(&name[2..4]).join(name),
+    };
+    Some(path)
+}
+
+fn read_dir_names(dir: &Path) -> Vec<String> {
+    fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+        .collect()
+}
+
+/// Parses a file of the index cache.
+///
+/// The file starts with a version byte and a little-endian `u32` index
+/// version, followed by NUL-terminated strings: the index revision and then
+/// pairs of a version and its JSON index line.
+fn parse_cache_entry(bytes: &[u8]) -> Vec<IndexVersion> {
+    #[derive(Deserialize)]
+    struct IndexLine {
+        vers: semver::Version,
+        #[serde(default)]
+        yanked: bool,
+        #[serde(default)]
+        features: FxHashMap<String, Vec<String>>,
+        #[serde(default)]
+        features2: FxHashMap<String, Vec<String>>,
+    }
+
+    let body = match bytes.get(5..) {
+        Some(it) => it,
+        None => return Vec::new(),
+    };
+    let mut parts = body.split(|&b| b == 0).skip(1);
+    let mut res = Vec::new();
+    while let (Some(_version), Some(json)) = (parts.next(), parts.next()) {
+        if let Ok(mut line) = serde_json::from_slice::<IndexLine>(json) {
+            line.features.extend(line.features2);
+            res.push(IndexVersion {
+                version: line.vers,
+                yanked: line.yanked,
+                features: line.features,
+            });
+        }
+    }
+    res
+}
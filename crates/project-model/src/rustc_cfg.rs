@@ -1,6 +1,7 @@
-//! Runs `rustc --print cfg` to get built-in cfg flags.
+//! Runs `rustc --print cfg` to get built-in cfg flags, and `rustc --print check-cfg` to get the
+//! cfgs rustc expects.
 
-use std::process::Command;
+use std::process::{Command, Stdio};
 
 use anyhow::Result;
 
@@ -35,6 +36,34 @@ pub(crate) fn get(cargo_toml: Option<&ManifestPath>, target: Option<&str>) -> Ve
     res
 }
 
+/// Returns rustc's `--check-cfg` specifications of the well-known cfgs, which include the values of
+/// the target cfgs like `target_os` for every target rustc supports. Printing those is unstable,
+/// so this is empty if the toolchain can't do it.
+pub(crate) fn get_check_cfg(
+    cargo_toml: Option<&ManifestPath>,
+    target: Option<&str>,
+) -> Vec<String> {
+    let _p = profile::span("rustc_cfg::get_check_cfg");
+    let mut cmd = Command::new(toolchain::rustc());
+    if let Some(cargo_toml) = cargo_toml {
+        // Pick up the toolchain override of the workspace, if any.
+        cmd.current_dir(cargo_toml.parent());
+    }
+    cmd.args(&["-Z", "unstable-options", "--print", "check-cfg", "--check-cfg", "cfg()", "-"])
+        .env("RUSTC_BOOTSTRAP", "1")
+        .stdin(Stdio::null());
+    if let Some(target) = target {
+        cmd.args(&["--target", target]);
+    }
+    match utf8_stdout(cmd) {
+        Ok(it) => it.lines().map(|it| it.to_string()).collect(),
+        Err(e) => {
+            tracing::debug!("failed to get rustc check-cfg: {e:?}");
+            Vec::new()
+        }
+    }
+}
+
 fn get_rust_cfgs(cargo_toml: Option<&ManifestPath>, target: Option<&str>) -> Result<String> {
     if let Some(cargo_toml) = cargo_toml {
         let mut cargo_config = Command::new(toolchain::cargo());
@@ -6,11 +6,15 @@
 
 use std::{env, fs, iter, ops, path::PathBuf, process::Command};
 
-use anyhow::{format_err, Result};
+use anyhow::{format_err, Context, Result};
 use la_arena::{Arena, Idx};
 use paths::{AbsPath, AbsPathBuf};
+use rustc_hash::FxHashMap;
 
-use crate::{utf8_stdout, ManifestPath};
+use crate::{
+    cargo_workspace::DepKind, utf8_stdout, CargoConfig, CargoWorkspace, ManifestPath,
+    SysrootManifest, TargetKind,
+};
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct Sysroot {
@@ -25,7 +29,8 @@ pub(crate) type SysrootCrate = Idx<SysrootCrateData>;
 pub struct SysrootCrateData {
     pub name: String,
     pub root: ManifestPath,
-    pub deps: Vec<SysrootCrate>,
+    /// The dependencies of this crate, with the names they are available under.
+    pub deps: Vec<(String, SysrootCrate)>,
 }
 
 impl ops::Index<SysrootCrate> for Sysroot {
@@ -75,6 +80,44 @@ impl Sysroot {
         Ok(res)
     }
 
+    /// Like [`Sysroot::discover`], but loads the sysroot crates and their dependencies by running
+    /// `cargo metadata` on `manifest`, as `-Zbuild-std` does.
+    pub fn discover_with_metadata(
+        dir: &AbsPath,
+        manifest: &SysrootManifest,
+        config: &CargoConfig,
+        progress: &dyn Fn(String),
+    ) -> Result<Sysroot> {
+        tracing::debug!("Discovering sysroot for {}", dir.display());
+        let sysroot_dir = discover_sysroot_dir(dir)?;
+        let sysroot_src_dir = discover_sysroot_src_dir(&sysroot_dir, dir)?;
+        let manifest = match manifest {
+            SysrootManifest::Path(path) => {
+                ManifestPath::try_from(path.clone()).map_err(|path| {
+                    format_err!("sysroot manifest is not a Cargo.toml file: {}", path.display())
+                })?
+            }
+            SysrootManifest::Discover => {
+                get_library_manifest(&sysroot_src_dir).ok_or_else(|| {
+                    format_err!("can't find a Cargo.toml in {}", sysroot_src_dir.display())
+                })?
+            }
+        };
+        match Sysroot::load_with_metadata(
+            sysroot_dir.clone(),
+            sysroot_src_dir.clone(),
+            &manifest,
+            config,
+            progress,
+        ) {
+            Ok(sysroot) => Ok(sysroot),
+            Err(e) => {
+                tracing::error!("{:#}, falling back to the built-in list of sysroot crates", e);
+                Sysroot::load(sysroot_dir, sysroot_src_dir)
+            }
+        }
+    }
+
     pub fn discover_rustc(cargo_toml: &ManifestPath) -> Option<ManifestPath> {
         tracing::debug!("Discovering rustc source for {}", cargo_toml.display());
         let current_dir = cargo_toml.parent();
@@ -105,24 +148,89 @@ impl Sysroot {
         if let Some(std) = sysroot.by_name("std") {
             for dep in STD_DEPS.trim().lines() {
                 if let Some(dep) = sysroot.by_name(dep) {
-                    sysroot.crates[std].deps.push(dep)
+                    sysroot.add_dep(std, dep)
                 }
             }
         }
 
         if let Some(alloc) = sysroot.by_name("alloc") {
             if let Some(core) = sysroot.by_name("core") {
-                sysroot.crates[alloc].deps.push(core);
+                sysroot.add_dep(alloc, core);
             }
         }
 
         if let Some(proc_macro) = sysroot.by_name("proc_macro") {
             if let Some(std) = sysroot.by_name("std") {
-                sysroot.crates[proc_macro].deps.push(std);
+                sysroot.add_dep(proc_macro, std);
             }
         }
 
-        if sysroot.by_name("core").is_none() {
+        sysroot.check_core()?;
+        Ok(sysroot)
+    }
+
+    /// Loads the crates of the sysroot from the `cargo metadata` of `manifest`, instead of
+    /// relying on a hardcoded list of crates and dependencies.
+    pub fn load_with_metadata(
+        sysroot_dir: AbsPathBuf,
+        sysroot_src_dir: AbsPathBuf,
+        manifest: &ManifestPath,
+        config: &CargoConfig,
+        progress: &dyn Fn(String),
+    ) -> Result<Sysroot> {
+        // Features of the user's workspace have nothing to do with the standard library.
+        let config = CargoConfig { target: config.target.clone(), ..CargoConfig::default() };
+        let meta = CargoWorkspace::fetch_sysroot_metadata(manifest, &config, progress)
+            .with_context(|| {
+                format!("Failed to read Cargo metadata of the sysroot {}", manifest.display())
+            })?;
+        Sysroot::from_cargo_workspace(sysroot_dir, sysroot_src_dir, &CargoWorkspace::new(meta))
+    }
+
+    /// Builds the sysroot out of the library targets of the packages `cargo` consists of.
+    pub(crate) fn from_cargo_workspace(
+        sysroot_dir: AbsPathBuf,
+        sysroot_src_dir: AbsPathBuf,
+        cargo: &CargoWorkspace,
+    ) -> Result<Sysroot> {
+        let mut sysroot =
+            Sysroot { root: sysroot_dir, src_root: sysroot_src_dir, crates: Arena::default() };
+        let mut crate_by_package = FxHashMap::default();
+        for pkg in cargo.packages() {
+            let lib = cargo[pkg].targets.iter().find(|&&tgt| cargo[tgt].kind == TargetKind::Lib);
+            let root = lib.and_then(|&tgt| ManifestPath::try_from(cargo[tgt].root.clone()).ok());
+            if let (Some(&lib), Some(root)) = (lib, root) {
+                let krate = sysroot.crates.alloc(SysrootCrateData {
+                    name: cargo[lib].name.clone(),
+                    root,
+                    deps: Vec::new(),
+                });
+                crate_by_package.insert(pkg, krate);
+            }
+        }
+        for pkg in cargo.packages() {
+            let from = match crate_by_package.get(&pkg) {
+                Some(&it) => it,
+                None => continue,
+            };
+            for dep in cargo[pkg].dependencies.iter().filter(|dep| dep.kind == DepKind::Normal) {
+                if let Some(&to) = crate_by_package.get(&dep.pkg) {
+                    sysroot.crates[from].deps.push((dep.name.clone(), to));
+                }
+            }
+        }
+
+        sysroot.check_core()?;
+        Ok(sysroot)
+    }
+
+    fn add_dep(&mut self, from: SysrootCrate, to: SysrootCrate) {
+        let name = self.crates[to].name.clone();
+        self.crates[from].deps.push((name, to));
+    }
+
+    fn check_core(&self) -> Result<()> {
+        if self.by_name("core").is_none() {
             let var_note = if env::var_os("RUST_SRC_PATH").is_some() {
                 " (`RUST_SRC_PATH` might be incorrect, try unsetting it)"
             } else {
@@ -130,12 +238,11 @@ impl Sysroot {
             };
             anyhow::bail!(
                 "could not find libcore in sysroot path `{}`{}",
-                sysroot.src_root.as_path().display(),
+                self.src_root.as_path().display(),
                 var_note,
             );
         }
-
-        Ok(sysroot)
+        Ok(())
     }
 
     fn by_name(&self, name: &str) -> Option<SysrootCrate> {
@@ -197,6 +304,16 @@ fn get_rustc_src(sysroot_path: &AbsPath) -> Option<ManifestPath> {
     }
 }
 
+/// Finds the manifest to load the standard library sources in `sysroot_src_dir` from: newer
+/// toolchains ship a workspace for the whole `library/` directory, older ones don't, but `test`
+/// depends on all the other sysroot crates.
+fn get_library_manifest(sysroot_src_dir: &AbsPath) -> Option<ManifestPath> {
+    ["Cargo.toml", "test/Cargo.toml"]
+        .into_iter()
+        .filter_map(|it| ManifestPath::try_from(sysroot_src_dir.join(it)).ok())
+        .find(|it| fs::metadata(it).is_ok())
+}
+
 fn get_rust_src(sysroot_path: &AbsPath) -> Option<AbsPathBuf> {
     let rust_src = sysroot_path.join("lib/rustlib/src/rust/library");
     tracing::debug!("Checking sysroot: {}", rust_src.display());
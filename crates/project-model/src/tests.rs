@@ -7,11 +7,14 @@ use base_db::{CrateGraph, FileId};
 use cfg::{CfgAtom, CfgDiff};
 use expect_test::{expect, Expect};
 use paths::{AbsPath, AbsPathBuf};
+use rustc_hash::FxHashMap;
 use serde::de::DeserializeOwned;
+use stdx::format_to;
 
 use crate::{
-    CargoWorkspace, CfgOverrides, ProjectJson, ProjectJsonData, ProjectWorkspace, Sysroot,
-    WorkspaceBuildScripts,
+    add_standalone_files_to_crate_graph, has_embedded_manifest, offline_metadata, CargoWorkspace,
+    CfgOverrides, ManifestPath, ProjectJson, ProjectJsonData, ProjectManifest, ProjectWorkspace,
+    Sysroot, WorkspaceBuildScripts,
 };
 
 fn load_cargo(file: &str) -> CrateGraph {
@@ -27,8 +30,10 @@ fn load_cargo_with_overrides(file: &str, cfg_overrides: CfgOverrides) -> CrateGr
         sysroot: None,
         rustc: None,
         rustc_cfg: Vec::new(),
+        rustc_check_cfg: Vec::new(),
         cfg_overrides,
         toolchain: None,
+        metadata_error: None,
     };
     to_crate_graph(project_workspace)
 }
@@ -146,6 +151,9 @@ fn cargo_hello_world_project_model_with_wildcard_overrides() {
                                 "debug_assertions",
                             ],
                         ),
+                        build_script_cfg_options: CfgOptions(
+                            [],
+                        ),
                         env: Env {
                             entries: {
                                 "CARGO_PKG_LICENSE": "",
@@ -212,6 +220,9 @@ fn cargo_hello_world_project_model_with_wildcard_overrides() {
                                 "debug_assertions",
                             ],
                         ),
+                        build_script_cfg_options: CfgOptions(
+                            [],
+                        ),
                         env: Env {
                             entries: {
                                 "CARGO_PKG_LICENSE": "",
@@ -287,6 +298,9 @@ fn cargo_hello_world_project_model_with_wildcard_overrides() {
                                 "debug_assertions",
                             ],
                         ),
+                        build_script_cfg_options: CfgOptions(
+                            [],
+                        ),
                         env: Env {
                             entries: {
                                 "CARGO_PKG_LICENSE": "",
@@ -362,6 +376,9 @@ fn cargo_hello_world_project_model_with_wildcard_overrides() {
                                 "debug_assertions",
                             ],
                         ),
+                        build_script_cfg_options: CfgOptions(
+                            [],
+                        ),
                         env: Env {
                             entries: {
                                 "CARGO_PKG_LICENSE": "",
@@ -446,6 +463,9 @@ fn cargo_hello_world_project_model_with_wildcard_overrides() {
                                 "feature=use_std",
                             ],
                         ),
+                        build_script_cfg_options: CfgOptions(
+                            [],
+                        ),
                         env: Env {
                             entries: {
                                 "CARGO_PKG_LICENSE": "",
@@ -528,6 +548,9 @@ fn cargo_hello_world_project_model_with_selective_overrides() {
                                 "test",
                             ],
                         ),
+                        build_script_cfg_options: CfgOptions(
+                            [],
+                        ),
                         env: Env {
                             entries: {
                                 "CARGO_PKG_LICENSE": "",
@@ -596,6 +619,9 @@ fn cargo_hello_world_project_model_with_selective_overrides() {
                                 "test",
                             ],
                         ),
+                        build_script_cfg_options: CfgOptions(
+                            [],
+                        ),
                         env: Env {
                             entries: {
                                 "CARGO_PKG_LICENSE": "",
@@ -673,6 +699,9 @@ fn cargo_hello_world_project_model_with_selective_overrides() {
                                 "test",
                             ],
                         ),
+                        build_script_cfg_options: CfgOptions(
+                            [],
+                        ),
                         env: Env {
                             entries: {
                                 "CARGO_PKG_LICENSE": "",
@@ -750,6 +779,9 @@ fn cargo_hello_world_project_model_with_selective_overrides() {
                                 "test",
                             ],
                         ),
+                        build_script_cfg_options: CfgOptions(
+                            [],
+                        ),
                         env: Env {
                             entries: {
                                 "CARGO_PKG_LICENSE": "",
@@ -834,6 +866,9 @@ fn cargo_hello_world_project_model_with_selective_overrides() {
                                 "feature=use_std",
                             ],
                         ),
+                        build_script_cfg_options: CfgOptions(
+                            [],
+                        ),
                         env: Env {
                             entries: {
                                 "CARGO_PKG_LICENSE": "",
@@ -907,6 +942,9 @@ fn cargo_hello_world_project_model() {
                                 "test",
                             ],
                         ),
+                        build_script_cfg_options: CfgOptions(
+                            [],
+                        ),
                         env: Env {
                             entries: {
                                 "CARGO_PKG_LICENSE": "",
@@ -975,6 +1013,9 @@ fn cargo_hello_world_project_model() {
                                 "test",
                             ],
                         ),
+                        build_script_cfg_options: CfgOptions(
+                            [],
+                        ),
                         env: Env {
                             entries: {
                                 "CARGO_PKG_LICENSE": "",
@@ -1052,6 +1093,9 @@ fn cargo_hello_world_project_model() {
                                 "test",
                             ],
                         ),
+                        build_script_cfg_options: CfgOptions(
+                            [],
+                        ),
                         env: Env {
                             entries: {
                                 "CARGO_PKG_LICENSE": "",
@@ -1129,6 +1173,9 @@ fn cargo_hello_world_project_model() {
                                 "test",
                             ],
                         ),
+                        build_script_cfg_options: CfgOptions(
+                            [],
+                        ),
                         env: Env {
                             entries: {
                                 "CARGO_PKG_LICENSE": "",
@@ -1213,6 +1260,9 @@ fn cargo_hello_world_project_model() {
                                 "feature=use_std",
                             ],
                         ),
+                        build_script_cfg_options: CfgOptions(
+                            [],
+                        ),
                         env: Env {
                             entries: {
                                 "CARGO_PKG_LICENSE": "",
@@ -1278,6 +1328,9 @@ fn rust_project_hello_world_project_model() {
                         potential_cfg_options: CfgOptions(
                             [],
                         ),
+                        build_script_cfg_options: CfgOptions(
+                            [],
+                        ),
                         env: Env {
                             entries: {},
                         },
@@ -1322,6 +1375,9 @@ fn rust_project_hello_world_project_model() {
                         potential_cfg_options: CfgOptions(
                             [],
                         ),
+                        build_script_cfg_options: CfgOptions(
+                            [],
+                        ),
                         env: Env {
                             entries: {},
                         },
@@ -1356,6 +1412,9 @@ fn rust_project_hello_world_project_model() {
                         potential_cfg_options: CfgOptions(
                             [],
                         ),
+                        build_script_cfg_options: CfgOptions(
+                            [],
+                        ),
                         env: Env {
                             entries: {},
                         },
@@ -1390,6 +1449,9 @@ fn rust_project_hello_world_project_model() {
                         potential_cfg_options: CfgOptions(
                             [],
                         ),
+                        build_script_cfg_options: CfgOptions(
+                            [],
+                        ),
                         env: Env {
                             entries: {},
                         },
@@ -1424,6 +1486,9 @@ fn rust_project_hello_world_project_model() {
                         potential_cfg_options: CfgOptions(
                             [],
                         ),
+                        build_script_cfg_options: CfgOptions(
+                            [],
+                        ),
                         env: Env {
                             entries: {},
                         },
@@ -1468,6 +1533,9 @@ fn rust_project_hello_world_project_model() {
                         potential_cfg_options: CfgOptions(
                             [],
                         ),
+                        build_script_cfg_options: CfgOptions(
+                            [],
+                        ),
                         env: Env {
                             entries: {},
                         },
@@ -1502,6 +1570,9 @@ fn rust_project_hello_world_project_model() {
                         potential_cfg_options: CfgOptions(
                             [],
                         ),
+                        build_script_cfg_options: CfgOptions(
+                            [],
+                        ),
                         env: Env {
                             entries: {},
                         },
@@ -1618,6 +1689,9 @@ fn rust_project_hello_world_project_model() {
                         potential_cfg_options: CfgOptions(
                             [],
                         ),
+                        build_script_cfg_options: CfgOptions(
+                            [],
+                        ),
                         env: Env {
                             entries: {},
                         },
@@ -1652,6 +1726,9 @@ fn rust_project_hello_world_project_model() {
                         potential_cfg_options: CfgOptions(
                             [],
                         ),
+                        build_script_cfg_options: CfgOptions(
+                            [],
+                        ),
                         env: Env {
                             entries: {},
                         },
@@ -1686,6 +1763,9 @@ fn rust_project_hello_world_project_model() {
                         potential_cfg_options: CfgOptions(
                             [],
                         ),
+                        build_script_cfg_options: CfgOptions(
+                            [],
+                        ),
                         env: Env {
                             entries: {},
                         },
@@ -1720,6 +1800,9 @@ fn rust_project_hello_world_project_model() {
                         potential_cfg_options: CfgOptions(
                             [],
                         ),
+                        build_script_cfg_options: CfgOptions(
+                            [],
+                        ),
                         env: Env {
                             entries: {},
                         },
@@ -1754,6 +1837,9 @@ fn rust_project_hello_world_project_model() {
                         potential_cfg_options: CfgOptions(
                             [],
                         ),
+                        build_script_cfg_options: CfgOptions(
+                            [],
+                        ),
                         env: Env {
                             entries: {},
                         },
@@ -1819,3 +1905,213 @@ fn rust_project_is_proc_macro_has_proc_macro_dep() {
     // on the proc_macro sysroot crate.
     crate_data.dependencies.iter().find(|&dep| dep.name.deref() == "proc_macro").unwrap();
 }
+
+#[test]
+fn cargo_hello_world_standalone_file_deps() {
+    let meta = get_test_json_file("hello-world-metadata.json");
+    let project_workspace = ProjectWorkspace::Cargo {
+        cargo: CargoWorkspace::new(meta),
+        build_scripts: WorkspaceBuildScripts::default(),
+        sysroot: Some(get_fake_sysroot()),
+        rustc: None,
+        rustc_cfg: Vec::new(),
+        rustc_check_cfg: Vec::new(),
+        cfg_overrides: CfgOverrides::default(),
+        toolchain: None,
+        metadata_error: None,
+    };
+    let mut file_ids = FxHashMap::default();
+    let mut load = |path: &AbsPath| {
+        let next_id = FileId(file_ids.len() as u32);
+        Some(*file_ids.entry(path.to_path_buf()).or_insert(next_id))
+    };
+    let workspaces = [project_workspace];
+    let mut crate_graph = workspaces[0].to_crate_graph(&mut |_, _| Ok(Vec::new()), &mut load);
+
+    let mut root = "$ROOT$hello-world/scratch.rs".to_string();
+    replace_root(&mut root, true);
+    let file = AbsPathBuf::assert(PathBuf::from(root));
+    add_standalone_files_to_crate_graph(&mut crate_graph, &workspaces, &[file], true, &mut load);
+
+    // The standalone crate is added last.
+    let crate_id = crate_graph.iter().max().unwrap();
+    let deps = crate_graph[crate_id]
+        .dependencies
+        .iter()
+        .map(|dep| (dep.name.to_string(), dep.is_prelude()))
+        .collect::<Vec<_>>();
+    expect![[r#"
+        [
+            (
+                "core",
+                true,
+            ),
+            (
+                "alloc",
+                true,
+            ),
+            (
+                "std",
+                true,
+            ),
+            (
+                "test",
+                false,
+            ),
+            (
+                "hello_world",
+                true,
+            ),
+            (
+                "libc",
+                true,
+            ),
+        ]
+    "#]]
+    .assert_debug_eq(&deps);
+}
+
+#[test]
+fn sysroot_from_cargo_metadata() {
+    let cargo = CargoWorkspace::new(get_test_json_file("sysroot-metadata.json"));
+    let sysroot_dir = AbsPathBuf::assert(get_test_path("fake-sysroot"));
+    let sysroot = Sysroot::from_cargo_workspace(sysroot_dir.clone(), sysroot_dir, &cargo).unwrap();
+    let crates = sysroot
+        .crates()
+        .map(|krate| {
+            let deps = sysroot[krate]
+                .deps
+                .iter()
+                .map(|(name, dep)| format!("{} -> {}", name, sysroot[*dep].name))
+                .collect::<Vec<_>>();
+            (sysroot[krate].name.clone(), deps)
+        })
+        .collect::<Vec<_>>();
+    // Build dependencies aren't linked, and renames like `core` for `rustc-std-workspace-core`
+    // are kept.
+    expect![[r#"
+        [
+            (
+                "alloc",
+                [
+                    "core -> core",
+                ],
+            ),
+            (
+                "cc",
+                [],
+            ),
+            (
+                "core",
+                [],
+            ),
+            (
+                "hashbrown",
+                [
+                    "core -> rustc_std_workspace_core",
+                ],
+            ),
+            (
+                "rustc_std_workspace_core",
+                [
+                    "core -> core",
+                ],
+            ),
+            (
+                "std",
+                [
+                    "alloc -> alloc",
+                    "core -> core",
+                    "hashbrown -> hashbrown",
+                ],
+            ),
+        ]
+    "#]]
+    .assert_debug_eq(&crates);
+}
+
+#[test]
+fn cargo_script_embedded_manifest() {
+    let check = |text: &str, expected: bool| assert_eq!(has_embedded_manifest(text), expected);
+
+    check("#!/usr/bin/env cargo\n---\n[dependencies]\nregex = \"1\"\n---\n\nfn main() {}\n", true);
+    check("---cargo\n[dependencies]\n---\nfn main() {}\n", true);
+    check(
+        "#!/usr/bin/env cargo\n\n----\n[package]\nedition = \"2021\"\n----\nfn main() {}\n",
+        true,
+    );
+    // Unterminated or mismatched fences.
+    check("---\n[dependencies]\nfn main() {}\n", false);
+    check("----\n[dependencies]\n---\nfn main() {}\n", false);
+    // Not a cargo manifest.
+    check("---toml\n[dependencies]\n---\nfn main() {}\n", false);
+    // An inner attribute is not a shebang.
+    check("#![allow(unused)]\n---\n[dependencies]\n---\n", false);
+    check("fn main() {}\n", false);
+}
+
+#[test]
+fn cargo_script_needs_embedded_manifest() {
+    let root = AbsPathBuf::assert(get_test_path("cargo-script"));
+
+    let script = root.join("script.rs");
+    assert!(ManifestPath::try_from(script.clone()).unwrap().is_cargo_script());
+    assert!(matches!(
+        ProjectManifest::from_manifest_file(script),
+        Ok(ProjectManifest::CargoScript(_))
+    ));
+
+    let plain = root.join("plain.rs");
+    assert!(!ManifestPath::try_from(plain.clone()).unwrap().is_cargo_script());
+    assert!(ProjectManifest::from_manifest_file(plain).is_err());
+}
+
+#[test]
+fn cargo_offline_metadata_from_lockfile() {
+    let root = AbsPathBuf::assert(get_test_path("offline-workspace"));
+    let manifest = ManifestPath::try_from(root.join("crates/app/Cargo.toml")).unwrap();
+    let cargo = CargoWorkspace::new(offline_metadata::fetch(&manifest).unwrap());
+    assert_eq!(cargo.workspace_root(), &*root);
+
+    let mut actual = String::new();
+    for pkg in cargo.packages() {
+        let data = &cargo[pkg];
+        let targets = data
+            .targets
+            .iter()
+            .map(|&tgt| format!("{} ({:?})", cargo[tgt].name, cargo[tgt].kind))
+            .collect::<Vec<_>>();
+        let deps = data
+            .dependencies
+            .iter()
+            .map(|dep| format!("{} ({:?})", dep.name, dep.kind))
+            .collect::<Vec<_>>();
+        format_to!(
+            actual,
+            "{} {} {:?} member={} local={}\n  targets: {:?}\n  deps: {:?}\n  features: {:?}\n",
+            data.name,
+            data.version,
+            data.edition,
+            data.is_member,
+            data.is_local,
+            targets,
+            deps,
+            data.active_features,
+        );
+    }
+    expect![[r#"
+        app 0.1.0 Edition2021 member=true local=true
+          targets: ["app (Bin)"]
+          deps: ["serde (Normal)", "util (Normal)"]
+          features: []
+        serde 1.0.140 Edition2015 member=false local=false
+          targets: ["serde (Lib)"]
+          deps: []
+          features: ["default", "derive", "std"]
+        util 0.1.0 Edition2018 member=true local=true
+          targets: ["util (Lib)", "build-script-build (BuildScript)"]
+          deps: []
+          features: []
+    "#]]
+    .assert_eq(&actual);
+}
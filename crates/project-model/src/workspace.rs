@@ -2,14 +2,14 @@
 //! metadata` or `rust-project.json`) into representation stored in the salsa
 //! database -- `CrateGraph`.
 
-use std::{collections::VecDeque, fmt, fs, process::Command};
+use std::{collections::VecDeque, fmt, fs, process::Command, sync::Arc};
 
 use anyhow::{format_err, Context, Result};
 use base_db::{
     CrateDisplayName, CrateGraph, CrateId, CrateName, CrateOrigin, Dependency, Edition, Env,
     FileId, LangCrateOrigin, ProcMacroLoadResult,
 };
-use cfg::{CfgDiff, CfgOptions};
+use cfg::{CfgDiff, CfgOptions, CheckCfg};
 use paths::{AbsPath, AbsPathBuf};
 use rustc_hash::{FxHashMap, FxHashSet};
 use semver::Version;
@@ -17,9 +17,9 @@ use stdx::{always, hash::NoHashHashMap};
 
 use crate::{
     build_scripts::BuildScriptOutput,
-    cargo_workspace::{DepKind, PackageData, RustcSource},
+    cargo_workspace::{DepKind, Package, PackageData, RustcSource},
     cfg_flag::CfgFlag,
-    rustc_cfg,
+    offline_metadata, rustc_cfg,
     sysroot::SysrootCrate,
     utf8_stdout, CargoConfig, CargoWorkspace, ManifestPath, ProjectJson, ProjectManifest, Sysroot,
     TargetKind, WorkspaceBuildScripts,
@@ -77,20 +77,21 @@ pub enum ProjectWorkspace {
         /// FIXME: make this a per-crate map, as, eg, build.rs might have a
         /// different target.
         rustc_cfg: Vec<CfgFlag>,
+        /// Holds the `--check-cfg` specifications of the cfgs rustc expects, from
+        /// `rustc --print check-cfg`. Empty if the toolchain can't print them.
+        rustc_check_cfg: Vec<String>,
         cfg_overrides: CfgOverrides,
         toolchain: Option<Version>,
+        /// Set if `cargo metadata` failed and `cargo` was reconstructed from the manifests and
+        /// the lockfile instead. Such a workspace might be missing dependencies.
+        metadata_error: Option<String>,
     },
     /// Project workspace was manually specified using a `rust-project.json` file.
     Json { project: ProjectJson, sysroot: Option<Sysroot>, rustc_cfg: Vec<CfgFlag> },
 
-    // FIXME: The primary limitation of this approach is that the set of detached files needs to be fixed at the beginning.
-    // That's not the end user experience we should strive for.
-    // Ideally, you should be able to just open a random detached file in existing cargo projects, and get the basic features working.
-    // That needs some changes on the salsa-level though.
-    // In particular, we should split the unified CrateGraph (which currently has maximal durability) into proper crate graph, and a set of ad hoc roots (with minimal durability).
-    // Then, we need to hide the graph behind the queries such that most queries look only at the proper crate graph, and fall back to ad hoc roots only if there's no results.
-    // After this, we should be able to tweak the logic in reload.rs to add newly opened files, which don't belong to any existing crates, to the set of the detached files.
-    // //
+    // FIXME: The set of detached files needs to be fixed at the beginning. Files opened later
+    // on which don't belong to any crate are handled separately, see
+    // `add_standalone_files_to_crate_graph`.
     /// Project with a set of disjoint files, not belonging to any particular workspace.
     /// Backed by basic sysroot crates for basic completion and highlighting.
     DetachedFiles { files: Vec<AbsPathBuf>, sysroot: Sysroot, rustc_cfg: Vec<CfgFlag> },
@@ -106,8 +107,10 @@ impl fmt::Debug for ProjectWorkspace {
                 sysroot,
                 rustc,
                 rustc_cfg,
+                rustc_check_cfg,
                 cfg_overrides,
                 toolchain,
+                metadata_error,
             } => f
                 .debug_struct("Cargo")
                 .field("root", &cargo.workspace_root().file_name())
@@ -118,8 +121,10 @@ impl fmt::Debug for ProjectWorkspace {
                     &rustc.as_ref().map_or(0, |rc| rc.packages().len()),
                 )
                 .field("n_rustc_cfg", &rustc_cfg.len())
+                .field("n_rustc_check_cfg", &rustc_check_cfg.len())
                 .field("n_cfg_overrides", &cfg_overrides.len())
                 .field("toolchain", &toolchain)
+                .field("metadata_error", &metadata_error.is_some())
                 .finish(),
             ProjectWorkspace::Json { project, sysroot, rustc_cfg } => {
                 let mut debug_struct = f.debug_struct("Json");
@@ -158,7 +163,7 @@ impl ProjectWorkspace {
                 let project_json = ProjectJson::new(&project_location, data);
                 ProjectWorkspace::load_inline(project_json, config.target.as_deref())?
             }
-            ProjectManifest::CargoToml(cargo_toml) => {
+            ProjectManifest::CargoToml(cargo_toml) | ProjectManifest::CargoScript(cargo_toml) => {
                 let cargo_version = utf8_stdout({
                     let mut cmd = Command::new(toolchain::cargo());
                     cmd.arg("--version");
@@ -168,7 +173,7 @@ impl ProjectWorkspace {
                     .get("cargo ".len()..)
                     .and_then(|it| Version::parse(it.split_whitespace().next()?).ok());
 
-                let meta = CargoWorkspace::fetch_metadata(
+                let (meta, metadata_error) = match CargoWorkspace::fetch_metadata(
                     &cargo_toml,
                     cargo_toml.parent(),
                     config,
@@ -180,19 +185,42 @@ impl ProjectWorkspace {
                         cargo_toml.display(),
                         toolchain
                     )
-                })?;
+                }) {
+                    Ok(meta) => (meta, None),
+                    // Single-file packages have no `Cargo.toml` to fall back to.
+                    Err(e) if cargo_toml.is_cargo_script() => return Err(e),
+                    Err(e) => match offline_metadata::fetch(&cargo_toml) {
+                        Ok(meta) => {
+                            tracing::warn!(
+                                "{:#}, falling back to the project model from Cargo.lock",
+                                e
+                            );
+                            (meta, Some(format!("{:#}", e)))
+                        }
+                        Err(fallback_err) => {
+                            tracing::warn!("{:#}", fallback_err);
+                            return Err(e);
+                        }
+                    },
+                };
                 let cargo = CargoWorkspace::new(meta);
 
-                let sysroot = if config.no_sysroot {
-                    None
-                } else {
-                    Some(Sysroot::discover(cargo_toml.parent()).with_context(|| {
-                        format!(
-                            "Failed to find sysroot for Cargo.toml file {}. Is rust-src installed?",
-                            cargo_toml.display()
-                        )
-                    })?)
+                let sysroot = match (config.no_sysroot, &config.sysroot_manifest) {
+                    (true, _) => None,
+                    (false, Some(manifest)) => Some(Sysroot::discover_with_metadata(
+                        cargo_toml.parent(),
+                        manifest,
+                        config,
+                        progress,
+                    )),
+                    (false, None) => Some(Sysroot::discover(cargo_toml.parent())),
                 };
+                let sysroot = sysroot.transpose().with_context(|| {
+                    format!(
+                        "Failed to find sysroot for Cargo.toml file {}. Is rust-src installed?",
+                        cargo_toml.display()
+                    )
+                })?;
 
                 let rustc_dir = match &config.rustc_source {
                     Some(RustcSource::Path(path)) => ManifestPath::try_from(path.clone()).ok(),
@@ -217,6 +245,8 @@ impl ProjectWorkspace {
                 };
 
                 let rustc_cfg = rustc_cfg::get(Some(&cargo_toml), config.target.as_deref());
+                let rustc_check_cfg =
+                    rustc_cfg::get_check_cfg(Some(&cargo_toml), config.target.as_deref());
 
                 let cfg_overrides = config.cfg_overrides();
                 ProjectWorkspace::Cargo {
@@ -225,8 +255,10 @@ impl ProjectWorkspace {
                     sysroot,
                     rustc,
                     rustc_cfg,
+                    rustc_check_cfg,
                     cfg_overrides,
                     toolchain,
+                    metadata_error,
                 }
             }
         };
@@ -279,6 +311,10 @@ impl ProjectWorkspace {
         progress: &dyn Fn(String),
     ) -> Result<WorkspaceBuildScripts> {
         match self {
+            // Cargo would fail to build the workspace just like it failed to resolve it.
+            ProjectWorkspace::Cargo { metadata_error: Some(_), .. } => {
+                Ok(WorkspaceBuildScripts::default())
+            }
             ProjectWorkspace::Cargo { cargo, toolchain, .. } => {
                 WorkspaceBuildScripts::run(config, cargo, progress, toolchain).with_context(|| {
                     format!("Failed to run build scripts for {}", &cargo.workspace_root().display())
@@ -299,6 +335,15 @@ impl ProjectWorkspace {
         }
     }
 
+    /// The error `cargo metadata` failed with, if this workspace was reconstructed from the
+    /// manifests and the lockfile instead.
+    pub fn metadata_error(&self) -> Option<&str> {
+        match self {
+            ProjectWorkspace::Cargo { metadata_error, .. } => metadata_error.as_deref(),
+            _ => None,
+        }
+    }
+
     /// Returns the roots for the current `ProjectWorkspace`
     /// The return type contains the path and whether or not
     /// the root is a member of the current workspace
@@ -326,9 +371,11 @@ impl ProjectWorkspace {
                 sysroot,
                 rustc,
                 rustc_cfg: _,
+                rustc_check_cfg: _,
                 cfg_overrides: _,
                 build_scripts,
                 toolchain: _,
+                metadata_error: _,
             } => {
                 cargo
                     .packages()
@@ -336,7 +383,12 @@ impl ProjectWorkspace {
                         let is_local = cargo[pkg].is_local;
                         let pkg_root = cargo[pkg].manifest.parent().to_path_buf();
 
-                        let mut include = vec![pkg_root.clone()];
+                        // A single-file package owns its script, not the whole directory.
+                        let mut include = if cargo[pkg].manifest.is_cargo_script() {
+                            vec![cargo[pkg].manifest.to_path_buf()]
+                        } else {
+                            vec![pkg_root.clone()]
+                        };
                         let out_dir =
                             build_scripts.get_output(pkg).and_then(|it| it.out_dir.clone());
                         include.extend(out_dir);
@@ -412,6 +464,32 @@ impl ProjectWorkspace {
         }
     }
 
+    fn sysroot(&self) -> Option<&Sysroot> {
+        match self {
+            ProjectWorkspace::Cargo { sysroot, .. } | ProjectWorkspace::Json { sysroot, .. } => {
+                sysroot.as_ref()
+            }
+            ProjectWorkspace::DetachedFiles { sysroot, .. } => Some(sysroot),
+        }
+    }
+
+    fn rustc_cfg(&self) -> &[CfgFlag] {
+        match self {
+            ProjectWorkspace::Cargo { rustc_cfg, .. }
+            | ProjectWorkspace::Json { rustc_cfg, .. }
+            | ProjectWorkspace::DetachedFiles { rustc_cfg, .. } => rustc_cfg,
+        }
+    }
+
+    /// Whether `path` lies within the directory of this workspace.
+    fn contains(&self, path: &AbsPath) -> bool {
+        match self {
+            ProjectWorkspace::Cargo { cargo, .. } => path.starts_with(cargo.workspace_root()),
+            ProjectWorkspace::Json { project, .. } => path.starts_with(project.path()),
+            ProjectWorkspace::DetachedFiles { .. } => false,
+        }
+    }
+
     pub fn to_crate_graph(
         &self,
         load_proc_macro: &mut dyn FnMut(&str, &AbsPath) -> ProcMacroLoadResult,
@@ -432,11 +510,14 @@ impl ProjectWorkspace {
                 sysroot,
                 rustc,
                 rustc_cfg,
+                rustc_check_cfg,
                 cfg_overrides,
                 build_scripts,
                 toolchain: _,
+                metadata_error: _,
             } => cargo_to_crate_graph(
                 rustc_cfg.clone(),
+                rustc_check_cfg,
                 cfg_overrides,
                 load_proc_macro,
                 load,
@@ -506,6 +587,7 @@ fn project_json_to_crate_graph(
                     krate.version.clone(),
                     cfg_options.clone(),
                     cfg_options,
+                    CfgOptions::default(),
                     env,
                     proc_macro,
                     krate.is_proc_macro,
@@ -547,6 +629,7 @@ fn project_json_to_crate_graph(
 
 fn cargo_to_crate_graph(
     rustc_cfg: Vec<CfgFlag>,
+    rustc_check_cfg: &[String],
     override_cfg: &CfgOverrides,
     load_proc_macro: &mut dyn FnMut(&str, &AbsPath) -> ProcMacroLoadResult,
     load: &mut dyn FnMut(&AbsPath) -> Option<FileId>,
@@ -564,6 +647,7 @@ fn cargo_to_crate_graph(
 
     let mut cfg_options = CfgOptions::default();
     cfg_options.extend(rustc_cfg);
+    cfg_options.set_expected(rustc_expected_cfgs(rustc_check_cfg));
 
     let mut pkg_to_lib_crate = FxHashMap::default();
 
@@ -734,6 +818,7 @@ fn detached_files_to_crate_graph(
             None,
             cfg_options.clone(),
             cfg_options.clone(),
+            CfgOptions::default(),
             Env::default(),
             Ok(Vec::new()),
             false,
@@ -745,6 +830,97 @@ fn detached_files_to_crate_graph(
     crate_graph
 }
 
+/// Adds a crate for each of the standalone `files` to `crate_graph`, which is expected to be the
+/// combined crate graph of `workspaces`.
+///
+/// Standalone files are files which are not part of any crate of the loaded workspaces, like a
+/// scratch file opened by the user. Unlike `DetachedFiles`, these are not loaded as a workspace
+/// of their own but are put on top of the existing crate graph, so they can be added and removed
+/// without reloading the workspaces. They depend on the sysroot crates of the workspace they are
+/// located in (or any other workspace, if there is none) and, if `with_workspace_deps` is set, on
+/// the libraries of the workspace members and their dependencies.
+pub fn add_standalone_files_to_crate_graph(
+    crate_graph: &mut CrateGraph,
+    workspaces: &[ProjectWorkspace],
+    files: &[AbsPathBuf],
+    with_workspace_deps: bool,
+    load: &mut dyn FnMut(&AbsPath) -> Option<FileId>,
+) {
+    let _p = profile::span("add_standalone_files_to_crate_graph");
+    for file in files {
+        let file_id = match load(file) {
+            Some(file_id) => file_id,
+            None => {
+                tracing::error!("Failed to load standalone file {:?}", file);
+                continue;
+            }
+        };
+        if crate_graph.crate_id_for_crate_root(file_id).is_some() {
+            continue;
+        }
+
+        let owner = workspaces.iter().find(|ws| ws.contains(file));
+        let ws = owner.or_else(|| workspaces.iter().find(|ws| ws.sysroot().is_some()));
+
+        let mut cfg_options = CfgOptions::default();
+        cfg_options.extend(ws.map_or(&[][..], |ws| ws.rustc_cfg()).iter().cloned());
+        cfg_options.insert_atom("debug_assertions".into());
+
+        let display_name = file
+            .file_stem()
+            .and_then(|os_str| os_str.to_str())
+            .map(|file_stem| CrateDisplayName::from_canonical_name(file_stem.to_string()));
+        let standalone_crate = crate_graph.add_crate_root(
+            file_id,
+            Edition::CURRENT,
+            display_name,
+            None,
+            cfg_options.clone(),
+            cfg_options,
+            CfgOptions::default(),
+            Env::default(),
+            Ok(Vec::new()),
+            false,
+            CrateOrigin::Local,
+        );
+
+        let mut deps: Vec<(CrateName, &AbsPath, bool)> = Vec::new();
+        if let Some(sysroot) = ws.and_then(|ws| ws.sysroot()) {
+            deps.extend(sysroot.public_deps().map(|(name, krate, prelude)| {
+                (CrateName::new(name).unwrap(), &*sysroot[krate].root, prelude)
+            }));
+        }
+        if let (true, Some(ProjectWorkspace::Cargo { cargo, .. })) = (with_workspace_deps, owner) {
+            let lib_root = |pkg: Package| {
+                let tgt =
+                    cargo[pkg].targets.iter().find(|&&tgt| cargo[tgt].kind == TargetKind::Lib)?;
+                Some((&cargo[*tgt].name, &*cargo[*tgt].root))
+            };
+            for pkg in cargo.packages().filter(|&pkg| cargo[pkg].is_member) {
+                if let Some((name, root)) = lib_root(pkg) {
+                    deps.push((CrateName::normalize_dashes(name), root, true));
+                }
+                for dep in cargo[pkg].dependencies.iter().filter(|dep| dep.kind != DepKind::Build) {
+                    if let Some((_, root)) = lib_root(dep.pkg) {
+                        deps.push((CrateName::normalize_dashes(&dep.name), root, true));
+                    }
+                }
+            }
+        }
+
+        for (name, root, prelude) in deps {
+            let to = match load(root).and_then(|it| crate_graph.crate_id_for_crate_root(it)) {
+                Some(it) => it,
+                None => continue,
+            };
+            if crate_graph[standalone_crate].dependencies.iter().any(|dep| dep.name == name) {
+                continue;
+            }
+            add_dep_with_prelude(crate_graph, standalone_crate, name, to, prelude);
+        }
+    }
+}
+
 fn handle_rustc_crates(
     rustc_workspace: &CargoWorkspace,
     load: &mut dyn FnMut(&AbsPath) -> Option<FileId>,
@@ -876,6 +1052,10 @@ fn add_target_crate_root(
             .iter()
             .map(|feat| CfgFlag::KeyValue { key: "feature".into(), value: feat.0.into() }),
     );
+    let mut build_script_cfg_options = CfgOptions::default();
+    if let Some(cfgs) = build_data.as_ref().map(|it| &it.cfgs) {
+        build_script_cfg_options.extend(cfgs.iter().cloned());
+    }
     let cfg_options = {
         let mut opts = cfg_options;
         for feature in pkg.active_features.iter() {
@@ -884,6 +1064,8 @@ fn add_target_crate_root(
         if let Some(cfgs) = build_data.as_ref().map(|it| &it.cfgs) {
             opts.extend(cfgs.iter().cloned());
         }
+        let expected = expected_cfgs(pkg, build_data, &opts);
+        opts.set_expected(expected);
         opts
     };
 
@@ -910,6 +1092,7 @@ fn add_target_crate_root(
         Some(pkg.version.to_string()),
         cfg_options,
         potential_cfg_options,
+        build_script_cfg_options,
         env,
         proc_macro,
         is_proc_macro,
@@ -917,6 +1100,47 @@ fn add_target_crate_root(
     )
 }
 
+/// The cfgs rustc expects in any crate. Without rustc's own specifications, the values of the
+/// target cfgs can't be checked.
+fn rustc_expected_cfgs(rustc_check_cfg: &[String]) -> Arc<CheckCfg> {
+    let mut expected = CheckCfg::well_known();
+    if rustc_check_cfg.is_empty() {
+        expected.expect_any_target_value();
+    }
+    for spec in rustc_check_cfg {
+        if let Err(err) = expected.add_spec(spec) {
+            tracing::warn!("rustc: {}", err);
+        }
+    }
+    Arc::new(expected)
+}
+
+/// The cfgs a cargo package is expected to use, mirroring the `--check-cfg` arguments cargo passes
+/// to rustc: the ones rustc expects, the ones of the target, the package's features and whatever
+/// the build script declares.
+fn expected_cfgs(
+    pkg: &PackageData,
+    build_data: Option<&BuildScriptOutput>,
+    cfg_options: &CfgOptions,
+) -> Arc<CheckCfg> {
+    let mut expected = match cfg_options.expected() {
+        Some(base) => CheckCfg::extending(Arc::clone(base)),
+        None => CheckCfg::well_known(),
+    };
+    expected.expect("docsrs".into(), None);
+    cfg_options.iter().for_each(|atom| expected.expect_atom(atom));
+    expected.expect_values(
+        "feature".into(),
+        pkg.features.keys().map(|feature| Some(feature.as_str().into())),
+    );
+    for spec in build_data.iter().flat_map(|it| &it.check_cfgs) {
+        if let Err(err) = expected.add_spec(spec) {
+            tracing::warn!("{}: {}", pkg.name, err);
+        }
+    }
+    Arc::new(expected)
+}
+
 #[derive(Default)]
 struct SysrootPublicDeps {
     deps: Vec<(CrateName, CrateId, bool)>,
@@ -954,6 +1178,7 @@ fn sysroot_to_crate_graph(
                 None,
                 cfg_options.clone(),
                 cfg_options.clone(),
+                CfgOptions::default(),
                 env,
                 Err("no proc macro loaded for sysroot crate".into()),
                 false,
@@ -964,9 +1189,9 @@ fn sysroot_to_crate_graph(
         .collect();
 
     for from in sysroot.crates() {
-        for &to in sysroot[from].deps.iter() {
-            let name = CrateName::new(&sysroot[to].name).unwrap();
-            if let (Some(&from), Some(&to)) = (sysroot_crates.get(&from), sysroot_crates.get(&to)) {
+        for (name, to) in sysroot[from].deps.iter() {
+            let name = CrateName::normalize_dashes(name);
+            if let (Some(&from), Some(&to)) = (sysroot_crates.get(&from), sysroot_crates.get(to)) {
                 add_dep(crate_graph, from, name, to);
             }
         }
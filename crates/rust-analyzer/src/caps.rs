@@ -125,6 +125,7 @@ pub fn server_capabilities(config: &Config) -> ServerCapabilities {
             },
         ))),
         experimental: Some(json!({
+            "changeSignature": true,
             "externalDocs": true,
             "hoverRange": true,
             "joinLines": true,
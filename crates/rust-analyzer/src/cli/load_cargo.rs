@@ -77,7 +77,7 @@ pub fn load_workspace(
         },
     );
 
-    let project_folders = ProjectFolders::new(&[ws], &[]);
+    let project_folders = ProjectFolders::new(&[ws], &[], &[]);
     loader.set_config(vfs::loader::Config {
         load: project_folders.load,
         watch: vec![],
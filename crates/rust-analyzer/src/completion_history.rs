@@ -0,0 +1,108 @@
+//! Remembers which completion items the user accepted, so that they can be
+//! ranked higher the next time.
+//!
+//! The counts are keyed by the kind of position the item was completed at
+//! (`expr`, `type`, ...) and the path of the item, and are persisted as JSON
+//! in the cache directory of the server.
+
+use std::{fs, path::PathBuf};
+
+use rustc_hash::FxHashMap;
+
+/// The number of distinct items remembered per kind of position.
+const MAX_ITEMS_PER_KIND: usize = 1000;
+
+#[derive(Debug, Default)]
+pub(crate) struct CompletionHistory {
+    path: Option<PathBuf>,
+    counts: FxHashMap<String, FxHashMap<String, u32>>,
+    /// Whether there are records which haven't been saved yet.
+    unsaved: bool,
+}
+
+impl CompletionHistory {
+    /// Loads the history stored in the cache directory, starting empty if
+    /// there is none or it can't be read.
+    pub(crate) fn load() -> CompletionHistory {
+        CompletionHistory::load_from(cache_dir().map(|it| it.join("completion-history.json")))
+    }
+
+    fn load_from(path: Option<PathBuf>) -> CompletionHistory {
+        let counts = path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default();
+        CompletionHistory { path, counts, unsaved: false }
+    }
+
+    pub(crate) fn count(&self, context: &str, item: &str) -> u32 {
+        self.counts.get(context).and_then(|it| it.get(item)).copied().unwrap_or(0)
+    }
+
+    /// Records an accepted item. Returns `true` if a save has to be scheduled,
+    /// because there is no earlier record waiting to be saved.
+    pub(crate) fn record(&mut self, context: &str, item: &str) -> bool {
+        if context.is_empty() || item.is_empty() {
+            return false;
+        }
+        let items = self.counts.entry(context.to_owned()).or_default();
+        if items.len() >= MAX_ITEMS_PER_KIND && !items.contains_key(item) {
+            // Forget the least accepted item to make room for the new one.
+            if let Some(least) =
+                items.iter().min_by_key(|(_, count)| **count).map(|(it, _)| it.clone())
+            {
+                items.remove(&least);
+            }
+        }
+        let count = items.entry(item.to_owned()).or_default();
+        *count = count.saturating_add(1);
+        !std::mem::replace(&mut self.unsaved, true)
+    }
+
+    pub(crate) fn save(&mut self) {
+        self.unsaved = false;
+        let path = match &self.path {
+            Some(it) => it,
+            None => return,
+        };
+        let res = path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|()| fs::write(path, serde_json::to_string(&self.counts)?));
+        if let Err(err) = res {
+            tracing::warn!("failed to save completion history to {}: {}", path.display(), err);
+        }
+    }
+}
+
+fn cache_dir() -> Option<PathBuf> {
+    let home = home::home_dir().filter(|it| it.is_absolute())?;
+    Some(home.join(".cache").join("rust-analyzer"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_survive_a_reload() {
+        let path = std::env::temp_dir()
+            .join(format!("ra-completion-history-{}", std::process::id()))
+            .join("completion-history.json");
+
+        let mut history = CompletionHistory::load_from(Some(path.clone()));
+        assert!(history.record("expr", "std::mem::swap"));
+        assert!(!history.record("expr", "std::mem::swap"));
+        assert!(!history.record("type", "Vec"));
+        history.save();
+        assert!(history.record("type", "Vec"));
+
+        let history = CompletionHistory::load_from(Some(path.clone()));
+        assert_eq!(history.count("expr", "std::mem::swap"), 2);
+        assert_eq!(history.count("type", "Vec"), 1);
+        assert_eq!(history.count("expr", "Vec"), 0);
+
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+}
@@ -22,7 +22,8 @@ use ide_db::{
 use itertools::Itertools;
 use lsp_types::{ClientCapabilities, MarkupKind};
 use project_model::{
-    CargoConfig, ProjectJson, ProjectJsonData, ProjectManifest, RustcSource, UnsetTestCrates,
+    CargoConfig, ProjectJson, ProjectJsonData, ProjectManifest, RustcSource, SysrootManifest,
+    UnsetTestCrates,
 };
 use rustc_hash::{FxHashMap, FxHashSet};
 use serde::{de::DeserializeOwned, Deserialize};
@@ -92,6 +93,14 @@ config_data! {
         cargo_noDefaultFeatures: bool    = "false",
         /// Internal config for debugging, disables loading of sysroot crates.
         cargo_noSysroot: bool            = "false",
+        /// Path to a `Cargo.toml` describing the standard library, like the one of the
+        /// `library/` workspace of a Rust checkout, or "discover" to use the one of the
+        /// `rust-src` component.
+        ///
+        /// If set, the sysroot crates and their dependencies are loaded with
+        /// `cargo metadata` on this manifest, like `-Zbuild-std` does, instead of from a
+        /// built-in list of crates.
+        cargo_sysrootManifest: Option<String> = "null",
         /// Compilation target override (target triple).
         cargo_target: Option<String>     = "null",
         /// Unsets `#[cfg(test)]` for the specified crates.
@@ -144,6 +153,9 @@ config_data! {
         completion_autoself_enable: bool        = "true",
         /// Whether to add parenthesis and argument snippets when completing function.
         completion_callable_snippets: CallableCompletionDef  = "\"fill_arguments\"",
+        /// Whether to rank completion items higher the more often they were accepted before.
+        /// The counts are stored in the cache directory of rust-analyzer.
+        completion_learnedRanking_enable: bool = "false",
         /// Whether to show postfix snippets like `dbg`, `if`, `not`, etc.
         completion_postfix_enable: bool         = "true",
         /// Enables completions of private items and fields that are defined in the current workspace even if they are not visible at the current position.
@@ -191,6 +203,9 @@ config_data! {
                 "scope": "expr"
             }
         }"#,
+        /// Whether to complete expressions of the expected type found by a type-directed search
+        /// over the locals, fields, constructors and functions in scope.
+        completion_termSearch_enable: bool = "false",
 
         /// List of rust-analyzer diagnostics to disable.
         diagnostics_disabled: FxHashSet<String> = "[]",
@@ -217,6 +232,12 @@ config_data! {
         /// relative to the workspace root, and globs are not supported. You may
         /// also need to add the folders to Code's `files.watcherExclude`.
         files_excludeDirs: Vec<PathBuf> = "[]",
+        /// Analyze opened files which don't belong to any crate of the loaded
+        /// workspaces as standalone crates, without reloading the workspaces.
+        files_standalone_enable: bool = "true",
+        /// Whether standalone files located inside a Cargo workspace may use the
+        /// libraries of the workspace members and of their dependencies.
+        files_standalone_workspaceDependencies: bool = "true",
         /// Controls file watching implementation.
         files_watcher: FilesWatcherDef = "\"client\"",
 
@@ -338,7 +359,8 @@ config_data! {
         /// of projects.
         ///
         /// Elements must be paths pointing to `Cargo.toml`,
-        /// `rust-project.json`, or JSON objects in `rust-project.json` format.
+        /// `rust-project.json`, single-file Cargo packages (`.rs` files with
+        /// an embedded manifest), or JSON objects in `rust-project.json` format.
         linkedProjects: Vec<ManifestOrProjectJson> = "[]",
 
         /// Number of syntax trees rust-analyzer keeps in memory. Defaults to 128.
@@ -555,6 +577,8 @@ impl HoverActionsConfig {
 pub struct FilesConfig {
     pub watcher: FilesWatcher,
     pub exclude: Vec<AbsPathBuf>,
+    pub standalone: bool,
+    pub standalone_workspace_deps: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -600,6 +624,7 @@ pub struct ClientCommandsConfig {
     pub show_reference: bool,
     pub goto_location: bool,
     pub trigger_parameter_hints: bool,
+    pub completion_accepted: bool,
 }
 
 #[derive(Debug)]
@@ -659,6 +684,7 @@ impl Config {
                 SnippetScopeDef::Expr => SnippetScope::Expr,
                 SnippetScopeDef::Type => SnippetScope::Type,
                 SnippetScopeDef::Item => SnippetScope::Item,
+                SnippetScopeDef::Stmt => SnippetScope::Stmt,
             };
             match Snippet::new(
                 &def.prefix,
@@ -666,6 +692,7 @@ impl Config {
                 &def.body,
                 def.description.as_ref().unwrap_or(name),
                 &def.requires,
+                &def.receiver,
                 scope,
             ) {
                 Some(snippet) => self.snippets.push(snippet),
@@ -746,7 +773,8 @@ impl Config {
                         .iter()
                         .filter(|p| {
                             let (ProjectManifest::ProjectJson(path)
-                            | ProjectManifest::CargoToml(path)) = p;
+                            | ProjectManifest::CargoToml(path)
+                            | ProjectManifest::CargoScript(path)) = p;
                             !exclude_dirs.iter().any(|p| path.starts_with(p))
                         })
                         .cloned()
@@ -961,6 +989,8 @@ impl Config {
                 _ => FilesWatcher::Server,
             },
             exclude: self.data.files_excludeDirs.iter().map(|it| self.root_path.join(it)).collect(),
+            standalone: self.data.files_standalone_enable,
+            standalone_workspace_deps: self.data.files_standalone_workspaceDependencies,
         }
     }
 
@@ -985,6 +1015,14 @@ impl Config {
             }
         });
 
+        let sysroot_manifest = self.data.cargo_sysrootManifest.as_ref().map(|manifest| {
+            if manifest == "discover" {
+                SysrootManifest::Discover
+            } else {
+                SysrootManifest::Path(self.root_path.join(manifest))
+            }
+        });
+
         CargoConfig {
             no_default_features: self.data.cargo_noDefaultFeatures,
             all_features: matches!(self.data.cargo_features, CargoFeatures::All),
@@ -994,6 +1032,7 @@ impl Config {
             },
             target: self.data.cargo_target.clone(),
             no_sysroot: self.data.cargo_noSysroot,
+            sysroot_manifest,
             rustc_source,
             unset_test_crates: UnsetTestCrates::Only(self.data.cargo_unsetTest.clone()),
             wrap_rustc_in_build_scripts: self.data.cargo_buildScripts_useRustcWrapper,
@@ -1127,6 +1166,7 @@ impl Config {
                 && completion_item_edit_resolve(&self.caps),
             enable_self_on_the_fly: self.data.completion_autoself_enable,
             enable_private_editable: self.data.completion_privateEditable_enable,
+            enable_term_search: self.data.completion_termSearch_enable,
             callable: match self.data.completion_callable_snippets {
                 CallableCompletionDef::FillArguments => Some(CallableSnippets::FillArguments),
                 CallableCompletionDef::AddParentheses => Some(CallableSnippets::AddParentheses),
@@ -1147,6 +1187,10 @@ impl Config {
         }
     }
 
+    pub fn completion_learned_ranking(&self) -> bool {
+        self.data.completion_learnedRanking_enable
+    }
+
     pub fn snippet_cap(&self) -> bool {
         self.experimental("snippetTextEdit")
     }
@@ -1291,6 +1335,7 @@ impl Config {
             show_reference: get("rust-analyzer.showReferences"),
             goto_location: get("rust-analyzer.gotoLocation"),
             trigger_parameter_hints: get("editor.action.triggerParameterHints"),
+            completion_accepted: get("rust-analyzer.completionAccepted"),
         }
     }
 
@@ -1422,6 +1467,7 @@ enum SnippetScopeDef {
     Expr,
     Item,
     Type,
+    Stmt,
 }
 
 impl Default for SnippetScopeDef {
@@ -1442,6 +1488,8 @@ struct SnippetDef {
     body: Vec<String>,
     #[serde(deserialize_with = "single_or_array")]
     requires: Vec<String>,
+    #[serde(deserialize_with = "single_or_array")]
+    receiver: Vec<String>,
     scope: SnippetScopeDef,
 }
 
@@ -8,16 +8,19 @@ use std::{sync::Arc, time::Instant};
 use crossbeam_channel::{unbounded, Receiver, Sender};
 use flycheck::FlycheckHandle;
 use ide::{Analysis, AnalysisHost, Cancellable, Change, FileId};
-use ide_db::base_db::{CrateId, FileLoader, SourceDatabase};
+use ide_db::base_db::{CrateGraph, CrateId, FileLoader, SourceDatabase};
 use lsp_types::{SemanticTokens, Url};
 use parking_lot::{Mutex, RwLock};
 use proc_macro_api::ProcMacroServer;
-use project_model::{CargoWorkspace, ProjectWorkspace, Target, WorkspaceBuildScripts};
+use project_model::{
+    CargoWorkspace, ProjectWorkspace, RegistryIndex, Target, WorkspaceBuildScripts,
+};
 use rustc_hash::FxHashMap;
 use stdx::hash::NoHashHashMap;
-use vfs::AnchoredPathBuf;
+use vfs::{AbsPathBuf, AnchoredPathBuf};
 
 use crate::{
+    completion_history::CompletionHistory,
     config::Config,
     diagnostics::{CheckFixes, DiagnosticCollection},
     from_proto,
@@ -58,6 +61,13 @@ pub(crate) struct GlobalState {
     pub(crate) diagnostics: DiagnosticCollection,
     pub(crate) mem_docs: MemDocs,
     pub(crate) semantic_tokens_cache: Arc<Mutex<FxHashMap<Url, SemanticTokens>>>,
+    pub(crate) completion_history: Arc<RwLock<CompletionHistory>>,
+    /// The open `Cargo.toml` files. They are kept out of the VFS, as changing
+    /// them there would reload the workspace on every keystroke.
+    pub(crate) cargo_toml_docs: Arc<FxHashMap<Url, String>>,
+    /// The registries cargo has downloaded, used to complete dependencies in
+    /// `Cargo.toml`. They are looked up again when the workspace changes.
+    pub(crate) registry_index: Arc<RegistryIndex>,
     pub(crate) shutdown_requested: bool,
     pub(crate) proc_macro_changed: bool,
     pub(crate) last_reported_status: Option<lsp_ext::ServerStatusParams>,
@@ -104,6 +114,19 @@ pub(crate) struct GlobalState {
     pub(crate) fetch_build_data_queue:
         OpQueue<(Arc<Vec<ProjectWorkspace>>, Vec<anyhow::Result<WorkspaceBuildScripts>>)>,
 
+    /// The crate graph handed to the database: the crates lowered from
+    /// `workspaces` plus a crate for each of the `standalone_files`, opened
+    /// files which don't belong to any crate of the workspaces.
+    ///
+    /// Standalone files come and go as the user opens and closes files, so
+    /// their crates are added to and removed from this graph in place instead
+    /// of lowering the workspaces anew each time.
+    pub(crate) crate_graph: CrateGraph,
+    pub(crate) standalone_files: Vec<AbsPathBuf>,
+    /// Opened files with an embedded manifest that aren't part of any workspace.
+    /// These are loaded as single-file Cargo packages next to the detached files.
+    pub(crate) cargo_scripts: Vec<AbsPathBuf>,
+
     pub(crate) prime_caches_queue: OpQueue<()>,
 }
 
@@ -114,6 +137,9 @@ pub(crate) struct GlobalStateSnapshot {
     pub(crate) check_fixes: CheckFixes,
     mem_docs: MemDocs,
     pub(crate) semantic_tokens_cache: Arc<Mutex<FxHashMap<Url, SemanticTokens>>>,
+    pub(crate) completion_history: Arc<RwLock<CompletionHistory>>,
+    pub(crate) cargo_toml_docs: Arc<FxHashMap<Url, String>>,
+    pub(crate) registry_index: Arc<RegistryIndex>,
     vfs: Arc<RwLock<(vfs::Vfs, NoHashHashMap<FileId, LineEndings>)>>,
     pub(crate) workspaces: Arc<Vec<ProjectWorkspace>>,
 }
@@ -148,6 +174,9 @@ impl GlobalState {
             diagnostics: Default::default(),
             mem_docs: MemDocs::default(),
             semantic_tokens_cache: Arc::new(Default::default()),
+            completion_history: Arc::new(RwLock::new(CompletionHistory::load())),
+            cargo_toml_docs: Arc::new(FxHashMap::default()),
+            registry_index: Arc::new(RegistryIndex::from_cargo_home()),
             shutdown_requested: false,
             proc_macro_changed: false,
             last_reported_status: None,
@@ -169,6 +198,10 @@ impl GlobalState {
             prime_caches_queue: OpQueue::default(),
 
             fetch_build_data_queue: OpQueue::default(),
+
+            crate_graph: CrateGraph::default(),
+            standalone_files: Vec::new(),
+            cargo_scripts: Vec::new(),
         };
         // Apply any required database inputs from the config.
         this.update_configuration(config);
@@ -256,6 +289,9 @@ impl GlobalState {
             check_fixes: Arc::clone(&self.diagnostics.check_fixes),
             mem_docs: self.mem_docs.clone(),
             semantic_tokens_cache: Arc::clone(&self.semantic_tokens_cache),
+            completion_history: Arc::clone(&self.completion_history),
+            cargo_toml_docs: Arc::clone(&self.cargo_toml_docs),
+            registry_index: Arc::clone(&self.registry_index),
         }
     }
 
@@ -5,13 +5,14 @@
 use std::{
     io::Write as _,
     process::{self, Stdio},
+    sync::Arc,
 };
 
 use anyhow::Context;
 use ide::{
     AnnotationConfig, AssistKind, AssistResolveStrategy, FileId, FilePosition, FileRange,
-    HoverAction, HoverGotoTypeData, Query, RangeInfo, Runnable, RunnableKind, SingleResolve,
-    SourceChange, TextEdit,
+    HoverAction, HoverGotoTypeData, Query, RangeInfo, Runnable, RunnableKind, SignatureParam,
+    SingleResolve, SourceChange, TextEdit,
 };
 use ide_db::SymbolKind;
 use lsp_server::ErrorCode;
@@ -25,7 +26,7 @@ use lsp_types::{
     SemanticTokensRangeParams, SemanticTokensRangeResult, SemanticTokensResult, SymbolInformation,
     SymbolTag, TextDocumentIdentifier, Url, WorkspaceEdit,
 };
-use project_model::{ManifestPath, ProjectWorkspace, TargetKind};
+use project_model::{ManifestInfo, ManifestPath, ProjectWorkspace, TargetKind};
 use serde_json::json;
 use stdx::{format_to, never};
 use syntax::{algo, ast, AstNode, TextRange, TextSize, T};
@@ -37,7 +38,7 @@ use crate::{
     diff::diff,
     from_proto,
     global_state::{GlobalState, GlobalStateSnapshot},
-    line_index::LineEndings,
+    line_index::{LineEndings, LineIndex},
     lsp_ext::{self, PositionOrRange, ViewCrateGraphParams, WorkspaceSymbolParams},
     lsp_utils::{all_edits_are_disjoint, invalid_params_error},
     to_proto, LspError, Result,
@@ -161,6 +162,42 @@ pub(crate) fn handle_view_crate_graph(
     Ok(dot)
 }
 
+pub(crate) fn handle_view_build_script_outputs(
+    snap: GlobalStateSnapshot,
+    _: (),
+) -> Result<Vec<lsp_ext::WorkspaceBuildScriptOutputs>> {
+    let _p = profile::span("handle_view_build_script_outputs");
+    let res = snap
+        .workspaces
+        .iter()
+        .filter_map(|ws| match ws {
+            ProjectWorkspace::Cargo { cargo, build_scripts, .. } => Some((cargo, build_scripts)),
+            _ => None,
+        })
+        .map(|(cargo, build_scripts)| lsp_ext::WorkspaceBuildScriptOutputs {
+            workspace_root: cargo.workspace_root().to_path_buf().into(),
+            error: build_scripts.error().map(ToOwned::to_owned),
+            packages: cargo
+                .packages()
+                .filter_map(|pkg| {
+                    let output = build_scripts.get_output(pkg)?;
+                    Some(lsp_ext::PackageBuildScriptOutput {
+                        name: cargo[pkg].name.clone(),
+                        manifest_path: cargo[pkg].manifest.to_path_buf().into(),
+                        cfgs: output.cfgs.iter().map(ToString::to_string).collect(),
+                        envs: output.envs.clone(),
+                        out_dir: output.out_dir.clone().map(Into::into),
+                        proc_macro_dylib_path: output.proc_macro_dylib_path.clone().map(Into::into),
+                        linked_libs: output.linked_libs.clone(),
+                        linked_paths: output.linked_paths.clone(),
+                    })
+                })
+                .collect(),
+        })
+        .collect();
+    Ok(res)
+}
+
 pub(crate) fn handle_expand_macro(
     snap: GlobalStateSnapshot,
     params: lsp_ext::ExpandMacroParams,
@@ -825,7 +862,7 @@ pub(crate) fn handle_completion(
     }
 
     let completion_config = &snap.config.completion();
-    let items = match snap.analysis.completions(
+    let mut items = match snap.analysis.completions(
         completion_config,
         position,
         completion_trigger_character,
@@ -833,6 +870,12 @@ pub(crate) fn handle_completion(
         None => return Ok(None),
         Some(items) => items,
     };
+    if snap.config.completion_learned_ranking() {
+        let history = snap.completion_history.read();
+        for item in &mut items {
+            item.set_accepted_count(history.count(item.context_kind(), &item.item_path()));
+        }
+    }
     let line_index = snap.file_line_index(position.file_id)?;
 
     let items =
@@ -1309,6 +1352,27 @@ pub(crate) fn handle_ssr(
     to_proto::workspace_edit(&snap, source_change)
 }
 
+pub(crate) fn handle_change_signature(
+    snap: GlobalStateSnapshot,
+    params: lsp_ext::ChangeSignatureParams,
+) -> Result<lsp_types::WorkspaceEdit> {
+    let _p = profile::span("handle_change_signature");
+    let position = from_proto::file_position(&snap, params.position)?;
+    let new_params = params
+        .parameters
+        .into_iter()
+        .map(|it| SignatureParam {
+            old_index: it.old_index,
+            name: it.name,
+            ty: it.ty,
+            default_value: it.default_value,
+        })
+        .collect();
+    let source_change =
+        snap.analysis.change_signature(position, new_params)?.map_err(to_proto::rename_error)?;
+    to_proto::workspace_edit(&snap, source_change)
+}
+
 pub(crate) fn publish_diagnostics(
     snap: &GlobalStateSnapshot,
     file_id: FileId,
@@ -1587,6 +1651,111 @@ pub(crate) fn handle_open_cargo_toml(
     Ok(Some(res))
 }
 
+pub(crate) fn handle_cargo_toml_completion(
+    snap: GlobalStateSnapshot,
+    params: lsp_types::CompletionParams,
+) -> Result<Option<lsp_types::CompletionResponse>> {
+    let _p = profile::span("handle_cargo_toml_completion");
+    let uri = &params.text_document_position.text_document.uri;
+    let (text, line_index) = match cargo_toml_text(&snap, uri) {
+        Some(it) => it,
+        None => return Ok(None),
+    };
+    let offset = from_proto::offset(&line_index, params.text_document_position.position)?;
+    let info = cargo_toml_info(&snap, uri, &text)?;
+    let index = &snap.registry_index;
+    let items = project_model::manifest_completions(&text, offset.into(), &info, index)
+        .into_iter()
+        .enumerate()
+        .map(|(i, item)| to_proto::manifest_completion_item(&line_index, i, item))
+        .collect();
+    let completion_list = lsp_types::CompletionList { is_incomplete: true, items };
+    Ok(Some(completion_list.into()))
+}
+
+pub(crate) fn handle_cargo_toml_hover(
+    snap: GlobalStateSnapshot,
+    params: lsp_ext::HoverParams,
+) -> Result<Option<lsp_ext::Hover>> {
+    let _p = profile::span("handle_cargo_toml_hover");
+    let uri = &params.text_document.uri;
+    let (text, line_index) = match cargo_toml_text(&snap, uri) {
+        Some(it) => it,
+        None => return Ok(None),
+    };
+    let position = match params.position {
+        PositionOrRange::Position(position) => position,
+        PositionOrRange::Range(range) => range.start,
+    };
+    let offset = from_proto::offset(&line_index, position)?;
+    let info = cargo_toml_info(&snap, uri, &text)?;
+    let index = &snap.registry_index;
+    let hover = match project_model::manifest_hover(&text, offset.into(), &info, index) {
+        Some(it) => it,
+        None => return Ok(None),
+    };
+    let hover = lsp_ext::Hover {
+        hover: lsp_types::Hover {
+            contents: HoverContents::Markup(lsp_types::MarkupContent {
+                kind: lsp_types::MarkupKind::Markdown,
+                value: hover.markdown,
+            }),
+            range: Some(to_proto::manifest_range(&line_index, hover.range)),
+        },
+        actions: Vec::new(),
+    };
+    Ok(Some(hover))
+}
+
+pub(crate) fn cargo_toml_diagnostics(
+    snap: &GlobalStateSnapshot,
+    uri: &Url,
+) -> Result<Vec<Diagnostic>> {
+    let _p = profile::span("cargo_toml_diagnostics");
+    let (text, line_index) = match cargo_toml_text(snap, uri) {
+        Some(it) => it,
+        None => return Ok(Vec::new()),
+    };
+    let info = cargo_toml_info(snap, uri, &text)?;
+    let diagnostics = project_model::manifest_diagnostics(&text, &info)
+        .into_iter()
+        .map(|d| Diagnostic {
+            range: to_proto::manifest_range(&line_index, d.range),
+            // The metadata the diagnostics are based on may be stale until the workspace is
+            // reloaded, so they are only advisory.
+            severity: Some(lsp_types::DiagnosticSeverity::WARNING),
+            source: Some("rust-analyzer".to_string()),
+            message: d.message,
+            ..Default::default()
+        })
+        .collect();
+    Ok(diagnostics)
+}
+
+fn cargo_toml_text(snap: &GlobalStateSnapshot, uri: &Url) -> Option<(String, LineIndex)> {
+    let (text, endings) = LineEndings::normalize(snap.cargo_toml_docs.get(uri)?.clone());
+    let line_index = LineIndex {
+        index: Arc::new(ide::LineIndex::new(&text)),
+        endings,
+        encoding: snap.config.offset_encoding(),
+    };
+    Some((text, line_index))
+}
+
+/// Finds what `cargo metadata` told us about the packages of the manifest at `uri`.
+fn cargo_toml_info(snap: &GlobalStateSnapshot, uri: &Url, text: &str) -> Result<ManifestInfo> {
+    let path = from_proto::abs_path(uri)?;
+    let info = snap.workspaces.iter().find_map(|ws| match ws {
+        ProjectWorkspace::Cargo { cargo, .. } => {
+            let in_workspace = *cargo.workspace_root().join("Cargo.toml") == *path
+                || cargo.packages().any(|pkg| *cargo[pkg].manifest == *path);
+            in_workspace.then(|| ManifestInfo::new(cargo, &path, text))
+        }
+        _ => None,
+    });
+    Ok(info.unwrap_or_default())
+}
+
 pub(crate) fn handle_move_item(
     snap: GlobalStateSnapshot,
     params: lsp_ext::MoveItemParams,
@@ -20,6 +20,7 @@ macro_rules! eprintln {
 
 mod caps;
 mod cargo_target_spec;
+mod completion_history;
 mod diagnostics;
 mod diff;
 mod dispatch;
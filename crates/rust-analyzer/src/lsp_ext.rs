@@ -107,6 +107,35 @@ impl Request for ViewItemTree {
     const METHOD: &'static str = "rust-analyzer/viewItemTree";
 }
 
+pub enum ViewBuildScriptOutputs {}
+
+impl Request for ViewBuildScriptOutputs {
+    type Params = ();
+    type Result = Vec<WorkspaceBuildScriptOutputs>;
+    const METHOD: &'static str = "rust-analyzer/viewBuildScriptOutputs";
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceBuildScriptOutputs {
+    pub workspace_root: PathBuf,
+    pub error: Option<String>,
+    pub packages: Vec<PackageBuildScriptOutput>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageBuildScriptOutput {
+    pub name: String,
+    pub manifest_path: PathBuf,
+    pub cfgs: Vec<String>,
+    pub envs: Vec<(String, String)>,
+    pub out_dir: Option<PathBuf>,
+    pub proc_macro_dylib_path: Option<PathBuf>,
+    pub linked_libs: Vec<String>,
+    pub linked_paths: Vec<String>,
+}
+
 pub enum ExpandMacro {}
 
 impl Request for ExpandMacro {
@@ -274,6 +303,37 @@ pub struct SsrParams {
     pub selections: Vec<lsp_types::Range>,
 }
 
+pub enum ChangeSignature {}
+
+impl Request for ChangeSignature {
+    type Params = ChangeSignatureParams;
+    type Result = lsp_types::WorkspaceEdit;
+    const METHOD: &'static str = "experimental/changeSignature";
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeSignatureParams {
+    /// Position of the function's definition or of a reference to it.
+    #[serde(flatten)]
+    pub position: lsp_types::TextDocumentPositionParams,
+    /// The new parameter list, not including `self`.
+    pub parameters: Vec<SignatureParameter>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignatureParameter {
+    /// Index of the parameter in the old signature, not counting `self`, or `null` for a new
+    /// parameter.
+    pub old_index: Option<usize>,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub ty: String,
+    /// The argument to pass at existing call sites, required for new parameters.
+    pub default_value: Option<String>,
+}
+
 pub enum ServerStatusNotification {}
 
 impl Notification for ServerStatusNotification {
@@ -281,6 +341,20 @@ impl Notification for ServerStatusNotification {
     const METHOD: &'static str = "experimental/serverStatus";
 }
 
+pub enum CompletionAccepted {}
+
+impl Notification for CompletionAccepted {
+    type Params = CompletionAcceptedParams;
+    const METHOD: &'static str = "rust-analyzer/completionAccepted";
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CompletionAcceptedParams {
+    pub context: String,
+    pub item: String,
+}
+
 #[derive(Deserialize, Serialize, PartialEq, Eq, Clone)]
 pub struct ServerStatusParams {
     pub health: Health,
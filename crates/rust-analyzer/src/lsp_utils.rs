@@ -128,6 +128,12 @@ impl GlobalState {
     }
 }
 
+/// Requests for `Cargo.toml` files are answered by the manifest analysis of
+/// `project_model` rather than by `ide`.
+pub(crate) fn is_cargo_toml(url: &lsp_types::Url) -> bool {
+    url.path().ends_with("/Cargo.toml")
+}
+
 pub(crate) fn apply_document_changes(
     old_text: &mut String,
     content_changes: Vec<lsp_types::TextDocumentContentChangeEvent>,
@@ -13,8 +13,8 @@ use flycheck::FlycheckHandle;
 use ide_db::base_db::{SourceDatabase, SourceDatabaseExt, VfsPath};
 use itertools::Itertools;
 use lsp_server::{Connection, Notification, Request};
-use lsp_types::notification::Notification as _;
-use vfs::{ChangeKind, FileId};
+use lsp_types::{notification::Notification as _, Url};
+use vfs::{AbsPathBuf, ChangeKind, FileId};
 
 use crate::{
     config::Config,
@@ -22,7 +22,7 @@ use crate::{
     from_proto,
     global_state::{file_id_to_url, url_to_file_id, GlobalState},
     handlers, lsp_ext,
-    lsp_utils::{apply_document_changes, notification_is, Progress},
+    lsp_utils::{apply_document_changes, is_cargo_toml, notification_is, Progress},
     mem_docs::DocumentData,
     reload::{self, BuildDataProgress, ProjectWorkspaceProgress},
     Result,
@@ -65,9 +65,11 @@ pub(crate) enum Task {
     Response(lsp_server::Response),
     Retry(lsp_server::Request),
     Diagnostics(Vec<(FileId, Vec<lsp_types::Diagnostic>)>),
+    CargoTomlDiagnostics(Url, Vec<lsp_types::Diagnostic>),
     PrimeCaches(PrimeCachesProgress),
     FetchWorkspace(ProjectWorkspaceProgress),
     FetchBuildData(BuildDataProgress),
+    StandaloneFiles { files: Vec<AbsPathBuf>, cargo_scripts: Vec<AbsPathBuf> },
 }
 
 #[derive(Debug)]
@@ -286,6 +288,10 @@ impl GlobalState {
                 || self.fetch_workspaces_queue.op_requested()
                 || self.fetch_build_data_queue.op_requested());
 
+            if became_quiescent || memdocs_added_or_removed {
+                self.update_standalone_files();
+            }
+
             if became_quiescent {
                 // Project has loaded properly, kick off initial flycheck
                 self.flycheck.iter().for_each(FlycheckHandle::restart);
@@ -406,6 +412,14 @@ impl GlobalState {
                     self.diagnostics.set_native_diagnostics(file_id, diagnostics)
                 }
             }
+            Task::CargoTomlDiagnostics(uri, diagnostics) => {
+                // Diagnostics of a manifest closed in the meantime are stale.
+                if self.cargo_toml_docs.contains_key(&uri) {
+                    self.send_notification::<lsp_types::notification::PublishDiagnostics>(
+                        lsp_types::PublishDiagnosticsParams::new(uri, diagnostics, None),
+                    );
+                }
+            }
             Task::PrimeCaches(progress) => match progress {
                 PrimeCachesProgress::Begin => prime_caches_progress.push(progress),
                 PrimeCachesProgress::Report(_) => {
@@ -457,6 +471,9 @@ impl GlobalState {
                     self.report_progress("Loading", state, msg, None);
                 }
             }
+            Task::StandaloneFiles { files, cargo_scripts } => {
+                self.add_standalone_files(files, cargo_scripts)
+            }
         }
     }
 
@@ -589,6 +606,11 @@ impl GlobalState {
             return;
         }
 
+        if is_cargo_toml_request(&req) {
+            self.on_cargo_toml_request(req);
+            return;
+        }
+
         RequestDispatcher { req: Some(req), global_state: self }
             .on_sync_mut::<lsp_types::request::Shutdown>(|s, ()| {
                 s.shutdown_requested = true;
@@ -608,6 +630,7 @@ impl GlobalState {
             .on::<lsp_ext::ViewFileText>(handlers::handle_view_file_text)
             .on::<lsp_ext::ViewCrateGraph>(handlers::handle_view_crate_graph)
             .on::<lsp_ext::ViewItemTree>(handlers::handle_view_item_tree)
+            .on::<lsp_ext::ViewBuildScriptOutputs>(handlers::handle_view_build_script_outputs)
             .on::<lsp_ext::ExpandMacro>(handlers::handle_expand_macro)
             .on::<lsp_ext::ParentModule>(handlers::handle_parent_module)
             .on::<lsp_ext::Runnables>(handlers::handle_runnables)
@@ -657,9 +680,22 @@ impl GlobalState {
             )
             .on::<lsp_types::request::WillRenameFiles>(handlers::handle_will_rename_files)
             .on::<lsp_ext::Ssr>(handlers::handle_ssr)
+            .on::<lsp_ext::ChangeSignature>(handlers::handle_change_signature)
             .finish();
     }
 
+    /// Handles a request for a `Cargo.toml` file. Only completion and hover are
+    /// supported, everything else gets an empty response.
+    fn on_cargo_toml_request(&mut self, req: Request) {
+        let mut dispatcher = RequestDispatcher { req: Some(req), global_state: self };
+        dispatcher
+            .on::<lsp_types::request::Completion>(handlers::handle_cargo_toml_completion)
+            .on::<lsp_ext::HoverRequest>(handlers::handle_cargo_toml_hover);
+        if let Some(req) = dispatcher.req.take() {
+            self.respond(lsp_server::Response::new_ok(req.id, serde_json::Value::Null));
+        }
+    }
+
     /// Handles an incoming notification.
     fn on_notification(&mut self, not: Notification) -> Result<()> {
         NotificationDispatcher { not: Some(not), global_state: self }
@@ -671,6 +707,16 @@ impl GlobalState {
                 this.cancel(id);
                 Ok(())
             })?
+            .on::<lsp_ext::CompletionAccepted>(|this, params| {
+                if this.config.completion_learned_ranking() {
+                    // Records made before the save runs are written out by it as well.
+                    if this.completion_history.write().record(&params.context, &params.item) {
+                        let history = Arc::clone(&this.completion_history);
+                        this.task_pool.handle.spawn_with_sender(move |_| history.write().save());
+                    }
+                }
+                Ok(())
+            })?
             .on::<lsp_types::notification::WorkDoneProgressCancel>(|_this, _params| {
                 // Just ignore this. It is OK to continue sending progress
                 // notifications for this token, as the client can't know when
@@ -678,6 +724,13 @@ impl GlobalState {
                 Ok(())
             })?
             .on::<lsp_types::notification::DidOpenTextDocument>(|this, params| {
+                if is_cargo_toml(&params.text_document.uri) {
+                    let uri = params.text_document.uri;
+                    Arc::make_mut(&mut this.cargo_toml_docs)
+                        .insert(uri.clone(), params.text_document.text);
+                    this.update_cargo_toml_diagnostics(uri);
+                    return Ok(());
+                }
                 if let Ok(path) = from_proto::vfs_path(&params.text_document.uri) {
                     let already_exists = this
                         .mem_docs
@@ -694,6 +747,14 @@ impl GlobalState {
                 Ok(())
             })?
             .on::<lsp_types::notification::DidChangeTextDocument>(|this, params| {
+                if is_cargo_toml(&params.text_document.uri) {
+                    let uri = params.text_document.uri;
+                    if let Some(text) = Arc::make_mut(&mut this.cargo_toml_docs).get_mut(&uri) {
+                        apply_document_changes(text, params.content_changes);
+                        this.update_cargo_toml_diagnostics(uri);
+                    }
+                    return Ok(());
+                }
                 if let Ok(path) = from_proto::vfs_path(&params.text_document.uri) {
                     match this.mem_docs.get_mut(&path) {
                         Some(doc) => {
@@ -717,6 +778,14 @@ impl GlobalState {
                 Ok(())
             })?
             .on::<lsp_types::notification::DidCloseTextDocument>(|this, params| {
+                if is_cargo_toml(&params.text_document.uri) {
+                    let uri = params.text_document.uri;
+                    Arc::make_mut(&mut this.cargo_toml_docs).remove(&uri);
+                    this.send_notification::<lsp_types::notification::PublishDiagnostics>(
+                        lsp_types::PublishDiagnosticsParams::new(uri, Vec::new(), None),
+                    );
+                    return Ok(());
+                }
                 if let Ok(path) = from_proto::vfs_path(&params.text_document.uri) {
                     if this.mem_docs.remove(&path).is_err() {
                         tracing::error!("orphan DidCloseTextDocument: {}", path);
@@ -864,6 +933,14 @@ impl GlobalState {
         Ok(())
     }
 
+    fn update_cargo_toml_diagnostics(&mut self, uri: Url) {
+        let snapshot = self.snapshot();
+        self.task_pool.handle.spawn(move || {
+            let diagnostics = handlers::cargo_toml_diagnostics(&snapshot, &uri).unwrap_or_default();
+            Task::CargoTomlDiagnostics(uri, diagnostics)
+        });
+    }
+
     fn update_diagnostics(&mut self) {
         let subscriptions = self
             .mem_docs
@@ -887,3 +964,11 @@ impl GlobalState {
         })
     }
 }
+
+fn is_cargo_toml_request(req: &Request) -> bool {
+    req.params
+        .get("textDocument")
+        .and_then(|it| it.get("uri"))
+        .and_then(|it| serde_json::from_value::<Url>(it.clone()).ok())
+        .map_or(false, |uri| is_cargo_toml(&uri))
+}
@@ -18,11 +18,12 @@ use flycheck::{FlycheckConfig, FlycheckHandle};
 use hir::db::DefDatabase;
 use ide::Change;
 use ide_db::base_db::{
-    CrateGraph, Env, ProcMacro, ProcMacroExpander, ProcMacroExpansionError, ProcMacroKind,
-    ProcMacroLoadResult, SourceRoot, VfsPath,
+    CrateGraph, CrateOrigin, Env, ProcMacro, ProcMacroExpander, ProcMacroExpansionError,
+    ProcMacroKind, ProcMacroLoadResult, SourceRoot, VfsPath,
 };
+use itertools::{Either, Itertools};
 use proc_macro_api::{MacroDylib, ProcMacroServer};
-use project_model::{ProjectWorkspace, WorkspaceBuildScripts};
+use project_model::{ProjectManifest, ProjectWorkspace, RegistryIndex, WorkspaceBuildScripts};
 use syntax::SmolStr;
 use vfs::{file_set::FileSetConfig, AbsPath, AbsPathBuf, ChangeKind};
 
@@ -94,6 +95,13 @@ impl GlobalState {
             status.message =
                 Some("Failed to run build scripts of some packages, check the logs.".to_string());
         }
+        if let Some(error) = self.workspaces.iter().find_map(|ws| ws.metadata_error()) {
+            status.health = lsp_ext::Health::Warning;
+            status.message = Some(format!(
+                "Using a project model built from Cargo.lock, some dependencies may be missing: {}",
+                error
+            ));
+        }
         if !self.config.cargo_autoreload()
             && self.is_quiescent()
             && self.fetch_workspaces_queue.op_requested()
@@ -114,7 +122,14 @@ impl GlobalState {
 
         self.task_pool.handle.spawn_with_sender({
             let linked_projects = self.config.linked_projects();
-            let detached_files = self.config.detached_files().to_vec();
+            let detached_files = self
+                .config
+                .detached_files()
+                .iter()
+                .chain(&self.cargo_scripts)
+                .unique()
+                .cloned()
+                .collect::<Vec<_>>();
             let cargo_config = self.config.cargo();
 
             move |sender| {
@@ -148,6 +163,21 @@ impl GlobalState {
                     })
                     .collect::<Vec<_>>();
 
+                // Detached files which turn out to be single-file Cargo packages get
+                // loaded like any other Cargo workspace, so that their dependencies resolve.
+                let (cargo_scripts, detached_files): (Vec<_>, Vec<_>) =
+                    detached_files.into_iter().partition_map(|file| {
+                        match ProjectManifest::from_manifest_file(file.clone()) {
+                            Ok(manifest @ ProjectManifest::CargoScript(_)) => {
+                                Either::Left(manifest)
+                            }
+                            _ => Either::Right(file),
+                        }
+                    });
+                workspaces.extend(cargo_scripts.into_iter().map(|manifest| {
+                    project_model::ProjectWorkspace::load(manifest, &cargo_config, &progress)
+                }));
+
                 if !detached_files.is_empty() {
                     workspaces
                         .push(project_model::ProjectWorkspace::load_detached_files(detached_files));
@@ -216,11 +246,21 @@ impl GlobalState {
                     sysroot,
                     rustc,
                     rustc_cfg,
+                    rustc_check_cfg,
                     cfg_overrides,
+                    metadata_error,
 
                     build_scripts: _,
                     toolchain: _,
-                } => Some((cargo, sysroot, rustc, rustc_cfg, cfg_overrides)),
+                } => Some((
+                    cargo,
+                    sysroot,
+                    rustc,
+                    rustc_cfg,
+                    rustc_check_cfg,
+                    cfg_overrides,
+                    metadata_error,
+                )),
                 _ => None,
             };
             match (key(left), key(right)) {
@@ -263,7 +303,9 @@ impl GlobalState {
 
             // Here, we completely changed the workspace (Cargo.toml edit), so
             // we don't care about build-script results, they are stale.
-            self.workspaces = Arc::new(workspaces)
+            self.workspaces = Arc::new(workspaces);
+            // Fetching the metadata may have downloaded the index of another registry.
+            self.registry_index = Arc::new(RegistryIndex::from_cargo_home());
         }
 
         if let FilesWatcher::Client = self.config.files().watcher {
@@ -299,7 +341,11 @@ impl GlobalState {
         let mut change = Change::new();
 
         let files_config = self.config.files();
-        let project_folders = ProjectFolders::new(&self.workspaces, &files_config.exclude);
+        if !files_config.standalone {
+            self.standalone_files.clear();
+        }
+        let project_folders =
+            ProjectFolders::new(&self.workspaces, &self.standalone_files, &files_config.exclude);
 
         let standalone_server_name =
             format!("rust-analyzer-proc-macro-srv{}", std::env::consts::EXE_SUFFIX);
@@ -400,7 +446,10 @@ impl GlobalState {
             }
             crate_graph
         };
-        change.set_crate_graph(crate_graph);
+        self.crate_graph = crate_graph;
+        let standalone_files = self.standalone_files.clone();
+        self.add_standalone_crates(&standalone_files);
+        change.set_crate_graph(self.crate_graph.clone());
 
         self.source_root_config = project_folders.source_root_config;
 
@@ -410,6 +459,140 @@ impl GlobalState {
         tracing::info!("did switch workspaces");
     }
 
+    /// Looks for opened files which don't belong to any crate and should thus be
+    /// added as standalone files, and drops standalone files which were closed.
+    pub(crate) fn update_standalone_files(&mut self) {
+        let files_config = self.config.files();
+        if !files_config.standalone || self.workspaces.is_empty() {
+            return;
+        }
+
+        let mem_docs = &self.mem_docs;
+        let (open, closed): (Vec<_>, Vec<_>) = mem::take(&mut self.standalone_files)
+            .into_iter()
+            .partition(|path| mem_docs.contains(&VfsPath::from(path.clone())));
+        self.standalone_files = open;
+        if !closed.is_empty() {
+            self.remove_standalone_crates(&closed);
+            self.switch_standalone_files();
+        }
+
+        let candidates = {
+            let vfs = &self.vfs.read().0;
+            self.mem_docs
+                .iter()
+                .filter_map(|path| {
+                    let abs_path = path.as_path()?;
+                    if abs_path.extension().unwrap_or_default() != "rs"
+                        || self.standalone_files.iter().any(|it| it == abs_path)
+                    {
+                        return None;
+                    }
+                    Some((vfs.file_id(path)?, abs_path.to_path_buf()))
+                })
+                .collect::<Vec<_>>()
+        };
+        if candidates.is_empty() {
+            return;
+        }
+
+        let analysis = self.analysis_host.analysis();
+        self.task_pool.handle.spawn(move || {
+            // Files with an embedded manifest are single-file Cargo packages, which
+            // need to be loaded as a workspace for their dependencies to resolve.
+            let (cargo_scripts, files) = candidates
+                .into_iter()
+                .filter(|&(file_id, _)| {
+                    matches!(analysis.crate_for(file_id), Ok(crates) if crates.is_empty())
+                })
+                .partition_map(|(file_id, path)| {
+                    match analysis.file_text(file_id) {
+                        Ok(text) if project_model::has_embedded_manifest(&text) => {
+                            Either::Left(path)
+                        }
+                        _ => Either::Right(path),
+                    }
+                });
+            Task::StandaloneFiles { files, cargo_scripts }
+        });
+    }
+
+    pub(crate) fn add_standalone_files(
+        &mut self,
+        files: Vec<AbsPathBuf>,
+        cargo_scripts: Vec<AbsPathBuf>,
+    ) {
+        for script in cargo_scripts {
+            if !self.cargo_scripts.contains(&script) {
+                self.fetch_workspaces_queue
+                    .request_op(format!("opened cargo script {}", script.display()));
+                self.cargo_scripts.push(script);
+            }
+        }
+
+        let mut added = Vec::new();
+        for file in files {
+            if self.mem_docs.contains(&VfsPath::from(file.clone()))
+                && !self.standalone_files.contains(&file)
+            {
+                tracing::info!("adding standalone file {}", file.display());
+                self.standalone_files.push(file.clone());
+                added.push(file);
+            }
+        }
+        if !added.is_empty() {
+            self.add_standalone_crates(&added);
+            self.switch_standalone_files();
+        }
+    }
+
+    /// Updates the source roots and hands the crate graph to the database after
+    /// the set of standalone files changed. Unlike `switch_workspaces`, this
+    /// neither touches the vfs loader nor lowers the workspaces again.
+    fn switch_standalone_files(&mut self) {
+        let _p = profile::span("GlobalState::switch_standalone_files");
+        let files_config = self.config.files();
+        let project_folders =
+            ProjectFolders::new(&self.workspaces, &self.standalone_files, &files_config.exclude);
+        self.source_root_config = project_folders.source_root_config;
+
+        let mut change = Change::new();
+        change.set_roots(self.source_root_config.partition(&self.vfs.read().0));
+        change.set_crate_graph(self.crate_graph.clone());
+        self.analysis_host.apply_change(change);
+    }
+
+    fn add_standalone_crates(&mut self, files: &[AbsPathBuf]) {
+        if files.is_empty() {
+            return;
+        }
+        let vfs = &self.vfs.read().0;
+        project_model::add_standalone_files_to_crate_graph(
+            &mut self.crate_graph,
+            &self.workspaces,
+            files,
+            self.config.files().standalone_workspace_deps,
+            &mut |path| vfs.file_id(&VfsPath::from(path.to_path_buf())),
+        );
+    }
+
+    fn remove_standalone_crates(&mut self, files: &[AbsPathBuf]) {
+        let files = files.iter().cloned().map(VfsPath::from).collect::<Vec<_>>();
+        let vfs = &self.vfs.read().0;
+        let crate_graph = &self.crate_graph;
+        let closed = crate_graph
+            .iter()
+            .filter(|&krate| {
+                let data = &crate_graph[krate];
+                data.origin == CrateOrigin::Local
+                    && files.contains(&vfs.file_path(data.root_file_id))
+            })
+            .collect::<Vec<_>>();
+        for krate in closed {
+            self.crate_graph.remove_crate(krate);
+        }
+    }
+
     fn fetch_workspace_error(&self) -> Result<(), String> {
         let mut buf = String::new();
 
@@ -464,6 +647,14 @@ impl GlobalState {
             .iter()
             .enumerate()
             .filter_map(|(id, w)| match w {
+                ProjectWorkspace::Cargo { cargo, .. } if cargo.cargo_script().is_some() => {
+                    // The default `cargo check` invocation expects a `Cargo.toml` in the
+                    // workspace root, which single-file packages lack.
+                    match config {
+                        FlycheckConfig::CustomCommand { .. } => Some((id, cargo.workspace_root())),
+                        _ => None,
+                    }
+                }
                 ProjectWorkspace::Cargo { cargo, .. } => Some((id, cargo.workspace_root())),
                 ProjectWorkspace::Json { project, .. } => {
                     // Enable flychecks for json projects if a custom flycheck command was supplied
@@ -498,6 +689,7 @@ pub(crate) struct ProjectFolders {
 impl ProjectFolders {
     pub(crate) fn new(
         workspaces: &[ProjectWorkspace],
+        standalone_files: &[AbsPathBuf],
         global_excludes: &[AbsPathBuf],
     ) -> ProjectFolders {
         let mut res = ProjectFolders::default();
@@ -537,6 +729,13 @@ impl ProjectFolders {
             fsc.add_file_set(file_set_roots)
         }
 
+        // Standalone files are opened by the user, so they are already in the vfs and
+        // don't need to be loaded or watched, but they should be local to get diagnostics.
+        for file in standalone_files {
+            local_filesets.push(fsc.len());
+            fsc.add_file_set(vec![VfsPath::from(file.clone())]);
+        }
+
         let fsc = fsc.build();
         res.source_root_config = SourceRootConfig { fsc, local_filesets };
 
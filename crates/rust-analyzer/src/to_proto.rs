@@ -1,7 +1,7 @@
 //! Conversion of rust-analyzer specific types to lsp_types equivalents.
 use std::{
     iter::once,
-    path,
+    ops, path,
     sync::atomic::{AtomicU32, Ordering},
 };
 
@@ -13,6 +13,7 @@ use ide::{
     SignatureHelp, SourceChange, StructureNodeKind, SymbolKind, TextEdit, TextRange, TextSize,
 };
 use itertools::Itertools;
+use project_model::{ManifestCompletion, ManifestCompletionKind};
 use serde_json::to_value;
 use vfs::AbsPath;
 
@@ -108,6 +109,7 @@ pub(crate) fn completion_item_kind(
     match completion_item_kind {
         CompletionItemKind::Binding => lsp_types::CompletionItemKind::VARIABLE,
         CompletionItemKind::BuiltinType => lsp_types::CompletionItemKind::STRUCT,
+        CompletionItemKind::Expression => lsp_types::CompletionItemKind::SNIPPET,
         CompletionItemKind::InferredType => lsp_types::CompletionItemKind::SNIPPET,
         CompletionItemKind::Keyword => lsp_types::CompletionItemKind::KEYWORD,
         CompletionItemKind::Method => lsp_types::CompletionItemKind::METHOD,
@@ -204,6 +206,39 @@ pub(crate) fn snippet_text_edit_vec(
         .collect()
 }
 
+pub(crate) fn manifest_range(line_index: &LineIndex, range: ops::Range<usize>) -> lsp_types::Range {
+    let range =
+        TextRange::new(TextSize::from(range.start as u32), TextSize::from(range.end as u32));
+    self::range(line_index, range)
+}
+
+pub(crate) fn manifest_completion_item(
+    line_index: &LineIndex,
+    position: usize,
+    item: ManifestCompletion,
+) -> lsp_types::CompletionItem {
+    let kind = match item.kind {
+        ManifestCompletionKind::Crate => lsp_types::CompletionItemKind::MODULE,
+        ManifestCompletionKind::Version => lsp_types::CompletionItemKind::VALUE,
+        ManifestCompletionKind::Feature => lsp_types::CompletionItemKind::ENUM_MEMBER,
+        ManifestCompletionKind::Key => lsp_types::CompletionItemKind::PROPERTY,
+    };
+    let text_edit = lsp_types::TextEdit {
+        range: manifest_range(line_index, item.range),
+        new_text: item.insert,
+    };
+    lsp_types::CompletionItem {
+        filter_text: Some(item.label.clone()),
+        label: item.label,
+        kind: Some(kind),
+        detail: item.detail,
+        // Keep the order of the items, like versions newest first.
+        sort_text: Some(format!("{:04}", position)),
+        text_edit: Some(text_edit.into()),
+        ..Default::default()
+    }
+}
+
 pub(crate) fn completion_items(
     config: &Config,
     line_index: &LineIndex,
@@ -258,7 +293,16 @@ fn completion_item(
 
     let insert_text_format = item.is_snippet().then(|| lsp_types::InsertTextFormat::SNIPPET);
     let tags = item.deprecated().then(|| vec![lsp_types::CompletionItemTag::DEPRECATED]);
-    let command = if item.trigger_call_info() && config.client_commands().trigger_parameter_hints {
+    let client_commands = config.client_commands();
+    let trigger_parameter_hints =
+        item.trigger_call_info() && client_commands.trigger_parameter_hints;
+    let command = if config.completion_learned_ranking() && client_commands.completion_accepted {
+        Some(command::completion_accepted(
+            item.context_kind(),
+            &item.item_path(),
+            trigger_parameter_hints,
+        ))
+    } else if trigger_parameter_hints {
         Some(command::trigger_parameter_hints())
     } else {
         None
@@ -1299,6 +1343,23 @@ pub(crate) mod command {
             arguments: None,
         }
     }
+
+    pub(crate) fn completion_accepted(
+        context: &str,
+        item: &str,
+        trigger_parameter_hints: bool,
+    ) -> lsp_types::Command {
+        let params =
+            lsp_ext::CompletionAcceptedParams { context: context.into(), item: item.into() };
+        lsp_types::Command {
+            title: "completionAccepted".into(),
+            command: "rust-analyzer.completionAccepted".into(),
+            arguments: Some(vec![
+                to_value(params).unwrap(),
+                to_value(trigger_parameter_hints).unwrap(),
+            ]),
+        }
+    }
 }
 
 pub(crate) fn implementation_title(count: usize) -> String {
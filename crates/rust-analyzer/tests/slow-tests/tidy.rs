@@ -245,7 +245,7 @@ fn check_todo(path: &Path, text: &str) {
         "ast/make.rs",
         // The documentation in string literals may contain anything for its own purposes
         "ide-db/src/generated/lints.rs",
-        "ide-assists/src/utils/gen_trait_fn_body.rs",
+        "ide-db/src/syntax_helpers/gen_trait_fn_body.rs",
         "ide-assists/src/tests/generated.rs",
         // The tests for missing fields
         "ide-diagnostics/src/handlers/missing_fields.rs",
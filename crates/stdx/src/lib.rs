@@ -44,6 +44,15 @@ pub fn to_lower_snake_case(s: &str) -> String {
 pub fn to_upper_snake_case(s: &str) -> String {
     to_snake_case(s, char::to_ascii_uppercase)
 }
+pub fn to_camel_case(s: &str) -> String {
+    s.split('_')
+        .filter(|it| !it.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            chars.next().map_or_else(String::new, |c| c.to_uppercase().chain(chars).collect())
+        })
+        .collect()
+}
 
 // Code partially taken from rust/compiler/rustc_lint/src/nonstandard_style.rs
 // commit: 9626f2b
@@ -209,6 +218,22 @@ pub fn slice_tails<T>(this: &[T]) -> impl Iterator<Item = &[T]> {
     (0..this.len()).map(|i| &this[i..])
 }
 
+/// Returns the Levenshtein distance between `a` and `b`, counted in chars.
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut prev_diag = row[0];
+        row[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let substitution = prev_diag + usize::from(ca != cb);
+            prev_diag = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(prev_diag + 1);
+        }
+    }
+    row[b.len()]
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -245,4 +270,20 @@ mod tests {
             "fn main() {\n    return 92;\n}\n"
         );
     }
+    #[test]
+    fn test_to_camel_case() {
+        assert_eq!(to_camel_case("foo"), "Foo");
+        assert_eq!(to_camel_case("foo_bar"), "FooBar");
+        assert_eq!(to_camel_case("_foo__bar_"), "FooBar");
+        assert_eq!(to_camel_case("FooBar"), "FooBar");
+    }
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("", ""), 0);
+        assert_eq!(edit_distance("foo", "foo"), 0);
+        assert_eq!(edit_distance("", "foo"), 3);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("Vec", "Vce"), 2);
+        assert_eq!(edit_distance("feature", "features"), 1);
+    }
 }
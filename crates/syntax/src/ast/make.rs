@@ -802,6 +802,30 @@ pub fn struct_(
     ))
 }
 
+pub fn ty_alias(name: ast::Name, ty: ast::Type) -> ast::TypeAlias {
+    ast_from_text(&format!("type {} = {};", name, ty))
+}
+
+pub fn trait_(
+    visibility: Option<ast::Visibility>,
+    trait_name: ast::Name,
+    generic_param_list: Option<ast::GenericParamList>,
+    where_clause: Option<ast::WhereClause>,
+    assoc_items: ast::AssocItemList,
+) -> ast::Trait {
+    let visibility = match visibility {
+        None => String::new(),
+        Some(it) => format!("{} ", it),
+    };
+    let type_params = generic_param_list.map_or_else(String::new, |it| it.to_string());
+    let where_clause = where_clause.map_or_else(String::new, |it| format!(" {}", it));
+
+    ast_from_text(&format!(
+        "{}trait {}{}{} {}",
+        visibility, trait_name, type_params, where_clause, assoc_items
+    ))
+}
+
 #[track_caller]
 fn ast_from_text<N: AstNode>(text: &str) -> N {
     let parse = SourceFile::parse(text);
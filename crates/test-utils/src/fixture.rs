@@ -51,6 +51,10 @@
 //! - crate names via `crate:cratename`
 //! - dependencies via `deps:dep1,dep2`
 //! - configuration settings via `cfg:dbg=false,opt_level=2`
+//! - expected configuration settings on top of the well-known and enabled ones via
+//!   `check-cfg:feature=serde,has_foo`
+//! - configuration settings emitted by the crate's build script, which are enabled as well, via
+//!   `build-script-cfg:has_foo,foo_version=2`
 //! - environment variables via `env:PATH=/bin,RUST_LOG=debug`
 //!
 //! Example using all available metadata:
@@ -73,6 +77,8 @@ pub struct Fixture {
     pub extern_prelude: Option<Vec<String>>,
     pub cfg_atoms: Vec<String>,
     pub cfg_key_values: Vec<(String, String)>,
+    pub check_cfg: Option<Vec<(String, Option<String>)>>,
+    pub build_script_cfg: Vec<(String, Option<String>)>,
     pub edition: Option<String>,
     pub env: FxHashMap<String, String>,
     pub introduce_new_source_root: Option<String>,
@@ -177,6 +183,8 @@ impl Fixture {
         let mut edition = None;
         let mut cfg_atoms = Vec::new();
         let mut cfg_key_values = Vec::new();
+        let mut check_cfg = None;
+        let mut build_script_cfg = Vec::new();
         let mut env = FxHashMap::default();
         let mut introduce_new_source_root = None;
         for component in components[1..].iter() {
@@ -203,6 +211,27 @@ impl Fixture {
                         }
                     }
                 }
+                "check-cfg" => {
+                    check_cfg = Some(
+                        value
+                            .split(',')
+                            .filter(|it| !it.is_empty())
+                            .map(|entry| match entry.split_once('=') {
+                                Some((k, v)) => (k.to_string(), Some(v.to_string())),
+                                None => (entry.to_string(), None),
+                            })
+                            .collect(),
+                    );
+                }
+                "build-script-cfg" => {
+                    build_script_cfg = value
+                        .split(',')
+                        .map(|entry| match entry.split_once('=') {
+                            Some((k, v)) => (k.to_string(), Some(v.to_string())),
+                            None => (entry.to_string(), None),
+                        })
+                        .collect();
+                }
                 "env" => {
                     for key in value.split(',') {
                         if let Some((k, v)) = key.split_once('=') {
@@ -232,6 +261,8 @@ impl Fixture {
             extern_prelude,
             cfg_atoms,
             cfg_key_values,
+            check_cfg,
+            build_script_cfg,
             edition,
             env,
             introduce_new_source_root,
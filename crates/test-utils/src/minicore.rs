@@ -29,6 +29,7 @@
 //!     clone: sized
 //!     copy: clone
 //!     from: sized
+//!     try_from: from, result
 //!     eq: sized
 //!     ord: eq, option
 //!     derive:
@@ -144,6 +145,27 @@ pub mod convert {
     }
     // endregion:from
 
+    // region:try_from
+    pub trait TryFrom<T>: Sized {
+        type Error;
+        fn try_from(value: T) -> Result<Self, Self::Error>;
+    }
+    pub trait TryInto<T>: Sized {
+        type Error;
+        fn try_into(self) -> Result<T, Self::Error>;
+    }
+
+    impl<T, U> TryInto<U> for T
+    where
+        U: TryFrom<T>,
+    {
+        type Error = U::Error;
+        fn try_into(self) -> Result<U, U::Error> {
+            U::try_from(self)
+        }
+    }
+    // endregion:try_from
+
     // region:as_ref
     pub trait AsRef<T: ?Sized> {
         fn as_ref(&self) -> &T;
@@ -403,6 +425,9 @@ pub mod fmt {
     pub trait Debug {
         fn fmt(&self, f: &mut Formatter<'_>) -> Result;
     }
+    pub trait Display {
+        fn fmt(&self, f: &mut Formatter<'_>) -> Result;
+    }
 }
 // endregion:fmt
 
@@ -445,6 +470,15 @@ pub mod result {
         #[lang = "Err"]
         Err(E),
     }
+
+    // region:try
+    impl<T, E> crate::ops::Try for Result<T, E> {
+        type Output = T;
+        type Residual = Result<crate::convert::Infallible, E>;
+        fn from_output(output: Self::Output) -> Self {}
+        fn branch(self) -> crate::ops::ControlFlow<Self::Residual, Self::Output> {}
+    }
+    // endregion:try
 }
 // endregion:result
 
@@ -653,6 +687,7 @@ pub mod prelude {
             cmp::{Ord, PartialOrd},             // :ord
             convert::AsRef,                     // :as_ref
             convert::{From, Into},              // :from
+            convert::{TryFrom, TryInto},        // :try_from
             default::Default,                   // :default
             iter::{IntoIterator, Iterator},     // :iterator
             macros::builtin::derive,            // :derive
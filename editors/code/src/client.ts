@@ -93,7 +93,11 @@ export async function createClient(
     }
 
     const clientOptions: lc.LanguageClientOptions = {
-        documentSelector: [{ scheme: "file", language: "rust" }],
+        documentSelector: [
+            { scheme: "file", language: "rust" },
+            // Only completion, hover and diagnostics are provided for manifests.
+            { scheme: "file", pattern: "**/Cargo.toml" },
+        ],
         initializationOptions,
         diagnosticCollectionName: "rustc",
         traceOutputChannel: traceOutputChannel(),
@@ -283,6 +287,7 @@ class ExperimentalFeatures implements lc.StaticFeature {
                 "rust-analyzer.debugSingle",
                 "rust-analyzer.showReferences",
                 "rust-analyzer.gotoLocation",
+                "rust-analyzer.completionAccepted",
                 "editor.action.triggerParameterHints",
             ],
         };
@@ -625,6 +625,64 @@ export function viewItemTree(ctx: Ctx): Cmd {
     };
 }
 
+export function viewBuildScriptOutputs(ctx: Ctx): Cmd {
+    const tdcp = new (class implements vscode.TextDocumentContentProvider {
+        readonly uri = vscode.Uri.parse("rust-analyzer-build-scripts://viewBuildScriptOutputs");
+        readonly eventEmitter = new vscode.EventEmitter<vscode.Uri>();
+
+        provideTextDocumentContent(
+            _uri: vscode.Uri,
+            ct: vscode.CancellationToken
+        ): vscode.ProviderResult<string> {
+            const client = ctx.client;
+            if (!client) return "";
+
+            return client.sendRequest(ra.viewBuildScriptOutputs, ct).then((workspaces) => {
+                const lines: string[] = [];
+                for (const ws of workspaces) {
+                    lines.push(`Workspace ${ws.workspaceRoot}`);
+                    if (ws.error) {
+                        lines.push("  Build scripts failed:", ...ws.error.split("\n"));
+                    }
+                    for (const pkg of ws.packages) {
+                        lines.push("", `  ${pkg.name} (${pkg.manifestPath})`);
+                        if (pkg.outDir) lines.push(`    OUT_DIR: ${pkg.outDir}`);
+                        if (pkg.procMacroDylibPath) {
+                            lines.push(`    proc-macro dylib: ${pkg.procMacroDylibPath}`);
+                        }
+                        if (pkg.cfgs.length) lines.push(`    cfgs: ${pkg.cfgs.join(", ")}`);
+                        if (pkg.linkedLibs.length) {
+                            lines.push(`    rustc-link-lib: ${pkg.linkedLibs.join(", ")}`);
+                        }
+                        if (pkg.linkedPaths.length) {
+                            lines.push(`    rustc-link-search: ${pkg.linkedPaths.join(", ")}`);
+                        }
+                        for (const [key, value] of pkg.envs) {
+                            lines.push(`    ${key}=${value}`);
+                        }
+                    }
+                    lines.push("");
+                }
+                return lines.join("\n");
+            });
+        }
+
+        get onDidChange(): vscode.Event<vscode.Uri> {
+            return this.eventEmitter.event;
+        }
+    })();
+
+    ctx.pushCleanup(
+        vscode.workspace.registerTextDocumentContentProvider("rust-analyzer-build-scripts", tdcp)
+    );
+
+    return async () => {
+        tdcp.eventEmitter.fire(tdcp.uri);
+        const document = await vscode.workspace.openTextDocument(tdcp.uri);
+        return vscode.window.showTextDocument(document, vscode.ViewColumn.Two, true);
+    };
+}
+
 function crateGraph(ctx: Ctx, full: boolean): Cmd {
     return async () => {
         const nodeModulesPath = vscode.Uri.file(path.join(ctx.extensionPath, "node_modules"));
@@ -798,6 +856,18 @@ export function gotoLocation(ctx: Ctx): Cmd {
     };
 }
 
+export function completionAccepted(ctx: Ctx): Cmd {
+    return async (params: ra.CompletionAcceptedParams, triggerParameterHints: boolean) => {
+        const client = ctx.client;
+        if (client) {
+            await client.sendNotification(ra.completionAccepted, params);
+        }
+        if (triggerParameterHints) {
+            await vscode.commands.executeCommand("editor.action.triggerParameterHints");
+        }
+    };
+}
+
 export function openDocs(ctx: Ctx): Cmd {
     return async () => {
         const client = ctx.client;
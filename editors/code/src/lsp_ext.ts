@@ -22,6 +22,14 @@ export const serverStatus = new lc.NotificationType<ServerStatusParams>(
     "experimental/serverStatus"
 );
 
+export interface CompletionAcceptedParams {
+    context: string;
+    item: string;
+}
+export const completionAccepted = new lc.NotificationType<CompletionAcceptedParams>(
+    "rust-analyzer/completionAccepted"
+);
+
 export const reloadWorkspace = new lc.RequestType0<null, void>("rust-analyzer/reloadWorkspace");
 
 export const hover = new lc.RequestType<HoverParams, lc.Hover | null, void>("textDocument/hover");
@@ -63,6 +71,25 @@ export const viewCrateGraph = new lc.RequestType<ViewCrateGraphParams, string, v
     "rust-analyzer/viewCrateGraph"
 );
 
+export interface PackageBuildScriptOutput {
+    name: string;
+    manifestPath: string;
+    cfgs: string[];
+    envs: [string, string][];
+    outDir: string | null;
+    procMacroDylibPath: string | null;
+    linkedLibs: string[];
+    linkedPaths: string[];
+}
+export interface WorkspaceBuildScriptOutputs {
+    workspaceRoot: string;
+    error: string | null;
+    packages: PackageBuildScriptOutput[];
+}
+export const viewBuildScriptOutputs = new lc.RequestType0<WorkspaceBuildScriptOutputs[], void>(
+    "rust-analyzer/viewBuildScriptOutputs"
+);
+
 export interface ExpandMacroParams {
     textDocument: lc.TextDocumentIdentifier;
     position: lc.Position;
@@ -92,6 +119,21 @@ export interface SsrParams {
 }
 export const ssr = new lc.RequestType<SsrParams, lc.WorkspaceEdit, void>("experimental/ssr");
 
+export interface SignatureParameter {
+    oldIndex: number | null;
+    name: string;
+    type: string;
+    defaultValue: string | null;
+}
+export interface ChangeSignatureParams {
+    textDocument: lc.TextDocumentIdentifier;
+    position: lc.Position;
+    parameters: SignatureParameter[];
+}
+export const changeSignature = new lc.RequestType<ChangeSignatureParams, lc.WorkspaceEdit, void>(
+    "experimental/changeSignature"
+);
+
 export interface MatchingBraceParams {
     textDocument: lc.TextDocumentIdentifier;
     positions: lc.Position[];
@@ -163,6 +163,7 @@ async function initCommonContext(context: vscode.ExtensionContext, ctx: Ctx) {
     ctx.registerCommand("viewItemTree", commands.viewItemTree);
     ctx.registerCommand("viewCrateGraph", commands.viewCrateGraph);
     ctx.registerCommand("viewFullCrateGraph", commands.viewFullCrateGraph);
+    ctx.registerCommand("viewBuildScriptOutputs", commands.viewBuildScriptOutputs);
     ctx.registerCommand("expandMacro", commands.expandMacro);
     ctx.registerCommand("run", commands.run);
     ctx.registerCommand("copyRunCommandLine", commands.copyRunCommandLine);
@@ -190,6 +191,7 @@ async function initCommonContext(context: vscode.ExtensionContext, ctx: Ctx) {
     ctx.registerCommand("resolveCodeAction", commands.resolveCodeAction);
     ctx.registerCommand("applyActionGroup", commands.applyActionGroup);
     ctx.registerCommand("gotoLocation", commands.gotoLocation);
+    ctx.registerCommand("completionAccepted", commands.completionAccepted);
 
     ctx.registerCommand("linkToCommand", commands.linkToCommand);
 }